@@ -29,6 +29,7 @@ readonly CLOUDSMITH_DOCKER_REGISTRY="docker.cloudsmith.io/grapl/raw"
 services=(
     analyzer-dispatcher
     analyzer-executor
+    auditd-generator
     graph-merger
     node-identifier
     node-identifier-retry
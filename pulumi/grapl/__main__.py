@@ -10,6 +10,7 @@ from infra.alarms import OpsAlarms
 from infra.analyzer_dispatcher import AnalyzerDispatcher
 from infra.analyzer_executor import AnalyzerExecutor
 from infra.api import Api
+from infra.auditd_generator import AuditdGenerator
 from infra.autotag import register_auto_tags
 from infra.bucket import Bucket
 from infra.cache import Cache
@@ -79,6 +80,7 @@ def main() -> None:
     # else uses them (or perhaps even if something else *does* use them)
     sysmon_log_emitter = emitter.EventEmitter("sysmon-log")
     osquery_log_emitter = emitter.EventEmitter("osquery-log")
+    auditd_log_emitter = emitter.EventEmitter("auditd-log")
     unid_subgraphs_generated_emitter = emitter.EventEmitter("unid-subgraphs-generated")
     subgraphs_generated_emitter = emitter.EventEmitter("subgraphs-generated")
     subgraphs_merged_emitter = emitter.EventEmitter("subgraphs-merged")
@@ -112,6 +114,9 @@ def main() -> None:
         osquery_generator_queue = ServiceQueue("osquery-generator")
         osquery_generator_queue.subscribe_to_emitter(osquery_log_emitter)
 
+        auditd_generator_queue = ServiceQueue("auditd-generator")
+        auditd_generator_queue.subscribe_to_emitter(auditd_log_emitter)
+
         node_identifier_queue = ServiceQueue("node-identifier")
         node_identifier_queue.subscribe_to_emitter(unid_subgraphs_generated_emitter)
 
@@ -151,6 +156,14 @@ def main() -> None:
             forwarder=forwarder,
         )
 
+        auditd_generator = AuditdGenerator(
+            input_emitter=auditd_log_emitter,
+            output_emitter=unid_subgraphs_generated_emitter,
+            network=network,
+            cache=cache,
+            forwarder=forwarder,
+        )
+
         node_identifier = NodeIdentifier(
             input_emitter=unid_subgraphs_generated_emitter,
             output_emitter=subgraphs_generated_emitter,
@@ -194,6 +207,7 @@ def main() -> None:
             [
                 sysmon_generator,
                 osquery_generator,
+                auditd_generator,
                 node_identifier,
                 graph_merger,
                 analyzer_dispatcher,
@@ -0,0 +1,58 @@
+from typing import Mapping
+
+from infra.cache import Cache
+from infra.config import configurable_envvars, repository_path
+from infra.emitter import EventEmitter
+from infra.fargate_service import FargateService, GraplDockerBuild
+from infra.metric_forwarder import MetricForwarder
+from infra.network import Network
+
+import pulumi
+
+
+def _default_hostname_envvar() -> Mapping[str, str]:
+    """auditd only logs which host each record came from when it's
+    configured with a `name_format`. Deployments that ship logs from
+    a single host without one can name the host with the optional
+    `AUDITD_DEFAULT_HOSTNAME` variable:
+
+    config:
+      grapl:env_vars:
+        auditd-generator:
+          AUDITD_DEFAULT_HOSTNAME: <HOSTNAME>
+    """
+    vars = (pulumi.Config().get_object("env_vars") or {}).get("auditd-generator", {})
+    default_hostname = vars.get("AUDITD_DEFAULT_HOSTNAME")
+    return {"AUDITD_DEFAULT_HOSTNAME": default_hostname} if default_hostname else {}
+
+
+class AuditdGenerator(FargateService):
+    def __init__(
+        self,
+        input_emitter: EventEmitter,
+        output_emitter: EventEmitter,
+        network: Network,
+        cache: Cache,
+        forwarder: MetricForwarder,
+    ) -> None:
+        super().__init__(
+            "auditd-generator",
+            image=GraplDockerBuild(
+                dockerfile=str(repository_path("src/rust/Dockerfile")),
+                target="auditd-generator-deploy",
+                context=str(repository_path("src")),
+            ),
+            env={
+                **configurable_envvars(
+                    "auditd-generator", ["RUST_LOG", "RUST_BACKTRACE"]
+                ),
+                **_default_hostname_envvar(),
+                "REDIS_ENDPOINT": cache.endpoint,
+            },
+            input_emitter=input_emitter,
+            output_emitter=output_emitter,
+            forwarder=forwarder,
+            network=network,
+        )
+
+        self.allow_egress_to_cache(cache)
@@ -146,6 +146,16 @@ def configurable_envvars(service_name: str, vars: Sequence[str]) -> Mapping[str,
     return {v: configurable_envvar(service_name, v) for v in vars}
 
 
+def optional_envvars(service_name: str, vars: Sequence[str]) -> Mapping[str, str]:
+    """Like `configurable_envvars`, but leaves out any variables that
+    aren't configured for the named service, for variables the service
+    has its own defaults for.
+    """
+    config_key = "env_vars"
+    service_vars = (pulumi.Config().get_object(config_key) or {}).get(service_name, {})
+    return {v: service_vars[v] for v in vars if service_vars.get(v)}
+
+
 # TODO: The verbiage "version" here is a bit restrictive.
 def configured_version_for(artifact_name: str) -> Optional[str]:
     """Given the name of an artifact, retrieves the version of that
@@ -33,6 +33,10 @@ class GraphMerger(FargateService):
                 "REDIS_ENDPOINT": cache.endpoint,
                 "MG_ALPHAS": dgraph_cluster.alpha_host_port,
                 "GRAPL_SCHEMA_TABLE": db.schema_table.name,
+                "GRAPL_SCHEMA_PROPERTIES_TABLE": db.schema_properties_table.name,
+                # Subgraphs that don't conform to the schemas are
+                # rejected rather than merged.
+                "ENFORCE_GRAPH_SCHEMA": "true",
             },
             input_emitter=input_emitter,
             output_emitter=output_emitter,
@@ -43,17 +47,9 @@ class GraphMerger(FargateService):
         for service in self.services:
             dgraph_cluster.allow_connections_from(service.security_group)
 
-            # TODO: both the default and retry services get READ
-            # permissions on the schema and schema properties table, even
-            # though only the schema table was passed into the
-            # environment.
-            #
-            # Update (wimax Jun 2021): The properties table *is* needed, it was introduced
-            # as part of the "python schema ---> json dynamodb ---> graphql schema"
-            # pipeline.
-            # It doesn't use the passed-in-via-environment mechanism because
-            # of the `get_schema_properties_table` in common.
-            # TODO: That function should be changed to instead depend on environment variables.
+            # The schema tables are read at startup, to load the
+            # schemas that have been provisioned alongside the
+            # endpoint schema the graph-merger embeds.
             dynamodb.grant_read_on_tables(
                 service.task_role, [db.schema_table, db.schema_properties_table]
             )
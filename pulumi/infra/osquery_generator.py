@@ -1,5 +1,5 @@
 from infra.cache import Cache
-from infra.config import configurable_envvars, repository_path
+from infra.config import configurable_envvars, optional_envvars, repository_path
 from infra.emitter import EventEmitter
 from infra.fargate_service import FargateService, GraplDockerBuild
 from infra.metric_forwarder import MetricForwarder
@@ -26,6 +26,11 @@ class OSQueryGenerator(FargateService):
                 **configurable_envvars(
                     "osquery-generator", ["RUST_LOG", "RUST_BACKTRACE"]
                 ),
+                # Routes queries from packs other than Grapl's own. Set
+                # `grapl:env_vars.osquery-generator.OSQUERY_ROUTES_FILE` to
+                # the routes file's path in the image; when unset, only the
+                # `pack_grapl_*` queries are parsed.
+                **optional_envvars("osquery-generator", ["OSQUERY_ROUTES_FILE"]),
                 "REDIS_ENDPOINT": cache.endpoint,
             },
             input_emitter=input_emitter,
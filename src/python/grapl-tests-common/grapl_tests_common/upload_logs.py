@@ -100,6 +100,19 @@ class OSQueryGeneratorOptions(GeneratorOptions):
         return cast(bytes, zstd.compress(b"\n".join(input).replace(b"\n\n", b"\n"), 4))
 
 
+class AuditdGeneratorOptions(GeneratorOptions):
+    def __init__(self) -> None:
+        super().__init__(
+            queue_suffix=f"auditd-generator-queue",
+            bucket_suffix="auditd-log-bucket",
+            key_infix="auditd",
+        )
+
+    def encode_chunk(self, input: List[bytes]) -> bytes:
+        # zstd encoded line delineated audit records
+        return cast(bytes, zstd.compress(b"\n".join(input).replace(b"\n\n", b"\n"), 4))
+
+
 def upload_logs(
     deployment_name: str,
     logfile: PathLike,
@@ -196,3 +209,22 @@ def upload_osquery_logs(
         s3_client=s3_client,
         sqs_client=sqs_client,
     )
+
+
+def upload_auditd_logs(
+    deployment_name: str,
+    logfile: PathLike,
+    delay: int = 0,
+    batch_size: int = 100,
+    s3_client: Optional[S3Client] = None,
+    sqs_client: Optional[SQSClient] = None,
+) -> None:
+    upload_logs(
+        deployment_name=deployment_name,
+        logfile=logfile,
+        generator_options=AuditdGeneratorOptions(),
+        delay=delay,
+        batch_size=batch_size,
+        s3_client=s3_client,
+        sqs_client=sqs_client,
+    )
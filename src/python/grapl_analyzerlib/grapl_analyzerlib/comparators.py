@@ -21,12 +21,13 @@ def escape_dgraph_regexp(input: str) -> str:
 
 
 class Not(object):
-    def __init__(self, value: Union[str, int]):
+    def __init__(self, value: Union[str, int, float]):
         self.value = value
 
 
 StrOrNot = Union[str, Not]
 IntOrNot = Union[int, Not]
+FloatOrNot = Union[float, Not]
 
 
 class Has(object):
@@ -44,7 +45,7 @@ class Has(object):
 
 
 class Eq(object):
-    def __init__(self, predicate: str, value: Union[Not, str, int]):
+    def __init__(self, predicate: str, value: Union[Not, str, int, float]):
         self.predicate = predicate
         self.value = extract_value(value)
         self.negated: bool = isinstance(value, Not)
@@ -305,7 +306,7 @@ def _int_cmps(
     return cmps
 
 
-def extract_value(value: Union[Not, int, str]) -> Union[int, str]:
+def extract_value(value: Union[Not, int, float, str]) -> Union[int, float, str]:
     if isinstance(value, Not):
         return value.value
     else:
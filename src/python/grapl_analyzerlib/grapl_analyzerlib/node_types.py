@@ -8,6 +8,7 @@ class PropPrimitive(Enum):
     Int = 1
     Str = 2
     Bool = 3
+    Float = 4
 
 
 class PropType(object):
@@ -32,6 +33,8 @@ class PropType(object):
             index_str = "@index(int)"
         elif self.primitive is PropPrimitive.Bool:
             index_str = "@index(bool)"
+        elif self.primitive is PropPrimitive.Float:
+            index_str = "@index(float)"
         else:
             raise Exception("Unreachable")
 
@@ -46,6 +49,8 @@ class PropType(object):
             prim_str = "int"
         elif self.primitive is PropPrimitive.Bool:
             prim_str = "bool"
+        elif self.primitive is PropPrimitive.Float:
+            prim_str = "float"
         else:
             raise Exception("Unreachable")
 
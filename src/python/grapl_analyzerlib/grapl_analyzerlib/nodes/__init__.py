@@ -12,6 +12,9 @@ from grapl_analyzerlib.nodes.process_outbound_connection import (
     ProcessOutboundConnectionSchema,
 )
 from grapl_analyzerlib.nodes.risk import RiskSchema
+from grapl_analyzerlib.nodes.registry_key import RegistryKeySchema
+from grapl_analyzerlib.nodes.named_pipe import NamedPipeSchema
+from grapl_analyzerlib.nodes.domain_name import DomainNameSchema
 
 AssetSchema().init_reverse()
 FileSchema().init_reverse()
@@ -23,3 +26,6 @@ ProcessInboundConnectionSchema().init_reverse()
 ProcessOutboundConnectionSchema().init_reverse()
 RiskSchema().init_reverse()
 ProcessSchema().init_reverse()
+RegistryKeySchema().init_reverse()
+NamedPipeSchema().init_reverse()
+DomainNameSchema().init_reverse()
@@ -0,0 +1,242 @@
+from __future__ import annotations
+from typing import Any, TypeVar, List, Set, Dict, Tuple, Optional
+
+from grapl_analyzerlib.node_types import (
+    EdgeT,
+    PropType,
+    PropPrimitive,
+    EdgeRelationship,
+)
+from grapl_analyzerlib.queryable import (
+    with_str_prop,
+    with_int_prop,
+)
+from grapl_analyzerlib.schema import Schema
+from grapl_analyzerlib.nodes.entity import EntityQuery, EntityView, EntitySchema
+from grapl_analyzerlib.comparators import IntOrNot, StrOrNot, OneOrMany
+
+DNQ = TypeVar("DNQ", bound="DomainNameQuery")
+DNV = TypeVar("DNV", bound="DomainNameView")
+
+
+def default_domain_name_properties() -> Dict[str, PropType]:
+    return {
+        "domain_name": PropType(PropPrimitive.Str, False),
+        "first_seen_timestamp": PropType(PropPrimitive.Int, False),
+        "last_seen_timestamp": PropType(PropPrimitive.Int, False),
+    }
+
+
+def default_domain_name_edges() -> Dict[str, Tuple[EdgeT, str]]:
+    from grapl_analyzerlib.nodes.ip_address import IpAddressSchema
+    from grapl_analyzerlib.nodes.process import ProcessSchema
+
+    return {
+        "resolved_ips": (
+            EdgeT(DomainNameSchema, IpAddressSchema, EdgeRelationship.ManyToMany),
+            "resolved_domains",
+        ),
+        "domain_queriers": (
+            EdgeT(DomainNameSchema, ProcessSchema, EdgeRelationship.ManyToMany),
+            "queried_domains",
+        ),
+    }
+
+
+class DomainNameSchema(EntitySchema):
+    def __init__(self):
+        super(DomainNameSchema, self).__init__(
+            default_domain_name_properties(),
+            default_domain_name_edges(),
+            lambda: DomainNameView,
+        )
+
+    @staticmethod
+    def self_type() -> str:
+        return "DomainName"
+
+    @staticmethod
+    def get_display_property() -> str:
+        return "domain_name"
+
+
+class DomainNameQuery(EntityQuery[DNV, DNQ]):
+    @with_str_prop("domain_name")
+    def with_domain_name(
+        self,
+        *,
+        eq: Optional[StrOrNot] = None,
+        contains: Optional[OneOrMany[StrOrNot]] = None,
+        starts_with: Optional[StrOrNot] = None,
+        ends_with: Optional[StrOrNot] = None,
+        regexp: Optional[OneOrMany[StrOrNot]] = None,
+        distance_lt: Optional[Tuple[str, int]] = None,
+    ):
+        pass
+
+    @with_int_prop("first_seen_timestamp")
+    def with_first_seen_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    @with_int_prop("last_seen_timestamp")
+    def with_last_seen_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    def with_resolved_ips(self, *resolved_ips):
+        return self.with_to_neighbor(
+            IpAddressQuery, "resolved_ips", "resolved_domains", resolved_ips
+        )
+
+    def with_domain_queriers(self, *domain_queriers):
+        return self.with_to_neighbor(
+            ProcessQuery, "domain_queriers", "queried_domains", domain_queriers
+        )
+
+    @classmethod
+    def node_schema(cls) -> "Schema":
+        return DomainNameSchema()
+
+
+class DomainNameView(EntityView[DNV, DNQ]):
+    """
+    .. list-table::
+        :header-rows: 1
+
+        * - Predicate
+          - Type
+          - Description
+        * - node_key
+          - string
+          - A unique identifier for this node.
+        * - domain_name
+          - string
+          - The domain name that this node represents.
+        * - first_seen_timestamp
+          - int
+          - Time the domain was first queried (in millis-since-epoch).
+        * - last_seen_timestamp
+          - int
+          - Time the domain was last queried (in millis-since-epoch).
+        * - resolved_ips
+          - List[:doc:`/nodes/ip_address`]
+          - Addresses the domain resolved to.
+        * - domain_queriers
+          - List[:doc:`/nodes/process`]
+          - Processes that looked up the domain.
+    """
+
+    queryable = DomainNameQuery
+
+    def __init__(
+        self,
+        uid: int,
+        node_key: str,
+        graph_client: Any,
+        node_types: Set[str],
+        domain_name: Optional[str] = None,
+        first_seen_timestamp: Optional[int] = None,
+        last_seen_timestamp: Optional[int] = None,
+        resolved_ips: Optional[List["IpAddressView"]] = None,
+        domain_queriers: Optional[List["ProcessView"]] = None,
+        **kwargs,
+    ):
+        super().__init__(uid, node_key, graph_client, node_types, **kwargs)
+        self.node_types = set(node_types)
+
+        self.set_predicate("domain_name", domain_name)
+        self.set_predicate("first_seen_timestamp", first_seen_timestamp)
+        self.set_predicate("last_seen_timestamp", last_seen_timestamp)
+        self.set_predicate("resolved_ips", resolved_ips or [])
+        self.set_predicate("domain_queriers", domain_queriers or [])
+
+    def get_domain_name(self, cached=True):
+        return self.get_str("domain_name", cached=cached)
+
+    def get_first_seen_timestamp(self, cached=True):
+        return self.get_int("first_seen_timestamp", cached=cached)
+
+    def get_last_seen_timestamp(self, cached=True):
+        return self.get_int("last_seen_timestamp", cached=cached)
+
+    def get_resolved_ips(self, *resolved_ips, cached=False):
+        return self.get_neighbor(
+            IpAddressQuery,
+            "resolved_ips",
+            "resolved_domains",
+            resolved_ips,
+            cached=cached,
+        )
+
+    def get_domain_queriers(self, *domain_queriers, cached=False):
+        return self.get_neighbor(
+            ProcessQuery,
+            "domain_queriers",
+            "queried_domains",
+            domain_queriers,
+            cached=cached,
+        )
+
+    @classmethod
+    def node_schema(cls) -> "Schema":
+        return DomainNameSchema()
+
+
+from grapl_analyzerlib.nodes.ip_address import IpAddressQuery
+from grapl_analyzerlib.nodes.process import ProcessQuery, ProcessView
+
+
+class DomainNameExtendsProcessQuery(ProcessQuery):
+    def with_queried_domains(self, *queried_domains):
+        return self.with_to_neighbor(
+            DomainNameQuery, "queried_domains", "domain_queriers", queried_domains
+        )
+
+
+class DomainNameExtendsProcessView(ProcessView):
+    queried_domains = None
+
+    def __init__(
+        self,
+        uid: int,
+        node_key: str,
+        graph_client: Any,
+        node_types: Set[str],
+        queried_domains: Optional[List[DomainNameView]] = None,
+        **kwargs,
+    ):
+        super().__init__(
+            uid=uid,
+            node_key=node_key,
+            graph_client=graph_client,
+            node_types=node_types,
+            **kwargs,
+        )
+        self.set_predicate("queried_domains", queried_domains or [])
+
+    def get_queried_domains(self, *queried_domains, cached=False):
+        return self.get_neighbor(
+            DomainNameQuery,
+            "queried_domains",
+            "domain_queriers",
+            queried_domains,
+            cached=cached,
+        )
+
+
+DomainNameSchema().init_reverse()
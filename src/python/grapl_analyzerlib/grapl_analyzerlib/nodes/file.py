@@ -294,6 +294,11 @@ class FileQuery(EntityQuery[FV, FQ]):
             ProcessQuery, "spawned_from", "bin_file", *spawned_from
         )
 
+    def with_image_loaders(self, *image_loaders: Optional["ProcessQuery"]) -> FileQuery:
+        return self.with_to_neighbor(
+            ProcessQuery, "image_loaders", "loaded_images", image_loaders
+        )
+
     @classmethod
     def node_schema(cls) -> "Schema":
         return FileSchema()
@@ -395,6 +400,7 @@ class FileView(EntityView[FV, FQ]):
         writers: Optional[List["ProcessView"]] = None,
         readers: Optional[List["ProcessView"]] = None,
         deleter: Optional["ProcessView"] = None,
+        image_loaders: Optional[List["ProcessView"]] = None,
         **kwargs,
     ):
         super(FileView, self).__init__(uid, node_key, graph_client, node_types)
@@ -421,6 +427,7 @@ class FileView(EntityView[FV, FQ]):
         self.set_predicate("writers", writers or [])
         self.set_predicate("readers", readers or [])
         self.set_predicate("deleter", deleter or [])
+        self.set_predicate("image_loaders", image_loaders or [])
 
     def get_file_path(
         self,
@@ -539,6 +546,11 @@ class FileView(EntityView[FV, FQ]):
             ProcessQuery, "spawned_from", "bin_file", filters, cached=cached
         )
 
+    def get_image_loaders(self, *filters: "ProcessQuery", cached=True):
+        return self.get_neighbor(
+            ProcessQuery, "image_loaders", "loaded_images", filters, cached=cached
+        )
+
     @classmethod
     def node_schema(cls) -> "Schema":
         return FileSchema()
@@ -567,6 +579,11 @@ class FileExtendsProcessQuery(ProcessQuery):
             FileQuery, "deleted_files", "deleter", deleted_files
         )
 
+    def with_loaded_images(self, *loaded_images: Optional[FileQuery]) -> "ProcessQuery":
+        return self.with_to_neighbor(
+            FileQuery, "loaded_images", "image_loaders", loaded_images
+        )
+
 
 class FileExtendsProcessView(ProcessView):
     bin_file = None
@@ -574,6 +591,7 @@ class FileExtendsProcessView(ProcessView):
     wrote_files = None
     read_files = None
     deleted_files = None
+    loaded_images = None
 
     def __init__(
         self,
@@ -586,6 +604,7 @@ class FileExtendsProcessView(ProcessView):
         wrote_files: Optional[List[FileQuery]] = None,
         read_files: Optional[List[FileQuery]] = None,
         deleted_files: Optional[List[FileQuery]] = None,
+        loaded_images: Optional[List[FileQuery]] = None,
         **kwargs,
     ):
         super().__init__(
@@ -602,6 +621,7 @@ class FileExtendsProcessView(ProcessView):
         self.set_predicate("wrote_files", wrote_files or [])
         self.set_predicate("read_files", read_files or [])
         self.set_predicate("deleted_files", deleted_files or [])
+        self.set_predicate("loaded_images", loaded_images or [])
 
     def get_bin_file(self, *filters, cached=True):
         return self.get_neighbor(
@@ -628,6 +648,11 @@ class FileExtendsProcessView(ProcessView):
             FileQuery, "deleted_files", "deleter", filters, cached=cached
         )
 
+    def get_loaded_images(self, *filters, cached=True):
+        return self.get_neighbor(
+            FileQuery, "loaded_images", "image_loaders", filters, cached=cached
+        )
+
 
 FileSchema().init_reverse()
 
@@ -104,6 +104,13 @@ class IpAddressQuery(EntityQuery[IPV, IPQ]):
             IpConnectionQuery(), "ip_connections", "connecting_ips", ip_connections
         )
 
+    def with_resolved_domains(self, *resolved_domains):
+        from grapl_analyzerlib.nodes.domain_name import DomainNameQuery
+
+        return self.with_to_neighbor(
+            DomainNameQuery, "resolved_domains", "resolved_ips", resolved_domains
+        )
+
     @classmethod
     def node_schema(cls) -> "Schema":
         return IpAddressSchema()
@@ -132,6 +139,9 @@ class IpAddressView(EntityView[IPV, IPQ]):
         * - ip_connections
           - List[:doc:`/nodes/ip_connection`]
           - Connections made from this address.
+        * - resolved_domains
+          - List[:doc:`/nodes/domain_name`]
+          - Domain names that resolved to this address.
     """
 
     queryable = IpAddressQuery
@@ -146,6 +156,7 @@ class IpAddressView(EntityView[IPV, IPQ]):
         last_seen_timestamp: Optional[int] = None,
         ip_address: Optional[str] = None,
         ip_connections: Optional[int] = None,
+        resolved_domains: Optional[List["DomainNameView"]] = None,
         **kwargs,
     ):
         super().__init__(uid, node_key, graph_client, node_types, **kwargs)
@@ -155,6 +166,7 @@ class IpAddressView(EntityView[IPV, IPQ]):
         self.set_predicate("last_seen_timestamp", last_seen_timestamp)
         self.set_predicate("ip_address", ip_address)
         self.set_predicate("ip_connections", ip_connections or [])
+        self.set_predicate("resolved_domains", resolved_domains or [])
 
     def get_first_seen_timestamp(self, cached=True):
         return self.get_int("first_seen_timestamp", cached=cached)
@@ -170,6 +182,17 @@ class IpAddressView(EntityView[IPV, IPQ]):
             IpConnectionQuery, "ip_connections", "connecting_ips", ip_connections
         )
 
+    def get_resolved_domains(self, *resolved_domains, cached=False):
+        from grapl_analyzerlib.nodes.domain_name import DomainNameQuery
+
+        return self.get_neighbor(
+            DomainNameQuery,
+            "resolved_domains",
+            "resolved_ips",
+            resolved_domains,
+            cached=cached,
+        )
+
     @classmethod
     def node_schema(cls) -> "Schema":
         return IpAddressSchema()
@@ -0,0 +1,271 @@
+from __future__ import annotations
+from typing import Any, TypeVar, List, Set, Dict, Tuple, Optional
+
+from grapl_analyzerlib.node_types import (
+    EdgeT,
+    PropType,
+    PropPrimitive,
+    EdgeRelationship,
+)
+from grapl_analyzerlib.queryable import (
+    with_str_prop,
+    with_int_prop,
+)
+from grapl_analyzerlib.schema import Schema
+from grapl_analyzerlib.nodes.entity import EntityQuery, EntityView, EntitySchema
+from grapl_analyzerlib.comparators import IntOrNot, StrOrNot, OneOrMany
+
+NPQ = TypeVar("NPQ", bound="NamedPipeQuery")
+NPV = TypeVar("NPV", bound="NamedPipeView")
+
+
+def default_named_pipe_properties() -> Dict[str, PropType]:
+    return {
+        "asset_id": PropType(PropPrimitive.Str, False),
+        "pipe_name": PropType(PropPrimitive.Str, False),
+        "first_seen_timestamp": PropType(PropPrimitive.Int, False),
+        "last_seen_timestamp": PropType(PropPrimitive.Int, False),
+    }
+
+
+def default_named_pipe_edges() -> Dict[str, Tuple[EdgeT, str]]:
+    from grapl_analyzerlib.nodes.process import ProcessSchema
+
+    return {
+        "pipe_creator": (
+            EdgeT(NamedPipeSchema, ProcessSchema, EdgeRelationship.OneToMany),
+            "created_pipes",
+        ),
+        "pipe_clients": (
+            EdgeT(NamedPipeSchema, ProcessSchema, EdgeRelationship.ManyToMany),
+            "connected_pipes",
+        ),
+    }
+
+
+class NamedPipeSchema(EntitySchema):
+    def __init__(self):
+        super(NamedPipeSchema, self).__init__(
+            default_named_pipe_properties(),
+            default_named_pipe_edges(),
+            lambda: NamedPipeView,
+        )
+
+    @staticmethod
+    def self_type() -> str:
+        return "NamedPipe"
+
+    @staticmethod
+    def get_display_property() -> str:
+        return "pipe_name"
+
+
+class NamedPipeQuery(EntityQuery[NPV, NPQ]):
+    @with_str_prop("asset_id")
+    def with_asset_id(
+        self,
+        *,
+        eq: Optional[StrOrNot] = None,
+        contains: Optional[OneOrMany[StrOrNot]] = None,
+        starts_with: Optional[StrOrNot] = None,
+        ends_with: Optional[StrOrNot] = None,
+        regexp: Optional[OneOrMany[StrOrNot]] = None,
+        distance_lt: Optional[Tuple[str, int]] = None,
+    ):
+        pass
+
+    @with_str_prop("pipe_name")
+    def with_pipe_name(
+        self,
+        *,
+        eq: Optional[StrOrNot] = None,
+        contains: Optional[OneOrMany[StrOrNot]] = None,
+        starts_with: Optional[StrOrNot] = None,
+        ends_with: Optional[StrOrNot] = None,
+        regexp: Optional[OneOrMany[StrOrNot]] = None,
+        distance_lt: Optional[Tuple[str, int]] = None,
+    ):
+        pass
+
+    @with_int_prop("first_seen_timestamp")
+    def with_first_seen_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    @with_int_prop("last_seen_timestamp")
+    def with_last_seen_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    def with_pipe_creator(self, *pipe_creator):
+        return self.with_to_neighbor(
+            ProcessQuery, "pipe_creator", "created_pipes", pipe_creator
+        )
+
+    def with_pipe_clients(self, *pipe_clients):
+        return self.with_to_neighbor(
+            ProcessQuery, "pipe_clients", "connected_pipes", pipe_clients
+        )
+
+    @classmethod
+    def node_schema(cls) -> "Schema":
+        return NamedPipeSchema()
+
+
+class NamedPipeView(EntityView[NPV, NPQ]):
+    """
+    .. list-table::
+        :header-rows: 1
+
+        * - Predicate
+          - Type
+          - Description
+        * - node_key
+          - string
+          - A unique identifier for this node.
+        * - asset_id
+          - string
+          - A unique identifier for the asset the pipe is on.
+        * - pipe_name
+          - string
+          - Name of the pipe, like "\\PSHost.1234".
+        * - first_seen_timestamp
+          - int
+          - Time the pipe was first seen (in millis-since-epoch).
+        * - last_seen_timestamp
+          - int
+          - Time the pipe was last seen (in millis-since-epoch).
+        * - pipe_creator
+          - :doc:`/nodes/process`
+          - The process that created the pipe.
+        * - pipe_clients
+          - List[:doc:`/nodes/process`]
+          - Processes that connected to the pipe.
+    """
+
+    queryable = NamedPipeQuery
+
+    def __init__(
+        self,
+        uid: int,
+        node_key: str,
+        graph_client: Any,
+        node_types: Set[str],
+        asset_id: Optional[str] = None,
+        pipe_name: Optional[str] = None,
+        first_seen_timestamp: Optional[int] = None,
+        last_seen_timestamp: Optional[int] = None,
+        pipe_creator: Optional["ProcessView"] = None,
+        pipe_clients: Optional[List["ProcessView"]] = None,
+        **kwargs,
+    ):
+        super().__init__(uid, node_key, graph_client, node_types, **kwargs)
+        self.node_types = set(node_types)
+
+        self.set_predicate("asset_id", asset_id)
+        self.set_predicate("pipe_name", pipe_name)
+        self.set_predicate("first_seen_timestamp", first_seen_timestamp)
+        self.set_predicate("last_seen_timestamp", last_seen_timestamp)
+        self.set_predicate("pipe_creator", pipe_creator)
+        self.set_predicate("pipe_clients", pipe_clients or [])
+
+    def get_asset_id(self, cached=True):
+        return self.get_str("asset_id", cached=cached)
+
+    def get_pipe_name(self, cached=True):
+        return self.get_str("pipe_name", cached=cached)
+
+    def get_first_seen_timestamp(self, cached=True):
+        return self.get_int("first_seen_timestamp", cached=cached)
+
+    def get_last_seen_timestamp(self, cached=True):
+        return self.get_int("last_seen_timestamp", cached=cached)
+
+    def get_pipe_creator(self, *pipe_creator, cached=False):
+        return self.get_neighbor(
+            ProcessQuery, "pipe_creator", "created_pipes", pipe_creator, cached=cached
+        )
+
+    def get_pipe_clients(self, *pipe_clients, cached=False):
+        return self.get_neighbor(
+            ProcessQuery, "pipe_clients", "connected_pipes", pipe_clients, cached=cached
+        )
+
+    @classmethod
+    def node_schema(cls) -> "Schema":
+        return NamedPipeSchema()
+
+
+from grapl_analyzerlib.nodes.process import ProcessQuery, ProcessView
+
+
+class NamedPipeExtendsProcessQuery(ProcessQuery):
+    def with_created_pipes(self, *created_pipes):
+        return self.with_to_neighbor(
+            NamedPipeQuery, "created_pipes", "pipe_creator", created_pipes
+        )
+
+    def with_connected_pipes(self, *connected_pipes):
+        return self.with_to_neighbor(
+            NamedPipeQuery, "connected_pipes", "pipe_clients", connected_pipes
+        )
+
+
+class NamedPipeExtendsProcessView(ProcessView):
+    created_pipes = None
+    connected_pipes = None
+
+    def __init__(
+        self,
+        uid: int,
+        node_key: str,
+        graph_client: Any,
+        node_types: Set[str],
+        created_pipes: Optional[List[NamedPipeView]] = None,
+        connected_pipes: Optional[List[NamedPipeView]] = None,
+        **kwargs,
+    ):
+        super().__init__(
+            uid=uid,
+            node_key=node_key,
+            graph_client=graph_client,
+            node_types=node_types,
+            **kwargs,
+        )
+        self.set_predicate("created_pipes", created_pipes or [])
+        self.set_predicate("connected_pipes", connected_pipes or [])
+
+    def get_created_pipes(self, *created_pipes, cached=False):
+        return self.get_neighbor(
+            NamedPipeQuery,
+            "created_pipes",
+            "pipe_creator",
+            created_pipes,
+            cached=cached,
+        )
+
+    def get_connected_pipes(self, *connected_pipes, cached=False):
+        return self.get_neighbor(
+            NamedPipeQuery,
+            "connected_pipes",
+            "pipe_clients",
+            connected_pipes,
+            cached=cached,
+        )
+
+
+NamedPipeSchema().init_reverse()
@@ -25,6 +25,7 @@ def default_process_edges() -> Dict[str, Tuple[EdgeT, str]]:
     from grapl_analyzerlib.nodes.process_inbound_connection import (
         ProcessInboundConnectionSchema,
     )
+    from grapl_analyzerlib.nodes.file import FileSchema
 
     return {
         "children": (
@@ -33,6 +34,18 @@ def default_process_edges() -> Dict[str, Tuple[EdgeT, str]]:
             ),  # this seems like its OneToMany?
             "parent",
         ),
+        "loaded_images": (
+            EdgeT(ProcessSchema, FileSchema, EdgeRelationship.ManyToMany),
+            "image_loaders",
+        ),
+        "created_remote_threads": (
+            EdgeT(ProcessSchema, ProcessSchema, EdgeRelationship.ManyToMany),
+            "remote_thread_creators",
+        ),
+        "accessed_processes": (
+            EdgeT(ProcessSchema, ProcessSchema, EdgeRelationship.ManyToMany),
+            "process_accessors",
+        ),
         "created_connections": (
             EdgeT(
                 ProcessSchema,
@@ -162,6 +175,22 @@ class ProcessQuery(EntityQuery[PV, PQ]):
     def with_parent(self: PQ, parent: PQ = None) -> PQ:
         return self
 
+    @with_to_neighbor(None, "created_remote_threads", "remote_thread_creators")
+    def with_created_remote_threads(self: PQ, *created_remote_threads: PQ) -> PQ:
+        return self
+
+    @with_to_neighbor(None, "remote_thread_creators", "created_remote_threads")
+    def with_remote_thread_creators(self: PQ, *remote_thread_creators: PQ) -> PQ:
+        return self
+
+    @with_to_neighbor(None, "accessed_processes", "process_accessors")
+    def with_accessed_processes(self: PQ, *accessed_processes: PQ) -> PQ:
+        return self
+
+    @with_to_neighbor(None, "process_accessors", "accessed_processes")
+    def with_process_accessors(self: PQ, *process_accessors: PQ) -> PQ:
+        return self
+
     @classmethod
     def node_schema(cls) -> Schema:
         return ProcessSchema()
@@ -217,6 +246,33 @@ class ProcessView(EntityView[PV, PQ]):
         * - wrote_files
           - List[:doc:`/nodes/file`]
           - Files written by this process.
+        * - loaded_images
+          - List[:doc:`/nodes/file`]
+          - Images, like DLLs, loaded by this process.
+        * - created_remote_threads
+          - List[:doc:`/nodes/process`]
+          - Processes this process created a thread in.
+        * - accessed_processes
+          - List[:doc:`/nodes/process`]
+          - Processes whose memory this process opened.
+        * - created_registry_keys
+          - List[:doc:`/nodes/registry_key`]
+          - Registry keys created by this process.
+        * - deleted_registry_keys
+          - List[:doc:`/nodes/registry_key`]
+          - Registry keys deleted by this process.
+        * - modified_registry_keys
+          - List[:doc:`/nodes/registry_key`]
+          - Registry keys whose values this process set.
+        * - created_pipes
+          - List[:doc:`/nodes/named_pipe`]
+          - Named pipes created by this process.
+        * - connected_pipes
+          - List[:doc:`/nodes/named_pipe`]
+          - Named pipes this process connected to.
+        * - queried_domains
+          - List[:doc:`/nodes/domain_name`]
+          - Domain names this process looked up.
         * - created_connections
           - List[:doc:`/nodes/process_inbound_connection`]
           - Outbound connections created by this process.
@@ -241,6 +297,10 @@ class ProcessView(EntityView[PV, PQ]):
         arguments: Optional[str] = None,
         children: Optional[List["ProcessView"]] = None,
         parent: Optional["ProcessView"] = None,
+        created_remote_threads: Optional[List["ProcessView"]] = None,
+        remote_thread_creators: Optional[List["ProcessView"]] = None,
+        accessed_processes: Optional[List["ProcessView"]] = None,
+        process_accessors: Optional[List["ProcessView"]] = None,
         **kwargs,
     ):
         super().__init__(uid, node_key, graph_client, node_types, **kwargs)
@@ -253,6 +313,10 @@ class ProcessView(EntityView[PV, PQ]):
         self.set_predicate("arguments", arguments)
         self.set_predicate("children", children or [])
         self.set_predicate("parent", parent)
+        self.set_predicate("created_remote_threads", created_remote_threads or [])
+        self.set_predicate("remote_thread_creators", remote_thread_creators or [])
+        self.set_predicate("accessed_processes", accessed_processes or [])
+        self.set_predicate("process_accessors", process_accessors or [])
 
     def get_image_name(self, cached=True) -> Optional[str]:
         return self.get_str("image_name", cached=cached)
@@ -362,6 +426,50 @@ class ProcessView(EntityView[PV, PQ]):
             self.children = self_node.children
         return self.children
 
+    def get_created_remote_threads(
+        self, *created_remote_threads: ProcessQuery, cached=True
+    ) -> "List[ProcessView]":
+        return self.get_neighbor(
+            ProcessQuery,
+            "created_remote_threads",
+            "remote_thread_creators",
+            created_remote_threads,
+            cached=cached,
+        )
+
+    def get_remote_thread_creators(
+        self, *remote_thread_creators: ProcessQuery, cached=True
+    ) -> "List[ProcessView]":
+        return self.get_neighbor(
+            ProcessQuery,
+            "remote_thread_creators",
+            "created_remote_threads",
+            remote_thread_creators,
+            cached=cached,
+        )
+
+    def get_accessed_processes(
+        self, *accessed_processes: ProcessQuery, cached=True
+    ) -> "List[ProcessView]":
+        return self.get_neighbor(
+            ProcessQuery,
+            "accessed_processes",
+            "process_accessors",
+            accessed_processes,
+            cached=cached,
+        )
+
+    def get_process_accessors(
+        self, *process_accessors: ProcessQuery, cached=True
+    ) -> "List[ProcessView]":
+        return self.get_neighbor(
+            ProcessQuery,
+            "process_accessors",
+            "accessed_processes",
+            process_accessors,
+            cached=cached,
+        )
+
     @classmethod
     def node_schema(cls) -> "Schema":
         return ProcessSchema()
@@ -493,3 +601,27 @@ from grapl_analyzerlib.nodes.asset import (
 
 ProcessQuery = ProcessQuery.extend_self(AssetExtendsProcessQuery)
 ProcessView = ProcessView.extend_self(AssetExtendsProcessView)
+
+from grapl_analyzerlib.nodes.registry_key import (
+    RegistryKeyExtendsProcessQuery,
+    RegistryKeyExtendsProcessView,
+)
+
+ProcessQuery = ProcessQuery.extend_self(RegistryKeyExtendsProcessQuery)
+ProcessView = ProcessView.extend_self(RegistryKeyExtendsProcessView)
+
+from grapl_analyzerlib.nodes.named_pipe import (
+    NamedPipeExtendsProcessQuery,
+    NamedPipeExtendsProcessView,
+)
+
+ProcessQuery = ProcessQuery.extend_self(NamedPipeExtendsProcessQuery)
+ProcessView = ProcessView.extend_self(NamedPipeExtendsProcessView)
+
+from grapl_analyzerlib.nodes.domain_name import (
+    DomainNameExtendsProcessQuery,
+    DomainNameExtendsProcessView,
+)
+
+ProcessQuery = ProcessQuery.extend_self(DomainNameExtendsProcessQuery)
+ProcessView = ProcessView.extend_self(DomainNameExtendsProcessView)
@@ -0,0 +1,380 @@
+from __future__ import annotations
+from typing import Any, TypeVar, List, Set, Dict, Tuple, Optional
+
+from grapl_analyzerlib.node_types import (
+    EdgeT,
+    PropType,
+    PropPrimitive,
+    EdgeRelationship,
+)
+from grapl_analyzerlib.queryable import (
+    with_str_prop,
+    with_int_prop,
+)
+from grapl_analyzerlib.schema import Schema
+from grapl_analyzerlib.nodes.entity import EntityQuery, EntityView, EntitySchema
+from grapl_analyzerlib.comparators import IntOrNot, StrOrNot, OneOrMany
+
+RKQ = TypeVar("RKQ", bound="RegistryKeyQuery")
+RKV = TypeVar("RKV", bound="RegistryKeyView")
+
+
+def default_registry_key_properties() -> Dict[str, PropType]:
+    return {
+        "asset_id": PropType(PropPrimitive.Str, False),
+        "key_path": PropType(PropPrimitive.Str, False),
+        "created_timestamp": PropType(PropPrimitive.Int, False),
+        "deleted_timestamp": PropType(PropPrimitive.Int, False),
+        "last_seen_timestamp": PropType(PropPrimitive.Int, False),
+        "value_data": PropType(PropPrimitive.Str, True),
+    }
+
+
+def default_registry_key_edges() -> Dict[str, Tuple[EdgeT, str]]:
+    from grapl_analyzerlib.nodes.process import ProcessSchema
+
+    return {
+        "registry_key_creator": (
+            EdgeT(RegistryKeySchema, ProcessSchema, EdgeRelationship.OneToMany),
+            "created_registry_keys",
+        ),
+        "registry_key_deleter": (
+            EdgeT(RegistryKeySchema, ProcessSchema, EdgeRelationship.OneToMany),
+            "deleted_registry_keys",
+        ),
+        "registry_key_modifiers": (
+            EdgeT(RegistryKeySchema, ProcessSchema, EdgeRelationship.ManyToMany),
+            "modified_registry_keys",
+        ),
+    }
+
+
+class RegistryKeySchema(EntitySchema):
+    def __init__(self):
+        super(RegistryKeySchema, self).__init__(
+            default_registry_key_properties(),
+            default_registry_key_edges(),
+            lambda: RegistryKeyView,
+        )
+
+    @staticmethod
+    def self_type() -> str:
+        return "RegistryKey"
+
+    @staticmethod
+    def get_display_property() -> str:
+        return "key_path"
+
+
+class RegistryKeyQuery(EntityQuery[RKV, RKQ]):
+    @with_str_prop("asset_id")
+    def with_asset_id(
+        self,
+        *,
+        eq: Optional[StrOrNot] = None,
+        contains: Optional[OneOrMany[StrOrNot]] = None,
+        starts_with: Optional[StrOrNot] = None,
+        ends_with: Optional[StrOrNot] = None,
+        regexp: Optional[OneOrMany[StrOrNot]] = None,
+        distance_lt: Optional[Tuple[str, int]] = None,
+    ):
+        pass
+
+    @with_str_prop("key_path")
+    def with_key_path(
+        self,
+        *,
+        eq: Optional[StrOrNot] = None,
+        contains: Optional[OneOrMany[StrOrNot]] = None,
+        starts_with: Optional[StrOrNot] = None,
+        ends_with: Optional[StrOrNot] = None,
+        regexp: Optional[OneOrMany[StrOrNot]] = None,
+        distance_lt: Optional[Tuple[str, int]] = None,
+    ):
+        pass
+
+    @with_int_prop("created_timestamp")
+    def with_created_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    @with_int_prop("deleted_timestamp")
+    def with_deleted_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    @with_int_prop("last_seen_timestamp")
+    def with_last_seen_timestamp(
+        self,
+        *,
+        eq: Optional[IntOrNot] = None,
+        gt: Optional[IntOrNot] = None,
+        ge: Optional[IntOrNot] = None,
+        lt: Optional[IntOrNot] = None,
+        le: Optional[IntOrNot] = None,
+    ):
+        pass
+
+    @with_str_prop("value_data")
+    def with_value_data(
+        self,
+        *,
+        eq: Optional[StrOrNot] = None,
+        contains: Optional[OneOrMany[StrOrNot]] = None,
+        starts_with: Optional[StrOrNot] = None,
+        ends_with: Optional[StrOrNot] = None,
+        regexp: Optional[OneOrMany[StrOrNot]] = None,
+        distance_lt: Optional[Tuple[str, int]] = None,
+    ):
+        pass
+
+    def with_registry_key_creator(self, *registry_key_creator):
+        return self.with_to_neighbor(
+            ProcessQuery,
+            "registry_key_creator",
+            "created_registry_keys",
+            registry_key_creator,
+        )
+
+    def with_registry_key_deleter(self, *registry_key_deleter):
+        return self.with_to_neighbor(
+            ProcessQuery,
+            "registry_key_deleter",
+            "deleted_registry_keys",
+            registry_key_deleter,
+        )
+
+    def with_registry_key_modifiers(self, *registry_key_modifiers):
+        return self.with_to_neighbor(
+            ProcessQuery,
+            "registry_key_modifiers",
+            "modified_registry_keys",
+            registry_key_modifiers,
+        )
+
+    @classmethod
+    def node_schema(cls) -> "Schema":
+        return RegistryKeySchema()
+
+
+class RegistryKeyView(EntityView[RKV, RKQ]):
+    """
+    .. list-table::
+        :header-rows: 1
+
+        * - Predicate
+          - Type
+          - Description
+        * - node_key
+          - string
+          - A unique identifier for this node.
+        * - asset_id
+          - string
+          - A unique identifier for the asset the key is on.
+        * - key_path
+          - string
+          - Fully qualified path of the key, like "HKLM\\System\\CurrentControlSet".
+        * - created_timestamp
+          - int
+          - Time the key was created (in millis-since-epoch).
+        * - deleted_timestamp
+          - int
+          - Time the key was deleted (in millis-since-epoch).
+        * - last_seen_timestamp
+          - int
+          - Time the key was last seen (in millis-since-epoch).
+        * - value_data
+          - Set[string]
+          - Every value that has been set on the key.
+        * - registry_key_creator
+          - :doc:`/nodes/process`
+          - The process that created the key.
+        * - registry_key_deleter
+          - :doc:`/nodes/process`
+          - The process that deleted the key.
+        * - registry_key_modifiers
+          - List[:doc:`/nodes/process`]
+          - Processes that set a value on the key.
+    """
+
+    queryable = RegistryKeyQuery
+
+    def __init__(
+        self,
+        uid: int,
+        node_key: str,
+        graph_client: Any,
+        node_types: Set[str],
+        asset_id: Optional[str] = None,
+        key_path: Optional[str] = None,
+        created_timestamp: Optional[int] = None,
+        deleted_timestamp: Optional[int] = None,
+        last_seen_timestamp: Optional[int] = None,
+        value_data: Optional[Set[str]] = None,
+        registry_key_creator: Optional["ProcessView"] = None,
+        registry_key_deleter: Optional["ProcessView"] = None,
+        registry_key_modifiers: Optional[List["ProcessView"]] = None,
+        **kwargs,
+    ):
+        super().__init__(uid, node_key, graph_client, node_types, **kwargs)
+        self.node_types = set(node_types)
+
+        self.set_predicate("asset_id", asset_id)
+        self.set_predicate("key_path", key_path)
+        self.set_predicate("created_timestamp", created_timestamp)
+        self.set_predicate("deleted_timestamp", deleted_timestamp)
+        self.set_predicate("last_seen_timestamp", last_seen_timestamp)
+        self.set_predicate("value_data", value_data)
+        self.set_predicate("registry_key_creator", registry_key_creator)
+        self.set_predicate("registry_key_deleter", registry_key_deleter)
+        self.set_predicate("registry_key_modifiers", registry_key_modifiers or [])
+
+    def get_asset_id(self, cached=True):
+        return self.get_str("asset_id", cached=cached)
+
+    def get_key_path(self, cached=True):
+        return self.get_str("key_path", cached=cached)
+
+    def get_created_timestamp(self, cached=True):
+        return self.get_int("created_timestamp", cached=cached)
+
+    def get_deleted_timestamp(self, cached=True):
+        return self.get_int("deleted_timestamp", cached=cached)
+
+    def get_last_seen_timestamp(self, cached=True):
+        return self.get_int("last_seen_timestamp", cached=cached)
+
+    def get_value_data(self, cached=True):
+        return self.get_str_set("value_data", cached=cached)
+
+    def get_registry_key_creator(self, *registry_key_creator, cached=False):
+        return self.get_neighbor(
+            ProcessQuery,
+            "registry_key_creator",
+            "created_registry_keys",
+            registry_key_creator,
+            cached=cached,
+        )
+
+    def get_registry_key_deleter(self, *registry_key_deleter, cached=False):
+        return self.get_neighbor(
+            ProcessQuery,
+            "registry_key_deleter",
+            "deleted_registry_keys",
+            registry_key_deleter,
+            cached=cached,
+        )
+
+    def get_registry_key_modifiers(self, *registry_key_modifiers, cached=False):
+        return self.get_neighbor(
+            ProcessQuery,
+            "registry_key_modifiers",
+            "modified_registry_keys",
+            registry_key_modifiers,
+            cached=cached,
+        )
+
+    @classmethod
+    def node_schema(cls) -> "Schema":
+        return RegistryKeySchema()
+
+
+from grapl_analyzerlib.nodes.process import ProcessQuery, ProcessView
+
+
+class RegistryKeyExtendsProcessQuery(ProcessQuery):
+    def with_created_registry_keys(self, *created_registry_keys):
+        return self.with_to_neighbor(
+            RegistryKeyQuery,
+            "created_registry_keys",
+            "registry_key_creator",
+            created_registry_keys,
+        )
+
+    def with_deleted_registry_keys(self, *deleted_registry_keys):
+        return self.with_to_neighbor(
+            RegistryKeyQuery,
+            "deleted_registry_keys",
+            "registry_key_deleter",
+            deleted_registry_keys,
+        )
+
+    def with_modified_registry_keys(self, *modified_registry_keys):
+        return self.with_to_neighbor(
+            RegistryKeyQuery,
+            "modified_registry_keys",
+            "registry_key_modifiers",
+            modified_registry_keys,
+        )
+
+
+class RegistryKeyExtendsProcessView(ProcessView):
+    created_registry_keys = None
+    deleted_registry_keys = None
+    modified_registry_keys = None
+
+    def __init__(
+        self,
+        uid: int,
+        node_key: str,
+        graph_client: Any,
+        node_types: Set[str],
+        created_registry_keys: Optional[List[RegistryKeyView]] = None,
+        deleted_registry_keys: Optional[List[RegistryKeyView]] = None,
+        modified_registry_keys: Optional[List[RegistryKeyView]] = None,
+        **kwargs,
+    ):
+        super().__init__(
+            uid=uid,
+            node_key=node_key,
+            graph_client=graph_client,
+            node_types=node_types,
+            **kwargs,
+        )
+        self.set_predicate("created_registry_keys", created_registry_keys or [])
+        self.set_predicate("deleted_registry_keys", deleted_registry_keys or [])
+        self.set_predicate("modified_registry_keys", modified_registry_keys or [])
+
+    def get_created_registry_keys(self, *created_registry_keys, cached=False):
+        return self.get_neighbor(
+            RegistryKeyQuery,
+            "created_registry_keys",
+            "registry_key_creator",
+            created_registry_keys,
+            cached=cached,
+        )
+
+    def get_deleted_registry_keys(self, *deleted_registry_keys, cached=False):
+        return self.get_neighbor(
+            RegistryKeyQuery,
+            "deleted_registry_keys",
+            "registry_key_deleter",
+            deleted_registry_keys,
+            cached=cached,
+        )
+
+    def get_modified_registry_keys(self, *modified_registry_keys, cached=False):
+        return self.get_neighbor(
+            RegistryKeyQuery,
+            "modified_registry_keys",
+            "registry_key_modifiers",
+            modified_registry_keys,
+            cached=cached,
+        )
+
+
+RegistryKeySchema().init_reverse()
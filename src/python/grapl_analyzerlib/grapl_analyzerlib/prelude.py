@@ -52,9 +52,24 @@ from grapl_analyzerlib.nodes.network_connection import (
     NetworkConnectionQuery,
     NetworkConnectionSchema,
 )
+from grapl_analyzerlib.nodes.registry_key import (
+    RegistryKeyView,
+    RegistryKeyQuery,
+    RegistryKeySchema,
+)
+from grapl_analyzerlib.nodes.named_pipe import (
+    NamedPipeView,
+    NamedPipeQuery,
+    NamedPipeSchema,
+)
+from grapl_analyzerlib.nodes.domain_name import (
+    DomainNameView,
+    DomainNameQuery,
+    DomainNameSchema,
+)
 
 from grapl_analyzerlib.queryable import Queryable
-from grapl_analyzerlib.viewable import Viewable
+from grapl_analyzerlib.viewable import EdgeFacets, Viewable
 from grapl_analyzerlib.comparators import Not
 
 from grapl_analyzerlib.execution import ExecutionHit
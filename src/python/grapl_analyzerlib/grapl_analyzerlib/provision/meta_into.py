@@ -34,6 +34,8 @@ def meta_into_property(predicate_meta) -> PropType:
         primitive = PropPrimitive.Int
     if type_name == "bool":
         primitive = PropPrimitive.Bool
+    if type_name == "float":
+        primitive = PropPrimitive.Float
 
     assert primitive is not None
     return PropType(primitive, is_set, index=predicate_meta.get("index", []))
@@ -25,6 +25,9 @@ from grapl_analyzerlib.comparators import (
     StrOrNot,
     OneOrMany,
     IntOrNot,
+    FloatOrNot,
+    Eq,
+    Has,
 )
 from grapl_analyzerlib.extendable import Extendable
 from grapl_analyzerlib.grapl_client import GraphClient
@@ -160,6 +163,33 @@ class Queryable(Generic[V, Q], Extendable, abc.ABC):
         )
         return self
 
+    def with_float_property(
+        self,
+        property_name: str,
+        *,
+        eq: Optional["FloatOrNot"] = None,
+    ):
+        if eq is None:
+            self._property_filters[property_name].append([Has(property_name)])
+        else:
+            self._property_filters[property_name].append([Eq(property_name, eq)])
+        return self
+
+    def with_bool_property(
+        self,
+        property_name: str,
+        *,
+        eq: Optional[bool] = None,
+    ):
+        if eq is None:
+            self._property_filters[property_name].append([Has(property_name)])
+        else:
+            # Dgraph's booleans are lowercase
+            self._property_filters[property_name].append(
+                [Eq(property_name, str(eq).lower())]
+            )
+        return self
+
     @classmethod
     @abc.abstractmethod
     def node_schema(cls) -> "Schema":
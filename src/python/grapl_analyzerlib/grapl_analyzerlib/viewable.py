@@ -1,5 +1,6 @@
 from __future__ import annotations
 import abc
+import json
 import logging
 import os
 import sys
@@ -18,6 +19,7 @@ from typing import (
     Iterator,
     TYPE_CHECKING,
 )
+from dataclasses import dataclass
 
 if TYPE_CHECKING:
     from grapl_analyzerlib.queryable import Queryable  # noqa: F401
@@ -37,6 +39,34 @@ T = TypeVar("T")
 OneOrMany = Union[List[T], T]
 
 
+@dataclass(frozen=True)
+class EdgeFacets:
+    """
+    When an edge was first and last seen, in milliseconds since the epoch, and how many times
+    it has been observed. Only edges whose generator records them have facets.
+    """
+
+    first_seen: Optional[int] = None
+    last_seen: Optional[int] = None
+    count: Optional[int] = None
+
+    @classmethod
+    def from_neighbor(
+        cls, edge_name: str, neighbor: Dict[str, Any]
+    ) -> Optional["EdgeFacets"]:
+        """
+        Dgraph returns the facets of an edge on the node it points to, as `<edge_name>|<facet>`
+        """
+        facets = cls(
+            first_seen=neighbor.get(f"{edge_name}|first_seen"),
+            last_seen=neighbor.get(f"{edge_name}|last_seen"),
+            count=neighbor.get(f"{edge_name}|count"),
+        )
+        if facets == cls():
+            return None
+        return facets
+
+
 class Viewable(Generic[V, Q], Extendable, abc.ABC):
     queryable: Type[Q] = None  # pytype: disable=not-supported-yet
 
@@ -78,6 +108,25 @@ class Viewable(Generic[V, Q], Extendable, abc.ABC):
 
         return getattr(self, property_name, None)
 
+    def get_str_set(self, property_name: str, cached=True) -> Optional[Set[str]]:
+        if cached and getattr(self, property_name, None) is not None:
+            return deserialize_str_set(getattr(self, property_name))
+
+        self_node = (
+            self.queryable()
+            .with_node_key(eq=self.node_key)
+            .with_str_property(property_name)
+            .query_first(self.graph_client)
+        )
+
+        if self_node and getattr(self_node, property_name, None) is not None:
+            self.set_predicate(
+                property_name, deserialize_str_set(getattr(self_node, property_name))
+            )
+
+        value = getattr(self, property_name, None)
+        return None if value is None else deserialize_str_set(value)
+
     def get_int(self, property_name: str, cached=True) -> Optional[int]:
         if cached and getattr(self, property_name, None) is not None:
             return getattr(self, property_name, None)
@@ -94,6 +143,38 @@ class Viewable(Generic[V, Q], Extendable, abc.ABC):
 
         return getattr(self, property_name, None)
 
+    def get_float(self, property_name: str, cached=True) -> Optional[float]:
+        if cached and getattr(self, property_name, None) is not None:
+            return getattr(self, property_name, None)
+
+        self_node = (
+            self.queryable()
+            .with_node_key(eq=self.node_key)
+            .with_float_property(property_name)
+            .query_first(self.graph_client)
+        )
+
+        if self_node and getattr(self_node, property_name, None) is not None:
+            self.set_predicate(property_name, getattr(self_node, property_name))
+
+        return getattr(self, property_name, None)
+
+    def get_bool(self, property_name: str, cached=True) -> Optional[bool]:
+        if cached and getattr(self, property_name, None) is not None:
+            return getattr(self, property_name, None)
+
+        self_node = (
+            self.queryable()
+            .with_node_key(eq=self.node_key)
+            .with_bool_property(property_name)
+            .query_first(self.graph_client)
+        )
+
+        if self_node and getattr(self_node, property_name, None) is not None:
+            self.set_predicate(property_name, getattr(self_node, property_name))
+
+        return getattr(self, property_name, None)
+
     def get_neighbor(
         self, default: "Type[Q]", f_edge: str, r_edge: str, filters, cached=True
     ) -> Optional["OneOrMany[V]"]:
@@ -114,6 +195,35 @@ class Viewable(Generic[V, Q], Extendable, abc.ABC):
 
         return getattr(self, f_edge, None)
 
+    def get_edge_facets(
+        self, f_edge: str, neighbor: "Viewable"
+    ) -> Optional[EdgeFacets]:
+        """
+        The facets of the `f_edge` edge from this node to `neighbor`, if the edge has any
+        """
+        query = f"""
+        query q0($a: string, $b: string) {{
+            q0(func: uid($a)) {{
+                {f_edge} @facets(first_seen, last_seen, count) @filter(uid($b)) {{
+                    uid
+                }}
+            }}
+        }}
+        """
+        variables = {"$a": str(self.uid), "$b": str(neighbor.uid)}
+        with self.graph_client.txn_context(read_only=True) as txn:
+            res = json.loads(txn.query(query, variables=variables).json)
+
+        for node in res.get("q0", []):
+            neighbors = node.get(f_edge, [])
+            if isinstance(neighbors, dict):
+                neighbors = [neighbors]
+            for edge_neighbor in neighbors:
+                facets = EdgeFacets.from_neighbor(f_edge, edge_neighbor)
+                if facets:
+                    return facets
+        return None
+
     @classmethod
     def associated_queryable(cls) -> Type[Q]:
         assert cls.queryable, f"{cls.__name__} cls.queryable"
@@ -190,10 +300,25 @@ def deserialize_prop(value, ty: "PropType"):
             return set([str(v) for v in value])
         else:
             return str(value)
+    if ty.primitive is PropPrimitive.Float:
+        if ty.is_set:
+            return set([float(v) for v in value])
+        else:
+            return float(value)
 
     raise NotImplementedError
 
 
+def deserialize_str_set(value) -> Set[str]:
+    """
+    Nodes without a schema don't know a predicate is a set, so it may still be the list Dgraph
+    returned, or a single str if that's what the node was created with
+    """
+    if isinstance(value, str):
+        return {value}
+    return set([str(v) for v in value])
+
+
 EdgeV = TypeVar("EdgeV", bound="Viewable")
 
 
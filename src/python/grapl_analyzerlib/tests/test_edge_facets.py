@@ -0,0 +1,22 @@
+import unittest
+
+from grapl_analyzerlib.viewable import EdgeFacets
+
+
+class TestEdgeFacets(unittest.TestCase):
+    def test_from_neighbor(self) -> None:
+        neighbor = {
+            "uid": "0x2",
+            "loaded_images|first_seen": 1544301484600,
+            "loaded_images|last_seen": 1544301485600,
+            "loaded_images|count": 3,
+        }
+        assert EdgeFacets.from_neighbor("loaded_images", neighbor) == EdgeFacets(
+            first_seen=1544301484600,
+            last_seen=1544301485600,
+            count=3,
+        )
+
+    def test_from_neighbor_without_facets(self) -> None:
+        neighbor = {"uid": "0x2", "children|count": 3}
+        assert EdgeFacets.from_neighbor("loaded_images", neighbor) is None
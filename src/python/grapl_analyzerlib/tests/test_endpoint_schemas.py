@@ -0,0 +1,54 @@
+import unittest
+
+from grapl_analyzerlib.prelude import (
+    DomainNameSchema,
+    FileSchema,
+    IpAddressSchema,
+    NamedPipeSchema,
+    ProcessSchema,
+    RegistryKeySchema,
+)
+from grapl_analyzerlib.node_types import PropPrimitive
+
+
+class TestEndpointSchemas(unittest.TestCase):
+    def test_process_edges(self) -> None:
+        edges = ProcessSchema().get_edges()
+        for (edge_name, reverse_name) in [
+            ("loaded_images", "image_loaders"),
+            ("created_remote_threads", "remote_thread_creators"),
+            ("accessed_processes", "process_accessors"),
+            ("created_registry_keys", "registry_key_creator"),
+            ("deleted_registry_keys", "registry_key_deleter"),
+            ("modified_registry_keys", "registry_key_modifiers"),
+            ("created_pipes", "pipe_creator"),
+            ("connected_pipes", "pipe_clients"),
+            ("queried_domains", "domain_queriers"),
+        ]:
+            assert edges[edge_name][1] == reverse_name
+
+    def test_reverse_edges(self) -> None:
+        assert FileSchema().get_edges()["image_loaders"][1] == "loaded_images"
+        assert IpAddressSchema().get_edges()["resolved_domains"][1] == "resolved_ips"
+
+    def test_dgraph_schema(self) -> None:
+        registry_key_schema = RegistryKeySchema().generate_schema()
+        assert "registry_key_creator: uid ." in registry_key_schema
+        assert "registry_key_modifiers: [uid] ." in registry_key_schema
+        assert "value_data: [string]" in registry_key_schema
+
+        process_schema = ProcessSchema().generate_schema()
+        assert "created_registry_keys: [uid] ." in process_schema
+        assert "loaded_images: [uid] ." in process_schema
+
+        assert "pipe_creator: uid ." in NamedPipeSchema().generate_schema()
+
+    def test_registry_key_value_data_is_a_set(self) -> None:
+        value_data = RegistryKeySchema().get_properties()["value_data"]
+        assert value_data.primitive == PropPrimitive.Str
+        assert value_data.is_set
+
+    def test_self_types(self) -> None:
+        assert RegistryKeySchema().self_type() == "RegistryKey"
+        assert NamedPipeSchema().self_type() == "NamedPipe"
+        assert DomainNameSchema().self_type() == "DomainName"
@@ -0,0 +1,14 @@
+import unittest
+
+from grapl_analyzerlib.viewable import deserialize_str_set
+
+
+class TestDeserializeStrSet(unittest.TestCase):
+    def test_list(self) -> None:
+        assert deserialize_str_set(["-c", "ls", "-c"]) == {"-c", "ls"}
+
+    def test_set(self) -> None:
+        assert deserialize_str_set({"-c", "ls"}) == {"-c", "ls"}
+
+    def test_single_value(self) -> None:
+        assert deserialize_str_set("ls") == {"ls"}
@@ -4,7 +4,11 @@ from os import PathLike
 from pathlib import Path
 
 import click
-from grapl_tests_common.upload_logs import upload_osquery_logs, upload_sysmon_logs
+from grapl_tests_common.upload_logs import (
+    upload_auditd_logs,
+    upload_osquery_logs,
+    upload_sysmon_logs,
+)
 from graplctl import idempotency_checks
 from graplctl.common import State, pass_graplctl_state
 from graplctl.upload.lib import upload_analyzer
@@ -77,3 +81,21 @@ def osquery(graplctl_state: State, logfile: PathLike) -> None:
         deployment_name=graplctl_state.grapl_deployment_name,
         logfile=Path(logfile).resolve(),
     )
+
+
+@upload.command()
+@click.option(
+    "--logfile",
+    type=click.Path(exists=True, file_okay=True, dir_okay=False, resolve_path=True),
+    required=True,
+    help="The log file to upload",
+)
+@pass_graplctl_state
+def auditd(graplctl_state: State, logfile: PathLike) -> None:
+    """Upload an auditd log file to the S3 bucket"""
+    upload_auditd_logs(
+        s3_client=graplctl_state.s3,
+        sqs_client=graplctl_state.sqs,
+        deployment_name=graplctl_state.grapl_deployment_name,
+        logfile=Path(logfile).resolve(),
+    )
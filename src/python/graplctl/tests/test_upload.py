@@ -115,6 +115,33 @@ def test_upload_osquery__calls_s3() -> None:
     assert "Completed uploading 236 chunks" in result.output
 
 
+def test_upload_auditd__calls_s3() -> None:
+    with patch_boto3_session() as mock_session:
+        sample_data_path = "etc/sample_data/auditd.log"
+        result = invoke_with_default_args(
+            ["upload", "auditd", "--logfile", sample_data_path],
+        )
+
+    mock_s3_client = mock_session.client("s3")
+    # Should call to s3 once
+    mock_s3_client.put_object.assert_has_calls(
+        [
+            call(
+                Body=ANY,
+                Bucket="fake-deployment-auditd-log-bucket",
+                Key=ANY,
+            )
+        ]
+    )
+
+    assert result.exit_code == 0
+    assert (
+        "Writing events to fake-deployment with 0 seconds between batches of 100"
+        in result.output
+    )
+    assert "Completed uploading 1 chunks" in result.output
+
+
 def _mock_grapl_is_provisioned(
     mock_session: BotoSessionMock, is_provisioned: bool
 ) -> None:
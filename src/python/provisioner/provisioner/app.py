@@ -9,16 +9,19 @@ from typing import TYPE_CHECKING, Any
 import boto3
 from grapl_analyzerlib.prelude import (
     AssetSchema,
+    DomainNameSchema,
     FileSchema,
     GraphClient,
     IpAddressSchema,
     IpConnectionSchema,
     IpPortSchema,
     LensSchema,
+    NamedPipeSchema,
     NetworkConnectionSchema,
     ProcessInboundConnectionSchema,
     ProcessOutboundConnectionSchema,
     ProcessSchema,
+    RegistryKeySchema,
     RiskSchema,
 )
 from grapl_analyzerlib.provision import provision_common
@@ -58,6 +61,9 @@ def _provision_graph(
         NetworkConnectionSchema(),
         ProcessInboundConnectionSchema(),
         ProcessOutboundConnectionSchema(),
+        RegistryKeySchema(),
+        NamedPipeSchema(),
+        DomainNameSchema(),
         RiskSchema(),
         LensSchema(),
     ]
@@ -5,7 +5,13 @@ use serde::{
     Serialize,
 };
 use serde_json::json;
-use sqs_executor::completion_event_serializer::CompletionEventSerializer;
+use sqs_executor::{
+    completion_event_serializer::CompletionEventSerializer,
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalyzerDispatchEvent {
@@ -17,6 +23,14 @@ impl AnalyzerDispatchEvent {
     pub fn new(key: String, subgraph: MergedGraph) -> Self {
         Self { key, subgraph }
     }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn subgraph(&self) -> &MergedGraph {
+        &self.subgraph
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -29,6 +43,12 @@ pub enum DispatchEventEncoderError {
     ProtoEncodeError(#[from] prost::EncodeError),
 }
 
+impl CheckedError for DispatchEventEncoderError {
+    fn error_type(&self) -> Recoverable {
+        Recoverable::Persistent
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct AnalyzerDispatchSerializer {
     proto: Vec<u8>,
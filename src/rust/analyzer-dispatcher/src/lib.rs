@@ -0,0 +1,200 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use failure::{
+    bail,
+    format_err,
+    Error,
+};
+use grapl_graph_descriptions::graph_description::*;
+use log::{
+    info,
+    warn,
+};
+use rusoto_s3::{
+    ListObjectsRequest,
+    S3,
+};
+use sqs_executor::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_handler::{
+        CompletedEvents,
+        EventHandler,
+    },
+};
+
+use crate::dispatch_event::AnalyzerDispatchEvent;
+
+pub mod dispatch_event;
+
+/// Lists the analyzers that every merged subgraph is dispatched to
+#[async_trait]
+pub trait AnalyzerLister: Send + Sync {
+    async fn list_analyzers(&self) -> Result<Vec<Result<String, Error>>, Error>;
+}
+
+/// Lists the analyzers stored in the analyzers bucket
+#[derive(Debug)]
+pub struct S3AnalyzerLister<S>
+where
+    S: S3 + Send + Sync + 'static,
+{
+    s3_client: Arc<S>,
+    bucket: String,
+}
+
+impl<S> S3AnalyzerLister<S>
+where
+    S: S3 + Send + Sync + 'static,
+{
+    pub fn new(s3_client: Arc<S>, bucket: impl Into<String>) -> Self {
+        Self {
+            s3_client,
+            bucket: bucket.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> AnalyzerLister for S3AnalyzerLister<S>
+where
+    S: S3 + Send + Sync + 'static,
+{
+    async fn list_analyzers(&self) -> Result<Vec<Result<String, Error>>, Error> {
+        info!("Retrieving S3 keys");
+        get_s3_keys(self.s3_client.as_ref(), &self.bucket)
+            .await
+            .map(|keys| keys.into_iter().collect())
+            .map_err(|e| format_err!("Failed to list bucket: {} with {:?}", self.bucket, e))
+    }
+}
+
+#[derive(Debug)]
+pub struct AnalyzerDispatcher<L>
+where
+    L: AnalyzerLister + 'static,
+{
+    analyzer_lister: Arc<L>,
+}
+
+impl<L> AnalyzerDispatcher<L>
+where
+    L: AnalyzerLister + 'static,
+{
+    pub fn new(analyzer_lister: L) -> Self {
+        Self {
+            analyzer_lister: Arc::new(analyzer_lister),
+        }
+    }
+}
+
+impl<L> Clone for AnalyzerDispatcher<L>
+where
+    L: AnalyzerLister + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            analyzer_lister: self.analyzer_lister.clone(),
+        }
+    }
+}
+
+async fn get_s3_keys(
+    s3_client: &impl S3,
+    bucket: impl Into<String>,
+) -> Result<impl IntoIterator<Item = Result<String, Error>>, Error> {
+    let bucket = bucket.into();
+
+    let list_res = tokio::time::timeout(
+        Duration::from_secs(2),
+        s3_client.list_objects(ListObjectsRequest {
+            bucket,
+            ..Default::default()
+        }),
+    )
+    .await??;
+
+    let contents = match list_res.contents {
+        Some(contents) => contents,
+        None => {
+            warn!("List response returned nothing");
+            Vec::new()
+        }
+    };
+
+    Ok(contents.into_iter().map(|object| match object.key {
+        Some(key) => Ok(key),
+        None => bail!("S3Object is missing key"),
+    }))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AnalyzerDispatcherError {
+    #[error("Unexpected")]
+    Unexpected(String),
+}
+
+impl CheckedError for AnalyzerDispatcherError {
+    fn error_type(&self) -> Recoverable {
+        Recoverable::Transient
+    }
+}
+
+#[async_trait]
+impl<L> EventHandler for AnalyzerDispatcher<L>
+where
+    L: AnalyzerLister + 'static,
+{
+    type InputEvent = MergedGraph;
+    type OutputEvent = Vec<AnalyzerDispatchEvent>;
+    type Error = AnalyzerDispatcherError;
+
+    async fn handle_event(
+        &mut self,
+        subgraph: Self::InputEvent,
+        _completed: &mut CompletedEvents,
+    ) -> Result<Self::OutputEvent, Result<(Self::OutputEvent, Self::Error), Self::Error>> {
+        if subgraph.is_empty() {
+            warn!("Attempted to handle empty subgraph");
+            return Ok(vec![]);
+        }
+
+        let keys = match self.analyzer_lister.list_analyzers().await {
+            Ok(keys) => keys,
+            Err(e) => {
+                return Err(Err(AnalyzerDispatcherError::Unexpected(e.to_string())));
+            }
+        };
+
+        let mut dispatch_events = Vec::new();
+
+        let mut failed = None;
+        for key in keys {
+            let key = match key {
+                Ok(key) => key,
+                Err(e) => {
+                    warn!("Failed to retrieve key with {:?}", e);
+                    failed = Some(e);
+                    continue;
+                }
+            };
+
+            dispatch_events.push(AnalyzerDispatchEvent::new(key, subgraph.clone()));
+        }
+
+        if let Some(e) = failed {
+            Err(Ok((
+                dispatch_events,
+                AnalyzerDispatcherError::Unexpected(e.to_string()),
+            )))
+        } else {
+            Ok(dispatch_events)
+        }
+    }
+}
@@ -11,9 +11,12 @@ use syn::{
     Data,
     Field,
     Fields,
+    GenericArgument,
     Ident,
     Meta,
     NestedMeta,
+    Path,
+    PathArguments,
     Type,
 };
 
@@ -23,13 +26,16 @@ const TERMINATE_TIME: &str = "terminate_time";
 const IMMUTABLE: &str = "immutable";
 const INCREMENT: &str = "increment";
 const DECREMENT: &str = "decrement";
+const UNION: &str = "union";
+const STATIC_ID: &str = "static_id";
+const PSEUDO_KEY: &str = "pseudo_key";
 
 fn name_and_ty(field: &Field) -> (&Ident, &Type, String) {
     let mut resolution = None;
     for attr in &field.attrs {
         on_grapl_attrs(&attr, |attr| {
             match attr {
-                IMMUTABLE | INCREMENT | DECREMENT => resolution = Some(attr.to_string()),
+                IMMUTABLE | INCREMENT | DECREMENT | UNION => resolution = Some(attr.to_string()),
                 _ => (),
             };
         });
@@ -110,11 +116,19 @@ pub fn derive_node_description(input: TokenStream) -> TokenStream {
 
         impl #node_name {
             pub fn new(strategy: grapl_graph_descriptions::graph_description::IdStrategy) -> Self {
+                Self::new_with_strategies(vec![strategy])
+            }
+
+            /// The node-identifier tries each strategy in order, using the first one that
+            /// the node has all of the properties for
+            pub fn new_with_strategies(
+                strategies: Vec<grapl_graph_descriptions::graph_description::IdStrategy>
+            ) -> Self {
                 let mut properties = std::collections::HashMap::with_capacity(1);
 
                 let dynamic_node = grapl_graph_descriptions::graph_description::NodeDescription {
                     node_type: #struct_name_string .to_owned(),
-                    id_strategy: vec![strategy],
+                    id_strategy: strategies,
                     node_key: uuid::Uuid::new_v4().to_string(),
                     properties,
                 };
@@ -175,7 +189,7 @@ pub fn derive_grapl_static(input: TokenStream) -> TokenStream {
     fields.iter().for_each(|field| {
         for attr in &field.attrs {
             on_grapl_attrs(attr, |meta_attr| {
-                if meta_attr == "static_id" {
+                if meta_attr == STATIC_ID {
                     let f = field
                         .ident
                         .as_ref()
@@ -193,6 +207,18 @@ pub fn derive_grapl_static(input: TokenStream) -> TokenStream {
 
     let node_name_str = format!("{}Node", struct_name);
     let node_name = syn::Ident::new(&node_name_str, struct_name.span());
+
+    // When the node also derives GraplSessionId, that derive provides the identity strategies
+    let identity_strategy = if has_grapl_attr(fields.iter(), PSEUDO_KEY) {
+        quote!()
+    } else {
+        quote!(
+            pub fn identity_strategy() -> IdStrategy {
+                return #node_name :: static_strategy()
+            }
+        )
+    };
+
     // Add node name to id
     let q = quote!(
 
@@ -206,9 +232,7 @@ pub fn derive_grapl_static(input: TokenStream) -> TokenStream {
                 }.into()
             }
 
-            pub fn identity_strategy() -> IdStrategy {
-                return #node_name :: static_strategy()
-            }
+            #identity_strategy
         }
     );
 
@@ -242,10 +266,10 @@ pub fn derive_grapl_session(input: TokenStream) -> TokenStream {
     }
 
     let mut id_fields = quote!();
-    for field in fields {
+    for field in fields.iter() {
         for attr in &field.attrs {
             on_grapl_attrs(attr, |meta_attr| {
-                if meta_attr == "pseudo_key" {
+                if meta_attr == PSEUDO_KEY {
                     let f = field
                         .ident
                         .as_ref()
@@ -264,6 +288,26 @@ pub fn derive_grapl_session(input: TokenStream) -> TokenStream {
 
     let node_name_str = format!("{}Node", struct_name);
     let node_name = syn::Ident::new(&node_name_str, struct_name.span());
+
+    // A node with `static_id` fields also derives GraplStaticId. The static identity is
+    // preferred, falling back to the session when the static properties are missing.
+    let identity_strategy = if has_grapl_attr(fields.iter(), STATIC_ID) {
+        quote!(
+            pub fn identity_strategies() -> Vec<IdStrategy> {
+                vec![
+                    #node_name :: static_strategy(),
+                    #node_name :: session_strategy(),
+                ]
+            }
+        )
+    } else {
+        quote!(
+            pub fn identity_strategy() -> IdStrategy {
+                return #node_name :: session_strategy()
+            }
+        )
+    };
+
     // Add node name to id
     let q = quote!(
         impl #node_name {
@@ -279,9 +323,7 @@ pub fn derive_grapl_session(input: TokenStream) -> TokenStream {
                 }.into()
             }
 
-            pub fn identity_strategy() -> IdStrategy {
-                return #node_name :: session_strategy()
-            }
+            #identity_strategy
         }
     );
 
@@ -311,6 +353,16 @@ fn on_grapl_attrs(attr: &Attribute, mut on: impl FnMut(&str)) {
     }
 }
 
+fn has_grapl_attr<'a>(mut fields: impl Iterator<Item = &'a Field>, meta_attr_match: &str) -> bool {
+    fields.any(|field| {
+        let mut matched = false;
+        for attr in &field.attrs {
+            on_grapl_attrs(attr, |meta_attr| matched |= meta_attr == meta_attr_match);
+        }
+        matched
+    })
+}
+
 fn assert_meta_attr_combo(field: &Field, meta_attr_match_a: &str, meta_attr_match_b: &str) {
     let mut a_matched = false;
     let mut b_matched = false;
@@ -333,55 +385,111 @@ fn assert_meta_attr_combo(field: &Field, meta_attr_match_a: &str, meta_attr_matc
     }
 }
 
+// janky way to get String="fully::qualified::path::Type" given a Path
+fn path_name(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|x| x.ident.to_string())
+        .collect::<Vec<String>>()
+        .join("::")
+}
+
+/// The name of `T` in a path ending in `Container<T>`
+fn element_type_name(path: &Path) -> Option<String> {
+    match &path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(Type::Path(element_type)) => Some(path_name(&element_type.path)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn resolvable_type_from(
     property_type: &Type,
     resolution_name: &str,
-) -> Option<(syn::Type, syn::Ident)> {
+) -> Result<Option<(syn::Type, syn::Ident)>, syn::Error> {
     let (return_type, method_ident): (syn::Type, syn::Ident) = match property_type {
-        // janky way to get String="fully::qualified::path::Type" given a TypePath
         Type::Path(typepath) => {
-            let typepath = typepath
-                .path
-                .segments
-                .iter()
-                .map(|x| x.ident.to_string())
-                .collect::<Vec<String>>()
-                .join("::");
-            match (typepath.as_ref(), resolution_name) {
+            let element_type = element_type_name(&typepath.path);
+            let typepath = path_name(&typepath.path);
+            match (typepath.as_ref(), element_type.as_deref(), resolution_name) {
                 /* underlying struct field type    maps to this type   via this method on NodeProperty */
-                ("String", IMMUTABLE) => (
+                ("String", None, IMMUTABLE) => (
                     parse_quote!(grapl_graph_descriptions::ImmutableStrProp),
                     parse_quote!(as_immutable_str),
                 ),
-                ("std::string::String", IMMUTABLE) => (
+                ("std::string::String", None, IMMUTABLE) => (
                     parse_quote!(grapl_graph_descriptions::ImmutableStrProp),
                     parse_quote!(as_immutable_str),
                 ),
-                ("u64", IMMUTABLE) => (
+                ("u64", None, IMMUTABLE) => (
                     parse_quote!(grapl_graph_descriptions::ImmutableUintProp),
                     parse_quote!(as_immutable_uint),
                 ),
-                ("u64", INCREMENT) => (
+                ("u64", None, INCREMENT) => (
                     parse_quote!(grapl_graph_descriptions::IncrementOnlyUintProp),
                     parse_quote!(as_increment_only_uint),
                 ),
-                ("u64", DECREMENT) => (
+                ("u64", None, DECREMENT) => (
                     parse_quote!(grapl_graph_descriptions::DecrementOnlyUintProp),
                     parse_quote!(as_decrement_only_uint),
                 ),
-                ("i64", IMMUTABLE) => (
+                ("i64", None, IMMUTABLE) => (
                     parse_quote!(grapl_graph_descriptions::ImmutableIntProp),
                     parse_quote!(as_immutable_int),
                 ),
-                ("i64", INCREMENT) => (
+                ("i64", None, INCREMENT) => (
                     parse_quote!(grapl_graph_descriptions::IncrementOnlyIntProp),
                     parse_quote!(as_increment_only_int),
                 ),
-                ("i64", DECREMENT) => (
+                ("i64", None, DECREMENT) => (
                     parse_quote!(grapl_graph_descriptions::DecrementOnlyIntProp),
                     parse_quote!(as_decrement_only_int),
                 ),
-                _ => return None,
+                ("bool", None, IMMUTABLE) => (
+                    parse_quote!(grapl_graph_descriptions::ImmutableBoolProp),
+                    parse_quote!(as_immutable_bool),
+                ),
+                ("f64", None, INCREMENT) => (
+                    parse_quote!(grapl_graph_descriptions::IncrementOnlyFloatProp),
+                    parse_quote!(as_increment_only_float),
+                ),
+                ("f64", None, DECREMENT) => (
+                    parse_quote!(grapl_graph_descriptions::DecrementOnlyFloatProp),
+                    parse_quote!(as_decrement_only_float),
+                ),
+                ("SystemTime", None, IMMUTABLE) | ("std::time::SystemTime", None, IMMUTABLE) => (
+                    parse_quote!(grapl_graph_descriptions::ImmutableTimestampProp),
+                    parse_quote!(as_immutable_timestamp),
+                ),
+                ("SystemTime", None, INCREMENT) | ("std::time::SystemTime", None, INCREMENT) => (
+                    parse_quote!(grapl_graph_descriptions::IncrementOnlyTimestampProp),
+                    parse_quote!(as_increment_only_timestamp),
+                ),
+                ("SystemTime", None, DECREMENT) | ("std::time::SystemTime", None, DECREMENT) => (
+                    parse_quote!(grapl_graph_descriptions::DecrementOnlyTimestampProp),
+                    parse_quote!(as_decrement_only_timestamp),
+                ),
+                ("Vec", Some("u8"), IMMUTABLE) | ("std::vec::Vec", Some("u8"), IMMUTABLE) => (
+                    parse_quote!(grapl_graph_descriptions::ImmutableBytesProp),
+                    parse_quote!(as_immutable_bytes),
+                ),
+                ("Vec", Some("String"), UNION)
+                | ("Vec", Some("std::string::String"), UNION)
+                | ("std::vec::Vec", Some("String"), UNION)
+                | ("std::vec::Vec", Some("std::string::String"), UNION) => (
+                    parse_quote!(grapl_graph_descriptions::StrSetProp),
+                    parse_quote!(as_str_set),
+                ),
+                ("Vec", _, _) | ("std::vec::Vec", _, _) => {
+                    return Err(syn::Error::new(
+                        property_type.span(),
+                        "only Vec<u8> with #[grapl(immutable)] and Vec<String> with \
+                         #[grapl(union)] are supported",
+                    ))
+                }
+                _ => return Ok(None),
             }
         }
         // If you're seeing this panic, then a field on the struct you're deriving
@@ -391,7 +499,7 @@ fn resolvable_type_from(
         _ => panic!("Tried to dynamically construct getter for unrecognized type!"),
     };
 
-    Some((return_type, method_ident))
+    Ok(Some((return_type, method_ident)))
 }
 
 fn identity_prop_setter(field: &Field, property_name: &Ident) -> TS2 {
@@ -424,14 +532,20 @@ fn identity_prop_setter(field: &Field, property_name: &Ident) -> TS2 {
         _ => return quote!(),
     };
     quote!(
-        let mut self_strategy = mut_self.id_strategy[0].strategy.as_mut().unwrap();
-        match self_strategy {
-            grapl_graph_descriptions::graph_description::id_strategy::Strategy::Session(
-                grapl_graph_descriptions::graph_description::Session{ref mut #ident, ..}
-            ) => {
-                * #ident = #property_name.as_inner();
+        let mut set_timestamp = false;
+        for self_strategy in mut_self.id_strategy.iter_mut() {
+            match self_strategy.strategy.as_mut() {
+                Some(grapl_graph_descriptions::graph_description::id_strategy::Strategy::Session(
+                    grapl_graph_descriptions::graph_description::Session{ref mut #ident, ..}
+                )) => {
+                    * #ident = #property_name.as_inner();
+                    set_timestamp = true;
+                }
+                _ => (),
             }
-            s => panic!("Can not set timestamps on non-Session strategy {:?}", s)
+        }
+        if !set_timestamp {
+            panic!("Can not set timestamps on non-Session strategies {:?}", mut_self.id_strategy)
         }
     )
 }
@@ -453,8 +567,9 @@ fn property_methods(field: &Field) -> TS2 {
     let mut implementation: TS2 = quote!();
 
     let (return_type, method_ident) = match resolvable_type_from(&property_type, &resolution_name) {
-        Some(property_type) => property_type,
-        None => return implementation,
+        Ok(Some(property_type)) => property_type,
+        Ok(None) => return implementation,
+        Err(e) => return e.to_compile_error(),
     };
 
     let with_method_implementation = quote!(
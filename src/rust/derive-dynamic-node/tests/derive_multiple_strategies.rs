@@ -0,0 +1,65 @@
+use derive_dynamic_node::{
+    GraplSessionId,
+    GraplStaticId,
+    NodeDescription,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+#[derive(NodeDescription, GraplStaticId, GraplSessionId)]
+pub struct GuidProcess {
+    #[grapl(create_time, immutable)]
+    pub create_time: u64,
+    #[grapl(last_seen_time, increment)]
+    pub seen_at: u64,
+    #[grapl(terminate_time, immutable)]
+    pub terminate_time: u64,
+    #[grapl(pseudo_key, immutable)]
+    pub asset_id: String,
+    #[grapl(pseudo_key, immutable)]
+    pub process_id: u64,
+    #[grapl(static_id, immutable)]
+    pub process_guid: String,
+}
+
+impl IGuidProcessNode for GuidProcessNode {
+    fn get_mut_dynamic_node(&mut self) -> &mut NodeDescription {
+        &mut self.dynamic_node
+    }
+
+    fn get_dynamic_node(&self) -> &NodeDescription {
+        &self.dynamic_node
+    }
+}
+
+#[test]
+fn test_multiple_strategies() {
+    let mut guid_proc =
+        GuidProcessNode::new_with_strategies(GuidProcessNode::identity_strategies());
+
+    guid_proc.with_create_time(1u64);
+    guid_proc.with_process_guid("{5ad5b2c8-c0b9-5e5d-0000-0010e1d50a00}");
+
+    let strategies: Vec<_> = guid_proc
+        .get_dynamic_node()
+        .id_strategy
+        .iter()
+        .map(|strategy| strategy.strategy.as_ref().unwrap())
+        .collect();
+
+    match strategies.as_slice() {
+        [id_strategy::Strategy::Static(static_strategy), id_strategy::Strategy::Session(session_strategy)] =>
+        {
+            assert_eq!(
+                static_strategy.primary_key_properties,
+                vec!["process_guid".to_string()]
+            );
+            assert_eq!(
+                session_strategy.primary_key_properties,
+                vec!["asset_id".to_string(), "process_id".to_string()]
+            );
+            // Timestamps are set on the session strategy, regardless of its position
+            assert_eq!(session_strategy.create_time, 1u64);
+        }
+        strategies => panic!("Expected a static and a session strategy {:?}", strategies),
+    }
+}
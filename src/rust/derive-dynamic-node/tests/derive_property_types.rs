@@ -0,0 +1,93 @@
+use std::time::{
+    Duration,
+    UNIX_EPOCH,
+};
+
+use derive_dynamic_node::{
+    GraplStaticId,
+    NodeDescription,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+#[derive(NodeDescription, GraplStaticId)]
+pub struct Binary {
+    #[grapl(static_id, immutable)]
+    pub sha256: String,
+    #[grapl(immutable)]
+    pub is_signed: bool,
+    #[grapl(increment)]
+    pub max_entropy: f64,
+    #[grapl(decrement)]
+    pub min_entropy: f64,
+    #[grapl(immutable)]
+    pub header: Vec<u8>,
+    #[grapl(union)]
+    pub section_names: Vec<String>,
+    #[grapl(immutable)]
+    pub compiled_at: std::time::SystemTime,
+    #[grapl(decrement)]
+    pub first_seen: std::time::SystemTime,
+    #[grapl(increment)]
+    pub last_seen: std::time::SystemTime,
+}
+
+impl IBinaryNode for BinaryNode {
+    fn get_mut_dynamic_node(&mut self) -> &mut NodeDescription {
+        &mut self.dynamic_node
+    }
+
+    fn get_dynamic_node(&self) -> &NodeDescription {
+        &self.dynamic_node
+    }
+}
+
+#[test]
+fn test_property_types() {
+    let mut binary = BinaryNode::new(BinaryNode::static_strategy());
+
+    binary
+        .with_sha256("e3b0c442")
+        .with_is_signed(true)
+        .with_max_entropy(7.5)
+        .with_min_entropy(0.5)
+        .with_header(&b"MZ"[..])
+        .with_section_names(vec![".text", ".data", ".text"])
+        .with_compiled_at(UNIX_EPOCH + Duration::from_millis(1_000))
+        .with_first_seen(2_000)
+        .with_last_seen(3_000);
+
+    assert_eq!(
+        binary.get_is_signed(),
+        Some(ImmutableBoolProp { prop: true })
+    );
+    assert_eq!(
+        binary.get_max_entropy(),
+        Some(IncrementOnlyFloatProp { prop: 7.5 })
+    );
+    assert_eq!(
+        binary.get_min_entropy(),
+        Some(DecrementOnlyFloatProp { prop: 0.5 })
+    );
+    assert_eq!(
+        binary.get_header(),
+        Some(ImmutableBytesProp {
+            prop: b"MZ".to_vec()
+        })
+    );
+    assert_eq!(
+        binary.get_section_names().unwrap().as_inner(),
+        [".data".to_string(), ".text".to_string()]
+    );
+    assert_eq!(
+        binary.get_compiled_at(),
+        Some(ImmutableTimestampProp { prop: 1_000 })
+    );
+    assert_eq!(
+        binary.get_first_seen(),
+        Some(DecrementOnlyTimestampProp { prop: 2_000 })
+    );
+    assert_eq!(
+        binary.get_last_seen(),
+        Some(IncrementOnlyTimestampProp { prop: 3_000 })
+    );
+}
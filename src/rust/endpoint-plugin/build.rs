@@ -0,0 +1,62 @@
+use std::{
+    fmt::Write,
+    path::Path,
+};
+
+use graphql_parser::schema::parse_schema;
+use grapl_graph_descriptions::schema::PropertyType;
+use grapl_graphql_codegen::node_type::parse_into_node_types;
+
+const ENDPOINT_GRAPHQL: &str = "schemas/endpoint.graphql";
+
+/// Generates the `GraphSchema` builder expression for the node types of `ENDPOINT_GRAPHQL`, so
+/// `endpoint_schema()` can't drift from the GraphQL
+fn main() {
+    println!("cargo:rerun-if-changed={}", ENDPOINT_GRAPHQL);
+
+    let graphql = std::fs::read_to_string(ENDPOINT_GRAPHQL)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", ENDPOINT_GRAPHQL, e));
+    let document = parse_schema::<&str>(&graphql)
+        .unwrap_or_else(|e| panic!("failed to parse {}: {}", ENDPOINT_GRAPHQL, e));
+    let node_types = parse_into_node_types(&document)
+        .unwrap_or_else(|e| panic!("invalid node types in {}: {}", ENDPOINT_GRAPHQL, e));
+
+    let mut schema = String::from("GraphSchema::new()\n");
+    for node_type in node_types {
+        writeln!(schema, "    .with_node_type({:?})", node_type.type_name).unwrap();
+        for predicate in &node_type.predicates {
+            let property_type =
+                PropertyType::of_predicate(predicate.predicate_type, predicate.conflict_resolution)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "{}.{} has no property type for {:?} with {:?}",
+                            node_type.type_name,
+                            predicate.predicate_name,
+                            predicate.predicate_type,
+                            predicate.conflict_resolution,
+                        )
+                    });
+            writeln!(
+                schema,
+                "    .with_property({:?}, {:?}, PropertyType::{:?})",
+                node_type.type_name, predicate.predicate_name, property_type,
+            )
+            .unwrap();
+        }
+        for edge in &node_type.edges {
+            writeln!(
+                schema,
+                "    .with_edge({:?}, {:?}, {:?}, {:?})",
+                edge.source_type_name,
+                edge.edge_name,
+                edge.target_type_name,
+                edge.reverse_edge_name,
+            )
+            .unwrap();
+        }
+    }
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    std::fs::write(Path::new(&out_dir).join("endpoint_schema.rs"), schema)
+        .expect("failed to write endpoint_schema.rs");
+}
@@ -0,0 +1,29 @@
+use derive_dynamic_node::{
+    GraplStaticId,
+    NodeDescription,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+#[derive(NodeDescription, GraplStaticId)]
+pub struct KernelModule {
+    #[grapl(static_id, immutable)]
+    asset_id: String,
+    #[grapl(static_id, immutable)]
+    module_name: String,
+    #[grapl(immutable)]
+    module_size: u64,
+    #[grapl(decrement)]
+    first_seen_timestamp: u64,
+    #[grapl(increment)]
+    last_seen_timestamp: u64,
+}
+
+impl IKernelModuleNode for KernelModuleNode {
+    fn get_mut_dynamic_node(&mut self) -> &mut NodeDescription {
+        &mut self.dynamic_node
+    }
+
+    fn get_dynamic_node(&self) -> &NodeDescription {
+        &self.dynamic_node
+    }
+}
@@ -1,13 +1,20 @@
 pub mod asset;
+pub mod domain_name;
 pub mod error;
 pub mod file;
 pub mod ip_address;
 pub mod ip_connection;
 pub mod ip_port;
+pub mod kernel_module;
+pub mod named_pipe;
 pub mod network_connection;
 pub mod process;
 pub mod process_inbound_connection;
 pub mod process_outbound_connection;
+pub mod registry_key;
+pub mod schema;
+pub mod startup_item;
+pub mod user;
 
 pub use crate::{
     asset::{
@@ -15,6 +22,11 @@ pub use crate::{
         AssetNode,
         IAssetNode,
     },
+    domain_name::{
+        DomainName,
+        DomainNameNode,
+        IDomainNameNode,
+    },
     error::Error,
     file::{
         File,
@@ -36,6 +48,16 @@ pub use crate::{
         IpPort,
         IpPortNode,
     },
+    kernel_module::{
+        IKernelModuleNode,
+        KernelModule,
+        KernelModuleNode,
+    },
+    named_pipe::{
+        INamedPipeNode,
+        NamedPipe,
+        NamedPipeNode,
+    },
     network_connection::{
         INetworkConnectionNode,
         NetworkConnection,
@@ -56,4 +78,23 @@ pub use crate::{
         ProcessOutboundConnection,
         ProcessOutboundConnectionNode,
     },
+    registry_key::{
+        IRegistryKeyNode,
+        RegistryKey,
+        RegistryKeyNode,
+    },
+    schema::{
+        endpoint_schema,
+        ENDPOINT_GRAPHQL,
+    },
+    startup_item::{
+        IStartupItemNode,
+        StartupItem,
+        StartupItemNode,
+    },
+    user::{
+        IUserNode,
+        User,
+        UserNode,
+    },
 };
@@ -1,10 +1,11 @@
 use derive_dynamic_node::{
     GraplSessionId,
+    GraplStaticId,
     NodeDescription,
 };
 use grapl_graph_descriptions::graph_description::*;
 
-#[derive(NodeDescription, GraplSessionId)]
+#[derive(NodeDescription, GraplStaticId, GraplSessionId)]
 pub struct Process {
     #[grapl(pseudo_key, immutable)]
     asset_id: String,
@@ -12,7 +13,7 @@ pub struct Process {
     #[grapl(pseudo_key, immutable)]
     process_id: u64,
 
-    #[grapl(immutable)]
+    #[grapl(static_id, immutable)]
     process_guid: String,
 
     #[grapl(create_time, immutable)]
@@ -0,0 +1,33 @@
+use derive_dynamic_node::{
+    GraplSessionId,
+    NodeDescription,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+/// A registry key, or a value by its path under its key
+#[derive(NodeDescription, GraplSessionId)]
+pub struct RegistryKey {
+    #[grapl(pseudo_key, immutable)]
+    asset_id: String,
+    #[grapl(pseudo_key, immutable)]
+    key_path: String,
+    #[grapl(create_time, immutable)]
+    created_timestamp: u64,
+    #[grapl(terminate_time, immutable)]
+    deleted_timestamp: u64,
+    #[grapl(last_seen_time, increment)]
+    last_seen_timestamp: u64,
+    /// Every value written to the key
+    #[grapl(union)]
+    value_data: Vec<String>,
+}
+
+impl IRegistryKeyNode for RegistryKeyNode {
+    fn get_mut_dynamic_node(&mut self) -> &mut NodeDescription {
+        &mut self.dynamic_node
+    }
+
+    fn get_dynamic_node(&self) -> &NodeDescription {
+        &self.dynamic_node
+    }
+}
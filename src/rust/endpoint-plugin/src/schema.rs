@@ -0,0 +1,13 @@
+use grapl_graph_descriptions::schema::{
+    GraphSchema,
+    PropertyType,
+};
+
+/// The GraphQL schema of the endpoint plugin's node types, which the graph-merger embeds
+pub const ENDPOINT_GRAPHQL: &str = include_str!("../schemas/endpoint.graphql");
+
+/// The node types, properties and edges of the endpoint plugin, generated from `ENDPOINT_GRAPHQL`
+/// by the build script
+pub fn endpoint_schema() -> GraphSchema {
+    include!(concat!(env!("OUT_DIR"), "/endpoint_schema.rs"))
+}
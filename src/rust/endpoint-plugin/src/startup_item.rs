@@ -0,0 +1,41 @@
+use derive_dynamic_node::{
+    GraplStaticId,
+    NodeDescription,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+/// A command an asset is configured to run on its own, such as a crontab entry, a launch agent
+/// or a systemd unit
+#[derive(NodeDescription, GraplStaticId)]
+pub struct StartupItem {
+    #[grapl(static_id, immutable)]
+    asset_id: String,
+    /// Where the item is configured, such as the crontab or plist that declares it
+    #[grapl(static_id, immutable)]
+    item_source: String,
+    #[grapl(static_id, immutable)]
+    item_command: String,
+    #[grapl(immutable)]
+    item_name: String,
+    #[grapl(immutable)]
+    item_type: String,
+    /// When a scheduled item runs, e.g. a crontab entry's `minute hour day month weekday`
+    #[grapl(immutable)]
+    schedule: String,
+    #[grapl(immutable)]
+    user_name: String,
+    #[grapl(decrement)]
+    first_seen_timestamp: u64,
+    #[grapl(increment)]
+    last_seen_timestamp: u64,
+}
+
+impl IStartupItemNode for StartupItemNode {
+    fn get_mut_dynamic_node(&mut self) -> &mut NodeDescription {
+        &mut self.dynamic_node
+    }
+
+    fn get_dynamic_node(&self) -> &NodeDescription {
+        &self.dynamic_node
+    }
+}
@@ -0,0 +1,37 @@
+use derive_dynamic_node::{
+    GraplStaticId,
+    NodeDescription,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+#[derive(NodeDescription, GraplStaticId)]
+pub struct User {
+    #[grapl(static_id, immutable)]
+    asset_id: String,
+    #[grapl(static_id, immutable)]
+    user_name: String,
+    #[grapl(immutable)]
+    user_id: u64,
+    #[grapl(immutable)]
+    group_id: u64,
+    #[grapl(immutable)]
+    home_directory: String,
+    #[grapl(immutable)]
+    shell: String,
+    #[grapl(increment)]
+    last_login_timestamp: u64,
+    #[grapl(decrement)]
+    first_seen_timestamp: u64,
+    #[grapl(increment)]
+    last_seen_timestamp: u64,
+}
+
+impl IUserNode for UserNode {
+    fn get_mut_dynamic_node(&mut self) -> &mut NodeDescription {
+        &mut self.dynamic_node
+    }
+
+    fn get_dynamic_node(&self) -> &NodeDescription {
+        &self.dynamic_node
+    }
+}
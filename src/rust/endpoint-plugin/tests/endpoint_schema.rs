@@ -0,0 +1,227 @@
+use endpoint_plugin::{
+    endpoint_schema,
+    AssetNode,
+    DomainNameNode,
+    FileNode,
+    IAssetNode,
+    IDomainNameNode,
+    IFileNode,
+    IIpAddressNode,
+    IKernelModuleNode,
+    INamedPipeNode,
+    IProcessNode,
+    IRegistryKeyNode,
+    IStartupItemNode,
+    IUserNode,
+    IpAddressNode,
+    KernelModuleNode,
+    NamedPipeNode,
+    ProcessNode,
+    RegistryKeyNode,
+    StartupItemNode,
+    UserNode,
+};
+use grapl_graph_descriptions::graph_description::GraphDescription;
+
+#[test]
+fn endpoint_nodes_conform_to_endpoint_schema() {
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id("asset")
+        .with_hostname("asset")
+        .with_last_seen_time(1u64);
+
+    let mut process = ProcessNode::new(ProcessNode::session_strategy());
+    process
+        .with_asset_id("asset")
+        .with_process_id(1u64)
+        .with_process_name("cmd.exe")
+        .with_created_timestamp(1u64)
+        .with_last_seen_timestamp(1u64);
+
+    let mut file = FileNode::new(FileNode::session_strategy());
+    file.with_asset_id("asset")
+        .with_file_path("C:\\Windows\\System32\\cmd.exe")
+        .with_last_seen_timestamp(1u64);
+
+    let mut graph = GraphDescription::new();
+    graph.add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    );
+    graph.add_edge("bin_file", process.clone_node_key(), file.clone_node_key());
+    graph.add_edge(
+        "files_on_asset",
+        asset.clone_node_key(),
+        file.clone_node_key(),
+    );
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(file);
+
+    assert_eq!(endpoint_schema().validate(&graph), vec![]);
+}
+
+#[test]
+fn registry_pipe_and_dns_nodes_conform_to_endpoint_schema() {
+    let mut process = ProcessNode::new(ProcessNode::session_strategy());
+    process
+        .with_asset_id("asset")
+        .with_process_id(1u64)
+        .with_last_seen_timestamp(1u64);
+
+    let mut registry_key = RegistryKeyNode::new(RegistryKeyNode::session_strategy());
+    registry_key
+        .with_asset_id("asset")
+        .with_key_path("HKLM\\Software\\Microsoft\\Windows\\CurrentVersion\\Run\\updater")
+        .with_value_data(vec!["C:\\updater.exe"])
+        .with_last_seen_timestamp(1u64);
+
+    let mut pipe = NamedPipeNode::new(NamedPipeNode::static_strategy());
+    pipe.with_asset_id("asset")
+        .with_pipe_name("\\MSSE-1234-server")
+        .with_first_seen_timestamp(1u64)
+        .with_last_seen_timestamp(1u64);
+
+    let mut domain = DomainNameNode::new(DomainNameNode::static_strategy());
+    domain
+        .with_domain_name("www.example.com")
+        .with_last_seen_timestamp(1u64);
+
+    let mut ip = IpAddressNode::new(IpAddressNode::static_strategy());
+    ip.with_ip_address("93.184.216.34")
+        .with_last_seen_timestamp(1u64);
+
+    let mut graph = GraphDescription::new();
+    graph.add_edge(
+        "modified_registry_keys",
+        process.clone_node_key(),
+        registry_key.clone_node_key(),
+    );
+    graph.add_edge(
+        "connected_pipes",
+        process.clone_node_key(),
+        pipe.clone_node_key(),
+    );
+    graph.add_edge(
+        "queried_domains",
+        process.clone_node_key(),
+        domain.clone_node_key(),
+    );
+    graph.add_edge("resolved_ips", domain.clone_node_key(), ip.clone_node_key());
+    graph.add_node(process);
+    graph.add_node(registry_key);
+    graph.add_node(pipe);
+    graph.add_node(domain);
+    graph.add_node(ip);
+
+    assert_eq!(endpoint_schema().validate(&graph), vec![]);
+}
+
+#[test]
+fn user_and_persistence_nodes_conform_to_endpoint_schema() {
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset.with_asset_id("asset").with_hostname("asset");
+
+    let mut process = ProcessNode::new(ProcessNode::session_strategy());
+    process
+        .with_asset_id("asset")
+        .with_process_id(1u64)
+        .with_last_seen_timestamp(1u64);
+
+    let mut user = UserNode::new(UserNode::static_strategy());
+    user.with_asset_id("asset")
+        .with_user_name("rsmith")
+        .with_user_id(1000u64)
+        .with_shell("/bin/bash")
+        .with_last_seen_timestamp(1u64);
+
+    let mut startup_item = StartupItemNode::new(StartupItemNode::static_strategy());
+    startup_item
+        .with_asset_id("asset")
+        .with_item_source("/etc/crontab")
+        .with_item_command("/usr/local/bin/updater")
+        .with_item_type("crontab")
+        .with_schedule("*/5 * * * *")
+        .with_last_seen_timestamp(1u64);
+
+    let mut crontab = FileNode::new(FileNode::session_strategy());
+    crontab
+        .with_asset_id("asset")
+        .with_file_path("/etc/crontab")
+        .with_last_seen_timestamp(1u64);
+
+    let mut kernel_module = KernelModuleNode::new(KernelModuleNode::static_strategy());
+    kernel_module
+        .with_asset_id("asset")
+        .with_module_name("nf_conntrack")
+        .with_module_size(139264u64)
+        .with_last_seen_timestamp(1u64);
+
+    let mut resolver = IpAddressNode::new(IpAddressNode::static_strategy());
+    resolver
+        .with_ip_address("127.0.0.53")
+        .with_last_seen_timestamp(1u64);
+
+    let mut graph = GraphDescription::new();
+    graph.add_edge(
+        "users_on_asset",
+        asset.clone_node_key(),
+        user.clone_node_key(),
+    );
+    graph.add_edge(
+        "process_user",
+        process.clone_node_key(),
+        user.clone_node_key(),
+    );
+    graph.add_edge(
+        "startup_items_on_asset",
+        asset.clone_node_key(),
+        startup_item.clone_node_key(),
+    );
+    graph.add_edge(
+        "startup_item_file",
+        startup_item.clone_node_key(),
+        crontab.clone_node_key(),
+    );
+    graph.add_edge(
+        "kernel_modules_on_asset",
+        asset.clone_node_key(),
+        kernel_module.clone_node_key(),
+    );
+    graph.add_edge(
+        "dns_resolvers",
+        asset.clone_node_key(),
+        resolver.clone_node_key(),
+    );
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(user);
+    graph.add_node(startup_item);
+    graph.add_node(crontab);
+    graph.add_node(kernel_module);
+    graph.add_node(resolver);
+
+    assert_eq!(endpoint_schema().validate(&graph), vec![]);
+}
+
+#[test]
+fn misnamed_edges_are_reported() {
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset.with_asset_id("asset");
+    let mut process = ProcessNode::new(ProcessNode::session_strategy());
+    process.with_asset_id("asset").with_process_id(1u64);
+
+    let mut graph = GraphDescription::new();
+    // `process_asset` goes from the process to the asset
+    graph.add_edge(
+        "process_asset",
+        asset.clone_node_key(),
+        process.clone_node_key(),
+    );
+    graph.add_node(asset);
+    graph.add_node(process);
+
+    assert_eq!(endpoint_schema().validate(&graph).len(), 1);
+}
@@ -0,0 +1,60 @@
+use auditd_generator_lib::{
+    generator::AuditdGenerator,
+    metrics::AuditdGeneratorMetrics,
+    serialization::AuditdDecoder,
+};
+use criterion::{
+    criterion_group,
+    criterion_main,
+    Criterion,
+};
+use sqs_executor::{
+    cache::NopCache,
+    event_decoder::PayloadDecoder,
+    event_handler::{
+        CompletedEvents,
+        EventHandler,
+    },
+};
+use tokio::runtime::Runtime;
+
+const AUDITD_SAMPLE_DATA_FILE: &'static str = "sample_data/audit_log.zstd";
+
+async fn auditd_generator_process_events(
+    auditd_test_events: <AuditdGenerator<NopCache> as EventHandler>::InputEvent,
+) {
+    let mut generator =
+        AuditdGenerator::new(NopCache {}, AuditdGeneratorMetrics::new("AUDITD_TEST"));
+
+    let mut completed_events = CompletedEvents { identities: vec![] };
+
+    let _ = generator
+        .handle_event(auditd_test_events, &mut completed_events)
+        .await;
+}
+
+fn bench_auditd_generator_1000_events(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let auditd_events: Vec<_> = runtime.block_on(async {
+        let test_data_bytes = tokio::fs::read(AUDITD_SAMPLE_DATA_FILE)
+            .await
+            .expect("Unable to read auditd sample data into test.");
+
+        AuditdDecoder::default()
+            .decode(test_data_bytes)
+            .expect("Unable to parse auditd sample data into audit events.")
+            .into_iter()
+            .take(1_000)
+            .collect()
+    });
+
+    c.bench_function("Auditd Generator - 1000 Events", |bencher| {
+        bencher.to_async(&runtime).iter(|| async {
+            auditd_generator_process_events(auditd_events.clone()).await;
+        });
+    });
+}
+
+criterion_group!(generator_benches, bench_auditd_generator_1000_events);
+criterion_main!(generator_benches);
@@ -0,0 +1,21 @@
+/// Why audit records couldn't be parsed into an event
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AuditdParseError {
+    #[error("Malformed audit record: {0}")]
+    MalformedRecord(String),
+    #[error("Event {serial} ({description}) isn't a supported event")]
+    UnsupportedEvent { serial: u64, description: String },
+    #[error("{record_type} record of event {serial} is missing {field}")]
+    MissingField {
+        record_type: String,
+        serial: u64,
+        field: String,
+    },
+    #[error("{record_type} record of event {serial} has an invalid {field}: {value}")]
+    InvalidField {
+        record_type: String,
+        serial: u64,
+        field: String,
+        value: String,
+    },
+}
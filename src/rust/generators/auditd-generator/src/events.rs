@@ -0,0 +1,452 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    net::{
+        IpAddr,
+        SocketAddr,
+    },
+};
+
+use crate::{
+    error::AuditdParseError,
+    records::{
+        decode_hex,
+        decode_string,
+        Record,
+    },
+};
+
+/// An event auditd logged that the generator has a graph for
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AuditEvent {
+    ProcessExec(ExecEvent),
+    FileCreate(FileEvent),
+    FileDelete(FileEvent),
+    Connect(NetworkEvent),
+    Bind(NetworkEvent),
+}
+
+/// The process that made an event's syscall, and when, from the event's `SYSCALL` record
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Syscall {
+    pub hostname: String,
+    /// Milliseconds since the epoch
+    pub timestamp: u64,
+    pub serial: u64,
+    pub pid: u64,
+    pub ppid: u64,
+    pub comm: Option<String>,
+    pub exe: Option<String>,
+}
+
+impl Syscall {
+    /// The name of the process's binary, or the name the process gave itself when the binary
+    /// isn't known
+    pub fn process_name(&self) -> Option<String> {
+        self.exe
+            .as_deref()
+            .and_then(|exe| exe.rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .or_else(|| self.comm.clone())
+    }
+}
+
+/// A process executing a binary, from `execve` or `execveat`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExecEvent {
+    pub syscall: Syscall,
+    pub arguments: Vec<String>,
+    pub cwd: Option<String>,
+}
+
+/// A process creating or deleting a file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileEvent {
+    pub syscall: Syscall,
+    pub path: String,
+}
+
+/// A process connecting a socket to, or binding a socket to, an IP address and port
+///
+/// auditd doesn't record the socket's type, so every connection is taken to be TCP.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkEvent {
+    pub syscall: Syscall,
+    pub address: SocketAddr,
+}
+
+/// Groups records into the events they belong to
+///
+/// auditd logs an event as several records that share the event's host, time and serial
+/// number, and may interleave the records of concurrent events. The events are returned in the
+/// order they started in, without the `EOE` records that mark their ends.
+pub fn correlate(records: impl IntoIterator<Item = Record>) -> Vec<Vec<Record>> {
+    let mut events: Vec<Vec<Record>> = Vec::new();
+    let mut event_indices: HashMap<(Option<String>, u64, u64), usize> = HashMap::new();
+
+    for record in records {
+        if record.record_type == "EOE" {
+            continue;
+        }
+
+        let key = (record.node.clone(), record.timestamp, record.serial);
+        let index = *event_indices.entry(key).or_insert_with(|| {
+            events.push(Vec::new());
+            events.len() - 1
+        });
+        events[index].push(record);
+    }
+
+    events
+}
+
+impl TryFrom<Vec<Record>> for AuditEvent {
+    type Error = AuditdParseError;
+
+    fn try_from(records: Vec<Record>) -> Result<Self, Self::Error> {
+        let serial = records.first().map(|record| record.serial).unwrap_or(0);
+        let unsupported = |description: String| AuditdParseError::UnsupportedEvent {
+            serial,
+            description,
+        };
+
+        let syscall_record = match find_record(&records, "SYSCALL") {
+            Some(record) => record,
+            None => {
+                let record_type = records.first().map(|record| record.record_type.clone());
+                return Err(unsupported(record_type.unwrap_or_default()));
+            }
+        };
+
+        let syscall_number = syscall_record
+            .raw("syscall")
+            .ok_or_else(|| syscall_record.missing("syscall"))?;
+        let syscall_name = match syscall_name(syscall_record.raw("arch"), syscall_number) {
+            Some(syscall_name) => syscall_name,
+            None => return Err(unsupported(format!("syscall {}", syscall_number))),
+        };
+
+        // Failed syscalls are audited too, but didn't do anything
+        if syscall_record.raw("success") != Some("yes") {
+            return Err(unsupported(format!("failed {}", syscall_name)));
+        }
+
+        let syscall = Syscall {
+            hostname: syscall_record
+                .node
+                .clone()
+                .ok_or_else(|| syscall_record.missing("node"))?,
+            timestamp: syscall_record.timestamp,
+            serial: syscall_record.serial,
+            pid: syscall_record.parse("pid")?,
+            ppid: syscall_record.parse("ppid")?,
+            comm: syscall_record.string("comm"),
+            exe: syscall_record.string("exe"),
+        };
+        let cwd = find_record(&records, "CWD").and_then(|record| record.string("cwd"));
+
+        match syscall_name {
+            "execve" | "execveat" => Ok(AuditEvent::ProcessExec(ExecEvent {
+                syscall,
+                arguments: execve_arguments(&records)?,
+                cwd,
+            })),
+            "open" | "openat" | "openat2" | "creat" => match named_path(&records, "CREATE") {
+                Some(path) => Ok(AuditEvent::FileCreate(FileEvent {
+                    path: resolve_path(path, cwd.as_deref()),
+                    syscall,
+                })),
+                None => Err(unsupported(format!("{} of an existing file", syscall_name))),
+            },
+            "unlink" | "unlinkat" => match named_path(&records, "DELETE") {
+                Some(path) => Ok(AuditEvent::FileDelete(FileEvent {
+                    path: resolve_path(path, cwd.as_deref()),
+                    syscall,
+                })),
+                None => Err(unsupported(format!("{} of no file", syscall_name))),
+            },
+            "connect" | "bind" => {
+                let sockaddr = find_record(&records, "SOCKADDR")
+                    .ok_or_else(|| unsupported(format!("{} without an address", syscall_name)))?;
+                let saddr = sockaddr
+                    .raw("saddr")
+                    .ok_or_else(|| sockaddr.missing("saddr"))?;
+                let address = match parse_sockaddr(saddr) {
+                    Some(address) => address,
+                    None => {
+                        return Err(unsupported(format!("{} to a non-IP socket", syscall_name)))
+                    }
+                };
+
+                let network_event = NetworkEvent { syscall, address };
+                if syscall_name == "connect" {
+                    Ok(AuditEvent::Connect(network_event))
+                } else {
+                    Ok(AuditEvent::Bind(network_event))
+                }
+            }
+            _ => Err(unsupported(syscall_name.to_string())),
+        }
+    }
+}
+
+fn find_record<'a>(records: &'a [Record], record_type: &str) -> Option<&'a Record> {
+    records
+        .iter()
+        .find(|record| record.record_type == record_type)
+}
+
+/// The syscalls the generator has graphs for
+const SYSCALLS: &[&str] = &[
+    "execve", "execveat", "open", "openat", "openat2", "creat", "unlink", "unlinkat", "connect",
+    "bind",
+];
+
+/// Names a syscall
+///
+/// Raw logs number syscalls by the audited process's architecture, while logs interpreted by
+/// `ausearch -i` name them. Only x86_64 and aarch64 numbers are known.
+fn syscall_name(arch: Option<&str>, syscall: &str) -> Option<&'static str> {
+    let number: u64 = match syscall.parse() {
+        Ok(number) => number,
+        Err(_) => return SYSCALLS.iter().copied().find(|name| *name == syscall),
+    };
+
+    let name = match (arch?, number) {
+        ("c000003e", 2) => "open",
+        ("c000003e", 42) => "connect",
+        ("c000003e", 49) => "bind",
+        ("c000003e", 59) => "execve",
+        ("c000003e", 85) => "creat",
+        ("c000003e", 87) => "unlink",
+        ("c000003e", 257) => "openat",
+        ("c000003e", 263) => "unlinkat",
+        ("c000003e", 322) => "execveat",
+        ("c00000b7", 35) => "unlinkat",
+        ("c00000b7", 56) => "openat",
+        ("c00000b7", 200) => "bind",
+        ("c00000b7", 203) => "connect",
+        ("c00000b7", 221) => "execve",
+        ("c00000b7", 281) => "execveat",
+        ("c000003e", 437) | ("c00000b7", 437) => "openat2",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Reads the arguments of an exec from its `EXECVE` records
+///
+/// Arguments too long for a single field are logged in hex-encoded chunks, as
+/// `a1_len=1024 a1[0]=... a1[1]=...`, and very long argument lists over several records.
+fn execve_arguments(records: &[Record]) -> Result<Vec<String>, AuditdParseError> {
+    let execve: Vec<&Record> = records
+        .iter()
+        .filter(|record| record.record_type == "EXECVE")
+        .collect();
+    let field = |name: &str| execve.iter().find_map(|record| record.raw(name));
+
+    let first = match execve.first() {
+        Some(first) => first,
+        None => return Ok(vec![]),
+    };
+    let argc: usize = first.parse("argc")?;
+
+    (0..argc)
+        .map(|i| {
+            let name = format!("a{}", i);
+            if let Some(argument) = field(&name) {
+                return Ok(decode_string(argument).unwrap_or_default());
+            }
+
+            let chunks: String = (0..)
+                .map(|chunk| field(&format!("{}[{}]", name, chunk)))
+                .take_while(Option::is_some)
+                .flatten()
+                .collect();
+            match decode_hex(&chunks) {
+                Some(argument) => Ok(String::from_utf8_lossy(&argument).into_owned()),
+                None => Err(first.missing(&name)),
+            }
+        })
+        .collect()
+}
+
+/// The name of the event's `PATH` record of the given `nametype`
+fn named_path(records: &[Record], nametype: &str) -> Option<String> {
+    records
+        .iter()
+        .filter(|record| record.record_type == "PATH")
+        .find(|record| record.raw("nametype") == Some(nametype))
+        .and_then(|record| record.string("name"))
+}
+
+/// Resolves a path relative to the process's working directory
+///
+/// Paths relative to a directory file descriptor, as the `*at` syscalls allow, are resolved
+/// relative to the working directory too, as the directory's path isn't logged.
+fn resolve_path(path: String, cwd: Option<&str>) -> String {
+    match cwd {
+        Some(cwd) if !path.starts_with('/') => {
+            format!(
+                "{}/{}",
+                cwd.trim_end_matches('/'),
+                path.trim_start_matches("./")
+            )
+        }
+        _ => path,
+    }
+}
+
+/// Parses a `SOCKADDR` record's hex-encoded `struct sockaddr`, when it's an IP socket's
+fn parse_sockaddr(saddr: &str) -> Option<SocketAddr> {
+    const AF_INET: u16 = 2;
+    const AF_INET6: u16 = 10;
+
+    let bytes = decode_hex(saddr)?;
+    let family = u16::from_le_bytes([*bytes.first()?, *bytes.get(1)?]);
+    let port = u16::from_be_bytes([*bytes.get(2)?, *bytes.get(3)?]);
+
+    let ip = match family {
+        AF_INET => IpAddr::from(<[u8; 4]>::try_from(bytes.get(4..8)?).ok()?),
+        AF_INET6 => IpAddr::from(<[u8; 16]>::try_from(bytes.get(8..24)?).ok()?),
+        _ => return None,
+    };
+
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::{
+        read_records,
+        read_unit_sample,
+    };
+
+    #[test]
+    fn parse_execve() {
+        match read_unit_sample("execve") {
+            AuditEvent::ProcessExec(exec) => {
+                assert_eq!(exec.syscall.hostname, "web-1");
+                assert_eq!(exec.syscall.timestamp, 1603400082123);
+                assert_eq!(exec.syscall.pid, 9355);
+                assert_eq!(exec.syscall.ppid, 8753);
+                assert_eq!(exec.syscall.process_name().as_deref(), Some("ls"));
+                assert_eq!(exec.arguments, vec!["ls", "-la", "/tmp/my dir"]);
+                assert_eq!(exec.cwd.as_deref(), Some("/home/grapl"));
+            }
+            event => panic!("expected AuditEvent::ProcessExec, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn parse_file_create() {
+        match read_unit_sample("file_create") {
+            AuditEvent::FileCreate(file) => assert_eq!(file.path, "/tmp/payload.sh"),
+            event => panic!("expected AuditEvent::FileCreate, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn parse_file_delete() {
+        match read_unit_sample("file_delete") {
+            AuditEvent::FileDelete(file) => assert_eq!(file.path, "/tmp/payload.sh"),
+            event => panic!("expected AuditEvent::FileDelete, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn parse_connect() {
+        match read_unit_sample("connect") {
+            AuditEvent::Connect(connect) => {
+                assert_eq!(connect.address, "192.168.1.10:80".parse().unwrap())
+            }
+            event => panic!("expected AuditEvent::Connect, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn parse_bind() {
+        match read_unit_sample("bind") {
+            AuditEvent::Bind(bind) => assert_eq!(bind.address, "10.0.0.5:8080".parse().unwrap()),
+            event => panic!("expected AuditEvent::Bind, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn interleaved_records_are_correlated_by_serial() {
+        let execve = std::fs::read_to_string("sample_data/unit/execve.log").unwrap();
+        let connect = std::fs::read_to_string("sample_data/unit/connect.log").unwrap();
+
+        let mut records = vec![];
+        for (execve, connect) in execve.lines().zip(connect.lines()) {
+            records.push(execve);
+            records.push(connect);
+        }
+        records.extend(execve.lines().skip(connect.lines().count()));
+
+        let events = correlate(read_records(&records.join("\n")));
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].iter().all(|record| record.serial == 2345));
+        assert!(events[1].iter().all(|record| record.serial == 2360));
+        assert_eq!(events[0].len(), 6);
+    }
+
+    #[test]
+    fn long_arguments_are_reassembled() {
+        let records = read_records(
+            r#"node=web-1 type=SYSCALL msg=audit(1603400082.123:2345): arch=c00000b7 syscall=221 success=yes exit=0 ppid=8753 pid=9355 comm="echo" exe="/usr/bin/echo"
+node=web-1 type=EXECVE msg=audit(1603400082.123:2345): argc=2 a0="echo" a1_len=10 a1[0]=68656C6C6F a1[1]=776F726C64"#,
+        );
+
+        match AuditEvent::try_from(records) {
+            Ok(AuditEvent::ProcessExec(exec)) => {
+                assert_eq!(exec.arguments, vec!["echo", "helloworld"])
+            }
+            event => panic!("expected AuditEvent::ProcessExec, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn unsupported_events() {
+        let unsupported = |log: &str| match AuditEvent::try_from(read_records(log)) {
+            Err(AuditdParseError::UnsupportedEvent { .. }) => {}
+            event => panic!(
+                "expected AuditdParseError::UnsupportedEvent, got {:?}",
+                event
+            ),
+        };
+
+        // Not a syscall
+        unsupported(
+            "node=web-1 type=USER_LOGIN msg=audit(1603400082.123:2346): pid=811 uid=0 msg='op=login acct=\"root\" res=success'",
+        );
+        // A syscall without a graph
+        unsupported(
+            r#"node=web-1 type=SYSCALL msg=audit(1603400082.123:2347): arch=c000003e syscall=0 success=yes exit=0 ppid=1 pid=811 comm="sshd" exe="/usr/sbin/sshd""#,
+        );
+        // A failed syscall
+        unsupported(
+            r#"node=web-1 type=SYSCALL msg=audit(1603400082.123:2348): arch=c000003e syscall=59 success=no exit=-2 ppid=1 pid=811 comm="sshd" exe="/usr/sbin/sshd""#,
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_named() {
+        let records = read_records(
+            r#"type=SYSCALL msg=audit(1603400082.123:2345): arch=c000003e syscall=59 success=yes exit=0 ppid=8753 pid=9355 comm="ls" exe="/usr/bin/ls""#,
+        );
+
+        assert_eq!(
+            AuditEvent::try_from(records),
+            Err(AuditdParseError::MissingField {
+                record_type: "SYSCALL".to_string(),
+                serial: 2345,
+                field: "node".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use grapl_graph_descriptions::{
+    graph_description::*,
+    MergeError,
+};
+use sqs_executor::{
+    cache::Cache,
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_handler::{
+        CompletedEvents,
+        EventHandler,
+    },
+    event_status::EventStatus,
+};
+
+use crate::{
+    events::AuditEvent,
+    metrics::AuditdGeneratorMetrics,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuditdGeneratorError {
+    #[error("MergeError")]
+    MergeError(#[from] MergeError),
+}
+
+impl CheckedError for AuditdGeneratorError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::MergeError(_) => Recoverable::Persistent,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditdGenerator<C>
+where
+    C: Cache + Clone + Send + Sync + 'static,
+{
+    cache: C,
+    metrics: AuditdGeneratorMetrics,
+}
+
+impl<C> AuditdGenerator<C>
+where
+    C: Cache + Clone + Send + Sync + 'static,
+{
+    pub fn new(cache: C, metrics: AuditdGeneratorMetrics) -> Self {
+        Self { cache, metrics }
+    }
+}
+
+#[async_trait]
+impl<C> EventHandler for AuditdGenerator<C>
+where
+    C: Cache + Clone + Send + Sync + 'static,
+{
+    type InputEvent = Vec<AuditEvent>;
+    type OutputEvent = GraphDescription;
+    type Error = AuditdGeneratorError;
+
+    #[tracing::instrument(skip(self, events, completed))]
+    async fn handle_event(
+        &mut self,
+        events: Self::InputEvent,
+        completed: &mut CompletedEvents,
+    ) -> Result<Self::OutputEvent, Result<(Self::OutputEvent, Self::Error), Self::Error>> {
+        tracing::info!(
+            message = "Processing incoming events.",
+            num_events = events.len()
+        );
+
+        // Skip events we've successfully processed and stored in the event cache.
+        let events = self.cache.filter_cached(&events).await;
+
+        let mut last_error: Option<AuditdGeneratorError> = None;
+        let mut final_subgraph = GraphDescription::new();
+
+        for event in events {
+            let result = GraphDescription::try_from(event.clone()).and_then(|subgraph| {
+                final_subgraph
+                    .try_merge(&subgraph)
+                    .map_err(AuditdGeneratorError::from)
+            });
+            self.metrics.report_subgraph_generation(&result);
+            match result {
+                Ok(_) => {
+                    completed.add_identity(event, EventStatus::Success);
+                }
+                Err(error) => {
+                    completed.add_identity(event, EventStatus::Failure);
+                    tracing::error!(message="GraphDescription::try_from failed with.", error=?error);
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        tracing::info!(
+            message = "Completed mapping subgraphs.",
+            num_graphs = completed.len()
+        );
+
+        let final_result = match (last_error, final_subgraph.is_empty()) {
+            (None, _) => Ok(final_subgraph),
+            (Some(error), false) => Err(Ok((final_subgraph, error))),
+            (Some(error), true) => Err(Err(error)),
+        };
+
+        self.metrics.report_handle_event_success(&final_result);
+
+        final_result
+    }
+}
@@ -0,0 +1,8 @@
+pub mod error;
+pub mod events;
+pub mod generator;
+pub mod metrics;
+pub mod models;
+pub mod records;
+pub mod serialization;
+mod tests;
@@ -0,0 +1,31 @@
+use auditd_generator_lib::{
+    generator::AuditdGenerator,
+    metrics::AuditdGeneratorMetrics,
+    serialization::AuditdDecoder,
+};
+use graph_generator_lib::run_graph_generator;
+pub use grapl_service::serialization::{
+    GraphDescriptionSerializer,
+    GraphDescriptionSerializerError,
+};
+
+#[tokio::main]
+#[tracing::instrument]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (env, _guard) = grapl_config::init_grapl_env!();
+    let service_name = env.service_name.clone();
+
+    tracing::info!(
+        message = "Starting generator.",
+        name =% service_name
+    );
+
+    run_graph_generator(
+        env,
+        move |cache| AuditdGenerator::new(cache, AuditdGeneratorMetrics::new(&service_name)),
+        AuditdDecoder::from_env(),
+    )
+    .await;
+
+    Ok(())
+}
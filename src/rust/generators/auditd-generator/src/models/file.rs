@@ -0,0 +1,97 @@
+use endpoint_plugin::{
+    FileNode,
+    IFileNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+use super::asset_and_process;
+use crate::{
+    events::FileEvent,
+    generator::AuditdGeneratorError,
+};
+
+/// Creates a subgraph describing a `FileEvent` of a file being created
+///
+/// The subgraph generation for a file creation includes the following:
+/// * A creator `Process` node - denotes the process that created the file
+/// * A subject `File` node - the file that is created as part of this event
+pub fn generate_file_create_subgraph(
+    file_create: &FileEvent,
+) -> Result<GraphDescription, AuditdGeneratorError> {
+    let syscall = &file_create.syscall;
+    let mut graph = GraphDescription::new();
+
+    let (asset, creator) = asset_and_process(syscall);
+
+    let mut file = FileNode::new(FileNode::session_strategy());
+    file.with_asset_id(syscall.hostname.clone())
+        .with_file_path(file_create.path.clone())
+        .with_created_timestamp(syscall.timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        creator.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "created_files",
+        creator.clone_node_key(),
+        file.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "files_on_asset",
+        asset.clone_node_key(),
+        file.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(creator);
+    graph.add_node(file);
+
+    Ok(graph)
+}
+
+/// Creates a subgraph describing a `FileEvent` of a file being deleted
+///
+/// The subgraph generation for a file deletion includes the following:
+/// * A deleter `Process` node - denotes the process that deleted the file
+/// * A subject `File` node - the file that is deleted as part of this event
+pub fn generate_file_delete_subgraph(
+    file_delete: &FileEvent,
+) -> Result<GraphDescription, AuditdGeneratorError> {
+    let syscall = &file_delete.syscall;
+    let mut graph = GraphDescription::new();
+
+    let (asset, deleter) = asset_and_process(syscall);
+
+    let mut file = FileNode::new(FileNode::session_strategy());
+    file.with_asset_id(syscall.hostname.clone())
+        .with_file_path(file_delete.path.clone())
+        .with_deleted_timestamp(syscall.timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        deleter.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "deleted_files",
+        deleter.clone_node_key(),
+        file.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "files_on_asset",
+        asset.clone_node_key(),
+        file.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(deleter);
+    graph.add_node(file);
+
+    Ok(graph)
+}
@@ -0,0 +1,272 @@
+use std::convert::TryFrom;
+
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IProcessNode,
+    ProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+use crate::{
+    events::{
+        AuditEvent,
+        Syscall,
+    },
+    generator::AuditdGeneratorError,
+};
+
+mod file;
+mod network;
+mod process;
+
+impl TryFrom<AuditEvent> for GraphDescription {
+    type Error = AuditdGeneratorError;
+
+    #[tracing::instrument]
+    fn try_from(instance: AuditEvent) -> Result<Self, Self::Error> {
+        let (event_type, result) = match &instance {
+            AuditEvent::ProcessExec(event) => (
+                "ProcessExec",
+                process::generate_process_exec_subgraph(event),
+            ),
+            AuditEvent::FileCreate(event) => {
+                ("FileCreate", file::generate_file_create_subgraph(event))
+            }
+            AuditEvent::FileDelete(event) => {
+                ("FileDelete", file::generate_file_delete_subgraph(event))
+            }
+            AuditEvent::Connect(event) => ("Connect", network::generate_connect_subgraph(event)),
+            AuditEvent::Bind(event) => ("Bind", network::generate_bind_subgraph(event)),
+        };
+
+        tracing::info!(event = event_type);
+
+        if let Err(e) = &result {
+            tracing::warn!(message="Failed to process event.", event = event_type, error=?e);
+        }
+
+        result
+    }
+}
+
+/// The asset a syscall was made on and the process that made it, which every subgraph starts
+/// from
+fn asset_and_process(syscall: &Syscall) -> (AssetNode, ProcessNode) {
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(syscall.hostname.clone())
+        .with_hostname(syscall.hostname.clone());
+
+    let mut process = ProcessNode::new(ProcessNode::session_strategy());
+    process
+        .with_asset_id(syscall.hostname.clone())
+        .with_process_id(syscall.pid)
+        .with_last_seen_timestamp(syscall.timestamp);
+    if let Some(process_name) = syscall.process_name() {
+        process.with_process_name(process_name);
+    }
+
+    (asset, process)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::read_unit_sample;
+
+    fn generate_unit_sample(name: &str) -> GraphDescription {
+        let graph =
+            GraphDescription::try_from(read_unit_sample(name)).expect("failed to generate graph");
+
+        #[cfg(feature = "schema_validation")]
+        assert_eq!(
+            endpoint_plugin::endpoint_schema().validate(&graph),
+            vec![],
+            "{} doesn't conform to the endpoint schema",
+            name
+        );
+
+        graph
+    }
+
+    /// The key of the only node of the type
+    fn node_key<'a>(graph: &'a GraphDescription, node_type: &str) -> &'a str {
+        let mut nodes = graph
+            .nodes
+            .values()
+            .filter(|node| node.node_type == node_type);
+        let node = nodes
+            .next()
+            .unwrap_or_else(|| panic!("missing {} node", node_type));
+        assert!(nodes.next().is_none(), "more than one {} node", node_type);
+
+        &node.node_key
+    }
+
+    /// The key of the process with the pid
+    fn process_key<'a>(graph: &'a GraphDescription, pid: &str) -> &'a str {
+        graph
+            .nodes
+            .values()
+            .find(|node| {
+                node.node_type == "Process"
+                    && property(graph, &node.node_key, "process_id").as_deref() == Some(pid)
+            })
+            .map(|node| node.node_key.as_str())
+            .unwrap_or_else(|| panic!("missing process {}", pid))
+    }
+
+    fn property(graph: &GraphDescription, node_key: &str, name: &str) -> Option<String> {
+        graph.nodes[node_key]
+            .get_property(name)
+            .map(|property| property.to_string())
+    }
+
+    /// The (from, to) node keys of every edge with the name
+    fn edges<'a>(graph: &'a GraphDescription, edge_name: &str) -> Vec<(&'a str, &'a str)> {
+        let mut edges: Vec<_> = graph
+            .edges
+            .values()
+            .flat_map(|edges| &edges.edges)
+            .filter(|edge| edge.edge_name == edge_name)
+            .map(|edge| (edge.from_node_key.as_str(), edge.to_node_key.as_str()))
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    #[test]
+    fn execve_subgraph() {
+        let graph = generate_unit_sample("execve");
+
+        let asset = node_key(&graph, "Asset");
+        let parent = process_key(&graph, "8753");
+        let child = process_key(&graph, "9355");
+        let bin_file = node_key(&graph, "File");
+        assert_eq!(graph.nodes.len(), 4);
+
+        assert_eq!(
+            property(&graph, asset, "hostname").as_deref(),
+            Some("web-1")
+        );
+        assert_eq!(
+            property(&graph, child, "process_name").as_deref(),
+            Some("ls")
+        );
+        assert_eq!(
+            property(&graph, child, "process_command_line").as_deref(),
+            Some("ls -la /tmp/my dir")
+        );
+        assert_eq!(
+            property(&graph, bin_file, "file_path").as_deref(),
+            Some("/usr/bin/ls")
+        );
+
+        assert_eq!(edges(&graph, "children"), vec![(parent, child)]);
+        assert_eq!(edges(&graph, "bin_file"), vec![(child, bin_file)]);
+        assert_eq!(edges(&graph, "files_on_asset"), vec![(asset, bin_file)]);
+        let mut process_asset = vec![(parent, asset), (child, asset)];
+        process_asset.sort_unstable();
+        assert_eq!(edges(&graph, "process_asset"), process_asset);
+    }
+
+    #[test]
+    fn file_create_subgraph() {
+        let graph = generate_unit_sample("file_create");
+
+        let asset = node_key(&graph, "Asset");
+        let creator = process_key(&graph, "9360");
+        let file = node_key(&graph, "File");
+        assert_eq!(graph.nodes.len(), 3);
+
+        assert_eq!(
+            property(&graph, creator, "process_name").as_deref(),
+            Some("touch")
+        );
+        assert_eq!(
+            property(&graph, file, "file_path").as_deref(),
+            Some("/tmp/payload.sh")
+        );
+
+        assert_eq!(edges(&graph, "created_files"), vec![(creator, file)]);
+        assert_eq!(edges(&graph, "files_on_asset"), vec![(asset, file)]);
+        assert_eq!(edges(&graph, "process_asset"), vec![(creator, asset)]);
+        assert!(edges(&graph, "deleted_files").is_empty());
+    }
+
+    #[test]
+    fn file_delete_subgraph() {
+        let graph = generate_unit_sample("file_delete");
+
+        let asset = node_key(&graph, "Asset");
+        let deleter = process_key(&graph, "9365");
+        let file = node_key(&graph, "File");
+        assert_eq!(graph.nodes.len(), 3);
+
+        assert_eq!(
+            property(&graph, file, "file_path").as_deref(),
+            Some("/tmp/payload.sh")
+        );
+
+        assert_eq!(edges(&graph, "deleted_files"), vec![(deleter, file)]);
+        assert_eq!(edges(&graph, "files_on_asset"), vec![(asset, file)]);
+        assert_eq!(edges(&graph, "process_asset"), vec![(deleter, asset)]);
+        assert!(edges(&graph, "created_files").is_empty());
+    }
+
+    #[test]
+    fn connect_subgraph() {
+        let graph = generate_unit_sample("connect");
+
+        let asset = node_key(&graph, "Asset");
+        let process = process_key(&graph, "9370");
+        let outbound = node_key(&graph, "ProcessOutboundConnection");
+        let dst = node_key(&graph, "IpPort");
+        assert_eq!(graph.nodes.len(), 4);
+
+        for node_key in &[outbound, dst] {
+            assert_eq!(
+                property(&graph, node_key, "ip_address").as_deref(),
+                Some("192.168.1.10")
+            );
+            assert_eq!(property(&graph, node_key, "port").as_deref(), Some("80"));
+        }
+
+        assert_eq!(
+            edges(&graph, "created_connections"),
+            vec![(process, outbound)]
+        );
+        assert_eq!(edges(&graph, "connected_to"), vec![(outbound, dst)]);
+        assert_eq!(edges(&graph, "process_asset"), vec![(process, asset)]);
+    }
+
+    #[test]
+    fn bind_subgraph() {
+        let graph = generate_unit_sample("bind");
+
+        let asset = node_key(&graph, "Asset");
+        let process = process_key(&graph, "9375");
+        let inbound = node_key(&graph, "ProcessInboundConnection");
+        let bound_ip = node_key(&graph, "IpAddress");
+        let bound_port = node_key(&graph, "IpPort");
+        assert_eq!(graph.nodes.len(), 5);
+
+        assert_eq!(
+            property(&graph, bound_ip, "ip_address").as_deref(),
+            Some("10.0.0.5")
+        );
+        for node_key in &[inbound, bound_port] {
+            assert_eq!(property(&graph, node_key, "port").as_deref(), Some("8080"));
+        }
+
+        assert_eq!(
+            edges(&graph, "inbound_connections"),
+            vec![(process, inbound)]
+        );
+        assert_eq!(edges(&graph, "bound_port"), vec![(inbound, bound_port)]);
+        assert_eq!(edges(&graph, "bound_ip"), vec![(inbound, bound_ip)]);
+        assert_eq!(edges(&graph, "asset_ip"), vec![(asset, bound_ip)]);
+        assert_eq!(edges(&graph, "process_asset"), vec![(process, asset)]);
+    }
+}
@@ -0,0 +1,173 @@
+use endpoint_plugin::{
+    IIpAddressNode,
+    IIpPortNode,
+    IProcessInboundConnectionNode,
+    IProcessOutboundConnectionNode,
+    IpAddressNode,
+    IpPortNode,
+    ProcessInboundConnectionNode,
+    ProcessOutboundConnectionNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+use super::asset_and_process;
+use crate::{
+    events::NetworkEvent,
+    generator::AuditdGeneratorError,
+};
+
+/// auditd doesn't record the socket's type, so every connection is taken to be TCP
+const PROTOCOL: &str = "tcp";
+
+/// Creates a subgraph describing a `NetworkEvent` of a process connecting a socket
+///
+/// auditd only records the address connected to, so unlike the sysmon-generator's outbound
+/// connections, the connection is identified by its destination.
+/// * An `Asset` node - indicating the asset on which the process connected
+/// * A `Process` node - indicating the process which connected
+/// * A subject `OutboundConnection` node - indicating the connection made by the process
+/// * A destination IP Port node
+pub fn generate_connect_subgraph(
+    connect: &NetworkEvent,
+) -> Result<GraphDescription, AuditdGeneratorError> {
+    let syscall = &connect.syscall;
+    let dst_ip_address = connect.address.ip().to_string();
+    let dst_port = connect.address.port() as u64;
+
+    let mut graph = GraphDescription::new();
+
+    let (asset, process) = asset_and_process(syscall);
+
+    let mut outbound =
+        ProcessOutboundConnectionNode::new(ProcessOutboundConnectionNode::identity_strategy());
+    outbound
+        .with_asset_id(syscall.hostname.clone())
+        .with_hostname(syscall.hostname.clone())
+        .with_ip_address(dst_ip_address.clone())
+        .with_protocol(PROTOCOL)
+        .with_port(dst_port)
+        .with_created_timestamp(syscall.timestamp);
+
+    let mut dst = IpPortNode::new(IpPortNode::identity_strategy());
+    dst.with_ip_address(dst_ip_address)
+        .with_port(dst_port)
+        .with_protocol(PROTOCOL);
+
+    // A process spawns on an asset
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    // A process creates a connection
+    graph.try_add_edge(
+        "created_connections",
+        process.clone_node_key(),
+        outbound.clone_node_key(),
+    )?;
+
+    // The outbound process connection is to a dst ip + port
+    graph.try_add_edge(
+        "connected_to",
+        outbound.clone_node_key(),
+        dst.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(outbound);
+    graph.add_node(dst);
+
+    Ok(graph)
+}
+
+/// Creates a subgraph describing a `NetworkEvent` of a process binding a socket
+///
+/// The subgraph generated is similar to the graph the sysmon-generator generates for inbound
+/// connections:
+/// * An `Asset` node - indicating the asset on which the process bound the socket
+/// * A `Process` node - indicating the process which bound the socket
+/// * A subject `InboundConnection` node - indicating the port the process is bound to
+/// * The bound IP Address and Port nodes
+///
+/// Binding to port 0 leaves the kernel to pick the port, which auditd doesn't record, so those
+/// binds have an empty subgraph.
+pub fn generate_bind_subgraph(
+    bind: &NetworkEvent,
+) -> Result<GraphDescription, AuditdGeneratorError> {
+    let syscall = &bind.syscall;
+    let ip_address = bind.address.ip().to_string();
+    let port = bind.address.port() as u64;
+
+    let mut graph = GraphDescription::new();
+
+    if port == 0 {
+        return Ok(graph);
+    }
+
+    let (asset, process) = asset_and_process(syscall);
+
+    let mut inbound =
+        ProcessInboundConnectionNode::new(ProcessInboundConnectionNode::identity_strategy());
+    inbound
+        .with_asset_id(syscall.hostname.clone())
+        .with_port(port)
+        .with_ip_address(ip_address.clone())
+        .with_protocol(PROTOCOL)
+        .with_created_timestamp(syscall.timestamp);
+
+    let mut bound_ip = IpAddressNode::new(IpAddressNode::identity_strategy());
+    bound_ip
+        .with_ip_address(ip_address.clone())
+        .with_last_seen_timestamp(syscall.timestamp);
+
+    let mut bound_port = IpPortNode::new(IpPortNode::identity_strategy());
+    bound_port
+        .with_ip_address(ip_address)
+        .with_port(port)
+        .with_protocol(PROTOCOL);
+
+    // An asset is assigned an IP, unless the socket is bound to every address
+    if !bind.address.ip().is_unspecified() {
+        graph.try_add_edge(
+            "asset_ip",
+            asset.clone_node_key(),
+            bound_ip.clone_node_key(),
+        )?;
+    }
+
+    // A process spawns on an asset
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    // A process binds a port
+    graph.try_add_edge(
+        "inbound_connections",
+        process.clone_node_key(),
+        inbound.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "bound_port",
+        inbound.clone_node_key(),
+        bound_port.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "bound_ip",
+        inbound.clone_node_key(),
+        bound_ip.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(inbound);
+    graph.add_node(bound_ip);
+    graph.add_node(bound_port);
+
+    Ok(graph)
+}
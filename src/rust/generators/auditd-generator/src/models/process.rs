@@ -0,0 +1,82 @@
+use endpoint_plugin::{
+    FileNode,
+    IFileNode,
+    IProcessNode,
+    ProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+use super::asset_and_process;
+use crate::{
+    events::ExecEvent,
+    generator::AuditdGeneratorError,
+};
+
+/// Creates a graph describing an `ExecEvent`
+///
+/// Graph generation for an `ExecEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which the binary was executed
+/// * A parent `Process` node - indicating the parent of the executing process
+/// * A subject `Process` node - indicating the process executing the binary
+/// * A process `File` node - indicating the binary that was executed, when auditd knows it
+pub fn generate_process_exec_subgraph(
+    exec: &ExecEvent,
+) -> Result<GraphDescription, AuditdGeneratorError> {
+    let syscall = &exec.syscall;
+    let mut graph = GraphDescription::new();
+
+    let (asset, mut child) = asset_and_process(syscall);
+    child.with_created_timestamp(syscall.timestamp);
+
+    let mut parent = ProcessNode::new(ProcessNode::session_strategy());
+    parent
+        .with_asset_id(syscall.hostname.clone())
+        .with_process_id(syscall.ppid)
+        .with_last_seen_timestamp(syscall.timestamp);
+
+    if !exec.arguments.is_empty() {
+        child.with_process_command_line(exec.arguments.join(" "));
+    }
+
+    graph.try_add_edge(
+        "process_asset",
+        parent.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "process_asset",
+        child.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge("children", parent.clone_node_key(), child.clone_node_key())?;
+
+    if let Some(exe) = &syscall.exe {
+        let mut child_exe = FileNode::new(FileNode::session_strategy());
+        child_exe
+            .with_asset_id(syscall.hostname.clone())
+            .with_last_seen_timestamp(syscall.timestamp)
+            .with_file_path(exe.clone());
+
+        graph.try_add_edge(
+            "bin_file",
+            child.clone_node_key(),
+            child_exe.clone_node_key(),
+        )?;
+
+        graph.try_add_edge(
+            "files_on_asset",
+            asset.clone_node_key(),
+            child_exe.clone_node_key(),
+        )?;
+
+        graph.add_node(child_exe);
+    }
+
+    graph.add_node(asset);
+    graph.add_node(parent);
+    graph.add_node(child);
+
+    Ok(graph)
+}
@@ -0,0 +1,227 @@
+use std::str::FromStr;
+
+use crate::error::AuditdParseError;
+
+/// A single line of the audit log
+///
+/// ```text
+/// node=web-1 type=SYSCALL msg=audit(1603400082.123:2345): arch=c000003e syscall=59 success=yes ...
+/// ```
+///
+/// Every record of an event shares the event's `msg=audit(<time>:<serial>)` header. Logs
+/// written with `log_format = ENRICHED` have interpreted fields appended after a `0x1d`
+/// separator, which are ignored, as the raw fields are always logged too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    /// The host the record was logged on, when auditd is configured with a `name_format`
+    pub node: Option<String>,
+    pub record_type: String,
+    /// Milliseconds since the epoch
+    pub timestamp: u64,
+    pub serial: u64,
+    fields: Vec<(String, String)>,
+}
+
+impl Record {
+    /// A field's value as it was logged, quotes and all
+    pub fn raw(&self, field: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// A string field's value, decoded
+    pub fn string(&self, field: &str) -> Option<String> {
+        self.raw(field).and_then(decode_string)
+    }
+
+    /// A field that must be present, parsed
+    pub fn parse<T: FromStr>(&self, field: &str) -> Result<T, AuditdParseError> {
+        let value = self.raw(field).ok_or_else(|| self.missing(field))?;
+        value.parse().map_err(|_| self.invalid(field, value))
+    }
+
+    pub(crate) fn missing(&self, field: &str) -> AuditdParseError {
+        AuditdParseError::MissingField {
+            record_type: self.record_type.clone(),
+            serial: self.serial,
+            field: field.to_string(),
+        }
+    }
+
+    pub(crate) fn invalid(&self, field: &str, value: &str) -> AuditdParseError {
+        AuditdParseError::InvalidField {
+            record_type: self.record_type.clone(),
+            serial: self.serial,
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl FromStr for Record {
+    type Err = AuditdParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.split('\x1d').next().unwrap_or(line).trim_end();
+        let malformed = || AuditdParseError::MalformedRecord(line.to_string());
+
+        let mut node = None;
+        let mut record_type = None;
+        let mut header = None;
+        let mut fields = Vec::new();
+
+        for (name, value) in tokenize(line) {
+            match name {
+                "node" if header.is_none() => node = Some(value.to_string()),
+                "type" if header.is_none() => record_type = Some(value.to_string()),
+                "msg" if header.is_none() => {
+                    header = Some(parse_header(value).ok_or_else(malformed)?)
+                }
+                _ => fields.push((name.to_string(), value.to_string())),
+            }
+        }
+
+        let (timestamp, serial) = header.ok_or_else(malformed)?;
+
+        Ok(Record {
+            node,
+            record_type: record_type.ok_or_else(malformed)?,
+            timestamp,
+            serial,
+            fields,
+        })
+    }
+}
+
+/// Splits a record into its `name=value` fields, keeping quoted values whole
+fn tokenize(line: &str) -> Vec<(&str, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+
+    while !rest.is_empty() {
+        let (token, remaining) = rest.split_at(token_end(rest));
+        if let Some(separator) = token.find('=') {
+            tokens.push((&token[..separator], &token[separator + 1..]));
+        }
+        rest = remaining.trim_start();
+    }
+
+    tokens
+}
+
+fn token_end(s: &str) -> usize {
+    let mut quote = None;
+
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => return i,
+            None => {}
+        }
+    }
+
+    s.len()
+}
+
+/// Parses the `audit(1603400082.123:2345):` header every record starts with, into its time in
+/// milliseconds and the serial number of its event
+fn parse_header(header: &str) -> Option<(u64, u64)> {
+    let header = header.strip_prefix("audit(")?;
+    let header = header
+        .strip_suffix("):")
+        .or_else(|| header.strip_suffix(')'))?;
+
+    let (time, serial) = header.split_once(':')?;
+    let (seconds, fraction) = time.split_once('.').unwrap_or((time, "0"));
+
+    let seconds: u64 = seconds.parse().ok()?;
+    let millis: u64 = format!("{:0<3.3}", fraction).parse().ok()?;
+
+    Some((seconds * 1000 + millis, serial.parse().ok()?))
+}
+
+/// Decodes a string field
+///
+/// auditd quotes the strings it logs as they are, and hex-encodes those with spaces, quotes or
+/// control characters in them. Strings that aren't set are logged as `(null)` or `?`.
+pub(crate) fn decode_string(value: &str) -> Option<String> {
+    match value {
+        "(null)" | "?" => None,
+        quoted if quoted.len() >= 2 && quoted.starts_with('"') && quoted.ends_with('"') => {
+            Some(quoted[1..quoted.len() - 1].to_string())
+        }
+        encoded => Some(match decode_hex(encoded) {
+            Some(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            None => encoded.to_string(),
+        }),
+    }
+}
+
+pub(crate) fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.is_empty() || value.len() % 2 != 0 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_syscall_record() {
+        let record = Record::from_str(
+            r#"node=web-1 type=SYSCALL msg=audit(1603400082.123:2345): arch=c000003e syscall=59 success=yes exit=0 ppid=8753 pid=9355 comm="ls" exe="/usr/bin/ls" key=(null)"#,
+        )
+        .expect("failed to parse record");
+
+        assert_eq!(record.node.as_deref(), Some("web-1"));
+        assert_eq!(record.record_type, "SYSCALL");
+        assert_eq!(record.timestamp, 1603400082123);
+        assert_eq!(record.serial, 2345);
+        assert_eq!(record.parse::<u64>("pid"), Ok(9355));
+        assert_eq!(record.string("exe").as_deref(), Some("/usr/bin/ls"));
+        assert_eq!(record.string("key"), None);
+    }
+
+    #[test]
+    fn strings_are_decoded() {
+        let record = Record::from_str(
+            r#"type=EXECVE msg=audit(1603400082.123:2345): argc=3 a0="ls" a1="-la" a2=2F746D702F6D7920646972"#,
+        )
+        .expect("failed to parse record");
+
+        assert_eq!(record.node, None);
+        assert_eq!(record.string("a1").as_deref(), Some("-la"));
+        assert_eq!(record.string("a2").as_deref(), Some("/tmp/my dir"));
+    }
+
+    #[test]
+    fn quoted_values_are_kept_whole() {
+        let record = Record::from_str(
+            "type=USER_LOGIN msg=audit(1603400082.123:2346): pid=811 uid=0 msg='op=login acct=\"root\" res=success'\x1dUID=\"root\"",
+        )
+        .expect("failed to parse record");
+
+        assert_eq!(record.serial, 2346);
+        assert_eq!(
+            record.raw("msg"),
+            Some("'op=login acct=\"root\" res=success'")
+        );
+        assert_eq!(record.raw("UID"), None);
+    }
+
+    #[test]
+    fn reject_records_without_headers() {
+        assert!(Record::from_str("type=SYSCALL arch=c000003e syscall=59").is_err());
+        assert!(Record::from_str("type=SYSCALL msg=audit(160340008x:2345): syscall=59").is_err());
+    }
+}
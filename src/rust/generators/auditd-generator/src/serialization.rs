@@ -0,0 +1,158 @@
+use std::{
+    convert::TryFrom,
+    str::FromStr,
+};
+
+use grapl_service::decoder::decompress::PayloadDecompressionError;
+use sqs_executor::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_decoder::PayloadDecoder,
+};
+
+use crate::{
+    error::AuditdParseError,
+    events::{
+        correlate,
+        AuditEvent,
+    },
+    records::Record,
+};
+
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum AuditdDecoderError {
+    #[error("DeserializeError")]
+    DeserializeError(String),
+    #[error("DecompressionError")]
+    DecompressionError(#[from] PayloadDecompressionError),
+}
+
+impl CheckedError for AuditdDecoderError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::DeserializeError(_) => Recoverable::Persistent,
+            Self::DecompressionError(_) => Recoverable::Persistent,
+        }
+    }
+}
+
+/// Decodes newline-delimited auditd logs into an event per audit serial
+///
+/// Records are correlated within a single payload, so an event whose records are split across
+/// payloads is not reassembled. Events for syscalls we don't graph are skipped.
+///
+/// auditd only logs which host a record came from when it's configured with a `name_format`, as
+/// `node=`. Records without one are attributed to the default hostname, when there is one.
+#[derive(Debug, Clone, Default)]
+pub struct AuditdDecoder {
+    default_hostname: Option<String>,
+}
+
+impl AuditdDecoder {
+    pub fn new(default_hostname: Option<String>) -> Self {
+        Self { default_hostname }
+    }
+
+    /// Reads the default hostname from `AUDITD_DEFAULT_HOSTNAME`, if set
+    pub fn from_env() -> Self {
+        let default_hostname = std::env::var("AUDITD_DEFAULT_HOSTNAME")
+            .ok()
+            .filter(|hostname| !hostname.is_empty());
+        Self::new(default_hostname)
+    }
+}
+
+impl PayloadDecoder<Vec<AuditEvent>> for AuditdDecoder {
+    type DecoderError = AuditdDecoderError;
+
+    fn decode(&mut self, body: Vec<u8>) -> Result<Vec<AuditEvent>, Self::DecoderError> {
+        let decompressed = grapl_service::decoder::decompress::maybe_decompress(body.as_slice())?;
+        let decompressed = String::from_utf8_lossy(&decompressed);
+
+        let mut first_deserialization_error: Option<AuditdDecoderError> = None;
+        let mut record_error = |error: AuditdParseError| {
+            tracing::error!(message="Unable to deserialize audit event.", error=?error);
+
+            if first_deserialization_error.is_none() {
+                first_deserialization_error =
+                    Some(AuditdDecoderError::DeserializeError(error.to_string()));
+            }
+        };
+
+        let mut records = Vec::new();
+        for line in decompressed.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match Record::from_str(line) {
+                Ok(mut record) => {
+                    if record.node.is_none() {
+                        record.node = self.default_hostname.clone();
+                    }
+                    records.push(record)
+                }
+                Err(error) => record_error(error),
+            }
+        }
+
+        let mut events = Vec::new();
+        for event_records in correlate(records) {
+            match AuditEvent::try_from(event_records) {
+                Ok(event) => events.push(event),
+                Err(AuditdParseError::UnsupportedEvent {
+                    serial,
+                    description,
+                }) => {
+                    tracing::debug!(message="Skipping unsupported audit event.", serial=%serial, description=%description);
+                }
+                Err(error) => record_error(error),
+            }
+        }
+
+        // As with the other decoders, we only fail the payload if nothing in it could be decoded
+        match first_deserialization_error {
+            Some(error) if events.is_empty() => Err(error),
+            _ => Ok(events),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXECVE_WITHOUT_NODE: &str = r#"type=SYSCALL msg=audit(1603400082.123:2345): arch=c000003e syscall=59 success=yes exit=0 ppid=8753 pid=9355 comm="ls" exe="/usr/bin/ls"
+type=EXECVE msg=audit(1603400082.123:2345): argc=2 a0="ls" a1="-la"
+type=EOE msg=audit(1603400082.123:2345):"#;
+
+    #[test]
+    fn records_without_node_use_the_default_hostname() {
+        let events = AuditdDecoder::new(Some("web-1".to_string()))
+            .decode(EXECVE_WITHOUT_NODE.as_bytes().to_vec())
+            .expect("failed to decode events");
+
+        match events.as_slice() {
+            [AuditEvent::ProcessExec(exec)] => assert_eq!(exec.syscall.hostname, "web-1"),
+            events => panic!(
+                "expected a single AuditEvent::ProcessExec, got {:?}",
+                events
+            ),
+        }
+    }
+
+    #[test]
+    fn records_without_node_or_default_hostname_fail() {
+        match AuditdDecoder::default().decode(EXECVE_WITHOUT_NODE.as_bytes().to_vec()) {
+            Err(AuditdDecoderError::DeserializeError(error)) => {
+                assert_eq!(error, "SYSCALL record of event 2345 is missing node")
+            }
+            events => panic!(
+                "expected AuditdDecoderError::DeserializeError, got {:?}",
+                events
+            ),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::{
+    convert::TryFrom,
+    str::FromStr,
+};
+
+use crate::{
+    events::{
+        correlate,
+        AuditEvent,
+    },
+    records::Record,
+};
+
+pub(crate) fn read_records(log: &str) -> Vec<Record> {
+    log.lines()
+        .map(|line| Record::from_str(line).expect("failed to parse record"))
+        .collect()
+}
+
+/// Reads one of the unit samples, each of which holds a single event
+pub(crate) fn read_unit_sample(name: &str) -> AuditEvent {
+    let log = std::fs::read_to_string(format!("sample_data/unit/{}.log", name))
+        .expect("unable to read test file.");
+
+    let mut events = correlate(read_records(&log));
+    assert_eq!(events.len(), 1);
+
+    AuditEvent::try_from(events.remove(0)).expect("failed to parse event")
+}
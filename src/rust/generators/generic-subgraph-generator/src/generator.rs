@@ -1,7 +1,10 @@
 use std::convert::TryFrom;
 
 use async_trait::async_trait;
-use grapl_graph_descriptions::graph_description::*;
+use grapl_graph_descriptions::{
+    graph_description::*,
+    MergeError,
+};
 use sqs_executor::{
     cache::Cache,
     errors::{
@@ -22,11 +25,16 @@ use crate::models::GenericEvent;
 pub enum GenericSubgraphGeneratorError {
     #[error("Unexpected")]
     Unexpected(String),
+    #[error("MergeError")]
+    MergeError(#[from] MergeError),
 }
 
 impl CheckedError for GenericSubgraphGeneratorError {
     fn error_type(&self) -> Recoverable {
-        Recoverable::Transient
+        match self {
+            Self::Unexpected(_) => Recoverable::Transient,
+            Self::MergeError(_) => Recoverable::Persistent,
+        }
     }
 }
 
@@ -81,7 +89,7 @@ impl<C: Cache> GenericSubgraphGenerator<C> {
         Result<(GraphDescription, GenericSubgraphGeneratorError), GenericSubgraphGeneratorError>,
     > {
         let mut final_subgraph = GraphDescription::new();
-        let mut failed: Option<eyre::Report> = None;
+        let mut failed: Option<GenericSubgraphGeneratorError> = None;
 
         // Skip events we've successfully processed and stored in the event cache.
         let events = self.cache.filter_cached(&events).await;
@@ -93,23 +101,24 @@ impl<C: Cache> GenericSubgraphGenerator<C> {
                 Ok(subgraph) => subgraph,
                 Err(e) => {
                     error!("Failed to generate subgraph with: {}", e);
-                    failed = Some(eyre::Report::msg(e));
+                    failed = Some(GenericSubgraphGeneratorError::Unexpected(e));
                     continue;
                 }
             };
 
-            completed.add_identity(identity, EventStatus::Success);
-            final_subgraph.merge(&subgraph);
+            match final_subgraph.try_merge(&subgraph) {
+                Ok(()) => completed.add_identity(identity, EventStatus::Success),
+                Err(e) => {
+                    error!("Failed to merge subgraph with: {}", e);
+                    completed.add_identity(identity, EventStatus::Failure);
+                    failed = Some(e.into());
+                }
+            }
         }
 
         match failed {
-            Some(e) if final_subgraph.is_empty() => Err(Err(
-                GenericSubgraphGeneratorError::Unexpected(e.to_string()),
-            )),
-            Some(e) => Err(Ok((
-                final_subgraph,
-                GenericSubgraphGeneratorError::Unexpected(e.to_string()),
-            ))),
+            Some(e) if final_subgraph.is_empty() => Err(Err(e)),
+            Some(e) => Err(Ok((final_subgraph, e))),
             None => Ok(final_subgraph),
         }
     }
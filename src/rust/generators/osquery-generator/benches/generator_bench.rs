@@ -3,10 +3,12 @@ use criterion::{
     criterion_main,
     Criterion,
 };
-use grapl_service::decoder::ndjson::NdjsonDecoder;
+use grapl_service::decoder::decompress::PayloadDecompression;
 use osquery_generator_lib::{
     generator::OSQueryGenerator,
     metrics::OSQueryGeneratorMetrics,
+    parsers::OSQueryRoutes,
+    serialization::OSQueryDecoder,
 };
 use sqs_executor::{
     cache::NopCache,
@@ -41,12 +43,16 @@ fn bench_osquery_generator_1000_events(c: &mut Criterion) {
             .await
             .expect("Unable to read osquery sample data into test.");
 
-        NdjsonDecoder::default()
-            .decode(test_data_bytes)
-            .expect("Failed to decode raw data.") // error only occurs on decompression
-            .into_iter()
-            .take(1_000)
-            .collect()
+        OSQueryDecoder::new(
+            OSQueryRoutes::default(),
+            OSQueryGeneratorMetrics::new("OSQUERY_TEST"),
+            PayloadDecompression::from_env(),
+        )
+        .decode(test_data_bytes)
+        .expect("Failed to decode raw data.") // error only occurs on decompression
+        .into_iter()
+        .take(1_000)
+        .collect()
     });
 
     c.bench_function("OSQuery Generator - 1000 Events", |bencher| {
@@ -1,5 +1,10 @@
+use std::convert::TryFrom;
+
 use async_trait::async_trait;
-use grapl_graph_descriptions::graph_description::*;
+use grapl_graph_descriptions::{
+    graph_description::*,
+    MergeError,
+};
 use sqs_executor::{
     cache::Cache,
     errors::{
@@ -15,7 +20,10 @@ use sqs_executor::{
 
 use crate::{
     metrics::OSQueryGeneratorMetrics,
-    parsers::OSQueryEvent,
+    parsers::{
+        OSQueryEvent,
+        OSQueryEventError,
+    },
 };
 
 #[derive(Clone)]
@@ -37,11 +45,19 @@ where
 }
 
 #[derive(thiserror::Error, Debug)]
-pub enum OSQueryGeneratorError {}
+pub enum OSQueryGeneratorError {
+    #[error("InvalidEvent: {0}")]
+    InvalidEvent(#[from] OSQueryEventError),
+    #[error("MergeError")]
+    MergeError(#[from] MergeError),
+}
 
 impl CheckedError for OSQueryGeneratorError {
     fn error_type(&self) -> Recoverable {
-        Recoverable::Persistent
+        match self {
+            Self::InvalidEvent(_) => Recoverable::Persistent,
+            Self::MergeError(_) => Recoverable::Persistent,
+        }
     }
 }
 
@@ -65,23 +81,41 @@ impl<C: Cache> EventHandler for OSQueryGenerator<C> {
         // Skip events we've successfully processed and stored in the event cache.
         let events = self.cache.filter_cached(&events).await;
 
-        let final_subgraph = events
-            .into_iter()
-            .map(|event| {
-                completed.add_identity(&event, EventStatus::Success);
-                GraphDescription::from(event)
-            })
-            .fold(GraphDescription::new(), |mut current_graph, subgraph| {
-                current_graph.merge(&subgraph);
-                current_graph
-            });
+        let mut last_error: Option<OSQueryGeneratorError> = None;
+        let mut final_subgraph = GraphDescription::new();
+
+        for event in events {
+            let result = GraphDescription::try_from(event.clone())
+                .map_err(OSQueryGeneratorError::from)
+                .and_then(|subgraph| {
+                    final_subgraph
+                        .try_merge(&subgraph)
+                        .map_err(OSQueryGeneratorError::from)
+                });
+            self.metrics.report_subgraph_generation(&result);
+            match result {
+                Ok(()) => completed.add_identity(&event, EventStatus::Success),
+                Err(error) => {
+                    completed.add_identity(&event, EventStatus::Failure);
+                    tracing::error!(message="Failed to generate subgraph.", error=?error);
+                    last_error = Some(error);
+                }
+            }
+        }
 
         tracing::info!(
             message = "Completed mapping subgraphs",
             num_completed = completed.len()
         );
-        self.metrics.report_subgraph_generation();
 
-        Ok(final_subgraph)
+        let final_result = match (last_error, final_subgraph.is_empty()) {
+            (None, _) => Ok(final_subgraph),
+            (Some(error), false) => Err(Ok((final_subgraph, error))),
+            (Some(error), true) => Err(Err(error)),
+        };
+
+        self.metrics.report_handle_event_success(&final_result);
+
+        final_result
     }
 }
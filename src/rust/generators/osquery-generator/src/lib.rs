@@ -1,4 +1,5 @@
 pub mod generator;
 pub mod metrics;
 pub mod parsers;
+pub mod serialization;
 mod tests;
@@ -1,7 +1,10 @@
 use graph_generator_lib::*;
+use grapl_service::decoder::decompress::PayloadDecompression;
 use osquery_generator_lib::{
     generator::OSQueryGenerator,
     metrics::OSQueryGeneratorMetrics,
+    parsers::OSQueryRoutes,
+    serialization::OSQueryDecoder,
 };
 #[tokio::main]
 #[tracing::instrument]
@@ -14,10 +17,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         name =% service_name
     );
 
+    let routes = OSQueryRoutes::from_env()?;
+    let decoder = OSQueryDecoder::new(
+        routes,
+        OSQueryGeneratorMetrics::new(&service_name),
+        PayloadDecompression::from_env(),
+    );
+
     run_graph_generator(
         env,
         move |cache| OSQueryGenerator::new(cache, OSQueryGeneratorMetrics::new(&service_name)),
-        grapl_service::decoder::NdjsonDecoder::default(),
+        decoder,
     )
     .await;
 
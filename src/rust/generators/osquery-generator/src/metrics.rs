@@ -2,9 +2,38 @@ use std::io::Stdout;
 
 use grapl_observe::metric_reporter::{
     common_strs,
+    tag,
     MetricReporter,
     TagPair,
 };
+use sqs_executor::errors::{
+    CheckedError,
+    Recoverable,
+};
+
+pub enum Status {
+    Success,
+    Partial,
+    Failure,
+}
+
+impl Status {
+    fn from_result<T, E>(r: &Result<T, Result<(T, E), E>>) -> Self {
+        match r {
+            Ok(_) => Status::Success,
+            Err(Ok((_, _))) => Status::Partial,
+            Err(Err(_)) => Status::Failure,
+        }
+    }
+
+    fn to_str(&self) -> &'static str {
+        match self {
+            Status::Success => "success",
+            Status::Partial => "partial",
+            Status::Failure => "failure",
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct OSQueryGeneratorMetrics {
@@ -18,12 +47,75 @@ impl OSQueryGeneratorMetrics {
         }
     }
 
-    pub fn report_subgraph_generation(&mut self) {
+    #[tracing::instrument(skip(self, event_result))]
+    pub fn report_handle_event_success<T, E>(
+        &mut self,
+        event_result: &Result<T, Result<(T, E), E>>,
+    ) {
+        let status = Status::from_result(event_result);
+        self.metric_reporter
+            .gauge(
+                "osquery-generator-completion",
+                1.0,
+                &[TagPair(common_strs::STATUS, status.to_str())],
+            )
+            .unwrap_or_else(|e| tracing::warn!(message="Metric failed.", error=?e))
+    }
+
+    /// Reports an event's subgraph being generated, and counts the failures by whether
+    /// they're worth retrying
+    #[tracing::instrument(skip(self, result))]
+    pub fn report_subgraph_generation<T, E: CheckedError>(&mut self, result: &Result<T, E>) {
+        let status = match result {
+            Ok(_) => common_strs::SUCCESS,
+            Err(_) => common_strs::FAIL,
+        };
+
         self.metric_reporter
             .gauge(
                 "osquery-generation",
                 1.0,
-                &[TagPair(common_strs::STATUS, common_strs::SUCCESS)],
+                &[TagPair(common_strs::STATUS, status)],
+            )
+            .unwrap_or_else(|e| tracing::warn!(message="Metric failed.", error=?e));
+
+        if let Err(error) = result {
+            let error_type = match error.error_type() {
+                Recoverable::Transient => "transient",
+                Recoverable::Persistent => "persistent",
+            };
+
+            self.metric_reporter
+                .counter(
+                    "osquery_generator.subgraph_failure.count",
+                    1.0,
+                    None,
+                    &[tag("error_type", error_type)],
+                )
+                .unwrap_or_else(|e| tracing::warn!(message="Metric failed.", error=?e))
+        }
+    }
+
+    /// Counts the rows of a payload that couldn't be parsed, which are skipped
+    pub fn report_malformed_rows(&mut self, count: usize) {
+        self.metric_reporter
+            .counter(
+                "osquery_generator.malformed_row.count",
+                count as f64,
+                None,
+                &[],
+            )
+            .unwrap_or_else(|e| tracing::warn!(message="Metric failed.", error=?e))
+    }
+
+    /// Counts the results of a query no parser is routed for, which are skipped
+    pub fn report_unknown_query(&mut self, query_name: &str, count: usize) {
+        self.metric_reporter
+            .counter(
+                "osquery_generator.unknown_query.count",
+                count as f64,
+                None,
+                &[tag("query", query_name)],
             )
             .unwrap_or_else(|e| tracing::warn!(message="Metric failed.", error=?e))
     }
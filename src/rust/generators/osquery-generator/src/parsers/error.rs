@@ -0,0 +1,15 @@
+/// Why a row osquery logged can't be turned into a graph
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum OSQueryEventError {
+    #[error("Row of {table} is missing {column}")]
+    MissingColumn {
+        table: &'static str,
+        column: &'static str,
+    },
+    #[error("Row of {table} has an invalid {column}: {value}")]
+    InvalidColumn {
+        table: &'static str,
+        column: &'static str,
+        value: String,
+    },
+}
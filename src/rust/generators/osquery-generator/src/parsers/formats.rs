@@ -0,0 +1,237 @@
+use serde::{
+    de::Error as _,
+    Deserialize,
+};
+use serde_json::{
+    Map,
+    Value,
+};
+
+use super::{
+    routes::QueryRoute,
+    OSQueryEvent,
+    OSQueryRoutes,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ResultsLineError {
+    #[error("Malformed results: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No parser is routed for query: {0}")]
+    UnknownQuery(String),
+}
+
+/// The rows a batched differential result adds and removes
+#[derive(Deserialize)]
+struct DiffResults {
+    #[serde(default)]
+    added: Vec<Value>,
+    #[serde(default)]
+    removed: Vec<Value>,
+}
+
+/// Parses a line of osquery's results log into an event per row, by the parser its query is
+/// routed to
+///
+/// osquery logs results in one of three formats, depending on the query and the logger's
+/// configuration:
+/// * event-format differential results, with an `action` and the `columns` of a single row
+/// * snapshots, with every row of the query in `snapshot`
+/// * batched differential results, with the rows added and removed in `diffResults`
+///
+/// Each row is given the line's name, host and time, so every format ends up as the events the
+/// event format would have logged. Snapshot rows are given the `snapshot` action.
+///
+/// A line that can't be read at all is an error, while each row that doesn't fit its parser is
+/// returned as an error of its own, alongside the rows that do.
+///
+/// See https://osquery.readthedocs.io/en/stable/deployment/logging/
+pub fn parse_results_line(
+    line: &[u8],
+    routes: &OSQueryRoutes,
+) -> Result<Vec<Result<OSQueryEvent, serde_json::Error>>, ResultsLineError> {
+    let mut result: Map<String, Value> = serde_json::from_slice(line)?;
+
+    let query_name = result
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| serde_json::Error::custom("missing field `name`"))?;
+    let route = routes
+        .route(query_name)
+        .ok_or_else(|| ResultsLineError::UnknownQuery(query_name.to_string()))?;
+
+    if let Some(columns) = result.remove("columns") {
+        let action = result.remove("action").unwrap_or(Value::Null);
+        return Ok(vec![row_event(route, &result, action, columns)]);
+    }
+
+    if let Some(snapshot) = result.remove("snapshot") {
+        let rows: Vec<Value> = serde_json::from_value(snapshot)?;
+
+        return Ok(rows
+            .into_iter()
+            .map(|columns| row_event(route, &result, Value::from("snapshot"), columns))
+            .collect());
+    }
+
+    if let Some(diff_results) = result.remove("diffResults") {
+        let DiffResults { added, removed } = serde_json::from_value(diff_results)?;
+
+        let added = added
+            .into_iter()
+            .map(|columns| row_event(route, &result, Value::from("added"), columns));
+        let removed = removed
+            .into_iter()
+            .map(|columns| row_event(route, &result, Value::from("removed"), columns));
+
+        return Ok(added.chain(removed).collect());
+    }
+
+    Err(serde_json::Error::custom("expected one of `columns`, `snapshot` or `diffResults`").into())
+}
+
+/// Builds the event-format result of a single row, from the rest of the line it was logged in
+fn row_event(
+    route: &QueryRoute,
+    result: &Map<String, Value>,
+    action: Value,
+    columns: Value,
+) -> Result<OSQueryEvent, serde_json::Error> {
+    let mut row = result.clone();
+    row.insert("action".to_string(), action);
+    row.insert("columns".to_string(), route.rename_columns(columns));
+
+    route.parser.parse_row(Value::Object(row))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_results_line,
+        ResultsLineError,
+    };
+    use crate::parsers::{
+        OSQueryEvent,
+        OSQueryRoutes,
+    };
+
+    fn read_sample(path: &str) -> Vec<u8> {
+        std::fs::read(format!("sample_data/unit/{}", path)).expect("unable to read test file.")
+    }
+
+    fn parse_rows(line: &[u8], routes: &OSQueryRoutes) -> Vec<OSQueryEvent> {
+        parse_results_line(line, routes)
+            .expect("parse_results_line failed.")
+            .into_iter()
+            .map(|row| row.expect("failed to parse row."))
+            .collect()
+    }
+
+    #[test]
+    fn parse_event_format() {
+        let events = parse_rows(
+            &read_sample("pack_grapl_processes.json"),
+            &OSQueryRoutes::default(),
+        );
+
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            OSQueryEvent::Process(_) => {}
+            _ => panic!("expected OSQueryEvent::Process"),
+        };
+    }
+
+    #[test]
+    fn parse_snapshot_format() {
+        let events = parse_rows(
+            &read_sample("snapshot_listening_ports.json"),
+            &OSQueryRoutes::default(),
+        );
+
+        assert_eq!(events.len(), 3);
+        for event in events {
+            match event {
+                OSQueryEvent::ListeningPort(_) => {}
+                _ => panic!("expected OSQueryEvent::ListeningPort"),
+            };
+        }
+    }
+
+    #[test]
+    fn parse_batched_differential_format() {
+        let events = parse_rows(
+            &read_sample("diff_results_processes.json"),
+            &OSQueryRoutes::default(),
+        );
+
+        assert_eq!(events.len(), 3);
+        for event in events {
+            match event {
+                OSQueryEvent::Process(_) => {}
+                _ => panic!("expected OSQueryEvent::Process"),
+            };
+        }
+    }
+
+    #[test]
+    fn reject_lines_without_rows() {
+        let line = br#"{"name": "pack_grapl_processes", "hostIdentifier": "host", "calendarTime": "Thu Oct 22 20:54:42 2020 UTC", "unixTime": 1603400082}"#;
+
+        assert!(parse_results_line(line, &OSQueryRoutes::default()).is_err());
+    }
+
+    #[test]
+    fn rows_fail_to_parse_individually() {
+        let line = String::from_utf8(read_sample("snapshot_listening_ports.json"))
+            .unwrap()
+            .replacen(r#""port": "22""#, r#""port": "ssh""#, 1);
+
+        let rows = parse_results_line(line.as_bytes(), &OSQueryRoutes::default())
+            .expect("parse_results_line failed.");
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows.iter().filter(|row| row.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn parse_routed_query_with_renamed_columns() {
+        let routes = OSQueryRoutes::from_json(
+            r#"{
+                "queries": {
+                    "pack_myorg_procs": {
+                        "parser": "processes",
+                        "columns": { "process_path": "path", "ppid": "parent" }
+                    }
+                }
+            }"#,
+        )
+        .expect("failed to parse routes");
+        let line = String::from_utf8(read_sample("pack_grapl_processes.json"))
+            .unwrap()
+            .replace("pack_grapl_processes", "pack_myorg_procs")
+            .replace(r#""path""#, r#""process_path""#)
+            .replace(r#""parent""#, r#""ppid""#);
+
+        let events = parse_rows(line.as_bytes(), &routes);
+
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            OSQueryEvent::Process(_) => {}
+            _ => panic!("expected OSQueryEvent::Process"),
+        };
+    }
+
+    #[test]
+    fn reject_unknown_queries() {
+        let line = String::from_utf8(read_sample("pack_grapl_processes.json"))
+            .unwrap()
+            .replace("pack_grapl_processes", "pack_myorg_procs");
+
+        match parse_results_line(line.as_bytes(), &OSQueryRoutes::default()) {
+            Err(ResultsLineError::UnknownQuery(query_name)) => {
+                assert_eq!(query_name, "pack_myorg_procs")
+            }
+            _ => panic!("expected ResultsLineError::UnknownQuery"),
+        };
+    }
+}
@@ -0,0 +1,282 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IIpAddressNode,
+    IIpPortNode,
+    INetworkConnectionNode,
+    IProcessInboundConnectionNode,
+    IProcessNode,
+    IProcessOutboundConnectionNode,
+    IpAddressNode,
+    IpPortNode,
+    NetworkConnectionNode,
+    ProcessInboundConnectionNode,
+    ProcessNode,
+    ProcessOutboundConnectionNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+
+/// A socket a process has open, as the socket tables report it
+pub(super) struct Connection<'a> {
+    pub host_identifier: &'a str,
+    pub pid: u64,
+    pub protocol: String,
+    pub local_address: &'a str,
+    pub local_port: u64,
+    pub remote_address: &'a str,
+    pub remote_port: u64,
+    /// Set when the row records the socket being opened, rather than it being seen open
+    pub created_timestamp: Option<u64>,
+    /// Set when the row records the socket being closed
+    pub terminated_timestamp: Option<u64>,
+    pub last_seen_timestamp: u64,
+}
+
+impl<'a> Connection<'a> {
+    /// Unix domain sockets have no address
+    pub fn is_network(&self) -> bool {
+        !self.local_address.is_empty()
+    }
+
+    fn has_remote(&self) -> bool {
+        !self.remote_address.is_empty() && self.remote_port != 0
+    }
+
+    /// A process connecting from its local port to a remote port
+    ///
+    /// The subgraph matches the sysmon-generator's outbound connections.
+    pub fn outbound_subgraph(&self) -> GraphDescription {
+        let mut graph = GraphDescription::new();
+
+        let (asset, process) = self.asset_and_process(&mut graph);
+
+        let mut outbound =
+            ProcessOutboundConnectionNode::new(ProcessOutboundConnectionNode::identity_strategy());
+        outbound
+            .with_asset_id(self.host_identifier)
+            .with_hostname(self.host_identifier)
+            .with_ip_address(self.local_address)
+            .with_protocol(self.protocol.clone())
+            .with_port(self.local_port)
+            .with_last_seen_timestamp(self.last_seen_timestamp);
+        if let Some(created_timestamp) = self.created_timestamp {
+            outbound.with_created_timestamp(created_timestamp);
+        }
+        if let Some(terminated_timestamp) = self.terminated_timestamp {
+            outbound.with_terminated_timestamp(terminated_timestamp);
+        }
+
+        let local_ip = self.ip_address(self.local_address);
+        let local_port = self.ip_port(self.local_address, self.local_port);
+
+        graph.add_edge(
+            "asset_ip",
+            asset.clone_node_key(),
+            local_ip.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "created_connections",
+            process.clone_node_key(),
+            outbound.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "connected_over",
+            outbound.clone_node_key(),
+            local_port.clone_node_key(),
+        );
+
+        if self.has_remote() {
+            let remote_ip = self.ip_address(self.remote_address);
+            let remote_port = self.ip_port(self.remote_address, self.remote_port);
+            let network_connection = self.network_connection(
+                (self.local_address, self.local_port),
+                (self.remote_address, self.remote_port),
+            );
+
+            graph.add_edge(
+                "connected_to",
+                outbound.clone_node_key(),
+                remote_port.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "network_connections",
+                local_port.clone_node_key(),
+                network_connection.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "inbound_network_connection_to",
+                network_connection.clone_node_key(),
+                remote_port.clone_node_key(),
+            );
+
+            graph.add_node(remote_ip);
+            graph.add_node(remote_port);
+            graph.add_node(network_connection);
+        }
+
+        graph.add_node(asset);
+        graph.add_node(process);
+        graph.add_node(outbound);
+        graph.add_node(local_ip);
+        graph.add_node(local_port);
+
+        graph
+    }
+
+    /// A process bound to its local port, and accepting a connection from a remote port when
+    /// there is one
+    ///
+    /// The subgraph matches the sysmon-generator's inbound connections.
+    pub fn inbound_subgraph(&self) -> GraphDescription {
+        let mut graph = GraphDescription::new();
+
+        let (asset, process) = self.asset_and_process(&mut graph);
+
+        let mut inbound =
+            ProcessInboundConnectionNode::new(ProcessInboundConnectionNode::identity_strategy());
+        inbound
+            .with_asset_id(self.host_identifier)
+            .with_port(self.local_port)
+            .with_ip_address(self.local_address)
+            .with_protocol(self.protocol.clone())
+            .with_last_seen_timestamp(self.last_seen_timestamp);
+        if let Some(created_timestamp) = self.created_timestamp {
+            inbound.with_created_timestamp(created_timestamp);
+        }
+        if let Some(terminated_timestamp) = self.terminated_timestamp {
+            inbound.with_terminated_timestamp(terminated_timestamp);
+        }
+
+        let local_ip = self.ip_address(self.local_address);
+        let local_port = self.ip_port(self.local_address, self.local_port);
+
+        graph.add_edge(
+            "asset_ip",
+            asset.clone_node_key(),
+            local_ip.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "inbound_connections",
+            process.clone_node_key(),
+            inbound.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "bound_port",
+            inbound.clone_node_key(),
+            local_port.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "bound_ip",
+            inbound.clone_node_key(),
+            local_ip.clone_node_key(),
+        );
+
+        if self.has_remote() {
+            let remote_ip = self.ip_address(self.remote_address);
+            let remote_port = self.ip_port(self.remote_address, self.remote_port);
+            let network_connection = self.network_connection(
+                (self.remote_address, self.remote_port),
+                (self.local_address, self.local_port),
+            );
+
+            graph.add_edge(
+                "network_connections",
+                remote_port.clone_node_key(),
+                network_connection.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "inbound_network_connection_to",
+                network_connection.clone_node_key(),
+                local_port.clone_node_key(),
+            );
+
+            graph.add_node(remote_ip);
+            graph.add_node(remote_port);
+            graph.add_node(network_connection);
+        }
+
+        graph.add_node(asset);
+        graph.add_node(process);
+        graph.add_node(inbound);
+        graph.add_node(local_ip);
+        graph.add_node(local_port);
+
+        graph
+    }
+
+    /// The asset and process every socket is on, which are left to the caller to add
+    fn asset_and_process(&self, graph: &mut GraphDescription) -> (AssetNode, ProcessNode) {
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(self.host_identifier)
+            .with_hostname(self.host_identifier);
+
+        let mut process = ProcessNode::new(ProcessNode::session_strategy());
+        process
+            .with_asset_id(self.host_identifier)
+            .with_process_id(self.pid)
+            .with_last_seen_timestamp(self.last_seen_timestamp);
+
+        graph.add_edge(
+            "asset_processes",
+            asset.clone_node_key(),
+            process.clone_node_key(),
+        );
+
+        (asset, process)
+    }
+
+    fn ip_address(&self, ip_address: &str) -> IpAddressNode {
+        let mut node = IpAddressNode::new(IpAddressNode::identity_strategy());
+        node.with_ip_address(ip_address)
+            .with_last_seen_timestamp(self.last_seen_timestamp);
+        node
+    }
+
+    fn ip_port(&self, ip_address: &str, port: u64) -> IpPortNode {
+        let mut node = IpPortNode::new(IpPortNode::identity_strategy());
+        node.with_ip_address(ip_address)
+            .with_port(port)
+            .with_protocol(self.protocol.clone())
+            .with_last_seen_timestamp(self.last_seen_timestamp);
+        node
+    }
+
+    fn network_connection(
+        &self,
+        (src_ip_address, src_port): (&str, u64),
+        (dst_ip_address, dst_port): (&str, u64),
+    ) -> NetworkConnectionNode {
+        let mut node = NetworkConnectionNode::new(NetworkConnectionNode::identity_strategy());
+        node.with_src_ip_address(src_ip_address)
+            .with_src_port(src_port)
+            .with_dst_ip_address(dst_ip_address)
+            .with_dst_port(dst_port)
+            .with_protocol(self.protocol.clone())
+            .with_last_seen_timestamp(self.last_seen_timestamp);
+        if let Some(created_timestamp) = self.created_timestamp {
+            node.with_created_timestamp(created_timestamp);
+        }
+        if let Some(terminated_timestamp) = self.terminated_timestamp {
+            node.with_terminated_timestamp(terminated_timestamp);
+        }
+        node
+    }
+}
+
+/// The name of an IP protocol number, as osquery reports protocols by number
+pub(super) fn protocol_name(protocol: u64) -> String {
+    match protocol {
+        6 => "tcp".to_string(),
+        17 => "udp".to_string(),
+        protocol => protocol.to_string(),
+    }
+}
@@ -0,0 +1,86 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IIpAddressNode,
+    IpAddressNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::parsers::OSQueryAction;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsResolverEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: DnsResolverEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#dns_resolvers
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct DnsResolverEventColumns {
+    #[serde(rename = "type")]
+    resolver_type: String,
+    address: String,
+}
+
+impl From<DnsResolverEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(dns_resolver_event: DnsResolverEvent) -> Self {
+        tracing::trace!(message = "Building Graph from DnsResolverEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        // `search`, `sortlist` and `options` entries configure how names are resolved, not
+        // who resolves them
+        if dns_resolver_event.columns.resolver_type != "nameserver" {
+            return graph;
+        }
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(dns_resolver_event.host_identifier.clone())
+            .with_hostname(dns_resolver_event.host_identifier.clone());
+
+        let mut resolver = IpAddressNode::new(IpAddressNode::identity_strategy());
+        resolver
+            .with_ip_address(dns_resolver_event.columns.address.clone())
+            .with_first_seen_timestamp(dns_resolver_event.unix_time)
+            .with_last_seen_timestamp(dns_resolver_event.unix_time);
+
+        graph.add_edge(
+            "dns_resolvers",
+            asset.clone_node_key(),
+            resolver.clone_node_key(),
+        );
+
+        graph.add_node(asset);
+        graph.add_node(resolver);
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_dns_resolvers_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_dns_resolvers.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::DnsResolver(_) => {}
+            _ => panic!("expected OSQueryEvent::DnsResolver"),
+        };
+    }
+}
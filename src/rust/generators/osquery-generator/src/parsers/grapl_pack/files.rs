@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use endpoint_plugin::{
     AssetNode,
     FileNode,
@@ -11,7 +13,10 @@ use serde::{
 };
 
 use super::from_str;
-use crate::parsers::OSQueryAction;
+use crate::parsers::{
+    OSQueryAction,
+    OSQueryEventError,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
 #[serde(rename_all = "camelCase")]
@@ -50,11 +55,20 @@ pub(crate) enum OSQueryFileAction {
     Opened,
 }
 
-impl From<FileEvent> for GraphDescription {
+impl TryFrom<FileEvent> for GraphDescription {
+    type Error = OSQueryEventError;
+
     #[tracing::instrument]
-    fn from(file_event: FileEvent) -> Self {
+    fn try_from(file_event: FileEvent) -> Result<Self, Self::Error> {
         tracing::trace!(message = "Building Graph from FileEvent.");
 
+        if file_event.columns.target_path.is_empty() {
+            return Err(OSQueryEventError::MissingColumn {
+                table: "file_events",
+                column: "target_path",
+            });
+        }
+
         let mut graph = GraphDescription::new();
 
         let mut asset = AssetNode::new(AssetNode::static_strategy());
@@ -91,7 +105,7 @@ impl From<FileEvent> for GraphDescription {
         graph.add_node(asset);
         graph.add_node(subject_file);
 
-        graph
+        Ok(graph)
     }
 }
 
@@ -0,0 +1,83 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IKernelModuleNode,
+    KernelModuleNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::from_str;
+use crate::parsers::OSQueryAction;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelModuleEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: KernelModuleEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#kernel_modules
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct KernelModuleEventColumns {
+    name: String,
+    #[serde(deserialize_with = "from_str")]
+    size: u64,
+}
+
+impl From<KernelModuleEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(kernel_module_event: KernelModuleEvent) -> Self {
+        tracing::trace!(message = "Building Graph from KernelModuleEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(kernel_module_event.host_identifier.clone())
+            .with_hostname(kernel_module_event.host_identifier.clone());
+
+        let mut kernel_module = KernelModuleNode::new(KernelModuleNode::static_strategy());
+        kernel_module
+            .with_asset_id(kernel_module_event.host_identifier.clone())
+            .with_module_name(kernel_module_event.columns.name.clone())
+            .with_module_size(kernel_module_event.columns.size)
+            .with_first_seen_timestamp(kernel_module_event.unix_time)
+            .with_last_seen_timestamp(kernel_module_event.unix_time);
+
+        graph.add_edge(
+            "kernel_modules_on_asset",
+            asset.clone_node_key(),
+            kernel_module.clone_node_key(),
+        );
+
+        graph.add_node(asset);
+        graph.add_node(kernel_module);
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_kernel_modules_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_kernel_modules.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::KernelModule(_) => {}
+            _ => panic!("expected OSQueryEvent::KernelModule"),
+        };
+    }
+}
@@ -0,0 +1,89 @@
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    connections::{
+        protocol_name,
+        Connection,
+    },
+    from_str,
+};
+use crate::parsers::OSQueryAction;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningPortEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: ListeningPortEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#listening_ports
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct ListeningPortEventColumns {
+    #[serde(deserialize_with = "from_str")]
+    pid: i64,
+    #[serde(deserialize_with = "from_str")]
+    port: u64,
+    #[serde(deserialize_with = "from_str")]
+    protocol: u64,
+    address: String,
+}
+
+impl From<ListeningPortEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(listening_port_event: ListeningPortEvent) -> Self {
+        tracing::trace!(message = "Building Graph from ListeningPortEvent.");
+
+        let columns = &listening_port_event.columns;
+
+        // osquery can record -1 for a pid when the port's process can't be determined
+        if columns.pid < 0 {
+            return GraphDescription::new();
+        }
+
+        let connection = Connection {
+            host_identifier: &listening_port_event.host_identifier,
+            pid: columns.pid as u64,
+            protocol: protocol_name(columns.protocol),
+            local_address: &columns.address,
+            local_port: columns.port,
+            remote_address: "",
+            remote_port: 0,
+            created_timestamp: None,
+            terminated_timestamp: listening_port_event
+                .action
+                .removed_at(listening_port_event.unix_time),
+            last_seen_timestamp: listening_port_event.unix_time,
+        };
+
+        if !connection.is_network() {
+            return GraphDescription::new();
+        }
+
+        connection.inbound_subgraph()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_listening_ports_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_listening_ports.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::ListeningPort(_) => {}
+            _ => panic!("expected OSQueryEvent::ListeningPort"),
+        };
+    }
+}
@@ -8,9 +8,39 @@ use serde::{
     Deserializer,
 };
 
+use super::routes::OSQueryTable;
+
+mod connections;
+pub mod dns_resolvers;
 pub mod files;
+pub mod kernel_modules;
+pub mod listening_ports;
+pub mod process_events;
 pub mod process_files;
 pub mod processes;
+pub mod sockets;
+pub mod startup_items;
+pub mod users;
+
+/// The queries of Grapl's osquery pack, by name
+pub(super) const QUERIES: &[(&str, OSQueryTable)] = &[
+    ("pack_grapl_processes", OSQueryTable::Processes),
+    ("pack_grapl_process-files", OSQueryTable::ProcessFiles),
+    ("pack_grapl_files", OSQueryTable::Files),
+    ("pack_grapl_process_events", OSQueryTable::ProcessEvents),
+    ("pack_grapl_socket_events", OSQueryTable::SocketEvents),
+    (
+        "pack_grapl_process_open_sockets",
+        OSQueryTable::ProcessOpenSockets,
+    ),
+    ("pack_grapl_listening_ports", OSQueryTable::ListeningPorts),
+    ("pack_grapl_users", OSQueryTable::Users),
+    ("pack_grapl_logged_in_users", OSQueryTable::LoggedInUsers),
+    ("pack_grapl_dns_resolvers", OSQueryTable::DnsResolvers),
+    ("pack_grapl_crontab", OSQueryTable::Crontab),
+    ("pack_grapl_startup_items", OSQueryTable::StartupItems),
+    ("pack_grapl_kernel_modules", OSQueryTable::KernelModules),
+];
 
 fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
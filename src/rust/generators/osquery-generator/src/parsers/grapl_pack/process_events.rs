@@ -0,0 +1,146 @@
+use endpoint_plugin::{
+    AssetNode,
+    FileNode,
+    IAssetNode,
+    IFileNode,
+    IProcessNode,
+    ProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::from_str;
+use crate::parsers::OSQueryAction;
+
+/// A process started, as recorded by osquery's audit-based `process_events`, rather than seen
+/// running as in `processes`
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditProcessEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: AuditProcessEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#process_events
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct AuditProcessEventColumns {
+    #[serde(deserialize_with = "from_str")]
+    pid: u64,
+    path: String,
+    cmdline: String,
+    #[serde(deserialize_with = "from_str")]
+    parent: i64,
+    #[serde(deserialize_with = "from_str")]
+    time: u64,
+}
+
+impl AuditProcessEventColumns {
+    fn process_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+impl From<AuditProcessEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(process_event: AuditProcessEvent) -> Self {
+        tracing::trace!(message = "Building Graph from AuditProcessEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        let process_start_time = process_event.columns.time;
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(process_event.host_identifier.clone())
+            .with_hostname(process_event.host_identifier.clone());
+
+        let mut child = ProcessNode::new(ProcessNode::session_strategy());
+        child
+            .with_asset_id(process_event.host_identifier.clone())
+            .with_created_timestamp(process_start_time)
+            .with_last_seen_timestamp(process_start_time)
+            .with_process_name(process_event.columns.process_name())
+            .with_process_command_line(process_event.columns.cmdline.clone())
+            .with_process_id(process_event.columns.pid);
+
+        if !process_event.columns.path.is_empty() {
+            let mut child_exe = FileNode::new(FileNode::session_strategy());
+            child_exe
+                .with_asset_id(process_event.host_identifier.clone())
+                .with_file_path(process_event.columns.path.clone())
+                .with_last_seen_timestamp(process_start_time);
+
+            graph.add_edge(
+                "bin_file",
+                child.clone_node_key(),
+                child_exe.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "files_on_asset",
+                asset.clone_node_key(),
+                child_exe.clone_node_key(),
+            );
+
+            graph.add_node(child_exe);
+        }
+
+        // osquery can record -1 for a parent when it can't be determined
+        if process_event.columns.parent >= 0 {
+            let mut parent_process = ProcessNode::new(ProcessNode::session_strategy());
+            parent_process
+                .with_asset_id(process_event.host_identifier.clone())
+                .with_process_id(process_event.columns.parent as u64)
+                .with_last_seen_timestamp(process_start_time);
+
+            graph.add_edge(
+                "children",
+                parent_process.clone_node_key(),
+                child.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "asset_processes",
+                asset.clone_node_key(),
+                parent_process.clone_node_key(),
+            );
+
+            graph.add_node(parent_process);
+        }
+
+        graph.add_edge(
+            "asset_processes",
+            asset.clone_node_key(),
+            child.clone_node_key(),
+        );
+
+        graph.add_node(child);
+        graph.add_node(asset);
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_process_events_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_process_events.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::AuditProcess(_) => {}
+            _ => panic!("expected OSQueryEvent::AuditProcess"),
+        };
+    }
+}
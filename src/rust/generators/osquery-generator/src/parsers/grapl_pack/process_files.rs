@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use endpoint_plugin::{
     AssetNode,
     FileNode,
@@ -13,7 +15,10 @@ use serde::{
 };
 
 use super::from_str;
-use crate::parsers::OSQueryAction;
+use crate::parsers::{
+    OSQueryAction,
+    OSQueryEventError,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
 #[serde(rename_all = "camelCase")]
@@ -34,11 +39,20 @@ pub struct ProcessFileInteractionEventColumns {
     pid: u64,
 }
 
-impl From<ProcessFileInteractionEvent> for GraphDescription {
+impl TryFrom<ProcessFileInteractionEvent> for GraphDescription {
+    type Error = OSQueryEventError;
+
     #[tracing::instrument]
-    fn from(process_file_event: ProcessFileInteractionEvent) -> Self {
+    fn try_from(process_file_event: ProcessFileInteractionEvent) -> Result<Self, Self::Error> {
         tracing::trace!(message = "Building Graph from ProcessFileInteractionEvent.");
 
+        if process_file_event.columns.path.is_empty() {
+            return Err(OSQueryEventError::MissingColumn {
+                table: "process_open_files",
+                column: "path",
+            });
+        }
+
         let mut graph = GraphDescription::new();
 
         let mut asset = AssetNode::new(AssetNode::static_strategy());
@@ -97,7 +111,7 @@ impl From<ProcessFileInteractionEvent> for GraphDescription {
         graph.add_node(file);
         graph.add_node(process);
 
-        graph
+        Ok(graph)
     }
 }
 
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use endpoint_plugin::{
     AssetNode,
     FileNode,
@@ -13,7 +15,10 @@ use serde::{
 };
 
 use super::from_str;
-use crate::parsers::OSQueryAction;
+use crate::parsers::{
+    OSQueryAction,
+    OSQueryEventError,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
 #[serde(rename_all = "camelCase")]
@@ -39,19 +44,37 @@ pub struct ProcessEventColumns {
     time: i64,
 }
 
-impl From<ProcessEvent> for GraphDescription {
+impl ProcessEventColumns {
+    /// The process's name, or its binary's when the query doesn't select the name
+    fn process_name(&self) -> Option<&str> {
+        match self.name.as_deref() {
+            Some(name) if !name.is_empty() => Some(name),
+            _ => self.path.rsplit('/').next().filter(|name| !name.is_empty()),
+        }
+    }
+}
+
+impl TryFrom<ProcessEvent> for GraphDescription {
+    type Error = OSQueryEventError;
+
     #[tracing::instrument]
-    fn from(process_event: ProcessEvent) -> Self {
+    fn try_from(process_event: ProcessEvent) -> Result<Self, Self::Error> {
         tracing::trace!(message = "Building Graph from ProcessEvent.");
 
         let mut graph = GraphDescription::new();
 
         // this field can be -1 in cases of error
         // https://osquery.io/schema/4.5.1/#processes
-        let process_start_time = if process_event.columns.time == -1 {
-            process_event.unix_time
-        } else {
-            process_event.columns.time as u64
+        let process_start_time = match process_event.columns.time {
+            -1 => process_event.unix_time,
+            time if time >= 0 => time as u64,
+            time => {
+                return Err(OSQueryEventError::InvalidColumn {
+                    table: "processes",
+                    column: "time",
+                    value: time.to_string(),
+                })
+            }
         };
 
         let mut asset = AssetNode::new(AssetNode::static_strategy());
@@ -64,8 +87,23 @@ impl From<ProcessEvent> for GraphDescription {
             .with_asset_id(process_event.host_identifier.clone())
             .with_created_timestamp(process_start_time)
             .with_last_seen_timestamp(process_start_time)
-            .with_process_name(process_event.columns.name.clone().unwrap_or("".to_string()))
             .with_process_id(process_event.columns.pid);
+        if let Some(process_name) = process_event.columns.process_name() {
+            child.with_process_name(process_name);
+        }
+
+        // A process that's no longer listed has exited since the query last ran
+        match process_event.action {
+            OSQueryAction::Removed => {
+                child
+                    .with_terminated_timestamp(process_event.unix_time)
+                    .with_last_seen_timestamp(process_event.unix_time);
+            }
+            OSQueryAction::Snapshot => {
+                child.with_last_seen_timestamp(process_event.unix_time);
+            }
+            _ => {}
+        };
 
         if !process_event.columns.path.is_empty() {
             let mut child_exe = FileNode::new(FileNode::session_strategy());
@@ -122,13 +160,111 @@ impl From<ProcessEvent> for GraphDescription {
         graph.add_node(child);
         graph.add_node(asset);
 
-        graph
+        Ok(graph)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parsers::OSQueryEvent;
+    use std::convert::TryFrom;
+
+    use grapl_graph_descriptions::graph_description::*;
+
+    use crate::parsers::{
+        parse_results_line,
+        OSQueryEvent,
+        OSQueryEventError,
+        OSQueryRoutes,
+    };
+
+    fn process_property(graph: &GraphDescription, process_id: u64, name: &str) -> Option<String> {
+        graph
+            .nodes
+            .values()
+            .filter(|node| node.node_type == "Process")
+            .find(|node| {
+                node.get_property("process_id").map(|p| p.to_string())
+                    == Some(process_id.to_string())
+            })
+            .and_then(|node| node.get_property(name))
+            .map(|property| property.to_string())
+    }
+
+    #[test]
+    fn removed_processes_are_terminated() {
+        let test_json = std::fs::read("sample_data/unit/diff_results_processes.json")
+            .expect("unable to read test file.");
+
+        let graphs: Vec<GraphDescription> =
+            parse_results_line(&test_json, &OSQueryRoutes::default())
+                .expect("parse_results_line failed.")
+                .into_iter()
+                .map(|row| {
+                    GraphDescription::try_from(row.expect("failed to parse row."))
+                        .expect("try_from failed.")
+                })
+                .collect();
+
+        assert_eq!(
+            process_property(&graphs[1], 8753, "terminated_timestamp"),
+            None
+        );
+        assert_eq!(
+            process_property(&graphs[2], 9301, "terminated_timestamp"),
+            Some("1603400700".to_string())
+        );
+    }
+
+    #[test]
+    fn snapshot_processes_are_last_seen() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_processes.json")
+            .expect("unable to read test file.")
+            .replace(r#""action": "added""#, r#""action": "snapshot""#);
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        let graph = GraphDescription::try_from(event).expect("try_from failed.");
+
+        assert_eq!(
+            process_property(&graph, 9355, "last_seen_timestamp"),
+            Some("1603400082".to_string())
+        );
+        assert_eq!(process_property(&graph, 9355, "terminated_timestamp"), None);
+    }
+
+    #[test]
+    fn unnamed_processes_are_named_after_their_binary() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_processes.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        let graph = GraphDescription::try_from(event).expect("try_from failed.");
+
+        assert_eq!(
+            process_property(&graph, 9355, "process_name"),
+            Some("bash".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_start_times_are_rejected() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_processes.json")
+            .expect("unable to read test file.")
+            .replace(r#""time": "1603399763""#, r#""time": "-5""#);
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+
+        assert_eq!(
+            GraphDescription::try_from(event).err(),
+            Some(OSQueryEventError::InvalidColumn {
+                table: "processes",
+                column: "time",
+                value: "-5".to_string(),
+            })
+        );
+    }
 
     #[test]
     fn parse_pack_grapl_processes_json() {
@@ -0,0 +1,185 @@
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    connections::{
+        protocol_name,
+        Connection,
+    },
+    from_str,
+};
+use crate::parsers::OSQueryAction;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: SocketEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#socket_events
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct SocketEventColumns {
+    action: SocketAction,
+    #[serde(deserialize_with = "from_str")]
+    pid: u64,
+    path: String,
+    #[serde(deserialize_with = "from_str")]
+    protocol: u64,
+    local_address: String,
+    remote_address: String,
+    #[serde(deserialize_with = "from_str")]
+    local_port: u64,
+    #[serde(deserialize_with = "from_str")]
+    remote_port: u64,
+    #[serde(deserialize_with = "from_str")]
+    time: u64,
+    success: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SocketAction {
+    Connect,
+    Bind,
+    Accept,
+}
+
+impl From<SocketEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(socket_event: SocketEvent) -> Self {
+        tracing::trace!(message = "Building Graph from SocketEvent.");
+
+        let columns = &socket_event.columns;
+        let connection = Connection {
+            host_identifier: &socket_event.host_identifier,
+            pid: columns.pid,
+            protocol: protocol_name(columns.protocol),
+            local_address: &columns.local_address,
+            local_port: columns.local_port,
+            remote_address: &columns.remote_address,
+            remote_port: columns.remote_port,
+            created_timestamp: Some(columns.time),
+            terminated_timestamp: None,
+            last_seen_timestamp: columns.time,
+        };
+
+        // Failed syscalls are recorded too, but didn't open anything
+        if columns.success == "0" || !connection.is_network() {
+            return GraphDescription::new();
+        }
+
+        match columns.action {
+            SocketAction::Connect => connection.outbound_subgraph(),
+            SocketAction::Bind | SocketAction::Accept => connection.inbound_subgraph(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessOpenSocketEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: ProcessOpenSocketEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#process_open_sockets
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct ProcessOpenSocketEventColumns {
+    #[serde(deserialize_with = "from_str")]
+    pid: i64,
+    #[serde(deserialize_with = "from_str")]
+    protocol: u64,
+    local_address: String,
+    remote_address: String,
+    #[serde(deserialize_with = "from_str")]
+    local_port: u64,
+    #[serde(deserialize_with = "from_str")]
+    remote_port: u64,
+    #[serde(default)]
+    state: String,
+}
+
+impl From<ProcessOpenSocketEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(socket_event: ProcessOpenSocketEvent) -> Self {
+        tracing::trace!(message = "Building Graph from ProcessOpenSocketEvent.");
+
+        let columns = &socket_event.columns;
+
+        // osquery can record -1 for a pid when the socket's process can't be determined
+        if columns.pid < 0 {
+            return GraphDescription::new();
+        }
+
+        let connection = Connection {
+            host_identifier: &socket_event.host_identifier,
+            pid: columns.pid as u64,
+            protocol: protocol_name(columns.protocol),
+            local_address: &columns.local_address,
+            local_port: columns.local_port,
+            remote_address: &columns.remote_address,
+            remote_port: columns.remote_port,
+            created_timestamp: None,
+            terminated_timestamp: socket_event.action.removed_at(socket_event.unix_time),
+            last_seen_timestamp: socket_event.unix_time,
+        };
+
+        if !connection.is_network() {
+            return GraphDescription::new();
+        }
+
+        // An open socket doesn't say which side initiated it. Servers usually listen on lower
+        // ports than the ephemeral ports clients connect from, so the side with the lower port
+        // is taken to be the server.
+        if columns.state == "LISTEN"
+            || columns.remote_port == 0
+            || columns.local_port < columns.remote_port
+        {
+            connection.inbound_subgraph()
+        } else {
+            connection.outbound_subgraph()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_socket_events_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_socket_events.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::Socket(_) => {}
+            _ => panic!("expected OSQueryEvent::Socket"),
+        };
+    }
+
+    #[test]
+    fn parse_pack_grapl_process_open_sockets_json() {
+        let test_json =
+            std::fs::read_to_string("sample_data/unit/pack_grapl_process_open_sockets.json")
+                .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::ProcessOpenSocket(_) => {}
+            _ => panic!("expected OSQueryEvent::ProcessOpenSocket"),
+        };
+    }
+}
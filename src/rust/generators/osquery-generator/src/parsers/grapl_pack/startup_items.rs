@@ -0,0 +1,234 @@
+use endpoint_plugin::{
+    AssetNode,
+    FileNode,
+    IAssetNode,
+    IFileNode,
+    IStartupItemNode,
+    StartupItemNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::parsers::OSQueryAction;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct CrontabEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: CrontabEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#crontab
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct CrontabEventColumns {
+    event: String,
+    minute: String,
+    hour: String,
+    day_of_month: String,
+    month: String,
+    day_of_week: String,
+    command: String,
+    path: String,
+}
+
+impl CrontabEventColumns {
+    /// The entry's `@reboot`-style event, or its schedule in crontab's own format
+    fn schedule(&self) -> String {
+        if !self.event.is_empty() {
+            return self.event.clone();
+        }
+
+        format!(
+            "{} {} {} {} {}",
+            self.minute, self.hour, self.day_of_month, self.month, self.day_of_week
+        )
+    }
+}
+
+impl From<CrontabEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(crontab_event: CrontabEvent) -> Self {
+        tracing::trace!(message = "Building Graph from CrontabEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(crontab_event.host_identifier.clone())
+            .with_hostname(crontab_event.host_identifier.clone());
+
+        let mut startup_item = StartupItemNode::new(StartupItemNode::static_strategy());
+        startup_item
+            .with_asset_id(crontab_event.host_identifier.clone())
+            .with_item_source(crontab_event.columns.path.clone())
+            .with_item_command(crontab_event.columns.command.clone())
+            .with_item_type("crontab")
+            .with_schedule(crontab_event.columns.schedule())
+            .with_first_seen_timestamp(crontab_event.unix_time)
+            .with_last_seen_timestamp(crontab_event.unix_time);
+
+        let mut crontab = FileNode::new(FileNode::session_strategy());
+        crontab
+            .with_asset_id(crontab_event.host_identifier.clone())
+            .with_file_path(crontab_event.columns.path.clone())
+            .with_last_seen_timestamp(crontab_event.unix_time);
+
+        graph.add_edge(
+            "startup_items_on_asset",
+            asset.clone_node_key(),
+            startup_item.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "startup_item_file",
+            startup_item.clone_node_key(),
+            crontab.clone_node_key(),
+        );
+
+        graph.add_edge(
+            "files_on_asset",
+            asset.clone_node_key(),
+            crontab.clone_node_key(),
+        );
+
+        graph.add_node(asset);
+        graph.add_node(startup_item);
+        graph.add_node(crontab);
+
+        graph
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupItemEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: StartupItemEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#startup_items
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct StartupItemEventColumns {
+    name: String,
+    path: String,
+    #[serde(default)]
+    args: String,
+    #[serde(rename = "type")]
+    item_type: String,
+    source: String,
+    #[serde(default)]
+    username: String,
+}
+
+impl StartupItemEventColumns {
+    fn command(&self) -> String {
+        if self.args.is_empty() {
+            self.path.clone()
+        } else {
+            format!("{} {}", self.path, self.args)
+        }
+    }
+
+    /// Windows' startup items can be configured in the registry rather than in a file
+    fn source_is_file(&self) -> bool {
+        !self.source.is_empty() && !self.source.starts_with("HKEY_")
+    }
+}
+
+impl From<StartupItemEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(startup_item_event: StartupItemEvent) -> Self {
+        tracing::trace!(message = "Building Graph from StartupItemEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(startup_item_event.host_identifier.clone())
+            .with_hostname(startup_item_event.host_identifier.clone());
+
+        let mut startup_item = StartupItemNode::new(StartupItemNode::static_strategy());
+        startup_item
+            .with_asset_id(startup_item_event.host_identifier.clone())
+            .with_item_source(startup_item_event.columns.source.clone())
+            .with_item_command(startup_item_event.columns.command())
+            .with_item_name(startup_item_event.columns.name.clone())
+            .with_item_type(startup_item_event.columns.item_type.clone())
+            .with_user_name(startup_item_event.columns.username.clone())
+            .with_first_seen_timestamp(startup_item_event.unix_time)
+            .with_last_seen_timestamp(startup_item_event.unix_time);
+
+        graph.add_edge(
+            "startup_items_on_asset",
+            asset.clone_node_key(),
+            startup_item.clone_node_key(),
+        );
+
+        if startup_item_event.columns.source_is_file() {
+            let mut source = FileNode::new(FileNode::session_strategy());
+            source
+                .with_asset_id(startup_item_event.host_identifier.clone())
+                .with_file_path(startup_item_event.columns.source.clone())
+                .with_last_seen_timestamp(startup_item_event.unix_time);
+
+            graph.add_edge(
+                "startup_item_file",
+                startup_item.clone_node_key(),
+                source.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "files_on_asset",
+                asset.clone_node_key(),
+                source.clone_node_key(),
+            );
+
+            graph.add_node(source);
+        }
+
+        graph.add_node(asset);
+        graph.add_node(startup_item);
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_crontab_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_crontab.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::Crontab(_) => {}
+            _ => panic!("expected OSQueryEvent::Crontab"),
+        };
+    }
+
+    #[test]
+    fn parse_pack_grapl_startup_items_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_startup_items.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::StartupItem(_) => {}
+            _ => panic!("expected OSQueryEvent::StartupItem"),
+        };
+    }
+}
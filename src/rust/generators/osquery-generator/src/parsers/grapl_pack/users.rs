@@ -0,0 +1,188 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IProcessNode,
+    IUserNode,
+    ProcessNode,
+    UserNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::from_str;
+use crate::parsers::OSQueryAction;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: UserEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#users
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct UserEventColumns {
+    #[serde(deserialize_with = "from_str")]
+    uid: u64,
+    #[serde(deserialize_with = "from_str")]
+    gid: u64,
+    username: String,
+    directory: String,
+    shell: String,
+}
+
+impl From<UserEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(user_event: UserEvent) -> Self {
+        tracing::trace!(message = "Building Graph from UserEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(user_event.host_identifier.clone())
+            .with_hostname(user_event.host_identifier.clone());
+
+        let mut user = UserNode::new(UserNode::static_strategy());
+        user.with_asset_id(user_event.host_identifier.clone())
+            .with_user_name(user_event.columns.username.clone())
+            .with_user_id(user_event.columns.uid)
+            .with_group_id(user_event.columns.gid)
+            .with_home_directory(user_event.columns.directory.clone())
+            .with_shell(user_event.columns.shell.clone())
+            .with_first_seen_timestamp(user_event.unix_time)
+            .with_last_seen_timestamp(user_event.unix_time);
+
+        graph.add_edge(
+            "users_on_asset",
+            asset.clone_node_key(),
+            user.clone_node_key(),
+        );
+
+        graph.add_node(asset);
+        graph.add_node(user);
+
+        graph
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggedInUserEvent {
+    host_identifier: String,
+    calendar_time: String,
+    unix_time: u64,
+    action: OSQueryAction,
+    columns: LoggedInUserEventColumns,
+}
+
+/// See https://osquery.io/schema/4.5.0/#logged_in_users
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct LoggedInUserEventColumns {
+    #[serde(rename = "type")]
+    login_type: String,
+    user: String,
+    #[serde(deserialize_with = "from_str")]
+    pid: i64,
+    #[serde(deserialize_with = "from_str")]
+    time: u64,
+}
+
+impl From<LoggedInUserEvent> for GraphDescription {
+    #[tracing::instrument]
+    fn from(logged_in_user_event: LoggedInUserEvent) -> Self {
+        tracing::trace!(message = "Building Graph from LoggedInUserEvent.");
+
+        let mut graph = GraphDescription::new();
+
+        // Only `user` entries are sessions of a user. The others record the system's logins,
+        // boots and the like.
+        if logged_in_user_event.columns.login_type != "user"
+            || logged_in_user_event.columns.user.is_empty()
+        {
+            return graph;
+        }
+
+        let mut asset = AssetNode::new(AssetNode::static_strategy());
+        asset
+            .with_asset_id(logged_in_user_event.host_identifier.clone())
+            .with_hostname(logged_in_user_event.host_identifier.clone());
+
+        let mut user = UserNode::new(UserNode::static_strategy());
+        user.with_asset_id(logged_in_user_event.host_identifier.clone())
+            .with_user_name(logged_in_user_event.columns.user.clone())
+            .with_last_login_timestamp(logged_in_user_event.columns.time)
+            .with_last_seen_timestamp(logged_in_user_event.unix_time);
+
+        graph.add_edge(
+            "users_on_asset",
+            asset.clone_node_key(),
+            user.clone_node_key(),
+        );
+
+        // The session's process, usually a login shell
+        if logged_in_user_event.columns.pid >= 0 {
+            let mut process = ProcessNode::new(ProcessNode::session_strategy());
+            process
+                .with_asset_id(logged_in_user_event.host_identifier.clone())
+                .with_process_id(logged_in_user_event.columns.pid as u64)
+                .with_last_seen_timestamp(logged_in_user_event.unix_time);
+
+            graph.add_edge(
+                "process_user",
+                process.clone_node_key(),
+                user.clone_node_key(),
+            );
+
+            graph.add_edge(
+                "asset_processes",
+                asset.clone_node_key(),
+                process.clone_node_key(),
+            );
+
+            graph.add_node(process);
+        }
+
+        graph.add_node(asset);
+        graph.add_node(user);
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::OSQueryEvent;
+
+    #[test]
+    fn parse_pack_grapl_users_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_users.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::User(_) => {}
+            _ => panic!("expected OSQueryEvent::User"),
+        };
+    }
+
+    #[test]
+    fn parse_pack_grapl_logged_in_users_json() {
+        let test_json = std::fs::read_to_string("sample_data/unit/pack_grapl_logged_in_users.json")
+            .expect("unable to read test file.");
+
+        let event: OSQueryEvent =
+            serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+        match event {
+            OSQueryEvent::LoggedInUser(_) => {}
+            _ => panic!("expected OSQueryEvent::LoggedInUser"),
+        };
+    }
+}
@@ -1,10 +1,27 @@
+use std::convert::TryFrom;
+
 use grapl_graph_descriptions::graph_description::*;
 use serde::{
     Deserialize,
     Serialize,
 };
 
+mod error;
+mod formats;
 mod grapl_pack;
+mod routes;
+
+pub use error::OSQueryEventError;
+pub use formats::{
+    parse_results_line,
+    ResultsLineError,
+};
+pub use routes::{
+    OSQueryRoutes,
+    OSQueryRoutesError,
+    OSQueryTable,
+    QueryRoute,
+};
 
 #[derive(Serialize, Deserialize, Clone, Hash)]
 #[serde(tag = "name")]
@@ -15,6 +32,26 @@ pub enum OSQueryEvent {
     ProcessFileAction(grapl_pack::process_files::ProcessFileInteractionEvent),
     #[serde(rename = "pack_grapl_files")]
     File(grapl_pack::files::FileEvent),
+    #[serde(rename = "pack_grapl_process_events")]
+    AuditProcess(grapl_pack::process_events::AuditProcessEvent),
+    #[serde(rename = "pack_grapl_socket_events")]
+    Socket(grapl_pack::sockets::SocketEvent),
+    #[serde(rename = "pack_grapl_process_open_sockets")]
+    ProcessOpenSocket(grapl_pack::sockets::ProcessOpenSocketEvent),
+    #[serde(rename = "pack_grapl_listening_ports")]
+    ListeningPort(grapl_pack::listening_ports::ListeningPortEvent),
+    #[serde(rename = "pack_grapl_users")]
+    User(grapl_pack::users::UserEvent),
+    #[serde(rename = "pack_grapl_logged_in_users")]
+    LoggedInUser(grapl_pack::users::LoggedInUserEvent),
+    #[serde(rename = "pack_grapl_dns_resolvers")]
+    DnsResolver(grapl_pack::dns_resolvers::DnsResolverEvent),
+    #[serde(rename = "pack_grapl_crontab")]
+    Crontab(grapl_pack::startup_items::CrontabEvent),
+    #[serde(rename = "pack_grapl_startup_items")]
+    StartupItem(grapl_pack::startup_items::StartupItemEvent),
+    #[serde(rename = "pack_grapl_kernel_modules")]
+    KernelModule(grapl_pack::kernel_modules::KernelModuleEvent),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
@@ -22,15 +59,39 @@ pub enum OSQueryEvent {
 pub(self) enum OSQueryAction {
     Added,
     Removed,
+    /// A row of a snapshot, which only says the row was there when the query ran
+    Snapshot,
     Other(String),
 }
 
-impl From<OSQueryEvent> for GraphDescription {
-    fn from(event: OSQueryEvent) -> Self {
+impl OSQueryAction {
+    /// When a differential row was removed, which osquery records as the time the query ran
+    fn removed_at(&self, unix_time: u64) -> Option<u64> {
+        match self {
+            OSQueryAction::Removed => Some(unix_time),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<OSQueryEvent> for GraphDescription {
+    type Error = OSQueryEventError;
+
+    fn try_from(event: OSQueryEvent) -> Result<Self, Self::Error> {
         match event {
-            OSQueryEvent::File(event) => event.into(),
-            OSQueryEvent::Process(event) => event.into(),
-            OSQueryEvent::ProcessFileAction(event) => event.into(),
+            OSQueryEvent::File(event) => GraphDescription::try_from(event),
+            OSQueryEvent::Process(event) => GraphDescription::try_from(event),
+            OSQueryEvent::ProcessFileAction(event) => GraphDescription::try_from(event),
+            OSQueryEvent::AuditProcess(event) => Ok(event.into()),
+            OSQueryEvent::Socket(event) => Ok(event.into()),
+            OSQueryEvent::ProcessOpenSocket(event) => Ok(event.into()),
+            OSQueryEvent::ListeningPort(event) => Ok(event.into()),
+            OSQueryEvent::User(event) => Ok(event.into()),
+            OSQueryEvent::LoggedInUser(event) => Ok(event.into()),
+            OSQueryEvent::DnsResolver(event) => Ok(event.into()),
+            OSQueryEvent::Crontab(event) => Ok(event.into()),
+            OSQueryEvent::StartupItem(event) => Ok(event.into()),
+            OSQueryEvent::KernelModule(event) => Ok(event.into()),
         }
     }
 }
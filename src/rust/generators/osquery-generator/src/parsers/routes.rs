@@ -0,0 +1,275 @@
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use serde_json::{
+    Map,
+    Value,
+};
+
+use super::{
+    grapl_pack,
+    OSQueryEvent,
+};
+
+/// The tables osquery-generator can parse the results of
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OSQueryTable {
+    Processes,
+    ProcessFiles,
+    Files,
+    ProcessEvents,
+    SocketEvents,
+    ProcessOpenSockets,
+    ListeningPorts,
+    Users,
+    LoggedInUsers,
+    DnsResolvers,
+    Crontab,
+    StartupItems,
+    KernelModules,
+}
+
+impl OSQueryTable {
+    /// Parses an event-format result of this table
+    pub(super) fn parse_row(self, row: Value) -> Result<OSQueryEvent, serde_json::Error> {
+        let event = match self {
+            OSQueryTable::Processes => OSQueryEvent::Process(serde_json::from_value(row)?),
+            OSQueryTable::ProcessFiles => {
+                OSQueryEvent::ProcessFileAction(serde_json::from_value(row)?)
+            }
+            OSQueryTable::Files => OSQueryEvent::File(serde_json::from_value(row)?),
+            OSQueryTable::ProcessEvents => OSQueryEvent::AuditProcess(serde_json::from_value(row)?),
+            OSQueryTable::SocketEvents => OSQueryEvent::Socket(serde_json::from_value(row)?),
+            OSQueryTable::ProcessOpenSockets => {
+                OSQueryEvent::ProcessOpenSocket(serde_json::from_value(row)?)
+            }
+            OSQueryTable::ListeningPorts => {
+                OSQueryEvent::ListeningPort(serde_json::from_value(row)?)
+            }
+            OSQueryTable::Users => OSQueryEvent::User(serde_json::from_value(row)?),
+            OSQueryTable::LoggedInUsers => OSQueryEvent::LoggedInUser(serde_json::from_value(row)?),
+            OSQueryTable::DnsResolvers => OSQueryEvent::DnsResolver(serde_json::from_value(row)?),
+            OSQueryTable::Crontab => OSQueryEvent::Crontab(serde_json::from_value(row)?),
+            OSQueryTable::StartupItems => OSQueryEvent::StartupItem(serde_json::from_value(row)?),
+            OSQueryTable::KernelModules => OSQueryEvent::KernelModule(serde_json::from_value(row)?),
+        };
+
+        Ok(event)
+    }
+}
+
+/// How the results of a query are parsed
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryRoute {
+    /// The table the query selects from, or whose columns it selects
+    pub parser: OSQueryTable,
+    /// Renames the query's columns to the columns of `parser`'s table, by the query's column
+    /// names
+    #[serde(default)]
+    pub columns: HashMap<String, String>,
+}
+
+impl QueryRoute {
+    pub fn new(parser: OSQueryTable) -> Self {
+        Self {
+            parser,
+            columns: HashMap::new(),
+        }
+    }
+
+    pub(super) fn rename_columns(&self, columns: Value) -> Value {
+        match columns {
+            Value::Object(columns) if !self.columns.is_empty() => Value::Object(
+                columns
+                    .into_iter()
+                    .map(|(column, value)| match self.columns.get(&column) {
+                        Some(renamed) => (renamed.clone(), value),
+                        None => (column, value),
+                    })
+                    .collect::<Map<_, _>>(),
+            ),
+            columns => columns,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum OSQueryRoutesError {
+    #[error("Failed to read osquery routes from {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Failed to parse osquery routes from {0}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+}
+
+#[derive(Deserialize)]
+struct RoutesFile {
+    queries: HashMap<String, QueryRoute>,
+}
+
+/// Which parser each osquery query's results are parsed by, by the query's name
+///
+/// The queries of Grapl's own pack are always routed. A routes file adds to and overrides them:
+///
+/// ```json
+/// {
+///     "queries": {
+///         "pack_myorg_procs": {
+///             "parser": "processes",
+///             "columns": { "process_path": "path", "ppid": "parent" }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OSQueryRoutes {
+    queries: Arc<HashMap<String, QueryRoute>>,
+}
+
+impl OSQueryRoutes {
+    /// Loads the routes file at `OSQUERY_ROUTES_FILE`, if set and non-empty
+    pub fn from_env() -> Result<Self, OSQueryRoutesError> {
+        let routes_file = std::env::var_os("OSQUERY_ROUTES_FILE").map(PathBuf::from);
+        Self::load(routes_file.as_deref())
+    }
+
+    /// Loads the routes file at `routes_file`. Without one, or with an empty path, only Grapl's
+    /// own pack is routed.
+    pub fn load(routes_file: Option<&Path>) -> Result<Self, OSQueryRoutesError> {
+        match routes_file.filter(|routes_file| !routes_file.as_os_str().is_empty()) {
+            Some(routes_file) => {
+                let routes = std::fs::read_to_string(routes_file)
+                    .map_err(|e| OSQueryRoutesError::Io(routes_file.to_owned(), e))?;
+                Self::from_json(&routes)
+                    .map_err(|e| OSQueryRoutesError::Parse(routes_file.to_owned(), e))
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_json(routes: &str) -> Result<Self, serde_json::Error> {
+        let RoutesFile { queries } = serde_json::from_str(routes)?;
+
+        let mut routes = Self::default();
+        Arc::make_mut(&mut routes.queries).extend(queries);
+        Ok(routes)
+    }
+
+    pub fn route(&self, query_name: &str) -> Option<&QueryRoute> {
+        self.queries.get(query_name)
+    }
+}
+
+impl Default for OSQueryRoutes {
+    /// Routes the queries of Grapl's own pack
+    fn default() -> Self {
+        let queries = grapl_pack::QUERIES
+            .iter()
+            .map(|(query_name, parser)| (query_name.to_string(), QueryRoute::new(*parser)))
+            .collect();
+
+        Self {
+            queries: Arc::new(queries),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn grapl_pack_is_routed_by_default() {
+        let routes = OSQueryRoutes::default();
+
+        assert_eq!(
+            routes.route("pack_grapl_processes"),
+            Some(&QueryRoute::new(OSQueryTable::Processes))
+        );
+        assert_eq!(routes.route("pack_myorg_procs"), None);
+    }
+
+    #[test]
+    fn routes_file_is_optional() {
+        for routes_file in &[None, Some(Path::new(""))] {
+            let routes = OSQueryRoutes::load(*routes_file).expect("failed to load default routes");
+            assert!(routes.route("pack_grapl_processes").is_some());
+            assert_eq!(routes.route("pack_myorg_procs"), None);
+        }
+    }
+
+    #[test]
+    fn routes_file_is_loaded() {
+        let routes_file = std::env::temp_dir().join(format!(
+            "osquery-generator-routes-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(
+            &routes_file,
+            r#"{"queries": {"pack_myorg_procs": {"parser": "processes"}}}"#,
+        )
+        .expect("failed to write routes file");
+
+        let routes = OSQueryRoutes::load(Some(&routes_file));
+        std::fs::remove_file(&routes_file).expect("failed to remove routes file");
+
+        let routes = routes.expect("failed to load routes file");
+        assert_eq!(
+            routes.route("pack_myorg_procs"),
+            Some(&QueryRoute::new(OSQueryTable::Processes))
+        );
+        assert!(routes.route("pack_grapl_processes").is_some());
+    }
+
+    #[test]
+    fn missing_routes_file_is_an_error() {
+        let routes_file = Path::new("/nonexistent/osquery-routes.json");
+
+        match OSQueryRoutes::load(Some(routes_file)) {
+            Err(OSQueryRoutesError::Io(path, _)) => assert_eq!(path, routes_file),
+            other => panic!("expected an io error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn routes_file_adds_queries() {
+        let routes = OSQueryRoutes::from_json(
+            r#"{
+                "queries": {
+                    "pack_myorg_procs": {
+                        "parser": "processes",
+                        "columns": { "process_path": "path" }
+                    }
+                }
+            }"#,
+        )
+        .expect("failed to parse routes");
+
+        let route = routes
+            .route("pack_myorg_procs")
+            .expect("missing pack_myorg_procs");
+        assert_eq!(route.parser, OSQueryTable::Processes);
+        assert_eq!(
+            route.rename_columns(json!({"process_path": "/usr/bin/bash", "pid": "9355"})),
+            json!({"path": "/usr/bin/bash", "pid": "9355"})
+        );
+        assert!(routes.route("pack_grapl_processes").is_some());
+    }
+
+    #[test]
+    fn unknown_parsers_are_rejected() {
+        assert!(OSQueryRoutes::from_json(
+            r#"{"queries": {"pack_myorg_procs": {"parser": "registry"}}}"#
+        )
+        .is_err());
+    }
+}
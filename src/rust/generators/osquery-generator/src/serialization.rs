@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use grapl_service::decoder::decompress::{
+    PayloadDecompression,
+    PayloadDecompressionError,
+};
+use sqs_executor::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_decoder::PayloadDecoder,
+};
+
+use crate::{
+    metrics::OSQueryGeneratorMetrics,
+    parsers::{
+        parse_results_line,
+        OSQueryEvent,
+        OSQueryRoutes,
+        ResultsLineError,
+    },
+};
+
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum OSQueryDecoderError {
+    #[error("DeserializeError")]
+    DeserializeError(String),
+    #[error("DecompressionError")]
+    DecompressionError(#[from] PayloadDecompressionError),
+}
+
+impl CheckedError for OSQueryDecoderError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::DeserializeError(_) => Recoverable::Persistent,
+            Self::DecompressionError(_) => Recoverable::Persistent,
+        }
+    }
+}
+
+/// Decodes osquery's newline-delimited results logs, in any of the formats osquery logs
+/// results in, into an event per row
+///
+/// Results of queries that no parser is routed for are counted and skipped, rather than failing
+/// the payload.
+#[derive(Clone)]
+pub struct OSQueryDecoder {
+    routes: OSQueryRoutes,
+    metrics: OSQueryGeneratorMetrics,
+    decompression: PayloadDecompression,
+}
+
+impl OSQueryDecoder {
+    pub fn new(
+        routes: OSQueryRoutes,
+        metrics: OSQueryGeneratorMetrics,
+        decompression: PayloadDecompression,
+    ) -> Self {
+        Self {
+            routes,
+            metrics,
+            decompression,
+        }
+    }
+}
+
+impl PayloadDecoder<Vec<OSQueryEvent>> for OSQueryDecoder {
+    type DecoderError = OSQueryDecoderError;
+
+    fn decode(&mut self, body: Vec<u8>) -> Result<Vec<OSQueryEvent>, Self::DecoderError> {
+        let decompressed = self.decompression.decompress(body.as_slice())?;
+
+        let mut first_deserialization_error: Option<OSQueryDecoderError> = None;
+        let mut unknown_queries: HashMap<String, usize> = HashMap::new();
+        let mut malformed_rows = 0;
+        let mut events = Vec::new();
+
+        let mut record_error = |error: &dyn std::error::Error| {
+            tracing::error!(message="Unable to deserialize OSQuery event.", error=?error);
+
+            malformed_rows += 1;
+            if first_deserialization_error.is_none() {
+                first_deserialization_error =
+                    Some(OSQueryDecoderError::DeserializeError(error.to_string()));
+            }
+        };
+
+        for line in decompressed.split(|byte| *byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_results_line(line, &self.routes) {
+                // A line's rows are parsed one by one, so a bad row doesn't take its siblings
+                // down with it
+                Ok(rows) => {
+                    for row in rows {
+                        match row {
+                            Ok(event) => events.push(event),
+                            Err(error) => record_error(&error),
+                        }
+                    }
+                }
+                Err(ResultsLineError::UnknownQuery(query_name)) => {
+                    tracing::debug!(message="Skipping results of unknown query.", query_name=%query_name);
+
+                    *unknown_queries.entry(query_name).or_insert(0) += 1;
+                }
+                Err(error) => record_error(&error),
+            }
+        }
+
+        if malformed_rows > 0 {
+            self.metrics.report_malformed_rows(malformed_rows);
+        }
+        for (query_name, count) in unknown_queries {
+            self.metrics.report_unknown_query(&query_name, count);
+        }
+
+        // This is a bit awkward at the moment, due to interfaces to the sqs-executor. If some of
+        // our events successfully parsed then we want to continue and send those to the event
+        // handler. Only if all parsing has failed and we have no events do we want to return an
+        // error here.
+        match first_deserialization_error {
+            Some(error) if events.is_empty() => Err(error),
+            _ => Ok(events),
+        }
+    }
+}
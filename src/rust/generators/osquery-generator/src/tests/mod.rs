@@ -1,4 +1,5 @@
 #![cfg(test)]
 
 mod process;
+mod tables;
 mod utils;
@@ -42,3 +42,23 @@ async fn test_subgraph_generation_process_create() {
         ),
     };
 }
+
+#[cfg(feature = "schema_validation")]
+#[tokio::test]
+async fn test_process_create_conforms_to_endpoint_schema() {
+    let metrics = OSQueryGeneratorMetrics::new("osquery-generator");
+    let mut generator = OSQueryGenerator::new(NopCache {}, metrics);
+
+    let logs = utils::read_osquery_test_data("process_create.zstd").await;
+
+    let mut completion = CompletedEvents::default();
+    let subgraph = generator
+        .handle_event(logs, &mut completion)
+        .await
+        .unwrap_or_else(|_| panic!("OSQuery subgraph generator failed to generate subgraph"));
+
+    assert_eq!(
+        endpoint_plugin::endpoint_schema().validate(&subgraph),
+        vec![]
+    );
+}
@@ -0,0 +1,53 @@
+use std::convert::TryFrom;
+
+use grapl_graph_descriptions::graph_description::GraphDescription;
+
+use crate::parsers::OSQueryEvent;
+
+/// The unit samples of the tables whose every row describes some of the graph
+const TABLES: &[&str] = &[
+    "pack_grapl_process_events",
+    "pack_grapl_socket_events",
+    "pack_grapl_process_open_sockets",
+    "pack_grapl_listening_ports",
+    "pack_grapl_users",
+    "pack_grapl_logged_in_users",
+    "pack_grapl_dns_resolvers",
+    "pack_grapl_crontab",
+    "pack_grapl_startup_items",
+    "pack_grapl_kernel_modules",
+];
+
+fn read_unit_sample(table: &str) -> GraphDescription {
+    let test_json = std::fs::read_to_string(format!("sample_data/unit/{}.json", table))
+        .expect("unable to read test file.");
+
+    let event: OSQueryEvent =
+        serde_json::from_str(&test_json).expect("serde_json::from_str failed.");
+
+    GraphDescription::try_from(event).expect("try_from failed.")
+}
+
+#[test]
+fn test_subgraph_generation_tables() {
+    for table in TABLES {
+        assert!(
+            !read_unit_sample(table).is_empty(),
+            "Generated subgraph for {} was empty.",
+            table
+        );
+    }
+}
+
+#[cfg(feature = "schema_validation")]
+#[test]
+fn test_tables_conform_to_endpoint_schema() {
+    for table in TABLES {
+        assert_eq!(
+            endpoint_plugin::endpoint_schema().validate(&read_unit_sample(table)),
+            vec![],
+            "{} doesn't conform to the endpoint schema",
+            table
+        );
+    }
+}
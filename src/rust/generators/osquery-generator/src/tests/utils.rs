@@ -1,13 +1,23 @@
-use grapl_service::decoder::NdjsonDecoder;
+use grapl_service::decoder::decompress::PayloadDecompression;
 use sqs_executor::event_decoder::PayloadDecoder;
 use tokio::fs;
 
+use crate::{
+    metrics::OSQueryGeneratorMetrics,
+    parsers::OSQueryRoutes,
+    serialization::OSQueryDecoder,
+};
+
 #[cfg(test)]
 pub(crate) async fn read_osquery_test_data(path: &str) -> Vec<crate::parsers::OSQueryEvent> {
     let file_data = fs::read(format!("sample_data/{}", path))
         .await
         .expect(&format!("Failed to read test data ({}).", path));
-    let mut decoder = NdjsonDecoder::default();
+    let mut decoder = OSQueryDecoder::new(
+        OSQueryRoutes::default(),
+        OSQueryGeneratorMetrics::new("osquery-generator"),
+        PayloadDecompression::Zstd,
+    );
 
     decoder.decode(file_data).unwrap()
 }
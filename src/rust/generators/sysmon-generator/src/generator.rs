@@ -1,5 +1,8 @@
 use async_trait::async_trait;
-use grapl_graph_descriptions::graph_description::*;
+use grapl_graph_descriptions::{
+    graph_description::*,
+    MergeError,
+};
 use sqs_executor::{
     cache::Cache,
     errors::{
@@ -24,8 +27,8 @@ pub enum SysmonGeneratorError {
     NegativeEventTime(i64),
     #[error("TimeError")]
     TimeError(#[from] chrono::ParseError),
-    #[error("Unsupported event type")]
-    UnsupportedEventType(String),
+    #[error("MergeError")]
+    MergeError(#[from] MergeError),
 }
 
 impl CheckedError for SysmonGeneratorError {
@@ -33,7 +36,7 @@ impl CheckedError for SysmonGeneratorError {
         match self {
             Self::NegativeEventTime(_) => Recoverable::Persistent,
             Self::TimeError(_) => Recoverable::Persistent,
-            Self::UnsupportedEventType(_) => Recoverable::Persistent,
+            Self::MergeError(_) => Recoverable::Persistent,
         }
     }
 }
@@ -80,41 +83,33 @@ where
         let events = self.cache.filter_cached(&events).await;
 
         let mut last_error: Option<SysmonGeneratorError> = None;
+        let mut final_subgraph = GraphDescription::new();
 
-        let subgraphs: Vec<_> = events
-            .into_iter()
-            .filter_map(|event| {
-                let result = GraphDescription::try_from(event.clone());
-                self.metrics.report_subgraph_generation(&result);
-                match result {
-                    Ok(graph) => {
-                        completed.add_identity(event, EventStatus::Success);
-                        Some(graph)
-                    }
-                    Err(error) => {
-                        completed.add_identity(event, EventStatus::Failure);
-                        tracing::error!(message="GraphDescription::try_from failed with.", error=?error);
-                        last_error = Some(error);
-                        None
-                    }
+        for event in events {
+            let result = GraphDescription::try_from(event.clone()).and_then(|subgraph| {
+                final_subgraph
+                    .try_merge(&subgraph)
+                    .map_err(SysmonGeneratorError::from)
+            });
+            self.metrics.report_subgraph_generation(&result);
+            match result {
+                Ok(_) => {
+                    completed.add_identity(event, EventStatus::Success);
                 }
-            })
-            .collect();
-
-        let final_subgraph =
-            subgraphs
-                .iter()
-                .fold(GraphDescription::new(), |mut current_graph, subgraph| {
-                    current_graph.merge(&subgraph);
-                    current_graph
-                });
+                Err(error) => {
+                    completed.add_identity(event, EventStatus::Failure);
+                    tracing::error!(message="GraphDescription::try_from failed with.", error=?error);
+                    last_error = Some(error);
+                }
+            }
+        }
 
         tracing::info!(
             message = "Completed mapping subgraphs.",
             num_graphs = completed.len()
         );
 
-        let final_result = match (last_error, subgraphs.is_empty()) {
+        let final_result = match (last_error, final_subgraph.is_empty()) {
             (None, _) => Ok(final_subgraph),
             (Some(error), false) => Err(Ok((final_subgraph, error))),
             (Some(error), true) => Err(Err(error)),
@@ -0,0 +1,3 @@
+mod query;
+
+pub use query::generate_dns_query_subgraph;
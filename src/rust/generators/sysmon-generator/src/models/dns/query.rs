@@ -0,0 +1,153 @@
+use endpoint_plugin::{
+    AssetNode,
+    DomainNameNode,
+    IAssetNode,
+    IDomainNameNode,
+    IIpAddressNode,
+    IProcessNode,
+    IpAddressNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::DnsQueryEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `DnsQueryEvent`
+///
+/// The subgraph generation for a `DnsQueryEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which the query was made
+/// * A `Process` node - the process that made the query
+/// * A subject `DomainName` node - the name that was queried
+/// * An `IpAddress` node for each address the name resolved to
+pub fn generate_dns_query_subgraph(
+    dns_query: &DnsQueryEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&dns_query.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(dns_query.system.computer.computer.clone())
+        .with_hostname(dns_query.system.computer.computer.clone());
+
+    let mut process = process_node(&dns_query.event_data.process_guid);
+    process
+        .with_asset_id(dns_query.system.computer.computer.clone())
+        .with_process_id(dns_query.event_data.process_id)
+        .with_process_name(get_image_name(&dns_query.event_data.image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut domain = DomainNameNode::new(DomainNameNode::static_strategy());
+    domain
+        .with_domain_name(dns_query.event_data.query_name.clone())
+        .with_first_seen_timestamp(timestamp)
+        .with_last_seen_timestamp(timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge_with_facets(
+        "queried_domains",
+        process.clone_node_key(),
+        domain.clone_node_key(),
+        EdgeFacets::seen_at(timestamp),
+    )?;
+
+    for address in dns_query.event_data.resolved_addresses() {
+        let mut ip_address = IpAddressNode::new(IpAddressNode::static_strategy());
+        ip_address
+            .with_ip_address(address.to_string())
+            .with_first_seen_timestamp(timestamp)
+            .with_last_seen_timestamp(timestamp);
+
+        graph.try_add_edge(
+            "resolved_ips",
+            domain.clone_node_key(),
+            ip_address.clone_node_key(),
+        )?;
+
+        graph.add_node(ip_address);
+    }
+
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(domain);
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sysmon::*;
+
+    use super::*;
+
+    const DNS_QUERY_EVENT: &str = r#"<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Sysmon' Guid='{5770385F-C22A-43E0-BF4C-06F5698FFBD9}'/><EventID>22</EventID><Version>5</Version><Level>4</Level><Task>22</Task><Opcode>0</Opcode><Keywords>0x8000000000000000</Keywords><TimeCreated SystemTime='2019-07-24T18:07:41.623408200Z'/><EventRecordID>574</EventRecordID><Correlation/><Execution ProcessID='3324' ThreadID='3220'/><Channel>Microsoft-Windows-Sysmon/Operational</Channel><Computer>DESKTOP-FVSHABR</Computer><Security UserID='S-1-5-18'/></System><EventData><Data Name='RuleName'></Data><Data Name='UtcTime'>2019-07-24 18:07:40.514</Data><Data Name='ProcessGuid'>{87E8D3BD-9DDA-5D38-0000-0010A3941D00}</Data><Data Name='ProcessId'>5752</Data><Data Name='QueryName'>www.example.com</Data><Data Name='QueryStatus'>0</Data><Data Name='QueryResults'>type:  5 www.example.com-v4.edgesuite.net;::ffff:93.184.216.34;::ffff:93.184.216.35;</Data><Data Name='Image'>C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe</Data></EventData></Event>"#;
+
+    fn dns_query_event() -> DnsQueryEvent {
+        match Event::from_str(DNS_QUERY_EVENT).unwrap() {
+            Event::DnsQuery(e) => e,
+            _ => panic!("must be DnsQuery"),
+        }
+    }
+
+    #[test]
+    fn dns_query() {
+        let graph =
+            generate_dns_query_subgraph(&dns_query_event()).expect("failed to generate graph");
+
+        let ip_addresses: Vec<_> = graph
+            .nodes
+            .values()
+            .filter(|n| n.node_type == "IpAddress")
+            .collect();
+        assert_eq!(ip_addresses.len(), 2);
+
+        let resolved_ips = graph
+            .edges
+            .values()
+            .flat_map(|edges| edges.edges.iter())
+            .filter(|e| e.edge_name == "resolved_ips")
+            .count();
+        assert_eq!(resolved_ips, 2);
+    }
+
+    #[test]
+    fn dns_query_records_when_the_domain_was_queried() {
+        let graph =
+            generate_dns_query_subgraph(&dns_query_event()).expect("failed to generate graph");
+
+        let queried_domains: Vec<_> = graph
+            .edges
+            .values()
+            .flat_map(|edges| edges.edges.iter())
+            .filter(|e| e.edge_name == "queried_domains")
+            .collect();
+        assert_eq!(queried_domains.len(), 1);
+        assert_eq!(
+            queried_domains[0].facets,
+            Some(EdgeFacets::seen_at(1_563_991_660_514))
+        );
+    }
+
+    #[cfg(feature = "schema_validation")]
+    #[test]
+    fn dns_query_conforms_to_endpoint_schema() {
+        let graph =
+            generate_dns_query_subgraph(&dns_query_event()).expect("failed to generate graph");
+
+        assert_eq!(endpoint_plugin::endpoint_schema().validate(&graph), vec![]);
+    }
+}
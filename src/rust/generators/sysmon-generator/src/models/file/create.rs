@@ -4,7 +4,6 @@ use endpoint_plugin::{
     IAssetNode,
     IFileNode,
     IProcessNode,
-    ProcessNode,
 };
 use grapl_graph_descriptions::graph_description::*;
 use sysmon::FileCreateEvent;
@@ -13,6 +12,7 @@ use crate::{
     generator::SysmonGeneratorError,
     models::{
         get_image_name,
+        process_node,
         strip_file_zone_identifier,
         utc_to_epoch,
     },
@@ -34,7 +34,7 @@ pub fn generate_file_create_subgraph(
         .with_asset_id(file_create.system.computer.computer.clone())
         .with_hostname(file_create.system.computer.computer.clone());
 
-    let mut creator = ProcessNode::new(ProcessNode::session_strategy());
+    let mut creator = process_node(&file_create.event_data.process_guid);
     creator
         .with_asset_id(file_create.system.computer.computer.clone())
         .with_process_id(file_create.event_data.process_id)
@@ -48,23 +48,23 @@ pub fn generate_file_create_subgraph(
         ))
         .with_created_timestamp(timestamp);
 
-    graph.add_edge(
+    graph.try_add_edge(
         "process_asset",
         creator.clone_node_key(),
         asset.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "created_files",
         creator.clone_node_key(),
         file.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "files_on_asset",
         asset.clone_node_key(),
         file.clone_node_key(),
-    );
+    )?;
 
     graph.add_node(asset);
     graph.add_node(creator);
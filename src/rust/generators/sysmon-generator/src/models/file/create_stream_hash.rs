@@ -0,0 +1,73 @@
+use endpoint_plugin::{
+    AssetNode,
+    FileNode,
+    IAssetNode,
+    IFileNode,
+    IProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::FileCreateStreamHashEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `FileCreateStreamHashEvent`
+///
+/// The subgraph generation for a `FileCreateStreamHashEvent` includes the following:
+/// * A creator `Process` node - denotes the process that created the stream
+/// * A subject `File` node - the alternate data stream, with the stream name kept in its path
+///   (e.g. `setup.exe:Zone.Identifier`) so that streams aren't merged into the file they're on
+pub fn generate_file_create_stream_hash_subgraph(
+    stream_create: &FileCreateStreamHashEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&stream_create.event_data.creation_utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(stream_create.system.computer.computer.clone())
+        .with_hostname(stream_create.system.computer.computer.clone());
+
+    let mut creator = process_node(&stream_create.event_data.process_guid);
+    creator
+        .with_asset_id(stream_create.system.computer.computer.clone())
+        .with_process_id(stream_create.event_data.process_id)
+        .with_process_name(get_image_name(&stream_create.event_data.image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut stream = FileNode::new(FileNode::session_strategy());
+    stream
+        .with_asset_id(stream_create.system.computer.computer.clone())
+        .with_file_path(stream_create.event_data.target_filename.clone())
+        .with_created_timestamp(timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        creator.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "created_files",
+        creator.clone_node_key(),
+        stream.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "files_on_asset",
+        asset.clone_node_key(),
+        stream.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(creator);
+    graph.add_node(stream);
+
+    Ok(graph)
+}
@@ -0,0 +1,71 @@
+use endpoint_plugin::{
+    AssetNode,
+    FileNode,
+    IAssetNode,
+    IFileNode,
+    IProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::FileDeleteEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `FileDeleteEvent`
+///
+/// The subgraph generation for a `FileDeleteEvent` includes the following:
+/// * A deleter `Process` node - denotes the process that deleted the file
+/// * A subject `File` node - the file that is deleted as part of this event
+pub fn generate_file_delete_subgraph(
+    file_delete: &FileDeleteEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&file_delete.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(file_delete.system.computer.computer.clone())
+        .with_hostname(file_delete.system.computer.computer.clone());
+
+    let mut deleter = process_node(&file_delete.event_data.process_guid);
+    deleter
+        .with_asset_id(file_delete.system.computer.computer.clone())
+        .with_process_id(file_delete.event_data.process_id)
+        .with_process_name(get_image_name(&file_delete.event_data.image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut file = FileNode::new(FileNode::session_strategy());
+    file.with_asset_id(file_delete.system.computer.computer.clone())
+        .with_file_path(file_delete.event_data.target_filename.clone())
+        .with_deleted_timestamp(timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        deleter.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "deleted_files",
+        deleter.clone_node_key(),
+        file.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "files_on_asset",
+        asset.clone_node_key(),
+        file.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(deleter);
+    graph.add_node(file);
+
+    Ok(graph)
+}
@@ -1,3 +1,7 @@
 mod create;
+mod create_stream_hash;
+mod delete;
 
 pub use create::generate_file_create_subgraph;
+pub use create_stream_hash::generate_file_create_stream_hash_subgraph;
+pub use delete::generate_file_delete_subgraph;
@@ -3,14 +3,24 @@ use chrono::{
     NaiveDateTime,
     Utc,
 };
+use endpoint_plugin::{
+    IProcessNode,
+    ProcessNode,
+};
 use grapl_graph_descriptions::graph_description::*;
-use sysmon::Event;
+use sysmon::{
+    Event,
+    ProcessGuid,
+};
 
 use crate::generator::SysmonGeneratorError;
 
+mod dns;
 mod file;
 mod network;
+mod pipe;
 mod process;
+mod registry;
 
 /// Because this crate doesn't own sysmon::Event nor grapl_graph_descriptions::graph_description::GraphDescription
 /// we need to create a new Trait to add a function to graph for Event.
@@ -22,75 +32,55 @@ pub(crate) trait SysmonTryFrom<T>: Sized {
     fn try_from(instance: T) -> Result<Self, Self::Error>;
 }
 
-fn get_event_type(event: Event) -> String {
-    match event {
-        Event::ProcessCreate(event) => event.system.event_id.event_id.to_string(),
-        Event::FileCreate(event) => event.system.event_id.event_id.to_string(),
-        Event::InboundNetwork(event) => event.system.event_id.event_id.to_string(),
-        Event::OutboundNetwork(event) => event.system.event_id.event_id.to_string(),
-    }
-}
-
 impl SysmonTryFrom<Event> for GraphDescription {
     type Error = SysmonGeneratorError;
 
     #[tracing::instrument]
     fn try_from(instance: Event) -> Result<Self, Self::Error> {
-        match instance {
-            Event::ProcessCreate(event) => {
-                tracing::info!(event = "ProcessCreate");
-
-                let result = process::generate_process_create_subgraph(&event);
-
-                if let Err(e) = &result {
-                    tracing::warn!(message="Failed to process process start event.", error=?e);
-                }
-
-                result
-            }
-            Event::FileCreate(event) => {
-                tracing::info!(event = "FileCreate");
-
-                let result = file::generate_file_create_subgraph(&event);
-
-                if let Err(e) = &result {
-                    tracing::warn!(message="Failed to process file create event", error=?e);
-                }
-
-                result
-            }
-            // Event::InboundNetwork(event) => {
-            //     info!("InboundNetwork");
-            //
-            //     let result = network::generate_inbound_connection_subgraph(&event);
-            //
-            //     if let Err(e) = &result {
-            //         warn!("Failed to process inbound network event: {}", e);
-            //     }
-            //
-            //     result
-            // }
-            Event::OutboundNetwork(event) => {
-                tracing::info!(event = "OutboundNetwork");
-
-                let result = network::generate_outbound_connection_subgraph(&event);
-
-                if let Err(e) = &result {
-                    tracing::warn!(message="Failed to process outbound network event.", error=?e);
-                }
-
-                result
-            }
-            unsupported_event => {
-                let message = format!("Unsupported event_type: {:?}", unsupported_event);
-
-                tracing::warn!(message =% message);
-
-                Err(SysmonGeneratorError::UnsupportedEventType(get_event_type(
-                    unsupported_event,
-                )))
-            }
+        let (event_type, result) = match &instance {
+            Event::ProcessCreate(event) => (
+                "ProcessCreate",
+                process::generate_process_create_subgraph(event),
+            ),
+            Event::FileCreate(event) => ("FileCreate", file::generate_file_create_subgraph(event)),
+            Event::InboundNetwork(event) => (
+                "InboundNetwork",
+                network::generate_inbound_connection_subgraph(event),
+            ),
+            Event::OutboundNetwork(event) => (
+                "OutboundNetwork",
+                network::generate_outbound_connection_subgraph(event),
+            ),
+            Event::ProcessTerminate(event) => (
+                "ProcessTerminate",
+                process::generate_process_terminate_subgraph(event),
+            ),
+            Event::ImageLoad(event) => ("ImageLoad", process::generate_image_load_subgraph(event)),
+            Event::CreateRemoteThread(event) => (
+                "CreateRemoteThread",
+                process::generate_create_remote_thread_subgraph(event),
+            ),
+            Event::ProcessAccess(event) => (
+                "ProcessAccess",
+                process::generate_process_access_subgraph(event),
+            ),
+            Event::Registry(event) => ("Registry", registry::generate_registry_subgraph(event)),
+            Event::FileCreateStreamHash(event) => (
+                "FileCreateStreamHash",
+                file::generate_file_create_stream_hash_subgraph(event),
+            ),
+            Event::Pipe(event) => ("Pipe", pipe::generate_pipe_subgraph(event)),
+            Event::DnsQuery(event) => ("DnsQuery", dns::generate_dns_query_subgraph(event)),
+            Event::FileDelete(event) => ("FileDelete", file::generate_file_delete_subgraph(event)),
+        };
+
+        tracing::info!(event = event_type);
+
+        if let Err(e) = &result {
+            tracing::warn!(message="Failed to process event.", event = event_type, error=?e);
         }
+
+        result
     }
 }
 
@@ -121,6 +111,15 @@ fn get_image_name(image_path: &str) -> Option<String> {
         .map(|name| name.replace("- ", "").replace('\\', ""))
 }
 
+/// Creates a `Process` node identified by its Sysmon process GUID, which unlike the process id is
+/// never reused. The node still carries its `asset_id` and `process_id` session, which processes
+/// from datasources without GUIDs are identified by.
+fn process_node(process_guid: &ProcessGuid) -> ProcessNode {
+    let mut process = ProcessNode::new_with_strategies(ProcessNode::identity_strategies());
+    process.with_process_guid(process_guid.process_guid.to_string());
+    process
+}
+
 /// Converts a Sysmon UTC string to UNIX Epoch time
 ///
 /// If the provided string is not parseable as a UTC timestamp, an error is returned.
@@ -11,7 +11,6 @@ use endpoint_plugin::{
     IpConnectionNode,
     IpPortNode,
     NetworkConnectionNode,
-    ProcessNode,
     ProcessOutboundConnectionNode,
 };
 use grapl_graph_descriptions::graph_description::*;
@@ -19,7 +18,10 @@ use sysmon::NetworkEvent;
 
 use crate::{
     generator::SysmonGeneratorError,
-    models::utc_to_epoch,
+    models::{
+        process_node,
+        utc_to_epoch,
+    },
 };
 
 /// Creates a subgraph describing an outbound `NetworkEvent`
@@ -43,7 +45,7 @@ pub fn generate_outbound_connection_subgraph(
         .with_hostname(conn_log.system.computer.computer.clone());
 
     // A process creates an outbound connection to dst_port
-    let mut process = ProcessNode::new(ProcessNode::session_strategy());
+    let mut process = process_node(&conn_log.event_data.process_guid);
     process
         .with_asset_id(conn_log.system.computer.computer.clone())
         .with_process_id(conn_log.event_data.process_id)
@@ -99,61 +101,61 @@ pub fn generate_outbound_connection_subgraph(
         .with_created_timestamp(timestamp);
 
     // An asset is assigned an IP
-    graph.add_edge("asset_ip", asset.clone_node_key(), src_ip.clone_node_key());
+    graph.try_add_edge("asset_ip", asset.clone_node_key(), src_ip.clone_node_key())?;
 
     // A process spawns on an asset
-    graph.add_edge(
+    graph.try_add_edge(
         "asset_processes",
         asset.clone_node_key(),
         process.clone_node_key(),
-    );
+    )?;
 
     // A process creates a connection
-    graph.add_edge(
+    graph.try_add_edge(
         "created_connections",
         process.clone_node_key(),
         outbound.clone_node_key(),
-    );
+    )?;
 
     // The connection is over an IP + Port
-    graph.add_edge(
+    graph.try_add_edge(
         "connected_over",
         outbound.clone_node_key(),
         src_port.clone_node_key(),
-    );
+    )?;
 
     // The outbound process connection is to a dst ip + port
-    graph.add_edge(
+    graph.try_add_edge(
         "connected_to",
         outbound.clone_node_key(),
         dst_port.clone_node_key(),
-    );
+    )?;
 
     // There is also a connection between the two IP addresses
 
-    graph.add_edge(
+    graph.try_add_edge(
         "ip_connections",
         src_ip.clone_node_key(),
         ip_connection.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "ip_connections",
         dst_ip.clone_node_key(),
         ip_connection.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "network_connections",
         src_port.clone_node_key(),
         network_connection.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "network_connections",
         dst_port.clone_node_key(),
         network_connection.clone_node_key(),
-    );
+    )?;
 
     graph.add_node(asset);
     graph.add_node(process);
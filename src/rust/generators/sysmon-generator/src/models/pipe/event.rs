@@ -0,0 +1,71 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    INamedPipeNode,
+    IProcessNode,
+    NamedPipeNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::{
+    PipeEvent,
+    PipeEventType,
+};
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `PipeEvent`
+///
+/// The subgraph generation for a `PipeEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which the pipe exists
+/// * A `Process` node - the process that created or connected to the pipe
+/// * A subject `NamedPipe` node - the pipe that was created or connected to
+pub fn generate_pipe_subgraph(
+    pipe_event: &PipeEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&pipe_event.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(pipe_event.system.computer.computer.clone())
+        .with_hostname(pipe_event.system.computer.computer.clone());
+
+    let mut process = process_node(&pipe_event.event_data.process_guid);
+    process
+        .with_asset_id(pipe_event.system.computer.computer.clone())
+        .with_process_id(pipe_event.event_data.process_id)
+        .with_process_name(get_image_name(&pipe_event.event_data.image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut pipe = NamedPipeNode::new(NamedPipeNode::static_strategy());
+    pipe.with_asset_id(pipe_event.system.computer.computer.clone())
+        .with_pipe_name(pipe_event.event_data.pipe_name.clone())
+        .with_first_seen_timestamp(timestamp)
+        .with_last_seen_timestamp(timestamp);
+
+    let edge_name = match pipe_event.event_data.event_type {
+        PipeEventType::CreatePipe => "created_pipes",
+        PipeEventType::ConnectPipe => "connected_pipes",
+    };
+
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(edge_name, process.clone_node_key(), pipe.clone_node_key())?;
+
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(pipe);
+
+    Ok(graph)
+}
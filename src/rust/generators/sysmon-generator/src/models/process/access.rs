@@ -0,0 +1,73 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::ProcessAccessEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `ProcessAccessEvent`
+///
+/// The subgraph generation for a `ProcessAccessEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which both processes run
+/// * A source `Process` node - the process that opened the target
+/// * A target `Process` node - the process that was opened, e.g. `lsass.exe`
+pub fn generate_process_access_subgraph(
+    process_access: &ProcessAccessEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&process_access.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(process_access.system.computer.computer.clone())
+        .with_hostname(process_access.system.computer.computer.clone());
+
+    let mut source = process_node(&process_access.event_data.source_process_guid);
+    source
+        .with_asset_id(process_access.system.computer.computer.clone())
+        .with_process_id(process_access.event_data.source_process_id)
+        .with_process_name(get_image_name(&process_access.event_data.source_image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut target = process_node(&process_access.event_data.target_process_guid);
+    target
+        .with_asset_id(process_access.system.computer.computer.clone())
+        .with_process_id(process_access.event_data.target_process_id)
+        .with_process_name(get_image_name(&process_access.event_data.target_image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        source.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "process_asset",
+        target.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge_with_facets(
+        "accessed_processes",
+        source.clone_node_key(),
+        target.clone_node_key(),
+        EdgeFacets::seen_at(timestamp),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(source);
+    graph.add_node(target);
+
+    Ok(graph)
+}
@@ -4,7 +4,6 @@ use endpoint_plugin::{
     IAssetNode,
     IFileNode,
     IProcessNode,
-    ProcessNode,
 };
 use grapl_graph_descriptions::graph_description::*;
 use sysmon::ProcessCreateEvent;
@@ -13,6 +12,7 @@ use crate::{
     generator::SysmonGeneratorError,
     models::{
         get_image_name,
+        process_node,
         strip_file_zone_identifier,
         utc_to_epoch,
     },
@@ -36,7 +36,7 @@ pub fn generate_process_create_subgraph(
         .with_asset_id(process_start.system.computer.computer.clone())
         .with_hostname(process_start.system.computer.computer.clone());
 
-    let mut parent = ProcessNode::new(ProcessNode::session_strategy());
+    let mut parent = process_node(&process_start.event_data.parent_process_guid);
     parent
         .with_asset_id(process_start.system.computer.computer.clone())
         .with_process_id(process_start.event_data.parent_process_id)
@@ -44,7 +44,7 @@ pub fn generate_process_create_subgraph(
         .with_process_command_line(&process_start.event_data.parent_command_line.command_line)
         .with_last_seen_timestamp(timestamp);
 
-    let mut child = ProcessNode::new(ProcessNode::session_strategy());
+    let mut child = process_node(&process_start.event_data.process_guid);
     child
         .with_asset_id(process_start.system.computer.computer.clone())
         .with_process_name(get_image_name(&process_start.event_data.image.clone()).unwrap())
@@ -58,31 +58,31 @@ pub fn generate_process_create_subgraph(
         .with_last_seen_timestamp(timestamp)
         .with_file_path(strip_file_zone_identifier(&process_start.event_data.image));
 
-    graph.add_edge(
+    graph.try_add_edge(
         "process_asset",
         parent.clone_node_key(),
         asset.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "process_asset",
         child.clone_node_key(),
         asset.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "bin_file",
         child.clone_node_key(),
         child_exe.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge(
+    graph.try_add_edge(
         "files_on_asset",
         asset.clone_node_key(),
         child_exe.clone_node_key(),
-    );
+    )?;
 
-    graph.add_edge("children", parent.clone_node_key(), child.clone_node_key());
+    graph.try_add_edge("children", parent.clone_node_key(), child.clone_node_key())?;
 
     graph.add_node(asset);
     graph.add_node(parent);
@@ -103,6 +103,8 @@ mod tests {
 
     use super::*;
 
+    const PROCESS_CREATE_EVENT: &str = r#"<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Sysmon' Guid='{5770385F-C22A-43E0-BF4C-06F5698FFBD9}'/><EventID>1</EventID><Version>5</Version><Level>4</Level><Task>1</Task><Opcode>0</Opcode><Keywords>0x8000000000000000</Keywords><TimeCreated SystemTime='2019-07-24T18:05:14.402156600Z'/><EventRecordID>550</EventRecordID><Correlation/><Execution ProcessID='3324' ThreadID='3220'/><Channel>Microsoft-Windows-Sysmon/Operational</Channel><Computer>DESKTOP-FVSHABR</Computer><Security UserID='S-1-5-18'/></System><EventData><Data Name='RuleName'></Data><Data Name='UtcTime'>2019-07-24 18:05:14.399</Data><Data Name='ProcessGuid'>{87E8D3BD-9DDA-5D38-0000-0010A3941D00}</Data><Data Name='ProcessId'>5752</Data><Data Name='Image'>C:\Windows\System32\cmd.exe</Data><Data Name='FileVersion'>10.0.10240.16384 (th1.150709-1700)</Data><Data Name='Description'>Windows Command Processor</Data><Data Name='Product'>Microsoft� Windows� Operating System</Data><Data Name='Company'>Microsoft Corporation</Data><Data Name='OriginalFileName'>Cmd.Exe</Data><Data Name='CommandLine'>"cmd" /C "msiexec /quiet /i cmd.msi"</Data><Data Name='CurrentDirectory'>C:\Users\grapltest\Downloads\</Data><Data Name='User'>DESKTOP-FVSHABR\grapltest</Data><Data Name='LogonGuid'>{87E8D3BD-99C8-5D38-0000-002088140200}</Data><Data Name='LogonId'>0x21488</Data><Data Name='TerminalSessionId'>1</Data><Data Name='IntegrityLevel'>Medium</Data><Data Name='Hashes'>MD5=A6177D080759CF4A03EF837A38F62401,SHA256=79D1FFABDD7841D9043D4DDF1F93721BCD35D823614411FD4EAB5D2C16A86F35</Data><Data Name='ParentProcessGuid'>{87E8D3BD-9DD8-5D38-0000-00109F871D00}</Data><Data Name='ParentProcessId'>6132</Data><Data Name='ParentImage'>C:\Users\grapltest\Downloads\svchost.exe</Data><Data Name='ParentCommandLine'>.\svchost.exe</Data></EventData></Event>"#;
+
     fn find_node<'a>(
         graph: &'a GraphDescription,
         o_p_name: &str,
@@ -121,7 +123,7 @@ mod tests {
         // When - We generate a graph from the event
         // Then - We expect a graph with a parent and child process, and an edge between them
 
-        let event = r#"<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Sysmon' Guid='{5770385F-C22A-43E0-BF4C-06F5698FFBD9}'/><EventID>1</EventID><Version>5</Version><Level>4</Level><Task>1</Task><Opcode>0</Opcode><Keywords>0x8000000000000000</Keywords><TimeCreated SystemTime='2019-07-24T18:05:14.402156600Z'/><EventRecordID>550</EventRecordID><Correlation/><Execution ProcessID='3324' ThreadID='3220'/><Channel>Microsoft-Windows-Sysmon/Operational</Channel><Computer>DESKTOP-FVSHABR</Computer><Security UserID='S-1-5-18'/></System><EventData><Data Name='RuleName'></Data><Data Name='UtcTime'>2019-07-24 18:05:14.399</Data><Data Name='ProcessGuid'>{87E8D3BD-9DDA-5D38-0000-0010A3941D00}</Data><Data Name='ProcessId'>5752</Data><Data Name='Image'>C:\Windows\System32\cmd.exe</Data><Data Name='FileVersion'>10.0.10240.16384 (th1.150709-1700)</Data><Data Name='Description'>Windows Command Processor</Data><Data Name='Product'>Microsoft� Windows� Operating System</Data><Data Name='Company'>Microsoft Corporation</Data><Data Name='OriginalFileName'>Cmd.Exe</Data><Data Name='CommandLine'>"cmd" /C "msiexec /quiet /i cmd.msi"</Data><Data Name='CurrentDirectory'>C:\Users\grapltest\Downloads\</Data><Data Name='User'>DESKTOP-FVSHABR\grapltest</Data><Data Name='LogonGuid'>{87E8D3BD-99C8-5D38-0000-002088140200}</Data><Data Name='LogonId'>0x21488</Data><Data Name='TerminalSessionId'>1</Data><Data Name='IntegrityLevel'>Medium</Data><Data Name='Hashes'>MD5=A6177D080759CF4A03EF837A38F62401,SHA256=79D1FFABDD7841D9043D4DDF1F93721BCD35D823614411FD4EAB5D2C16A86F35</Data><Data Name='ParentProcessGuid'>{87E8D3BD-9DD8-5D38-0000-00109F871D00}</Data><Data Name='ParentProcessId'>6132</Data><Data Name='ParentImage'>C:\Users\grapltest\Downloads\svchost.exe</Data><Data Name='ParentCommandLine'>.\svchost.exe</Data></EventData></Event>"#;
+        let event = PROCESS_CREATE_EVENT;
         let event = Event::from_str(&event).unwrap();
         let event = match event {
             Event::ProcessCreate(e) => e,
@@ -154,4 +156,59 @@ mod tests {
         let edge_to_b = edge_to_b.expect("missing edge to b");
         assert_eq!(edge_to_b.edge_name, "children");
     }
+
+    #[test]
+    fn process_create_identifies_processes_by_guid() {
+        // Given - A sysmon process creation event
+        // When - We generate a graph from the event
+        // Then - We expect both processes to be identified by their process GUIDs first
+
+        let event = match Event::from_str(PROCESS_CREATE_EVENT).unwrap() {
+            Event::ProcessCreate(e) => e,
+            _ => panic!("must be ProcessCreate"),
+        };
+
+        let graph = generate_process_create_subgraph(&event).expect("failed to generate graph");
+
+        for (process_id, process_guid) in &[
+            (6132, "87e8d3bd-9dd8-5d38-0000-00109f871d00"),
+            (5752, "87e8d3bd-9dda-5d38-0000-0010a3941d00"),
+        ] {
+            let process = find_node(
+                &graph,
+                "process_id",
+                ImmutableUintProp { prop: *process_id }.into(),
+            )
+            .expect("process missing");
+
+            assert_eq!(
+                process.properties["process_guid"].to_string(),
+                *process_guid
+            );
+            match process.id_strategy[0].strategy.as_ref() {
+                Some(id_strategy::Strategy::Static(strategy)) => assert_eq!(
+                    strategy.primary_key_properties,
+                    vec!["process_guid".to_owned()]
+                ),
+                strategy => panic!("expected a process_guid strategy, got {:?}", strategy),
+            }
+            assert!(matches!(
+                process.id_strategy[1].strategy,
+                Some(id_strategy::Strategy::Session(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "schema_validation")]
+    #[test]
+    fn process_create_conforms_to_endpoint_schema() {
+        let event = match Event::from_str(PROCESS_CREATE_EVENT).unwrap() {
+            Event::ProcessCreate(e) => e,
+            _ => panic!("must be ProcessCreate"),
+        };
+
+        let graph = generate_process_create_subgraph(&event).expect("failed to generate graph");
+
+        assert_eq!(endpoint_plugin::endpoint_schema().validate(&graph), vec![]);
+    }
 }
@@ -0,0 +1,72 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::CreateRemoteThreadEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `CreateRemoteThreadEvent`
+///
+/// The subgraph generation for a `CreateRemoteThreadEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which both processes run
+/// * A source `Process` node - the process that created the thread
+/// * A target `Process` node - the process the thread was created in
+pub fn generate_create_remote_thread_subgraph(
+    create_remote_thread: &CreateRemoteThreadEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&create_remote_thread.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(create_remote_thread.system.computer.computer.clone())
+        .with_hostname(create_remote_thread.system.computer.computer.clone());
+
+    let mut source = process_node(&create_remote_thread.event_data.source_process_guid);
+    source
+        .with_asset_id(create_remote_thread.system.computer.computer.clone())
+        .with_process_id(create_remote_thread.event_data.source_process_id)
+        .with_process_name(get_image_name(&create_remote_thread.event_data.source_image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut target = process_node(&create_remote_thread.event_data.target_process_guid);
+    target
+        .with_asset_id(create_remote_thread.system.computer.computer.clone())
+        .with_process_id(create_remote_thread.event_data.target_process_id)
+        .with_process_name(get_image_name(&create_remote_thread.event_data.target_image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        source.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "process_asset",
+        target.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "created_remote_threads",
+        source.clone_node_key(),
+        target.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(source);
+    graph.add_node(target);
+
+    Ok(graph)
+}
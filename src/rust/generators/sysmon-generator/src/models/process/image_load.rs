@@ -0,0 +1,86 @@
+use endpoint_plugin::{
+    AssetNode,
+    FileNode,
+    IAssetNode,
+    IFileNode,
+    IProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::ImageLoadEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing an `ImageLoadEvent`
+///
+/// The subgraph generation for an `ImageLoadEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which the image was loaded
+/// * A `Process` node - the process that loaded the image
+/// * A subject `File` node - the image (e.g. a DLL) that was loaded
+pub fn generate_image_load_subgraph(
+    image_load: &ImageLoadEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&image_load.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(image_load.system.computer.computer.clone())
+        .with_hostname(image_load.system.computer.computer.clone());
+
+    let mut process = process_node(&image_load.event_data.process_guid);
+    process
+        .with_asset_id(image_load.system.computer.computer.clone())
+        .with_process_id(image_load.event_data.process_id)
+        .with_process_name(get_image_name(&image_load.event_data.image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut image = FileNode::new(FileNode::session_strategy());
+    image
+        .with_asset_id(image_load.system.computer.computer.clone())
+        .with_file_path(image_load.event_data.image_loaded.clone())
+        .with_last_seen_timestamp(timestamp);
+
+    // Sysmon reports version information it can't read as "-"
+    let version_info = |info: &Option<String>| info.clone().filter(|info| info != "-");
+    if let Some(description) = version_info(&image_load.event_data.description) {
+        image.with_file_description(description);
+    }
+    if let Some(product) = version_info(&image_load.event_data.product) {
+        image.with_file_product(product);
+    }
+    if let Some(company) = version_info(&image_load.event_data.company) {
+        image.with_file_company(company);
+    }
+
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge_with_facets(
+        "loaded_images",
+        process.clone_node_key(),
+        image.clone_node_key(),
+        EdgeFacets::seen_at(timestamp),
+    )?;
+
+    graph.try_add_edge(
+        "files_on_asset",
+        asset.clone_node_key(),
+        image.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(image);
+
+    Ok(graph)
+}
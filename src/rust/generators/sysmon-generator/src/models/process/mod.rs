@@ -1,3 +1,11 @@
+mod access;
 mod create;
+mod create_remote_thread;
+mod image_load;
+mod terminate;
 
+pub use access::generate_process_access_subgraph;
 pub use create::generate_process_create_subgraph;
+pub use create_remote_thread::generate_create_remote_thread_subgraph;
+pub use image_load::generate_image_load_subgraph;
+pub use terminate::generate_process_terminate_subgraph;
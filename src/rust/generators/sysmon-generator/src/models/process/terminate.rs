@@ -0,0 +1,51 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IProcessNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::ProcessTerminateEvent;
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `ProcessTerminateEvent`
+///
+/// The subgraph generation for a `ProcessTerminateEvent` includes the following:
+/// * An `Asset` node - indicating the asset on which the process ran
+/// * A subject `Process` node - the process that terminated
+pub fn generate_process_terminate_subgraph(
+    process_terminate: &ProcessTerminateEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&process_terminate.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(process_terminate.system.computer.computer.clone())
+        .with_hostname(process_terminate.system.computer.computer.clone());
+
+    let mut process = process_node(&process_terminate.event_data.process_guid);
+    process
+        .with_asset_id(process_terminate.system.computer.computer.clone())
+        .with_process_id(process_terminate.event_data.process_id)
+        .with_process_name(get_image_name(&process_terminate.event_data.image).unwrap())
+        .with_terminated_timestamp(timestamp);
+
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.add_node(asset);
+    graph.add_node(process);
+
+    Ok(graph)
+}
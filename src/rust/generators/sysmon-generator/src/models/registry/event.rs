@@ -0,0 +1,217 @@
+use endpoint_plugin::{
+    AssetNode,
+    IAssetNode,
+    IProcessNode,
+    IRegistryKeyNode,
+    RegistryKeyNode,
+};
+use grapl_graph_descriptions::graph_description::*;
+use sysmon::{
+    RegistryEvent,
+    RegistryEventType,
+};
+
+use crate::{
+    generator::SysmonGeneratorError,
+    models::{
+        get_image_name,
+        process_node,
+        utc_to_epoch,
+    },
+};
+
+/// Creates a subgraph describing a `RegistryEvent`
+///
+/// The subgraph generation for a `RegistryEvent` includes the following:
+/// * An `Asset` node - indicating the asset whose registry changed
+/// * A `Process` node - the process that changed the registry
+/// * A subject `RegistryKey` node - the key or value that was created, deleted or set. Renaming
+///   deletes the key under its old path and creates it under its new path.
+pub fn generate_registry_subgraph(
+    registry_event: &RegistryEvent,
+) -> Result<GraphDescription, SysmonGeneratorError> {
+    let timestamp = utc_to_epoch(&registry_event.event_data.utc_time)?;
+    let mut graph = GraphDescription::new();
+
+    let mut asset = AssetNode::new(AssetNode::static_strategy());
+    asset
+        .with_asset_id(registry_event.system.computer.computer.clone())
+        .with_hostname(registry_event.system.computer.computer.clone());
+
+    let mut process = process_node(&registry_event.event_data.process_guid);
+    process
+        .with_asset_id(registry_event.system.computer.computer.clone())
+        .with_process_id(registry_event.event_data.process_id)
+        .with_process_name(get_image_name(&registry_event.event_data.image).unwrap())
+        .with_last_seen_timestamp(timestamp);
+
+    let mut registry_key = RegistryKeyNode::new(RegistryKeyNode::session_strategy());
+    registry_key
+        .with_asset_id(registry_event.system.computer.computer.clone())
+        .with_key_path(registry_event.event_data.target_object.clone());
+
+    let mut renamed_key = None;
+
+    let edge_name = match registry_event.event_data.event_type {
+        RegistryEventType::CreateKey | RegistryEventType::CreateValue => {
+            registry_key.with_created_timestamp(timestamp);
+            "created_registry_keys"
+        }
+        RegistryEventType::DeleteKey | RegistryEventType::DeleteValue => {
+            registry_key.with_deleted_timestamp(timestamp);
+            "deleted_registry_keys"
+        }
+        RegistryEventType::SetValue => {
+            registry_key.with_last_seen_timestamp(timestamp);
+            if let Some(details) = &registry_event.event_data.details {
+                registry_key.with_value_data(vec![details.clone()]);
+            }
+            "modified_registry_keys"
+        }
+        RegistryEventType::RenameKey | RegistryEventType::RenameValue => {
+            match &registry_event.event_data.new_name {
+                Some(new_name) => {
+                    let mut new_key = RegistryKeyNode::new(RegistryKeyNode::session_strategy());
+                    new_key
+                        .with_asset_id(registry_event.system.computer.computer.clone())
+                        .with_key_path(new_name.clone())
+                        .with_created_timestamp(timestamp);
+                    renamed_key = Some(new_key);
+
+                    registry_key.with_deleted_timestamp(timestamp);
+                    "deleted_registry_keys"
+                }
+                None => {
+                    registry_key.with_last_seen_timestamp(timestamp);
+                    "modified_registry_keys"
+                }
+            }
+        }
+    };
+
+    graph.try_add_edge(
+        "process_asset",
+        process.clone_node_key(),
+        asset.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        edge_name,
+        process.clone_node_key(),
+        registry_key.clone_node_key(),
+    )?;
+
+    graph.try_add_edge(
+        "registry_keys_on_asset",
+        asset.clone_node_key(),
+        registry_key.clone_node_key(),
+    )?;
+
+    if let Some(renamed_key) = renamed_key {
+        graph.try_add_edge(
+            "created_registry_keys",
+            process.clone_node_key(),
+            renamed_key.clone_node_key(),
+        )?;
+
+        graph.try_add_edge(
+            "registry_keys_on_asset",
+            asset.clone_node_key(),
+            renamed_key.clone_node_key(),
+        )?;
+
+        graph.add_node(renamed_key);
+    }
+
+    graph.add_node(asset);
+    graph.add_node(process);
+    graph.add_node(registry_key);
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sysmon::*;
+
+    use super::*;
+
+    const REGISTRY_SET_VALUE_EVENT: &str = r#"<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Sysmon' Guid='{5770385F-C22A-43E0-BF4C-06F5698FFBD9}'/><EventID>13</EventID><Version>2</Version><Level>4</Level><Task>13</Task><Opcode>0</Opcode><Keywords>0x8000000000000000</Keywords><TimeCreated SystemTime='2019-07-24T18:06:02.114335900Z'/><EventRecordID>561</EventRecordID><Correlation/><Execution ProcessID='3324' ThreadID='3220'/><Channel>Microsoft-Windows-Sysmon/Operational</Channel><Computer>DESKTOP-FVSHABR</Computer><Security UserID='S-1-5-18'/></System><EventData><Data Name='RuleName'></Data><Data Name='EventType'>SetValue</Data><Data Name='UtcTime'>2019-07-24 18:06:02.112</Data><Data Name='ProcessGuid'>{87E8D3BD-9DDA-5D38-0000-0010A3941D00}</Data><Data Name='ProcessId'>5752</Data><Data Name='Image'>C:\Windows\System32\reg.exe</Data><Data Name='TargetObject'>HKU\S-1-5-21-1-1000\Software\Microsoft\Windows\CurrentVersion\Run\updater</Data><Data Name='Details'>C:\Users\grapltest\AppData\Local\Temp\updater.exe</Data></EventData></Event>"#;
+
+    fn registry_event(event: &str) -> RegistryEvent {
+        match Event::from_str(event).unwrap() {
+            Event::Registry(e) => e,
+            _ => panic!("must be Registry"),
+        }
+    }
+
+    fn registry_keys(graph: &GraphDescription) -> Vec<&NodeDescription> {
+        graph
+            .nodes
+            .values()
+            .filter(|n| n.node_type == "RegistryKey")
+            .collect()
+    }
+
+    #[test]
+    fn registry_set_value() {
+        let event = registry_event(REGISTRY_SET_VALUE_EVENT);
+
+        let graph = generate_registry_subgraph(&event).expect("failed to generate graph");
+
+        let registry_keys = registry_keys(&graph);
+        assert_eq!(registry_keys.len(), 1);
+        assert_eq!(
+            registry_keys[0]
+                .get_property("value_data")
+                .unwrap()
+                .to_string(),
+            r"C:\Users\grapltest\AppData\Local\Temp\updater.exe"
+        );
+
+        let modified = graph
+            .edges
+            .values()
+            .flat_map(|edges| edges.edges.iter())
+            .find(|e| e.edge_name == "modified_registry_keys")
+            .expect("missing modified_registry_keys edge");
+        assert_eq!(modified.to_node_key, registry_keys[0].get_node_key());
+    }
+
+    #[test]
+    fn registry_rename_key() {
+        let event = REGISTRY_SET_VALUE_EVENT
+            .replace("<EventID>13</EventID>", "<EventID>14</EventID>")
+            .replace("SetValue", "RenameKey")
+            .replace(
+                r"<Data Name='Details'>C:\Users\grapltest\AppData\Local\Temp\updater.exe</Data>",
+                r"<Data Name='NewName'>HKU\S-1-5-21-1-1000\Software\Updater</Data>",
+            );
+        let event = registry_event(&event);
+
+        let graph = generate_registry_subgraph(&event).expect("failed to generate graph");
+
+        // The key is deleted under its old path and created under its new one
+        assert_eq!(registry_keys(&graph).len(), 2);
+        let edge_names: Vec<_> = graph
+            .edges
+            .values()
+            .flat_map(|edges| edges.edges.iter())
+            .map(|e| e.edge_name.as_str())
+            .collect();
+        assert!(edge_names.contains(&"deleted_registry_keys"));
+        assert!(edge_names.contains(&"created_registry_keys"));
+    }
+
+    #[cfg(feature = "schema_validation")]
+    #[test]
+    fn registry_conforms_to_endpoint_schema() {
+        let event = registry_event(REGISTRY_SET_VALUE_EVENT);
+
+        let graph = generate_registry_subgraph(&event).expect("failed to generate graph");
+
+        assert_eq!(endpoint_plugin::endpoint_schema().validate(&graph), vec![]);
+    }
+}
@@ -1,6 +1,7 @@
-use std::str::FromStr;
-
-use grapl_service::decoder::decompress::PayloadDecompressionError;
+use grapl_service::decoder::decompress::{
+    PayloadDecompression,
+    PayloadDecompressionError,
+};
 use sqs_executor::{
     errors::{
         CheckedError,
@@ -8,7 +9,10 @@ use sqs_executor::{
     },
     event_decoder::PayloadDecoder,
 };
-use sysmon::Event;
+use sysmon::{
+    Event,
+    EventStream,
+};
 
 #[derive(thiserror::Error, Clone, Debug)]
 pub enum SysmonDecoderError {
@@ -30,53 +34,62 @@ impl CheckedError for SysmonDecoderError {
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct SysmonDecoder;
+#[derive(Debug, Clone)]
+pub struct SysmonDecoder {
+    decompression: PayloadDecompression,
+}
+
+impl SysmonDecoder {
+    pub fn new(decompression: PayloadDecompression) -> Self {
+        Self { decompression }
+    }
+}
+
+impl Default for SysmonDecoder {
+    /// Decompresses payloads as `SOURCE_COMPRESSION` says
+    fn default() -> Self {
+        Self::new(PayloadDecompression::from_env())
+    }
+}
 
 impl PayloadDecoder<Vec<Event>> for SysmonDecoder {
     type DecoderError = SysmonDecoderError;
 
     fn decode(&mut self, body: Vec<u8>) -> Result<Vec<Event>, Self::DecoderError> {
-        let decompressed = grapl_service::decoder::decompress::maybe_decompress(body.as_slice())?;
+        let decompressed = self.decompression.decompress(body.as_slice())?;
 
         let mut first_deserialization_error: Option<SysmonDecoderError> = None;
 
         /*
-           This iterator is taking a set of bytes of the logs, splitting the logs on newlines,
-           converting the byte sequences to utf-8 strings, and then filtering on supported event
-           types: Process Creation, Network Connection, and File Creation.
+           This iterator is taking a stream of the logs' XML, one `<Event>` element at a time,
+           and parsing the event types that `sysmon::Event` supports. Events of other types are
+           skipped.
 
            https://docs.microsoft.com/en-us/sysinternals/downloads/sysmon#events
         */
-        let events: Vec<_> = decompressed
-            .split(|byte| *byte == b'\n')
-            .map(String::from_utf8_lossy)
-            .map(|s| s.to_string())
-            .filter_map(|event_str| {
-                let parsed_event = Event::from_str(&event_str);
-                match parsed_event {
-                    Ok(event) => Some(event),
-                    Err(error) => {
-                        tracing::error!(
-                            message = "Unable to deserialize Sysmon event",
-                            error =? error,
-                            event_str =% event_str
-                        );
+        let events: Vec<_> = EventStream::new(decompressed.as_slice())
+            .filter_map(|parsed_event| match parsed_event {
+                Ok(event) => Some(event),
+                Err(sysmon::Error::UnsupportedEventId(event_id)) => {
+                    tracing::debug!(
+                        message = "Skipping unsupported Sysmon event",
+                        event_id =% event_id
+                    );
+                    None
+                }
+                Err(error) => {
+                    tracing::error!(
+                        message = "Unable to deserialize Sysmon event",
+                        error =% error,
+                    );
 
-                        if first_deserialization_error.is_none() {
-                            first_deserialization_error =
-                                Some(SysmonDecoderError::DeserializeError(error.to_string()))
-                        }
-                        None
+                    if first_deserialization_error.is_none() {
+                        first_deserialization_error =
+                            Some(SysmonDecoderError::DeserializeError(error.to_string()))
                     }
+                    None
                 }
             })
-            .filter(|event| {
-                event.is_process_create()
-                    || event.is_file_create()
-                    || event.is_inbound_network()
-                    || event.is_outbound_network()
-            })
             .collect();
 
         // This is a bit awkward at the moment, due to interfaces to the sqs-executor. If some of
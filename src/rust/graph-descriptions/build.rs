@@ -1,11 +1,66 @@
+const TYPES_PROTO: &str = "../../proto/graplinc/grapl/api/graph/v1beta1/types.proto";
+
+/// The fully qualified paths of the top level messages in `proto` that hold no `double`, neither
+/// in their own fields nor in those of the messages they hold
+fn messages_without_floats(proto: &str) -> Vec<String> {
+    let package = proto
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("package "))
+        .map(|package| package.trim_end_matches(';').trim())
+        .expect("proto file has no package");
+
+    // The type names in each message's fields
+    let mut messages: Vec<(&str, Vec<&str>)> = vec![];
+    for line in proto.lines() {
+        if let Some(message) = line.strip_prefix("message ") {
+            messages.push((message.trim_end_matches('{').trim(), vec![]));
+        } else if line.trim_start().starts_with("//") {
+            continue;
+        } else if let Some((_, field_types)) = messages.last_mut() {
+            field_types.extend(
+                line.split(|c: char| c.is_whitespace() || "<>,".contains(c))
+                    .filter(|word| !word.is_empty()),
+            );
+        }
+    }
+
+    let mut with_floats = vec!["double"];
+    loop {
+        let found: Vec<&str> = messages
+            .iter()
+            .filter(|(message, _)| !with_floats.contains(message))
+            .filter(|(_, field_types)| field_types.iter().any(|word| with_floats.contains(word)))
+            .map(|(message, _)| *message)
+            .collect();
+        if found.is_empty() {
+            break;
+        }
+        with_floats.extend(found);
+    }
+
+    messages
+        .into_iter()
+        .filter(|(message, _)| !with_floats.contains(message))
+        .map(|(message, _)| format!(".{}.{}", package, message))
+        .collect()
+}
+
 fn main() {
     let mut config = prost_build::Config::new();
 
     config.type_attribute(
         ".",
-        "#[derive(Eq, serde_derive::Serialize, serde_derive::Deserialize)]",
+        "#[derive(serde_derive::Serialize, serde_derive::Deserialize)]",
     );
 
+    // f64 isn't Eq, as NaN isn't equal to itself, so only the messages that don't hold a float
+    // can derive it
+    let types_proto = std::fs::read_to_string(TYPES_PROTO)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", TYPES_PROTO, e));
+    for type_path in messages_without_floats(&types_proto) {
+        config.type_attribute(type_path, "#[derive(Eq)]");
+    }
+
     config.type_attribute(
         ".graplinc.grapl.api.graph.v1beta1.IncrementOnlyIntProp",
         "#[derive(Copy, Ord, PartialOrd)]",
@@ -36,6 +91,41 @@ fn main() {
         "#[derive(Copy, Ord, PartialOrd)]",
     );
 
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.ImmutableBoolProp",
+        "#[derive(Copy, Ord, PartialOrd)]",
+    );
+
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.ImmutableTimestampProp",
+        "#[derive(Copy, Ord, PartialOrd)]",
+    );
+
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.IncrementOnlyTimestampProp",
+        "#[derive(Copy, Ord, PartialOrd)]",
+    );
+
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.DecrementOnlyTimestampProp",
+        "#[derive(Copy, Ord, PartialOrd)]",
+    );
+
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.IncrementOnlyFloatProp",
+        "#[derive(Copy, PartialOrd)]",
+    );
+
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.DecrementOnlyFloatProp",
+        "#[derive(Copy, PartialOrd)]",
+    );
+
+    config.type_attribute(
+        ".graplinc.grapl.api.graph.v1beta1.EdgeFacets",
+        "#[derive(Copy, Ord, PartialOrd)]",
+    );
+
     config.type_attribute(
         ".graplinc.grapl.api.graph.v1beta1.MergedEdge",
         "#[derive(Ord, PartialOrd)]",
@@ -456,9 +546,6 @@ fn main() {
     );
 
     config
-        .compile_protos(
-            &["../../proto/graplinc/grapl/api/graph/v1beta1/types.proto"],
-            &["../../proto/"],
-        )
+        .compile_protos(&[TYPES_PROTO], &["../../proto/"])
         .unwrap_or_else(|e| panic!("protobuf compilation failed: {}", e));
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 pub mod graph_description {
     // TODO: Restructure the Rust modules to better reflect the new
     // Protobuf structure
@@ -6,14 +8,23 @@ pub mod graph_description {
         "/graplinc.grapl.api.graph.v1beta1.rs"
     ));
 }
+pub mod schema;
 pub use node_property::Property::{
+    DecrementOnlyFloat as ProtoDecrementOnlyFloatProp,
     DecrementOnlyInt as ProtoDecrementOnlyIntProp,
+    DecrementOnlyTimestamp as ProtoDecrementOnlyTimestampProp,
     DecrementOnlyUint as ProtoDecrementOnlyUintProp,
+    ImmutableBool as ProtoImmutableBoolProp,
+    ImmutableBytes as ProtoImmutableBytesProp,
     ImmutableInt as ProtoImmutableIntProp,
     ImmutableStr as ProtoImmutableStrProp,
+    ImmutableTimestamp as ProtoImmutableTimestampProp,
     ImmutableUint as ProtoImmutableUintProp,
+    IncrementOnlyFloat as ProtoIncrementOnlyFloatProp,
     IncrementOnlyInt as ProtoIncrementOnlyIntProp,
+    IncrementOnlyTimestamp as ProtoIncrementOnlyTimestampProp,
     IncrementOnlyUint as ProtoIncrementOnlyUintProp,
+    StrSet as ProtoStrSetProp,
 };
 
 pub use crate::{
@@ -51,6 +62,24 @@ macro_rules ! extra_assert {
     }
 }
 
+/// Why a node, edge or graph couldn't be merged into another
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    #[error("predicate {predicate} of node {node_key} is a {expected}, and can't merge a {found}")]
+    PropertyTypeMismatch {
+        node_key: String,
+        predicate: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("predicate {predicate} of node {node_key} has no value")]
+    MissingProperty { node_key: String, predicate: String },
+    #[error("predicate {predicate} of node {node_key} is a float that's NaN or infinite")]
+    NonFiniteFloat { node_key: String, predicate: String },
+    #[error("edge {edge_name} of node {node_key} points back to the node itself")]
+    SelfLoop { node_key: String, edge_name: String },
+}
+
 impl EdgeList {
     pub fn into_vec(self) -> Vec<Edge> {
         let Self { edges } = self;
@@ -76,32 +105,102 @@ impl GraphDescription {
         };
     }
 
+    /// Adds the node, or merges it into the node with the same key. Nothing is changed if any of
+    /// its predicates can't be merged.
+    pub fn try_add_node(&mut self, node: impl Into<NodeDescription>) -> Result<(), MergeError> {
+        let node = node.into();
+        match self.nodes.get_mut(&node.node_key) {
+            Some(n) => n.try_merge(&node)?,
+            None => {
+                check_properties(&node.node_key, &node.properties)?;
+                self.nodes.insert(node.clone_node_key(), node);
+            }
+        };
+        Ok(())
+    }
+
     pub fn add_edge(
         &mut self,
         edge_name: impl Into<String>,
         from_node_key: impl Into<String>,
         to_node_key: impl Into<String>,
     ) {
-        let from_node_key = from_node_key.into();
-        let to_node_key = to_node_key.into();
-        let edge_name = edge_name.into();
+        self.insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: None,
+        });
+    }
 
-        assert_ne!(from_node_key, to_node_key);
+    pub fn try_add_edge(
+        &mut self,
+        edge_name: impl Into<String>,
+        from_node_key: impl Into<String>,
+        to_node_key: impl Into<String>,
+    ) -> Result<(), MergeError> {
+        self.try_insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: None,
+        })
+    }
 
-        let edge = Edge {
-            from_node_key: from_node_key.clone(),
-            to_node_key,
-            edge_name,
-        };
+    pub fn add_edge_with_facets(
+        &mut self,
+        edge_name: impl Into<String>,
+        from_node_key: impl Into<String>,
+        to_node_key: impl Into<String>,
+        facets: EdgeFacets,
+    ) {
+        self.insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: Some(facets),
+        });
+    }
+
+    pub fn try_add_edge_with_facets(
+        &mut self,
+        edge_name: impl Into<String>,
+        from_node_key: impl Into<String>,
+        to_node_key: impl Into<String>,
+        facets: EdgeFacets,
+    ) -> Result<(), MergeError> {
+        self.try_insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: Some(facets),
+        })
+    }
+
+    /// Adds the edge, or accumulates its facets into those of the same edge if it's already
+    /// been added. Edges from a node to itself are dropped.
+    pub fn insert_edge(&mut self, edge: Edge) {
+        self.try_insert_edge(edge)
+            .unwrap_or_else(|e| tracing::warn!(message="Dropping invalid edge", error=%e));
+    }
+
+    /// Adds the edge, or accumulates its facets into those of the same edge if it's already
+    /// been added
+    pub fn try_insert_edge(&mut self, edge: Edge) -> Result<(), MergeError> {
+        edge.check_not_self_loop()?;
 
         let edge_list: &mut Vec<Edge> = &mut self
             .edges
-            .entry(from_node_key)
+            .entry(edge.from_node_key.clone())
             .or_insert_with(|| EdgeList {
                 edges: Vec::with_capacity(1),
             })
             .edges;
-        edge_list.push(edge);
+        match edge_list.iter_mut().find(|e| e.is_same_edge(&edge)) {
+            Some(e) => e.accumulate_facets(edge.facets.as_ref()),
+            None => edge_list.push(edge),
+        }
+        Ok(())
     }
 
     pub fn merge(&mut self, other: &Self) {
@@ -116,13 +215,28 @@ impl GraphDescription {
 
         for edge_list in other.edges.values() {
             for edge in edge_list.edges.iter() {
-                self.add_edge(
-                    edge.edge_name.clone(),
-                    edge.from_node_key.clone(),
-                    edge.to_node_key.clone(),
-                );
+                self.insert_edge(edge.clone());
+            }
+        }
+    }
+
+    /// Merges `other` into this graph. Nothing is changed if any of its nodes or edges can't be
+    /// merged.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), MergeError> {
+        for (node_key, other_node) in other.nodes.iter() {
+            match self.nodes.get(node_key) {
+                Some(n) => check_properties_merge(node_key, &n.properties, &other_node.properties)?,
+                None => check_properties(node_key, &other_node.properties)?,
+            }
+        }
+        for edge_list in other.edges.values() {
+            for edge in edge_list.edges.iter() {
+                edge.check_not_self_loop()?;
             }
         }
+
+        self.merge(other);
+        Ok(())
     }
 
     pub fn is_empty(&self) -> bool {
@@ -147,31 +261,94 @@ impl IdentifiedGraph {
         };
     }
 
+    /// Adds the node, or merges it into the node with the same key. Nothing is changed if any of
+    /// its predicates can't be merged.
+    pub fn try_add_node(&mut self, node: IdentifiedNode) -> Result<(), MergeError> {
+        match self.nodes.get_mut(&node.node_key) {
+            Some(n) => n.try_merge(&node)?,
+            None => {
+                check_properties(&node.node_key, &node.properties)?;
+                self.nodes.insert(node.clone_node_key(), node);
+            }
+        };
+        Ok(())
+    }
+
+    /// Checks that every predicate of every node has a value, and that no float is NaN or infinite
+    pub fn check_properties(&self) -> Result<(), MergeError> {
+        for node in self.nodes.values() {
+            check_properties(&node.node_key, &node.properties)?;
+        }
+        Ok(())
+    }
+
     pub fn add_edge(
         &mut self,
         edge_name: impl Into<String>,
         from_node_key: impl Into<String>,
         to_node_key: impl Into<String>,
     ) {
-        let from_node_key = from_node_key.into();
-        let to_node_key = to_node_key.into();
-        assert_ne!(from_node_key, to_node_key);
-
-        let edge_name = edge_name.into();
-        let edge = Edge {
-            from_node_key: from_node_key.clone(),
-            to_node_key,
-            edge_name,
-        };
+        self.insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: None,
+        });
+    }
+
+    pub fn try_add_edge(
+        &mut self,
+        edge_name: impl Into<String>,
+        from_node_key: impl Into<String>,
+        to_node_key: impl Into<String>,
+    ) -> Result<(), MergeError> {
+        self.try_insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: None,
+        })
+    }
+
+    pub fn add_edge_with_facets(
+        &mut self,
+        edge_name: impl Into<String>,
+        from_node_key: impl Into<String>,
+        to_node_key: impl Into<String>,
+        facets: EdgeFacets,
+    ) {
+        self.insert_edge(Edge {
+            from_node_key: from_node_key.into(),
+            to_node_key: to_node_key.into(),
+            edge_name: edge_name.into(),
+            facets: Some(facets),
+        });
+    }
+
+    /// Adds the edge, or accumulates its facets into those of the same edge if it's already
+    /// been added. Edges from a node to itself are dropped.
+    pub fn insert_edge(&mut self, edge: Edge) {
+        self.try_insert_edge(edge)
+            .unwrap_or_else(|e| tracing::warn!(message="Dropping invalid edge", error=%e));
+    }
+
+    /// Adds the edge, or accumulates its facets into those of the same edge if it's already
+    /// been added
+    pub fn try_insert_edge(&mut self, edge: Edge) -> Result<(), MergeError> {
+        edge.check_not_self_loop()?;
 
         let edge_list: &mut Vec<Edge> = &mut self
             .edges
-            .entry(from_node_key)
+            .entry(edge.from_node_key.clone())
             .or_insert_with(|| EdgeList {
                 edges: Vec::with_capacity(1),
             })
             .edges;
-        edge_list.push(edge);
+        match edge_list.iter_mut().find(|e| e.is_same_edge(&edge)) {
+            Some(e) => e.accumulate_facets(edge.facets.as_ref()),
+            None => edge_list.push(edge),
+        }
+        Ok(())
     }
 
     pub fn merge(&mut self, other: &Self) {
@@ -186,13 +363,28 @@ impl IdentifiedGraph {
 
         for edge_list in other.edges.values() {
             for edge in edge_list.edges.iter() {
-                self.add_edge(
-                    edge.edge_name.clone(),
-                    edge.from_node_key.clone(),
-                    edge.to_node_key.clone(),
-                );
+                self.insert_edge(edge.clone());
+            }
+        }
+    }
+
+    /// Merges `other` into this graph. Nothing is changed if any of its nodes or edges can't be
+    /// merged.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), MergeError> {
+        for (node_key, other_node) in other.nodes.iter() {
+            match self.nodes.get(node_key) {
+                Some(n) => check_properties_merge(node_key, &n.properties, &other_node.properties)?,
+                None => check_properties(node_key, &other_node.properties)?,
+            }
+        }
+        for edge_list in other.edges.values() {
+            for edge in edge_list.edges.iter() {
+                edge.check_not_self_loop()?;
             }
         }
+
+        self.merge(other);
+        Ok(())
     }
 
     pub fn is_empty(&self) -> bool {
@@ -217,16 +409,43 @@ impl MergedGraph {
         };
     }
 
+    /// Adds the node, or merges it into the node with the same key. Nothing is changed if any of
+    /// its predicates can't be merged.
+    pub fn try_add_node(&mut self, node: MergedNode) -> Result<(), MergeError> {
+        match self.nodes.get_mut(&node.node_key) {
+            Some(n) => n.try_merge(&node)?,
+            None => {
+                check_properties(&node.node_key, &node.properties)?;
+                self.nodes.insert(node.clone_node_key(), node);
+            }
+        };
+        Ok(())
+    }
+
+    /// Adds the edge, or accumulates its facets into those of the same edge if it's already
+    /// been added. Edges from a node to itself are dropped.
     pub fn add_merged_edge(&mut self, edge: MergedEdge) {
-        let from_node_key = edge.from_node_key.clone();
+        self.try_add_merged_edge(edge)
+            .unwrap_or_else(|e| tracing::warn!(message="Dropping invalid edge", error=%e));
+    }
+
+    /// Adds the edge, or accumulates its facets into those of the same edge if it's already
+    /// been added
+    pub fn try_add_merged_edge(&mut self, edge: MergedEdge) -> Result<(), MergeError> {
+        edge.check_not_self_loop()?;
+
         let edge_list: &mut Vec<MergedEdge> = &mut self
             .edges
-            .entry(from_node_key)
+            .entry(edge.from_node_key.clone())
             .or_insert_with(|| MergedEdgeList {
                 edges: Vec::with_capacity(1),
             })
             .edges;
-        edge_list.push(edge);
+        match edge_list.iter_mut().find(|e| e.is_same_edge(&edge)) {
+            Some(e) => e.facets = accumulate_facets(e.facets, edge.facets.as_ref()),
+            None => edge_list.push(edge),
+        }
+        Ok(())
     }
 
     pub fn add_edge(
@@ -237,29 +456,32 @@ impl MergedGraph {
         to_node_key: impl Into<String>,
         to_uid: impl Into<String>,
     ) {
-        let edge_name = edge_name.into();
-        let from_node_key = from_node_key.into();
-        let from_uid = from_uid.into();
-        let to_node_key = to_node_key.into();
-        let to_uid = to_uid.into();
-        assert_ne!(from_node_key, to_node_key);
-        assert_ne!(from_uid, to_uid);
-        let edge = MergedEdge {
-            from_node_key: from_node_key.clone(),
-            from_uid,
-            to_node_key,
-            to_uid,
-            edge_name,
-        };
+        self.add_merged_edge(MergedEdge {
+            from_node_key: from_node_key.into(),
+            from_uid: from_uid.into(),
+            to_node_key: to_node_key.into(),
+            to_uid: to_uid.into(),
+            edge_name: edge_name.into(),
+            facets: None,
+        });
+    }
 
-        let edge_list: &mut Vec<MergedEdge> = &mut self
-            .edges
-            .entry(from_node_key)
-            .or_insert_with(|| MergedEdgeList {
-                edges: Vec::with_capacity(1),
-            })
-            .edges;
-        edge_list.push(edge);
+    pub fn try_add_edge(
+        &mut self,
+        edge_name: impl Into<String>,
+        from_node_key: impl Into<String>,
+        from_uid: impl Into<String>,
+        to_node_key: impl Into<String>,
+        to_uid: impl Into<String>,
+    ) -> Result<(), MergeError> {
+        self.try_add_merged_edge(MergedEdge {
+            from_node_key: from_node_key.into(),
+            from_uid: from_uid.into(),
+            to_node_key: to_node_key.into(),
+            to_uid: to_uid.into(),
+            edge_name: edge_name.into(),
+            facets: None,
+        })
     }
 
     pub fn merge(&mut self, other: &Self) {
@@ -274,15 +496,28 @@ impl MergedGraph {
 
         for edge_list in other.edges.values() {
             for edge in edge_list.edges.iter() {
-                self.add_edge(
-                    edge.edge_name.clone(),
-                    edge.from_node_key.clone(),
-                    edge.from_uid.clone(),
-                    edge.to_node_key.clone(),
-                    edge.to_uid.clone(),
-                );
+                self.add_merged_edge(edge.clone());
+            }
+        }
+    }
+
+    /// Merges `other` into this graph. Nothing is changed if any of its nodes or edges can't be
+    /// merged.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), MergeError> {
+        for (node_key, other_node) in other.nodes.iter() {
+            match self.nodes.get(node_key) {
+                Some(n) => check_properties_merge(node_key, &n.properties, &other_node.properties)?,
+                None => check_properties(node_key, &other_node.properties)?,
+            }
+        }
+        for edge_list in other.edges.values() {
+            for edge in edge_list.edges.iter() {
+                edge.check_not_self_loop()?;
             }
         }
+
+        self.merge(other);
+        Ok(())
     }
 
     pub fn is_empty(&self) -> bool {
@@ -290,19 +525,125 @@ impl MergedGraph {
     }
 }
 
+impl EdgeFacets {
+    /// The facets of an edge that was seen once, at `timestamp`
+    pub fn seen_at(timestamp: u64) -> Self {
+        Self {
+            first_seen: Some(timestamp.into()),
+            last_seen: Some(timestamp.into()),
+            count: Some(1u64.into()),
+        }
+    }
+
+    /// Merges the facets of the same edge according to each facet's merge semantics, as a
+    /// NodeProperty is merged
+    pub fn merge(&mut self, other: &Self) {
+        merge_facet(
+            &mut self.first_seen,
+            other.first_seen,
+            DecrementOnlyUintProp::merge_property,
+        );
+        merge_facet(
+            &mut self.last_seen,
+            other.last_seen,
+            IncrementOnlyUintProp::merge_property,
+        );
+        merge_facet(
+            &mut self.count,
+            other.count,
+            IncrementOnlyUintProp::merge_property,
+        );
+    }
+
+    /// Combines the facets of an edge that was seen again within the same graph. Unlike `merge`,
+    /// this adds the counts together.
+    pub fn accumulate(&mut self, other: &Self) {
+        let count = match (self.count, other.count) {
+            (Some(count), Some(other_count)) => Some(IncrementOnlyUintProp {
+                prop: count.prop.saturating_add(other_count.prop),
+            }),
+            (count, other_count) => count.or(other_count),
+        };
+        self.merge(other);
+        self.count = count;
+    }
+}
+
+fn merge_facet<T: Copy>(facet: &mut Option<T>, other: Option<T>, merge: impl Fn(&mut T, &T)) {
+    match (facet.as_mut(), other) {
+        (Some(facet), Some(other)) => merge(facet, &other),
+        (None, other) => *facet = other,
+        (Some(_), None) => {}
+    }
+}
+
+fn accumulate_facets(facets: Option<EdgeFacets>, other: Option<&EdgeFacets>) -> Option<EdgeFacets> {
+    match (facets, other) {
+        (Some(mut facets), Some(other)) => {
+            facets.accumulate(other);
+            Some(facets)
+        }
+        (facets, other) => facets.or_else(|| other.copied()),
+    }
+}
+
+impl Edge {
+    fn check_not_self_loop(&self) -> Result<(), MergeError> {
+        if self.from_node_key == self.to_node_key {
+            return Err(MergeError::SelfLoop {
+                node_key: self.from_node_key.clone(),
+                edge_name: self.edge_name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether both describe the same edge, regardless of their facets
+    pub fn is_same_edge(&self, other: &Self) -> bool {
+        self.edge_name == other.edge_name
+            && self.from_node_key == other.from_node_key
+            && self.to_node_key == other.to_node_key
+    }
+
+    fn accumulate_facets(&mut self, other: Option<&EdgeFacets>) {
+        self.facets = accumulate_facets(self.facets, other);
+    }
+}
+
+impl MergedEdge {
+    fn check_not_self_loop(&self) -> Result<(), MergeError> {
+        if self.from_node_key == self.to_node_key || self.from_uid == self.to_uid {
+            return Err(MergeError::SelfLoop {
+                node_key: self.from_node_key.clone(),
+                edge_name: self.edge_name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether both describe the same edge, regardless of their facets
+    pub fn is_same_edge(&self, other: &Self) -> bool {
+        self.edge_name == other.edge_name
+            && self.from_node_key == other.from_node_key
+            && self.to_node_key == other.to_node_key
+    }
+}
+
 impl NodeDescription {
     pub fn merge(&mut self, other: &Self) {
         extra_assert!(debug_assert_eq!(self.node_type, other.node_type));
         extra_assert!(debug_assert_eq!(self.node_key, other.node_key));
-        for (prop_name, prop_value) in other.properties.iter() {
-            match self.properties.get_mut(prop_name) {
-                Some(self_prop) => self_prop.merge(prop_value),
-                None => {
-                    self.properties
-                        .insert(prop_name.clone(), prop_value.clone());
-                }
-            }
-        }
+        merge_properties(&self.node_key, &mut self.properties, &other.properties);
+    }
+
+    /// Merges `other`'s predicates into this node's. Nothing is changed if any of them can't be
+    /// merged.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), MergeError> {
+        extra_assert!(debug_assert_eq!(self.node_type, other.node_type));
+        extra_assert!(debug_assert_eq!(self.node_key, other.node_key));
+        check_properties_merge(&self.node_key, &self.properties, &other.properties)?;
+        merge_properties(&self.node_key, &mut self.properties, &other.properties);
+        Ok(())
     }
     pub fn get_node_key(&self) -> &str {
         self.node_key.as_str()
@@ -317,33 +658,42 @@ impl IdentifiedNode {
     pub fn merge(&mut self, other: &Self) {
         extra_assert!(debug_assert_eq!(self.node_type, other.node_type));
         extra_assert!(debug_assert_eq!(self.node_key, other.node_key));
-        for (prop_name, prop_value) in other.properties.iter() {
-            match self.properties.get_mut(prop_name) {
-                Some(self_prop) => self_prop.merge(prop_value),
-                None => {
-                    self.properties
-                        .insert(prop_name.clone(), prop_value.clone());
-                }
-            }
-        }
+        merge_properties(&self.node_key, &mut self.properties, &other.properties);
     }
 
-    pub fn get_cache_identities_for_predicates(&self) -> Vec<Vec<u8>> {
-        let mut predicate_cache_identities = Vec::with_capacity(self.properties.len());
-
-        for (key, prop) in &self.properties {
-            let prop_value = prop
-                .property
-                .as_ref()
-                .map(Property::to_string)
-                .unwrap_or_else(|| panic!("Invalid property on DynamicNode: {}", self.node_key));
-
-            predicate_cache_identities.push(format!("{}:{}:{}", &self.node_key, key, prop_value));
+    /// Merges `other`'s predicates into this node's. Nothing is changed if any of them can't be
+    /// merged.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), MergeError> {
+        extra_assert!(debug_assert_eq!(self.node_type, other.node_type));
+        extra_assert!(debug_assert_eq!(self.node_key, other.node_key));
+        check_properties_merge(&self.node_key, &self.properties, &other.properties)?;
+        merge_properties(&self.node_key, &mut self.properties, &other.properties);
+        Ok(())
+    }
+
+    /// Identifies the predicate's current value, for predicates that are safe to skip writing
+    /// once they've been written. Only immutable predicates are, as writing any other predicate
+    /// again may still change its stored value.
+    pub fn get_cache_identity_for_predicate(&self, predicate_name: &str) -> Option<Vec<u8>> {
+        let property = self.properties.get(predicate_name)?.property.as_ref()?;
+        if !property.is_immutable() {
+            return None;
         }
 
-        predicate_cache_identities
-            .into_iter()
-            .map(|item| item.into_bytes())
+        let identity = format!(
+            "{}:{}:{}",
+            &self.node_key,
+            predicate_name,
+            property.to_string()
+        );
+        Some(identity.into_bytes())
+    }
+
+    /// The cache identities of every immutable predicate on this node
+    pub fn get_cache_identities_for_predicates(&self) -> Vec<Vec<u8>> {
+        self.properties
+            .keys()
+            .filter_map(|predicate_name| self.get_cache_identity_for_predicate(predicate_name))
             .collect()
     }
 }
@@ -355,21 +705,107 @@ impl NodeProperty {
                 p.merge_property(op);
             }
             (None, Some(op)) => {
-                debug_assert!(false, "Unhandled property merge, self is None: {:?}", op);
                 tracing::warn!("Unhandled property merge, self is None: {:?}", op);
             }
             (Some(p), None) => {
-                debug_assert!(false, "Unhandled property merge, other is None: {:?}", p);
                 tracing::warn!("Unhandled property merge, other is None: {:?}", p);
             }
             (None, None) => {
-                debug_assert!(false, "Unhandled property merge, both properties are None");
                 tracing::warn!(message = "Unhandled property merge, both properties are None");
             }
         }
     }
 }
 
+/// Checks that every predicate in `other_properties` has a value of the same type as the
+/// predicate it would be merged into
+fn check_properties_merge(
+    node_key: &str,
+    properties: &HashMap<String, NodeProperty>,
+    other_properties: &HashMap<String, NodeProperty>,
+) -> Result<(), MergeError> {
+    for (predicate, other_prop) in other_properties.iter() {
+        check_property_merge(node_key, predicate, properties.get(predicate), other_prop)?;
+    }
+    Ok(())
+}
+
+fn check_property_merge(
+    node_key: &str,
+    predicate: &str,
+    self_prop: Option<&NodeProperty>,
+    other_prop: &NodeProperty,
+) -> Result<(), MergeError> {
+    let other_prop = property_value(node_key, predicate, other_prop)?;
+    let self_prop = match self_prop {
+        Some(self_prop) => property_value(node_key, predicate, self_prop)?,
+        None => return Ok(()),
+    };
+    if std::mem::discriminant(self_prop) != std::mem::discriminant(other_prop) {
+        return Err(MergeError::PropertyTypeMismatch {
+            node_key: node_key.to_owned(),
+            predicate: predicate.to_owned(),
+            expected: self_prop.type_name(),
+            found: other_prop.type_name(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that every predicate has a value, and that no float is NaN or infinite
+fn check_properties(
+    node_key: &str,
+    properties: &HashMap<String, NodeProperty>,
+) -> Result<(), MergeError> {
+    for (predicate, prop) in properties.iter() {
+        property_value(node_key, predicate, prop)?;
+    }
+    Ok(())
+}
+
+fn property_value<'a>(
+    node_key: &str,
+    predicate: &str,
+    node_property: &'a NodeProperty,
+) -> Result<&'a Property, MergeError> {
+    let property = node_property
+        .property
+        .as_ref()
+        .ok_or_else(|| MergeError::MissingProperty {
+            node_key: node_key.to_owned(),
+            predicate: predicate.to_owned(),
+        })?;
+    if !property.is_finite() {
+        return Err(MergeError::NonFiniteFloat {
+            node_key: node_key.to_owned(),
+            predicate: predicate.to_owned(),
+        });
+    }
+    Ok(property)
+}
+
+/// Merges every predicate in `other_properties` that can be merged, skipping the rest
+fn merge_properties(
+    node_key: &str,
+    properties: &mut HashMap<String, NodeProperty>,
+    other_properties: &HashMap<String, NodeProperty>,
+) {
+    for (prop_name, prop_value) in other_properties.iter() {
+        if let Err(e) =
+            check_property_merge(node_key, prop_name, properties.get(prop_name), prop_value)
+        {
+            tracing::warn!(message="Skipping predicate that can't be merged", error=%e);
+            continue;
+        }
+        match properties.get_mut(prop_name) {
+            Some(self_prop) => self_prop.merge(prop_value),
+            None => {
+                properties.insert(prop_name.clone(), prop_value.clone());
+            }
+        }
+    }
+}
+
 impl Property {
     pub fn merge_property(&mut self, other: &Self) {
         match (self, other) {
@@ -398,6 +834,36 @@ impl Property {
             (ProtoImmutableStrProp(ref mut self_prop), ProtoImmutableStrProp(ref other_prop)) => {
                 self_prop.merge_property(other_prop)
             }
+            (ProtoImmutableBoolProp(ref mut self_prop), ProtoImmutableBoolProp(ref other_prop)) => {
+                self_prop.merge_property(other_prop)
+            }
+            (
+                ProtoIncrementOnlyFloatProp(ref mut self_prop),
+                ProtoIncrementOnlyFloatProp(ref other_prop),
+            ) => self_prop.merge_property(other_prop),
+            (
+                ProtoDecrementOnlyFloatProp(ref mut self_prop),
+                ProtoDecrementOnlyFloatProp(ref other_prop),
+            ) => self_prop.merge_property(other_prop),
+            (
+                ProtoImmutableBytesProp(ref mut self_prop),
+                ProtoImmutableBytesProp(ref other_prop),
+            ) => self_prop.merge_property(other_prop),
+            (ProtoStrSetProp(ref mut self_prop), ProtoStrSetProp(ref other_prop)) => {
+                self_prop.merge_property(other_prop)
+            }
+            (
+                ProtoImmutableTimestampProp(ref mut self_prop),
+                ProtoImmutableTimestampProp(ref other_prop),
+            ) => self_prop.merge_property(other_prop),
+            (
+                ProtoIncrementOnlyTimestampProp(ref mut self_prop),
+                ProtoIncrementOnlyTimestampProp(ref other_prop),
+            ) => self_prop.merge_property(other_prop),
+            (
+                ProtoDecrementOnlyTimestampProp(ref mut self_prop),
+                ProtoDecrementOnlyTimestampProp(ref other_prop),
+            ) => self_prop.merge_property(other_prop),
             // technically we could improve type safety here by exhausting the combinations,
             // but I'm not going to type that all out right now
             (p, op) => {
@@ -414,6 +880,59 @@ impl Property {
     }
 }
 
+impl Property {
+    /// The name of this property's type, as it's written in the protobuf definitions
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ProtoIncrementOnlyUintProp(_) => "IncrementOnlyUintProp",
+            ProtoImmutableUintProp(_) => "ImmutableUintProp",
+            ProtoDecrementOnlyUintProp(_) => "DecrementOnlyUintProp",
+            ProtoDecrementOnlyIntProp(_) => "DecrementOnlyIntProp",
+            ProtoIncrementOnlyIntProp(_) => "IncrementOnlyIntProp",
+            ProtoImmutableIntProp(_) => "ImmutableIntProp",
+            ProtoImmutableStrProp(_) => "ImmutableStrProp",
+            ProtoImmutableBoolProp(_) => "ImmutableBoolProp",
+            ProtoIncrementOnlyFloatProp(_) => "IncrementOnlyFloatProp",
+            ProtoDecrementOnlyFloatProp(_) => "DecrementOnlyFloatProp",
+            ProtoImmutableBytesProp(_) => "ImmutableBytesProp",
+            ProtoStrSetProp(_) => "StrSetProp",
+            ProtoImmutableTimestampProp(_) => "ImmutableTimestampProp",
+            ProtoIncrementOnlyTimestampProp(_) => "IncrementOnlyTimestampProp",
+            ProtoDecrementOnlyTimestampProp(_) => "DecrementOnlyTimestampProp",
+        }
+    }
+
+    /// Whether the first value written for this property is the one that's kept
+    pub fn is_immutable(&self) -> bool {
+        match self {
+            ProtoImmutableUintProp(_)
+            | ProtoImmutableIntProp(_)
+            | ProtoImmutableStrProp(_)
+            | ProtoImmutableBoolProp(_)
+            | ProtoImmutableBytesProp(_)
+            | ProtoImmutableTimestampProp(_) => true,
+            ProtoIncrementOnlyUintProp(_)
+            | ProtoDecrementOnlyUintProp(_)
+            | ProtoDecrementOnlyIntProp(_)
+            | ProtoIncrementOnlyIntProp(_)
+            | ProtoIncrementOnlyFloatProp(_)
+            | ProtoDecrementOnlyFloatProp(_)
+            | ProtoStrSetProp(_)
+            | ProtoIncrementOnlyTimestampProp(_)
+            | ProtoDecrementOnlyTimestampProp(_) => false,
+        }
+    }
+
+    /// Whether this property's value is a number other than NaN or infinity, or isn't a float
+    pub fn is_finite(&self) -> bool {
+        match self {
+            ProtoIncrementOnlyFloatProp(prop) => prop.prop.is_finite(),
+            ProtoDecrementOnlyFloatProp(prop) => prop.prop.is_finite(),
+            _ => true,
+        }
+    }
+}
+
 impl From<Static> for IdStrategy {
     fn from(strategy: Static) -> IdStrategy {
         IdStrategy {
@@ -447,6 +966,24 @@ impl std::string::ToString for Property {
             }
             ProtoImmutableIntProp(immutable_int_prop) => immutable_int_prop.to_string(),
             ProtoImmutableStrProp(immutable_str_prop) => immutable_str_prop.to_string(),
+            ProtoImmutableBoolProp(immutable_bool_prop) => immutable_bool_prop.to_string(),
+            ProtoIncrementOnlyFloatProp(increment_only_float_prop) => {
+                increment_only_float_prop.to_string()
+            }
+            ProtoDecrementOnlyFloatProp(decrement_only_float_prop) => {
+                decrement_only_float_prop.to_string()
+            }
+            ProtoImmutableBytesProp(immutable_bytes_prop) => immutable_bytes_prop.to_string(),
+            ProtoStrSetProp(str_set_prop) => str_set_prop.to_string(),
+            ProtoImmutableTimestampProp(immutable_timestamp_prop) => {
+                immutable_timestamp_prop.to_string()
+            }
+            ProtoIncrementOnlyTimestampProp(increment_only_timestamp_prop) => {
+                increment_only_timestamp_prop.to_string()
+            }
+            ProtoDecrementOnlyTimestampProp(decrement_only_timestamp_prop) => {
+                decrement_only_timestamp_prop.to_string()
+            }
         }
     }
 }
@@ -504,54 +1041,193 @@ impl ImmutableIntProp {
         extra_assert!(debug_assert_eq!(*self, *other_prop));
     }
 }
-impl ImmutableStrProp {
-    pub fn as_inner(&self) -> &str {
-        self.prop.as_str()
-    }
+impl ImmutableStrProp {
+    pub fn as_inner(&self) -> &str {
+        self.prop.as_str()
+    }
+
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="ImmutableStrProp merge", self_prop=?self, other_prop=?other_prop);
+        extra_assert!(debug_assert_eq!(*self, *other_prop));
+    }
+}
+
+impl ImmutableBoolProp {
+    pub fn as_inner(&self) -> bool {
+        self.prop
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="ImmutableBoolProp merge", self_prop=?self, other_prop=?other_prop);
+        extra_assert!(debug_assert_eq!(*self, *other_prop));
+    }
+}
+impl IncrementOnlyFloatProp {
+    pub fn as_inner(&self) -> f64 {
+        self.prop
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="IncrementOnlyFloatProp merge", self_prop=?self, other_prop=?other_prop);
+        self.prop = self.prop.max(other_prop.prop);
+    }
+}
+impl DecrementOnlyFloatProp {
+    pub fn as_inner(&self) -> f64 {
+        self.prop
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="DecrementOnlyFloatProp merge", self_prop=?self, other_prop=?other_prop);
+        self.prop = self.prop.min(other_prop.prop);
+    }
+}
+impl ImmutableBytesProp {
+    pub fn as_inner(&self) -> &[u8] {
+        self.prop.as_slice()
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="ImmutableBytesProp merge", self_prop=?self, other_prop=?other_prop);
+        extra_assert!(debug_assert_eq!(*self, *other_prop));
+    }
+}
+impl StrSetProp {
+    pub fn as_inner(&self) -> &[String] {
+        self.prop.as_slice()
+    }
+
+    pub fn contains(&self, value: &str) -> bool {
+        self.prop
+            .binary_search_by(|prop| prop.as_str().cmp(value))
+            .is_ok()
+    }
+
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="StrSetProp merge", self_prop=?self, other_prop=?other_prop);
+        self.prop.extend(other_prop.prop.iter().cloned());
+        self.prop.sort_unstable();
+        self.prop.dedup();
+    }
+}
+
+impl ImmutableTimestampProp {
+    /// Milliseconds since the unix epoch
+    pub fn as_inner(&self) -> u64 {
+        self.prop
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="ImmutableTimestampProp merge", self_prop=?self, other_prop=?other_prop);
+        extra_assert!(debug_assert_eq!(*self, *other_prop));
+    }
+}
+impl IncrementOnlyTimestampProp {
+    /// Milliseconds since the unix epoch
+    pub fn as_inner(&self) -> u64 {
+        self.prop
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="IncrementOnlyTimestampProp merge", self_prop=?self, other_prop=?other_prop);
+        self.prop = std::cmp::max(self.prop, other_prop.prop);
+    }
+}
+impl DecrementOnlyTimestampProp {
+    /// Milliseconds since the unix epoch
+    pub fn as_inner(&self) -> u64 {
+        self.prop
+    }
+    pub fn merge_property(&mut self, other_prop: &Self) {
+        tracing::trace!(message="DecrementOnlyTimestampProp merge", self_prop=?self, other_prop=?other_prop);
+        self.prop = std::cmp::min(self.prop, other_prop.prop);
+    }
+}
+
+impl std::string::ToString for IncrementOnlyUintProp {
+    fn to_string(&self) -> String {
+        self.prop.to_string()
+    }
+}
+
+impl std::string::ToString for ImmutableUintProp {
+    fn to_string(&self) -> String {
+        self.prop.to_string()
+    }
+}
+
+impl std::string::ToString for DecrementOnlyUintProp {
+    fn to_string(&self) -> String {
+        self.prop.to_string()
+    }
+}
+
+impl std::string::ToString for DecrementOnlyIntProp {
+    fn to_string(&self) -> String {
+        self.prop.to_string()
+    }
+}
+
+impl std::string::ToString for IncrementOnlyIntProp {
+    fn to_string(&self) -> String {
+        self.prop.to_string()
+    }
+}
 
-    pub fn merge_property(&mut self, other_prop: &Self) {
-        tracing::trace!(message="ImmutableStrProp merge", self_prop=?self, other_prop=?other_prop);
-        extra_assert!(debug_assert_eq!(*self, *other_prop));
+impl std::string::ToString for ImmutableIntProp {
+    fn to_string(&self) -> String {
+        self.prop.to_string()
     }
 }
 
-impl std::string::ToString for IncrementOnlyUintProp {
+impl std::string::ToString for ImmutableStrProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
 }
 
-impl std::string::ToString for ImmutableUintProp {
+impl std::string::ToString for ImmutableBoolProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
 }
 
-impl std::string::ToString for DecrementOnlyUintProp {
+impl std::string::ToString for IncrementOnlyFloatProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
 }
 
-impl std::string::ToString for DecrementOnlyIntProp {
+impl std::string::ToString for DecrementOnlyFloatProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
 }
 
-impl std::string::ToString for IncrementOnlyIntProp {
+/// Bytes are written as lowercase hex
+impl std::string::ToString for ImmutableBytesProp {
+    fn to_string(&self) -> String {
+        self.prop
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+/// Sets are written as their sorted strings, comma separated
+impl std::string::ToString for StrSetProp {
+    fn to_string(&self) -> String {
+        self.prop.join(",")
+    }
+}
+
+impl std::string::ToString for ImmutableTimestampProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
 }
 
-impl std::string::ToString for ImmutableIntProp {
+impl std::string::ToString for IncrementOnlyTimestampProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
 }
 
-impl std::string::ToString for ImmutableStrProp {
+impl std::string::ToString for DecrementOnlyTimestampProp {
     fn to_string(&self) -> String {
         self.prop.to_string()
     }
@@ -581,7 +1257,30 @@ impl std::string::ToString for NodeProperty {
             Some(node_property::Property::ImmutableStr(immutable_str_prop)) => {
                 immutable_str_prop.to_string()
             }
-            None => panic!("Invalid property : {:?}", self),
+            Some(node_property::Property::ImmutableBool(immutable_bool_prop)) => {
+                immutable_bool_prop.to_string()
+            }
+            Some(node_property::Property::IncrementOnlyFloat(increment_only_float_prop)) => {
+                increment_only_float_prop.to_string()
+            }
+            Some(node_property::Property::DecrementOnlyFloat(decrement_only_float_prop)) => {
+                decrement_only_float_prop.to_string()
+            }
+            Some(node_property::Property::ImmutableBytes(immutable_bytes_prop)) => {
+                immutable_bytes_prop.to_string()
+            }
+            Some(node_property::Property::StrSet(str_set_prop)) => str_set_prop.to_string(),
+            Some(node_property::Property::ImmutableTimestamp(immutable_timestamp_prop)) => {
+                immutable_timestamp_prop.to_string()
+            }
+            Some(node_property::Property::IncrementOnlyTimestamp(
+                increment_only_timestamp_prop,
+            )) => increment_only_timestamp_prop.to_string(),
+            Some(node_property::Property::DecrementOnlyTimestamp(
+                decrement_only_timestamp_prop,
+            )) => decrement_only_timestamp_prop.to_string(),
+            // A property without a value has nothing to write
+            None => String::new(),
         }
     }
 }
@@ -634,15 +1333,17 @@ impl MergedNode {
     pub fn merge(&mut self, other: &Self) {
         extra_assert!(debug_assert_eq!(self.node_type, other.node_type));
         extra_assert!(debug_assert_eq!(self.node_key, other.node_key));
-        for (prop_name, prop_value) in other.properties.iter() {
-            match self.properties.get_mut(prop_name) {
-                Some(self_prop) => self_prop.merge(prop_value),
-                None => {
-                    self.properties
-                        .insert(prop_name.clone(), prop_value.clone());
-                }
-            }
-        }
+        merge_properties(&self.node_key, &mut self.properties, &other.properties);
+    }
+
+    /// Merges `other`'s predicates into this node's. Nothing is changed if any of them can't be
+    /// merged.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), MergeError> {
+        extra_assert!(debug_assert_eq!(self.node_type, other.node_type));
+        extra_assert!(debug_assert_eq!(self.node_key, other.node_key));
+        check_properties_merge(&self.node_key, &self.properties, &other.properties)?;
+        merge_properties(&self.node_key, &mut self.properties, &other.properties);
+        Ok(())
     }
 
     pub fn get_node_key(&self) -> &str {
@@ -747,6 +1448,57 @@ impl_from_for_unit!(
     &i8
 );
 impl_from_for_unit!(ImmutableStrProp, prop, String, &String, &str);
+impl_from_for_unit!(ImmutableBoolProp, prop, bool, &bool);
+impl_from_for_unit!(IncrementOnlyFloatProp, prop, f64, f32, &f64, &f32);
+impl_from_for_unit!(DecrementOnlyFloatProp, prop, f64, f32, &f64, &f32);
+impl_from_for_unit!(ImmutableBytesProp, prop, Vec<u8>, &Vec<u8>, &[u8]);
+impl_from_for_unit!(ImmutableTimestampProp, prop, u64, &u64);
+impl_from_for_unit!(IncrementOnlyTimestampProp, prop, u64, &u64);
+impl_from_for_unit!(DecrementOnlyTimestampProp, prop, u64, &u64);
+
+/// Milliseconds since the unix epoch, or 0 for times before it
+fn unix_millis(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl From<std::time::SystemTime> for ImmutableTimestampProp {
+    fn from(time: std::time::SystemTime) -> Self {
+        Self {
+            prop: unix_millis(time),
+        }
+    }
+}
+impl From<std::time::SystemTime> for IncrementOnlyTimestampProp {
+    fn from(time: std::time::SystemTime) -> Self {
+        Self {
+            prop: unix_millis(time),
+        }
+    }
+}
+impl From<std::time::SystemTime> for DecrementOnlyTimestampProp {
+    fn from(time: std::time::SystemTime) -> Self {
+        Self {
+            prop: unix_millis(time),
+        }
+    }
+}
+
+impl<S: Into<String>> std::iter::FromIterator<S> for StrSetProp {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut prop: Vec<String> = iter.into_iter().map(Into::into).collect();
+        prop.sort_unstable();
+        prop.dedup();
+        Self { prop }
+    }
+}
+
+impl<S: Into<String>> From<Vec<S>> for StrSetProp {
+    fn from(prop: Vec<S>) -> Self {
+        prop.into_iter().collect()
+    }
+}
 
 impl From<ImmutableUintProp> for Property {
     fn from(p: ImmutableUintProp) -> Self {
@@ -783,6 +1535,46 @@ impl From<ImmutableStrProp> for Property {
         Self::ImmutableStr(p)
     }
 }
+impl From<ImmutableBoolProp> for Property {
+    fn from(p: ImmutableBoolProp) -> Self {
+        Self::ImmutableBool(p)
+    }
+}
+impl From<IncrementOnlyFloatProp> for Property {
+    fn from(p: IncrementOnlyFloatProp) -> Self {
+        Self::IncrementOnlyFloat(p)
+    }
+}
+impl From<DecrementOnlyFloatProp> for Property {
+    fn from(p: DecrementOnlyFloatProp) -> Self {
+        Self::DecrementOnlyFloat(p)
+    }
+}
+impl From<ImmutableBytesProp> for Property {
+    fn from(p: ImmutableBytesProp) -> Self {
+        Self::ImmutableBytes(p)
+    }
+}
+impl From<StrSetProp> for Property {
+    fn from(p: StrSetProp) -> Self {
+        Self::StrSet(p)
+    }
+}
+impl From<ImmutableTimestampProp> for Property {
+    fn from(p: ImmutableTimestampProp) -> Self {
+        Self::ImmutableTimestamp(p)
+    }
+}
+impl From<IncrementOnlyTimestampProp> for Property {
+    fn from(p: IncrementOnlyTimestampProp) -> Self {
+        Self::IncrementOnlyTimestamp(p)
+    }
+}
+impl From<DecrementOnlyTimestampProp> for Property {
+    fn from(p: DecrementOnlyTimestampProp) -> Self {
+        Self::DecrementOnlyTimestamp(p)
+    }
+}
 
 impl NodeProperty {
     pub fn as_increment_only_uint(&self) -> Option<IncrementOnlyUintProp> {
@@ -833,6 +1625,62 @@ impl NodeProperty {
             _ => None,
         }
     }
+
+    pub fn as_immutable_bool(&self) -> Option<ImmutableBoolProp> {
+        match self.property {
+            Some(ProtoImmutableBoolProp(ref prop)) => Some(*prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_increment_only_float(&self) -> Option<IncrementOnlyFloatProp> {
+        match self.property {
+            Some(ProtoIncrementOnlyFloatProp(ref prop)) => Some(*prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_decrement_only_float(&self) -> Option<DecrementOnlyFloatProp> {
+        match self.property {
+            Some(ProtoDecrementOnlyFloatProp(ref prop)) => Some(*prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_immutable_bytes(&self) -> Option<&ImmutableBytesProp> {
+        match self.property {
+            Some(ProtoImmutableBytesProp(ref prop)) => Some(prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_str_set(&self) -> Option<&StrSetProp> {
+        match self.property {
+            Some(ProtoStrSetProp(ref prop)) => Some(prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_immutable_timestamp(&self) -> Option<ImmutableTimestampProp> {
+        match self.property {
+            Some(ProtoImmutableTimestampProp(ref prop)) => Some(*prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_increment_only_timestamp(&self) -> Option<IncrementOnlyTimestampProp> {
+        match self.property {
+            Some(ProtoIncrementOnlyTimestampProp(ref prop)) => Some(*prop),
+            _ => None,
+        }
+    }
+
+    pub fn as_decrement_only_timestamp(&self) -> Option<DecrementOnlyTimestampProp> {
+        match self.property {
+            Some(ProtoDecrementOnlyTimestampProp(ref prop)) => Some(*prop),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -901,6 +1749,81 @@ pub mod test {
         }
     }
 
+    impl Arbitrary for ImmutableBoolProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: bool::arbitrary(g),
+            }
+        }
+    }
+
+    /// Properties are never NaN or infinite
+    fn finite_f64(g: &mut Gen) -> f64 {
+        let prop = f64::arbitrary(g);
+        if prop.is_finite() {
+            prop
+        } else {
+            0.0
+        }
+    }
+    impl Arbitrary for IncrementOnlyFloatProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: finite_f64(g),
+            }
+        }
+    }
+    impl Arbitrary for DecrementOnlyFloatProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: finite_f64(g),
+            }
+        }
+    }
+    impl Arbitrary for ImmutableBytesProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: Vec::arbitrary(g),
+            }
+        }
+    }
+    impl Arbitrary for StrSetProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Vec::<String>::arbitrary(g).into()
+        }
+    }
+    impl Arbitrary for ImmutableTimestampProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: u64::arbitrary(g),
+            }
+        }
+    }
+    impl Arbitrary for IncrementOnlyTimestampProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: u64::arbitrary(g),
+            }
+        }
+    }
+    impl Arbitrary for DecrementOnlyTimestampProp {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                prop: u64::arbitrary(g),
+            }
+        }
+    }
+
+    impl Arbitrary for EdgeFacets {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                first_seen: Option::arbitrary(g),
+                last_seen: Option::arbitrary(g),
+                count: Option::arbitrary(g),
+            }
+        }
+    }
+
     impl Arbitrary for Property {
         fn arbitrary(g: &mut Gen) -> Self {
             let props = &[
@@ -911,6 +1834,14 @@ pub mod test {
                 Property::DecrementOnlyUint(DecrementOnlyUintProp::arbitrary(g)),
                 Property::ImmutableUint(ImmutableUintProp::arbitrary(g)),
                 Property::ImmutableStr(ImmutableStrProp::arbitrary(g)),
+                Property::ImmutableBool(ImmutableBoolProp::arbitrary(g)),
+                Property::IncrementOnlyFloat(IncrementOnlyFloatProp::arbitrary(g)),
+                Property::DecrementOnlyFloat(DecrementOnlyFloatProp::arbitrary(g)),
+                Property::ImmutableBytes(ImmutableBytesProp::arbitrary(g)),
+                Property::StrSet(StrSetProp::arbitrary(g)),
+                Property::ImmutableTimestamp(ImmutableTimestampProp::arbitrary(g)),
+                Property::IncrementOnlyTimestamp(IncrementOnlyTimestampProp::arbitrary(g)),
+                Property::DecrementOnlyTimestamp(DecrementOnlyTimestampProp::arbitrary(g)),
             ];
             g.choose(props).unwrap().clone()
         }
@@ -951,7 +1882,20 @@ pub mod test {
                 hash(&[node_key, property_name]) as i64
             )),
             Property::ImmutableUint(ImmutableUintProp::from(hash(&[node_key, property_name]))),
-            Property::ImmutableStr(ImmutableStrProp::from(s)),
+            Property::ImmutableStr(ImmutableStrProp::from(s.clone())),
+            Property::ImmutableBool(ImmutableBoolProp::from(
+                hash(&[node_key, property_name]) % 2 == 0,
+            )),
+            Property::IncrementOnlyFloat(IncrementOnlyFloatProp::arbitrary(g)),
+            Property::DecrementOnlyFloat(DecrementOnlyFloatProp::arbitrary(g)),
+            Property::ImmutableBytes(ImmutableBytesProp::from(s.as_bytes())),
+            Property::StrSet(StrSetProp::arbitrary(g)),
+            Property::ImmutableTimestamp(ImmutableTimestampProp::from(hash(&[
+                node_key,
+                property_name,
+            ]))),
+            Property::IncrementOnlyTimestamp(IncrementOnlyTimestampProp::arbitrary(g)),
+            Property::DecrementOnlyTimestamp(DecrementOnlyTimestampProp::arbitrary(g)),
         ];
         let p: Property = choice(node_key, props);
         p.into()
@@ -1050,6 +1994,120 @@ pub mod test {
         assert_eq!(x, std::cmp::min(x, y));
     }
 
+    #[cfg(not(feature = "extra_assertions"))]
+    #[quickcheck]
+    fn test_merge_immutable_bool(mut x: ImmutableBoolProp, y: ImmutableBoolProp) {
+        init_test_env();
+        let original = x;
+        x.merge_property(&y);
+        assert_eq!(x, original);
+    }
+
+    #[cfg(not(feature = "extra_assertions"))]
+    #[quickcheck]
+    fn test_merge_immutable_bytes(mut x: ImmutableBytesProp, y: ImmutableBytesProp) {
+        init_test_env();
+        let original = x.clone();
+        x.merge_property(&y);
+        assert_eq!(x, original);
+    }
+
+    #[quickcheck]
+    fn test_merge_float_max(mut x: IncrementOnlyFloatProp, y: IncrementOnlyFloatProp) {
+        init_test_env();
+        let expected = if x.prop >= y.prop { x } else { y };
+        x.merge_property(&y);
+        assert_eq!(x, expected);
+    }
+
+    #[quickcheck]
+    fn test_merge_float_min(mut x: DecrementOnlyFloatProp, y: DecrementOnlyFloatProp) {
+        init_test_env();
+        let expected = if x.prop <= y.prop { x } else { y };
+        x.merge_property(&y);
+        assert_eq!(x, expected);
+    }
+
+    #[quickcheck]
+    fn test_merge_timestamp_max(mut x: IncrementOnlyTimestampProp, y: IncrementOnlyTimestampProp) {
+        init_test_env();
+        x.merge_property(&y);
+        assert_eq!(x, std::cmp::max(x, y));
+    }
+
+    #[quickcheck]
+    fn test_merge_timestamp_min(mut x: DecrementOnlyTimestampProp, y: DecrementOnlyTimestampProp) {
+        init_test_env();
+        x.merge_property(&y);
+        assert_eq!(x, std::cmp::min(x, y));
+    }
+
+    #[cfg(not(feature = "extra_assertions"))]
+    #[quickcheck]
+    fn test_merge_immutable_timestamp(mut x: ImmutableTimestampProp, y: ImmutableTimestampProp) {
+        init_test_env();
+        let original = x;
+        x.merge_property(&y);
+        assert_eq!(x, original);
+    }
+
+    #[test]
+    fn test_timestamps_are_unix_millis() {
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_millis(1_563_991_660_514);
+        assert_eq!(
+            ImmutableTimestampProp::from(time).as_inner(),
+            1_563_991_660_514
+        );
+    }
+
+    #[test]
+    fn test_non_finite_floats_are_rejected() {
+        for prop in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut graph = GraphDescription::new();
+            let mut node = NodeDescription {
+                node_key: "node".to_owned(),
+                node_type: "Process".to_owned(),
+                ..Default::default()
+            };
+            node.set_property("cpu", IncrementOnlyFloatProp { prop: *prop });
+
+            assert!(matches!(
+                graph.try_add_node(node),
+                Err(MergeError::NonFiniteFloat { .. })
+            ));
+        }
+    }
+
+    #[quickcheck]
+    fn test_merge_str_set_union(x: StrSetProp, y: StrSetProp) {
+        init_test_env();
+        let mut xy = x.clone();
+        xy.merge_property(&y);
+        let mut yx = y.clone();
+        yx.merge_property(&x);
+        assert_eq!(xy, yx);
+
+        for value in x.as_inner().iter().chain(y.as_inner()) {
+            assert!(xy.contains(value));
+        }
+        let merged = xy.clone();
+        xy.merge_property(&y);
+        assert_eq!(xy, merged);
+    }
+
+    #[test]
+    fn test_str_set_is_sorted_without_duplicates() {
+        let set = StrSetProp::from(vec!["b", "a", "b"]);
+        assert_eq!(set.as_inner(), ["a".to_string(), "b".to_string()]);
+        assert_eq!(NodeProperty::from(set).to_string(), "a,b");
+    }
+
+    #[test]
+    fn test_bytes_are_written_as_hex() {
+        let bytes = ImmutableBytesProp::from(&[0x0au8, 0xff][..]);
+        assert_eq!(NodeProperty::from(bytes).to_string(), "0aff");
+    }
+
     #[quickcheck]
     fn test_merge_incr_uint_commutative(mut properties: Vec<IncrementOnlyUintProp>) {
         init_test_env();
@@ -1084,4 +2142,149 @@ pub mod test {
         //     let mut copy = o_pred_val.clone();
         // }
     }
+
+    #[quickcheck]
+    fn test_cache_identities_only_cover_immutable_predicates(node: IdentifiedNode) {
+        let immutable_predicates = node
+            .properties
+            .values()
+            .filter(|prop| prop.property.as_ref().unwrap().is_immutable())
+            .count();
+        assert_eq!(
+            node.get_cache_identities_for_predicates().len(),
+            immutable_predicates
+        );
+    }
+
+    #[quickcheck]
+    fn test_merge_edge_facets_commutative(x: EdgeFacets, y: EdgeFacets) {
+        let mut xy = x;
+        xy.merge(&y);
+        let mut yx = y;
+        yx.merge(&x);
+        assert_eq!(xy, yx);
+    }
+
+    #[quickcheck]
+    fn test_merge_edge_facets_idempotent(mut x: EdgeFacets, y: EdgeFacets) {
+        x.merge(&y);
+        let merged = x;
+        x.merge(&y);
+        assert_eq!(x, merged);
+    }
+
+    #[test]
+    fn test_add_same_edge_accumulates_facets() {
+        let mut graph = GraphDescription::new();
+        graph.add_edge_with_facets("edge", "from", "to", EdgeFacets::seen_at(20));
+        graph.add_edge_with_facets("edge", "from", "to", EdgeFacets::seen_at(10));
+        graph.add_edge("edge", "from", "to");
+        graph.add_edge("other_edge", "from", "to");
+
+        let edges = &graph.edges["from"].edges;
+        assert_eq!(edges.len(), 2);
+        assert_eq!(
+            edges[0].facets,
+            Some(EdgeFacets {
+                first_seen: Some(10u64.into()),
+                last_seen: Some(20u64.into()),
+                count: Some(2u64.into()),
+            })
+        );
+        assert_eq!(edges[1].facets, None);
+    }
+
+    #[test]
+    fn test_merge_graphs_carries_facets() {
+        let mut graph = IdentifiedGraph::new();
+        graph.add_edge_with_facets("edge", "from", "to", EdgeFacets::seen_at(10));
+        let mut other = IdentifiedGraph::new();
+        other.add_edge_with_facets("edge", "from", "to", EdgeFacets::seen_at(30));
+
+        graph.merge(&other);
+
+        let facets = graph.edges["from"].edges[0].facets.expect("facets");
+        assert_eq!(facets.first_seen, Some(10u64.into()));
+        assert_eq!(facets.last_seen, Some(30u64.into()));
+        assert_eq!(facets.count, Some(2u64.into()));
+    }
+
+    #[test]
+    fn test_try_add_edge_rejects_self_loops() {
+        let mut graph = GraphDescription::new();
+        assert_eq!(
+            graph.try_add_edge("edge", "node", "node"),
+            Err(MergeError::SelfLoop {
+                node_key: "node".to_owned(),
+                edge_name: "edge".to_owned(),
+            })
+        );
+        graph.add_edge("edge", "node", "node");
+        assert!(graph.is_empty());
+    }
+
+    #[test]
+    fn test_try_merge_rejects_mismatched_properties() {
+        let mut node = IdentifiedNode {
+            node_key: "node".to_owned(),
+            node_type: "Process".to_owned(),
+            properties: HashMap::new(),
+        };
+        node.properties
+            .insert("pid".to_owned(), ImmutableUintProp::from(1u64).into());
+        let mut other = node.clone();
+        other
+            .properties
+            .insert("pid".to_owned(), ImmutableStrProp::from("1").into());
+        other
+            .properties
+            .insert("name".to_owned(), ImmutableStrProp::from("a.exe").into());
+
+        assert_eq!(
+            node.try_merge(&other),
+            Err(MergeError::PropertyTypeMismatch {
+                node_key: "node".to_owned(),
+                predicate: "pid".to_owned(),
+                expected: "ImmutableUintProp",
+                found: "ImmutableStrProp",
+            })
+        );
+        assert_eq!(node.properties.len(), 1);
+
+        let mut graph = IdentifiedGraph::new();
+        graph.add_node(node.clone());
+        let mut other_graph = IdentifiedGraph::new();
+        other_graph.add_node(other.clone());
+        assert!(graph.try_merge(&other_graph).is_err());
+        assert_eq!(graph.nodes["node"], node);
+
+        // The infallible merge keeps whatever can be merged
+        node.merge(&other);
+        assert_eq!(
+            node.properties["pid"].as_immutable_uint(),
+            Some(1u64.into())
+        );
+        assert!(node.properties.contains_key("name"));
+    }
+
+    #[test]
+    fn test_try_add_node_rejects_missing_properties() {
+        let mut node = IdentifiedNode {
+            node_key: "node".to_owned(),
+            node_type: "Process".to_owned(),
+            properties: HashMap::new(),
+        };
+        node.properties
+            .insert("pid".to_owned(), NodeProperty { property: None });
+
+        let mut graph = IdentifiedGraph::new();
+        assert_eq!(
+            graph.try_add_node(node),
+            Err(MergeError::MissingProperty {
+                node_key: "node".to_owned(),
+                predicate: "pid".to_owned(),
+            })
+        );
+        assert_eq!(NodeProperty { property: None }.to_string(), "");
+    }
 }
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "graphql")]
+use grapl_graphql_codegen::{
+    conflict_resolution::ConflictResolution,
+    predicate_type::PredicateType,
+};
+
+use crate::{
+    graph_description::*,
+    node_property::Property,
+};
+
+/// A property's type, along with how it's merged
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PropertyType {
+    ImmutableUint,
+    IncrementOnlyUint,
+    DecrementOnlyUint,
+    ImmutableInt,
+    IncrementOnlyInt,
+    DecrementOnlyInt,
+    ImmutableStr,
+    ImmutableBool,
+    IncrementOnlyFloat,
+    DecrementOnlyFloat,
+    ImmutableBytes,
+    StrSet,
+    ImmutableTimestamp,
+    IncrementOnlyTimestamp,
+    DecrementOnlyTimestamp,
+}
+
+impl PropertyType {
+    pub fn of(property: &Property) -> Self {
+        match property {
+            Property::ImmutableUint(_) => Self::ImmutableUint,
+            Property::IncrementOnlyUint(_) => Self::IncrementOnlyUint,
+            Property::DecrementOnlyUint(_) => Self::DecrementOnlyUint,
+            Property::ImmutableInt(_) => Self::ImmutableInt,
+            Property::IncrementOnlyInt(_) => Self::IncrementOnlyInt,
+            Property::DecrementOnlyInt(_) => Self::DecrementOnlyInt,
+            Property::ImmutableStr(_) => Self::ImmutableStr,
+            Property::ImmutableBool(_) => Self::ImmutableBool,
+            Property::IncrementOnlyFloat(_) => Self::IncrementOnlyFloat,
+            Property::DecrementOnlyFloat(_) => Self::DecrementOnlyFloat,
+            Property::ImmutableBytes(_) => Self::ImmutableBytes,
+            Property::StrSet(_) => Self::StrSet,
+            Property::ImmutableTimestamp(_) => Self::ImmutableTimestamp,
+            Property::IncrementOnlyTimestamp(_) => Self::IncrementOnlyTimestamp,
+            Property::DecrementOnlyTimestamp(_) => Self::DecrementOnlyTimestamp,
+        }
+    }
+
+    /// The type a GraphQL predicate is stored as, if there's a property type for its type and
+    /// conflict resolution
+    #[cfg(feature = "graphql")]
+    pub fn of_predicate(
+        predicate_type: PredicateType,
+        conflict_resolution: ConflictResolution,
+    ) -> Option<Self> {
+        match (predicate_type, conflict_resolution) {
+            (PredicateType::String, ConflictResolution::Immutable) => Some(Self::ImmutableStr),
+            (PredicateType::U64, ConflictResolution::Immutable) => Some(Self::ImmutableUint),
+            (PredicateType::U64, ConflictResolution::IncrementOnly) => {
+                Some(Self::IncrementOnlyUint)
+            }
+            (PredicateType::U64, ConflictResolution::DecrementOnly) => {
+                Some(Self::DecrementOnlyUint)
+            }
+            (PredicateType::I64, ConflictResolution::Immutable) => Some(Self::ImmutableInt),
+            (PredicateType::I64, ConflictResolution::IncrementOnly) => Some(Self::IncrementOnlyInt),
+            (PredicateType::I64, ConflictResolution::DecrementOnly) => Some(Self::DecrementOnlyInt),
+            (PredicateType::Bool, ConflictResolution::Immutable) => Some(Self::ImmutableBool),
+            (PredicateType::F64, ConflictResolution::IncrementOnly) => {
+                Some(Self::IncrementOnlyFloat)
+            }
+            (PredicateType::F64, ConflictResolution::DecrementOnly) => {
+                Some(Self::DecrementOnlyFloat)
+            }
+            (PredicateType::Bytes, ConflictResolution::Immutable) => Some(Self::ImmutableBytes),
+            (PredicateType::StrSet, ConflictResolution::Union) => Some(Self::StrSet),
+            (PredicateType::Timestamp, ConflictResolution::Immutable) => {
+                Some(Self::ImmutableTimestamp)
+            }
+            (PredicateType::Timestamp, ConflictResolution::IncrementOnly) => {
+                Some(Self::IncrementOnlyTimestamp)
+            }
+            (PredicateType::Timestamp, ConflictResolution::DecrementOnly) => {
+                Some(Self::DecrementOnlyTimestamp)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}Prop", self)
+    }
+}
+
+/// The properties and edges of a node type
+#[derive(Clone, Debug, Default)]
+pub struct NodeSchema {
+    properties: HashMap<String, PropertyType>,
+    /// The node type each edge points to, by edge name
+    edges: HashMap<String, String>,
+}
+
+impl NodeSchema {
+    pub fn property_type(&self, property_name: &str) -> Option<PropertyType> {
+        self.properties.get(property_name).copied()
+    }
+
+    pub fn edge_target_type(&self, edge_name: &str) -> Option<&str> {
+        self.edges.get(edge_name).map(String::as_str)
+    }
+}
+
+/// The node types a graph may contain, and the properties and edges each of them may have
+///
+/// ```
+/// use grapl_graph_descriptions::schema::{
+///     GraphSchema,
+///     PropertyType,
+/// };
+///
+/// let schema = GraphSchema::new()
+///     .with_property("Process", "process_id", PropertyType::ImmutableUint)
+///     .with_property("File", "file_path", PropertyType::ImmutableStr)
+///     .with_edge("Process", "bin_file", "File", "spawned_from");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GraphSchema {
+    node_types: HashMap<String, NodeSchema>,
+}
+
+impl GraphSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines a node type, which has no properties or edges until they're added
+    pub fn with_node_type(mut self, node_type: impl Into<String>) -> Self {
+        self.node_types.entry(node_type.into()).or_default();
+        self
+    }
+
+    /// Defines a property of a node type, defining the node type as well if needed
+    pub fn with_property(
+        mut self,
+        node_type: impl Into<String>,
+        property_name: impl Into<String>,
+        property_type: PropertyType,
+    ) -> Self {
+        self.node_types
+            .entry(node_type.into())
+            .or_default()
+            .properties
+            .insert(property_name.into(), property_type);
+        self
+    }
+
+    /// Defines an edge between two node types along with its reverse, defining the node types
+    /// as well if needed
+    pub fn with_edge(
+        mut self,
+        from_node_type: impl Into<String>,
+        edge_name: impl Into<String>,
+        to_node_type: impl Into<String>,
+        reverse_edge_name: impl Into<String>,
+    ) -> Self {
+        let from_node_type = from_node_type.into();
+        let to_node_type = to_node_type.into();
+        self.node_types
+            .entry(from_node_type.clone())
+            .or_default()
+            .edges
+            .insert(edge_name.into(), to_node_type.clone());
+        self.node_types
+            .entry(to_node_type)
+            .or_default()
+            .edges
+            .insert(reverse_edge_name.into(), from_node_type);
+        self
+    }
+
+    pub fn node_type(&self, node_type: &str) -> Option<&NodeSchema> {
+        self.node_types.get(node_type)
+    }
+
+    /// Checks every node and edge of the graph against the schema, returning every violation
+    pub fn validate(&self, graph: &GraphDescription) -> Vec<SchemaViolation> {
+        let nodes = graph.nodes.values().map(|node| {
+            (
+                node.node_key.as_str(),
+                node.node_type.as_str(),
+                &node.properties,
+            )
+        });
+        self.validate_graph(nodes, &graph.edges)
+    }
+
+    /// Checks every node and edge of the graph against the schema, returning every violation
+    pub fn validate_identified(&self, graph: &IdentifiedGraph) -> Vec<SchemaViolation> {
+        let nodes = graph.nodes.values().map(|node| {
+            (
+                node.node_key.as_str(),
+                node.node_type.as_str(),
+                &node.properties,
+            )
+        });
+        self.validate_graph(nodes, &graph.edges)
+    }
+
+    /// Violations are ordered by the key of the node they're found on, so the same graph is
+    /// always reported the same way
+    fn validate_graph<'a>(
+        &self,
+        nodes: impl Iterator<Item = (&'a str, &'a str, &'a HashMap<String, NodeProperty>)>,
+        edges: &'a HashMap<String, EdgeList>,
+    ) -> Vec<SchemaViolation> {
+        let mut violations = vec![];
+
+        let mut nodes: Vec<_> = nodes.collect();
+        nodes.sort_by_key(|(node_key, ..)| *node_key);
+        let node_types: HashMap<&str, &str> = nodes
+            .iter()
+            .map(|(node_key, node_type, _)| (*node_key, *node_type))
+            .collect();
+
+        for (node_key, node_type, properties) in nodes {
+            let node_schema = match self.node_type(node_type) {
+                Some(node_schema) => node_schema,
+                None => {
+                    violations.push(SchemaViolation::UnknownNodeType {
+                        node_key: node_key.to_owned(),
+                        node_type: node_type.to_owned(),
+                    });
+                    continue;
+                }
+            };
+
+            let mut properties: Vec<_> = properties.iter().collect();
+            properties.sort_by_key(|(property_name, _)| *property_name);
+            for (property_name, property) in properties {
+                let violation = match (
+                    node_schema.property_type(property_name),
+                    property.property.as_ref(),
+                ) {
+                    (None, _) => SchemaViolation::UnknownProperty {
+                        node_key: node_key.to_owned(),
+                        node_type: node_type.to_owned(),
+                        property_name: property_name.clone(),
+                    },
+                    (Some(_), None) => SchemaViolation::MissingPropertyValue {
+                        node_key: node_key.to_owned(),
+                        property_name: property_name.clone(),
+                    },
+                    (Some(expected), Some(property)) if PropertyType::of(property) != expected => {
+                        SchemaViolation::PropertyTypeMismatch {
+                            node_key: node_key.to_owned(),
+                            property_name: property_name.clone(),
+                            expected,
+                            found: PropertyType::of(property),
+                        }
+                    }
+                    (Some(_), Some(_)) => continue,
+                };
+                violations.push(violation);
+            }
+        }
+
+        // Edges are only checked when the node they're from is part of the graph, since the
+        // edge's definition depends on that node's type
+        let mut edge_lists: Vec<_> = edges.iter().collect();
+        edge_lists.sort_by_key(|(from_node_key, _)| *from_node_key);
+        for edge in edge_lists
+            .into_iter()
+            .flat_map(|(_, edge_list)| edge_list.edges.iter())
+        {
+            let from_node_type = match node_types.get(edge.from_node_key.as_str()) {
+                Some(from_node_type) => *from_node_type,
+                None => continue,
+            };
+            // Unknown node types have already been reported
+            let node_schema = match self.node_type(from_node_type) {
+                Some(node_schema) => node_schema,
+                None => continue,
+            };
+            let expected_type = match node_schema.edge_target_type(&edge.edge_name) {
+                Some(expected_type) => expected_type,
+                None => {
+                    violations.push(SchemaViolation::UnknownEdge {
+                        from_node_key: edge.from_node_key.clone(),
+                        node_type: from_node_type.to_owned(),
+                        edge_name: edge.edge_name.clone(),
+                    });
+                    continue;
+                }
+            };
+            match node_types.get(edge.to_node_key.as_str()) {
+                Some(to_node_type) if *to_node_type != expected_type => {
+                    violations.push(SchemaViolation::EdgeTargetMismatch {
+                        from_node_key: edge.from_node_key.clone(),
+                        to_node_key: edge.to_node_key.clone(),
+                        edge_name: edge.edge_name.clone(),
+                        expected_type: expected_type.to_owned(),
+                        to_node_type: (*to_node_type).to_owned(),
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        violations
+    }
+}
+
+/// A way in which a graph doesn't conform to a `GraphSchema`
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    #[error("node {node_key} has undefined type {node_type}")]
+    UnknownNodeType { node_key: String, node_type: String },
+    #[error("property {property_name} is not defined on {node_type}, for node {node_key}")]
+    UnknownProperty {
+        node_key: String,
+        node_type: String,
+        property_name: String,
+    },
+    #[error("property {property_name} of node {node_key} has no value")]
+    MissingPropertyValue {
+        node_key: String,
+        property_name: String,
+    },
+    #[error("property {property_name} of node {node_key} must be {expected}, not {found}")]
+    PropertyTypeMismatch {
+        node_key: String,
+        property_name: String,
+        expected: PropertyType,
+        found: PropertyType,
+    },
+    #[error("edge {edge_name} is not defined on {node_type}, for node {from_node_key}")]
+    UnknownEdge {
+        from_node_key: String,
+        node_type: String,
+        edge_name: String,
+    },
+    #[error("edge {edge_name} from {from_node_key} must point to a {expected_type}, not {to_node_key}, a {to_node_type}")]
+    EdgeTargetMismatch {
+        from_node_key: String,
+        to_node_key: String,
+        edge_name: String,
+        expected_type: String,
+        to_node_type: String,
+    },
+}
+
+impl SchemaViolation {
+    /// Names the kind of violation, for tagging metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::UnknownNodeType { .. } => "unknown_node_type",
+            Self::UnknownProperty { .. } => "unknown_property",
+            Self::MissingPropertyValue { .. } => "missing_property_value",
+            Self::PropertyTypeMismatch { .. } => "property_type_mismatch",
+            Self::UnknownEdge { .. } => "unknown_edge",
+            Self::EdgeTargetMismatch { .. } => "edge_target_mismatch",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ImmutableStrProp,
+        ImmutableUintProp,
+    };
+
+    fn schema() -> GraphSchema {
+        GraphSchema::new()
+            .with_property("Asset", "hostname", PropertyType::ImmutableStr)
+            .with_property("Process", "process_id", PropertyType::ImmutableUint)
+            .with_property("File", "file_path", PropertyType::ImmutableStr)
+            .with_edge("Process", "bin_file", "File", "spawned_from")
+            .with_edge("Asset", "asset_processes", "Process", "process_asset")
+    }
+
+    fn node(node_key: &str, node_type: &str) -> NodeDescription {
+        NodeDescription {
+            node_key: node_key.to_owned(),
+            node_type: node_type.to_owned(),
+            id_strategy: vec![],
+            properties: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_conforming_graph_has_no_violations() {
+        let mut process = node("process", "Process");
+        process.set_property("process_id", ImmutableUintProp::from(1u64));
+        let mut file = node("file", "File");
+        file.set_property("file_path", ImmutableStrProp::from("/bin/sh"));
+
+        let mut graph = GraphDescription::new();
+        graph.add_node(process);
+        graph.add_node(file);
+        graph.add_edge("bin_file", "process", "file");
+        graph.add_edge("spawned_from", "file", "process");
+        // Edges from nodes outside of the graph can't be checked
+        graph.add_edge("anything", "elsewhere", "file");
+
+        assert_eq!(schema().validate(&graph), vec![]);
+    }
+
+    #[test]
+    fn test_every_violation_is_reported() {
+        let mut process = node("process", "Process");
+        process.set_property("process_id", ImmutableStrProp::from("1"));
+        process.set_property("pid", ImmutableUintProp::from(1u64));
+        let mut asset = node("asset", "Asset");
+        asset
+            .properties
+            .insert("hostname".to_owned(), NodeProperty { property: None });
+
+        let mut graph = GraphDescription::new();
+        graph.add_node(process);
+        graph.add_node(asset);
+        graph.add_node(node("user", "User"));
+        graph.add_edge("process_asset", "asset", "process");
+        graph.add_edge("bin_file", "process", "asset");
+        graph.add_edge("asset_processes", "user", "process");
+
+        assert_eq!(
+            schema().validate(&graph),
+            vec![
+                SchemaViolation::MissingPropertyValue {
+                    node_key: "asset".to_owned(),
+                    property_name: "hostname".to_owned(),
+                },
+                SchemaViolation::UnknownProperty {
+                    node_key: "process".to_owned(),
+                    node_type: "Process".to_owned(),
+                    property_name: "pid".to_owned(),
+                },
+                SchemaViolation::PropertyTypeMismatch {
+                    node_key: "process".to_owned(),
+                    property_name: "process_id".to_owned(),
+                    expected: PropertyType::ImmutableUint,
+                    found: PropertyType::ImmutableStr,
+                },
+                SchemaViolation::UnknownNodeType {
+                    node_key: "user".to_owned(),
+                    node_type: "User".to_owned(),
+                },
+                SchemaViolation::UnknownEdge {
+                    from_node_key: "asset".to_owned(),
+                    node_type: "Asset".to_owned(),
+                    edge_name: "process_asset".to_owned(),
+                },
+                SchemaViolation::EdgeTargetMismatch {
+                    from_node_key: "process".to_owned(),
+                    to_node_key: "asset".to_owned(),
+                    edge_name: "bin_file".to_owned(),
+                    expected_type: "File".to_owned(),
+                    to_node_type: "Asset".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_violations_name_property_types() {
+        let violation = SchemaViolation::PropertyTypeMismatch {
+            node_key: "process".to_owned(),
+            property_name: "process_id".to_owned(),
+            expected: PropertyType::ImmutableUint,
+            found: PropertyType::ImmutableStr,
+        };
+        assert_eq!(
+            violation.to_string(),
+            "property process_id of node process must be ImmutableUintProp, not ImmutableStrProp"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use grapl_graphql_codegen::edge_rel::EdgeRel;
+use grapl_utils::future_ext::GraplFutureExt;
+use lazy_static::lazy_static;
+use rusoto_dynamodb::{
+    AttributeValue,
+    DynamoDb,
+    ScanInput,
+};
+
+lazy_static! {
+    /// timeout for each page of a schema table scan
+    static ref DYNAMODB_SCAN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+}
+
+pub type Item = HashMap<String, AttributeValue>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeployedSchemasError {
+    #[error("Failed to scan {0}: {1}")]
+    Scan(String, String),
+}
+
+/// The primitive type of a property, as grapl_analyzerlib's `PropPrimitive` records it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropPrimitive {
+    Int,
+    Str,
+    Bool,
+    Float,
+}
+
+impl PropPrimitive {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Int" => Some(Self::Int),
+            "Str" => Some(Self::Str),
+            "Bool" => Some(Self::Bool),
+            "Float" => Some(Self::Float),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeployedProperty {
+    pub name: String,
+    pub primitive: PropPrimitive,
+    pub is_set: bool,
+}
+
+/// An edge declared on a deployed node type. Only forward edges are recorded with node types.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeployedEdge {
+    pub edge_name: String,
+    pub target_type_name: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeployedNodeType {
+    pub node_type: String,
+    pub properties: Vec<DeployedProperty>,
+    pub edges: Vec<DeployedEdge>,
+}
+
+/// The reverse and relationship of an edge, from the schema table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeployedEdgeRelationship {
+    pub reverse_edge_name: String,
+    pub relationship: EdgeRel,
+}
+
+/// The node types and edges that have been provisioned into the schema tables, by Grapl's
+/// provisioner or by deployed plugins
+#[derive(Clone, Debug, Default)]
+pub struct DeployedSchemas {
+    pub node_types: Vec<DeployedNodeType>,
+    /// The relationship of every edge in both directions, by edge name
+    pub edge_relationships: HashMap<String, DeployedEdgeRelationship>,
+}
+
+impl DeployedSchemas {
+    /// Scans the whole of both schema tables
+    pub async fn scan(
+        dynamodb: &impl DynamoDb,
+        schema_table_name: &str,
+        schema_properties_table_name: &str,
+    ) -> Result<Self, DeployedSchemasError> {
+        let schema_items = scan_table(dynamodb, schema_table_name).await?;
+        let schema_properties_items = scan_table(dynamodb, schema_properties_table_name).await?;
+        Ok(Self::from_items(&schema_items, &schema_properties_items))
+    }
+
+    /// Items that can't be parsed are skipped, since they'd only ever have been written by an
+    /// older or broken provisioner
+    pub fn from_items(schema_items: &[Item], schema_properties_items: &[Item]) -> Self {
+        let node_types = schema_properties_items
+            .iter()
+            .filter_map(|item| {
+                let node_type = node_type_from_item(item);
+                if node_type.is_none() {
+                    tracing::error!(message="Skipping malformed item in the schema properties table", item=?item);
+                }
+                node_type
+            })
+            .collect();
+        let edge_relationships = schema_items
+            .iter()
+            .filter_map(|item| {
+                let edge_relationship = edge_relationship_from_item(item);
+                if edge_relationship.is_none() {
+                    tracing::error!(message="Skipping malformed item in the schema table", item=?item);
+                }
+                edge_relationship
+            })
+            .collect();
+
+        Self {
+            node_types,
+            edge_relationships,
+        }
+    }
+}
+
+async fn scan_table(
+    dynamodb: &impl DynamoDb,
+    table_name: &str,
+) -> Result<Vec<Item>, DeployedSchemasError> {
+    let mut items = vec![];
+    let mut exclusive_start_key = None;
+    loop {
+        let output = dynamodb
+            .scan(ScanInput {
+                table_name: table_name.to_owned(),
+                consistent_read: Some(true),
+                exclusive_start_key,
+                ..Default::default()
+            })
+            .timeout(*DYNAMODB_SCAN_TIMEOUT)
+            .await
+            .map_err(|e| DeployedSchemasError::Scan(table_name.to_owned(), e.to_string()))?
+            .map_err(|e| DeployedSchemasError::Scan(table_name.to_owned(), e.to_string()))?;
+
+        items.extend(output.items.unwrap_or_default());
+        match output.last_evaluated_key {
+            Some(last_evaluated_key) if !last_evaluated_key.is_empty() => {
+                exclusive_start_key = Some(last_evaluated_key)
+            }
+            _ => return Ok(items),
+        }
+    }
+}
+
+fn string_attribute<'a>(item: &'a Item, name: &str) -> Option<&'a str> {
+    item.get(name)?.s.as_deref()
+}
+
+/// Parses an item of the schema properties table:
+/// `{node_type, type_definition: {properties: [{name, primitive, is_set}]}}`
+///
+/// Forward edges are stored as properties whose primitive is the name of the node type the edge
+/// points to.
+fn node_type_from_item(item: &Item) -> Option<DeployedNodeType> {
+    let mut node_type = DeployedNodeType {
+        node_type: string_attribute(item, "node_type")?.to_owned(),
+        ..Default::default()
+    };
+
+    let properties = item.get("type_definition")?.m.as_ref()?.get("properties")?;
+    for property in properties.l.as_ref()? {
+        let property = property.m.as_ref()?;
+        let name = string_attribute(property, "name")?.to_owned();
+        let primitive = string_attribute(property, "primitive")?;
+        let is_set = property.get("is_set")?.bool?;
+        match PropPrimitive::from_name(primitive) {
+            Some(primitive) => node_type.properties.push(DeployedProperty {
+                name,
+                primitive,
+                is_set,
+            }),
+            None => node_type.edges.push(DeployedEdge {
+                edge_name: name,
+                target_type_name: primitive.to_owned(),
+            }),
+        }
+    }
+    Some(node_type)
+}
+
+/// Parses an item of the schema table: `{f_edge, r_edge, relationship}`, where the relationship
+/// is grapl_analyzerlib's `EdgeRelationship`
+fn edge_relationship_from_item(item: &Item) -> Option<(String, DeployedEdgeRelationship)> {
+    let edge_name = string_attribute(item, "f_edge")?.to_owned();
+    let reverse_edge_name = string_attribute(item, "r_edge")?.to_owned();
+    let relationship = match item.get("relationship")?.n.as_deref()? {
+        "1" => EdgeRel::OneToOne,
+        "2" => EdgeRel::OneToMany,
+        "3" => EdgeRel::ManyToMany,
+        "4" => EdgeRel::ManyToOne,
+        _ => return None,
+    };
+    Some((
+        edge_name,
+        DeployedEdgeRelationship {
+            reverse_edge_name,
+            relationship,
+        },
+    ))
+}
@@ -0,0 +1,536 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use dgraph_query_lib::{
+    condition::{
+        Condition,
+        ConditionValue,
+    },
+    predicate::{
+        Field,
+        Predicate,
+    },
+    query::QueryBuilder,
+    queryblock::{
+        QueryBlock,
+        QueryBlockBuilder,
+        QueryBlockType,
+    },
+    ToQueryString,
+};
+use dgraph_tonic::{
+    Client as DgraphClient,
+    Mutate,
+    Query,
+};
+use futures::StreamExt;
+use futures_retry::{
+    FutureRetry,
+    RetryPolicy,
+};
+use grapl_graph_descriptions::graph_description::{
+    DecrementOnlyUintProp,
+    EdgeFacets,
+    IdentifiedNode,
+    IncrementOnlyUintProp,
+};
+use grapl_utils::iter_ext::GraplIterExt;
+
+use crate::{
+    graph_store::{
+        GraphStore,
+        UidEdge,
+    },
+    upsert_util,
+};
+
+const DGRAPH_CONCURRENCY_UPSERTS: usize = 8;
+// DGraph Live Loader uses a size of 1,000 elements and they claim this has relatively good performance
+const DGRAPH_UPSERT_CHUNK_SIZE: usize = 1024;
+
+/// Stores merged graphs in Dgraph
+#[derive(Clone)]
+pub struct DgraphGraphStore {
+    dgraph_client: Arc<DgraphClient>,
+}
+
+impl DgraphGraphStore {
+    pub fn new(mg_alphas: Vec<String>) -> Self {
+        let dgraph_client = DgraphClient::new(mg_alphas).expect("Failed to create dgraph client.");
+        Self::from_client(Arc::new(dgraph_client))
+    }
+
+    pub fn from_client(dgraph_client: Arc<DgraphClient>) -> Self {
+        Self { dgraph_client }
+    }
+
+    async fn enforce_transaction<Factory, Txn>(f: Factory) -> dgraph_tonic::Response
+    where
+        Factory: FnMut() -> Txn + 'static + Unpin,
+        Txn: std::future::Future<Output = Result<dgraph_tonic::Response, anyhow::Error>>,
+    {
+        let handle_upsert_err = UpsertErrorHandler {};
+        let (response, attempts) = FutureRetry::new(f, handle_upsert_err)
+            .await
+            .expect("Surfaced an error despite retry strategy while performing an upsert.");
+
+        tracing::info!(message = "Performed upsert", attempts = attempts);
+
+        response
+    }
+}
+
+#[async_trait]
+impl GraphStore for DgraphGraphStore {
+    async fn upsert_nodes(&self, nodes: Vec<IdentifiedNode>) -> HashMap<String, u64> {
+        let mut key_to_query_name = HashMap::new();
+        let mut node_upserts = Vec::with_capacity(nodes.len());
+        for (unique_id, node) in nodes.iter().enumerate() {
+            let (query, upserts) = upsert_util::build_upserts(
+                unique_id as u128,
+                &node.node_key,
+                &node.node_type,
+                &node.properties,
+                &mut key_to_query_name,
+            );
+            node_upserts.push((query, upserts));
+        }
+
+        tracing::info!(message = "Upserting nodes", count = node_upserts.len());
+        let dgraph_client = self.dgraph_client.clone();
+        let responses: Vec<dgraph_tonic::Response> = futures::stream::iter(
+            node_upserts
+                .into_iter()
+                .chunks_owned(DGRAPH_UPSERT_CHUNK_SIZE),
+        )
+        .map(move |upsert_chunk| {
+            let mut combined_query = String::new();
+            let mut all_mutations = Vec::new();
+            for (query_block, mutations) in upsert_chunk.iter() {
+                combined_query.push_str(&query_block);
+                all_mutations.extend_from_slice(mutations);
+            }
+
+            let combined_query = format!(
+                r"
+            {{
+                    {}
+            }}
+            ",
+                combined_query
+            );
+
+            tracing::debug!(message="Generated query for upsert", combined_query=?combined_query);
+
+            let dgraph_client = dgraph_client.clone();
+            Self::enforce_transaction(move || {
+                let mut txn = dgraph_client.new_mutated_txn();
+                txn.upsert_and_commit_now(combined_query.clone(), all_mutations.clone())
+            })
+        })
+        .buffer_unordered(DGRAPH_CONCURRENCY_UPSERTS)
+        .collect::<Vec<_>>()
+        .await;
+
+        let mut node_key_map_to_uid = HashMap::new();
+        let mut uids = HashMap::new();
+        for response in responses.iter() {
+            let query_responses: serde_json::Value = match serde_json::from_slice(&response.json) {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::error!(message="Failed to parse JSON response for upsert", error=?e);
+                    continue;
+                }
+            };
+            tracing::debug!(
+                message="Received upsert response",
+                query_response=?query_responses,
+                uids=?response.uids,
+            );
+            uids.extend(response.uids.clone());
+            extract_node_key_map_uid(&query_responses, &mut node_key_map_to_uid);
+        }
+
+        // Nodes that were created by this upsert aren't in the query responses
+        for node in nodes.iter() {
+            if node_key_map_to_uid.contains_key(&node.node_key) {
+                continue;
+            }
+            if let Some(uid) = uid_from_uids(&node.node_key, &key_to_query_name, &uids) {
+                node_key_map_to_uid.insert(node.node_key.clone(), uid);
+            }
+        }
+        node_key_map_to_uid
+    }
+
+    async fn upsert_edges(&self, edges: Vec<UidEdge>) {
+        // Edges with facets are read and written in the same transaction, so that the facets of
+        // concurrent observations are each accumulated
+        let (faceted_edges, edges): (Vec<UidEdge>, Vec<UidEdge>) =
+            edges.into_iter().partition(|edge| edge.facets.is_some());
+
+        let mut mutations = Vec::with_capacity(edges.len());
+        for items in edges.into_iter().chunks_owned(DGRAPH_UPSERT_CHUNK_SIZE) {
+            let set: Vec<serde_json::Value> = items.iter().map(edge_mutation).collect();
+            mutations.push(set);
+        }
+
+        futures::stream::iter(mutations.into_iter())
+            .map(|mutation| {
+                let dgraph_client = self.dgraph_client.clone();
+                Self::enforce_transaction(move || {
+                    let mut dgraph_mutation = dgraph_tonic::Mutation::new();
+                    dgraph_mutation.set_set_json(&mutation).unwrap_or_else(
+                        |e| tracing::error!(message="Failed to set json for mutation", error=?e),
+                    );
+
+                    let mut txn = dgraph_client.new_mutated_txn();
+                    txn.mutate_and_commit_now(dgraph_mutation.clone())
+                })
+            })
+            .buffer_unordered(DGRAPH_CONCURRENCY_UPSERTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        futures::stream::iter(
+            faceted_edges
+                .into_iter()
+                .chunks_owned(DGRAPH_UPSERT_CHUNK_SIZE),
+        )
+        .map(|edges| {
+            let dgraph_client = self.dgraph_client.clone();
+            Self::enforce_transaction(move || {
+                let dgraph_client = dgraph_client.clone();
+                let mut edges = edges.clone();
+                async move {
+                    let mut txn = dgraph_client.new_mutated_txn();
+                    accumulate_stored_facets(&mut txn, &mut edges).await?;
+
+                    let set: Vec<serde_json::Value> = edges.iter().map(edge_mutation).collect();
+                    let mut dgraph_mutation = dgraph_tonic::Mutation::new();
+                    dgraph_mutation.set_set_json(&set)?;
+                    let response = txn.mutate(dgraph_mutation).await?;
+                    txn.commit().await?;
+                    Ok(response)
+                }
+            })
+        })
+        .buffer_unordered(DGRAPH_CONCURRENCY_UPSERTS)
+        .collect::<Vec<_>>()
+        .await;
+    }
+
+    async fn delete_edges(&self, edges: Vec<UidEdge>) {
+        if edges.is_empty() {
+            return;
+        }
+        let nquads: String = edges
+            .iter()
+            .map(
+                |UidEdge {
+                     from_uid,
+                     to_uid,
+                     edge_name,
+                     ..
+                 }| {
+                    format!("<0x{:x}> <{}> <0x{:x}> .\n", from_uid, edge_name, to_uid)
+                },
+            )
+            .collect();
+
+        let dgraph_client = self.dgraph_client.clone();
+        Self::enforce_transaction(move || {
+            let mut dgraph_mutation = dgraph_tonic::Mutation::new();
+            dgraph_mutation.set_delete_nquads(nquads.clone());
+
+            let mut txn = dgraph_client.new_mutated_txn();
+            txn.mutate_and_commit_now(dgraph_mutation)
+        })
+        .await;
+    }
+
+    async fn lookup_edges(&self, edges: &[(u64, String)]) -> HashMap<(u64, String), Vec<u64>> {
+        let mut stored_edges = HashMap::new();
+        if edges.is_empty() {
+            return stored_edges;
+        }
+
+        let query_blocks: String = edges
+            .iter()
+            .enumerate()
+            .map(|(i, (from_uid, edge_name))| {
+                format!(
+                    "q{}(func: uid(0x{:x})) {{ {} {{ uid }} }}\n",
+                    i, from_uid, edge_name
+                )
+            })
+            .collect();
+        let query = format!("{{\n{}}}", query_blocks);
+
+        let mut txn = self.dgraph_client.new_read_only_txn();
+        let query_responses = txn.query(query).await.expect("query failed");
+        let query_responses: HashMap<String, Vec<serde_json::Value>> =
+            serde_json::from_slice(&query_responses.json).expect("response failed to parse");
+
+        for (query_name, query_response) in query_responses.into_iter() {
+            let edge = query_name
+                .trim_start_matches('q')
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| edges.get(i));
+            let (from_uid, edge_name) = match edge {
+                Some(edge) => edge,
+                None => {
+                    tracing::error!(message="Unexpected query in response", query_name=?query_name);
+                    continue;
+                }
+            };
+            let to_uids: Vec<u64> = query_response
+                .iter()
+                .filter_map(|node| node.get(edge_name.as_str()))
+                .flat_map(edge_targets)
+                .filter_map(extract_uid)
+                .collect();
+            if !to_uids.is_empty() {
+                stored_edges.insert((*from_uid, edge_name.clone()), to_uids);
+            }
+        }
+
+        stored_edges
+    }
+
+    async fn lookup_node_keys(&self, node_keys: &[&String]) -> HashMap<String, u64> {
+        let mut resolved_nodes = HashMap::new();
+
+        let mut query_blocks = Vec::with_capacity(node_keys.len());
+        for node_key in node_keys.iter() {
+            let query_block = gen_node_key_query(node_key);
+            query_blocks.push(query_block);
+        }
+
+        let query = QueryBuilder::default()
+            .query_blocks(query_blocks)
+            .build()
+            .unwrap();
+
+        let mut txn = self.dgraph_client.new_read_only_txn();
+        let query_responses = txn
+            .query(query.to_query_string())
+            .await
+            .expect("query failed");
+
+        let query_responses: HashMap<String, Vec<HashMap<String, String>>> =
+            serde_json::from_slice(&query_responses.json).expect("response failed to parse");
+
+        for (_, query_response) in query_responses.into_iter() {
+            let query_response = match query_response.as_slice() {
+                [query_response] => query_response,
+                [] => {
+                    tracing::error!(message = "Empty response for node_key");
+                    continue;
+                }
+                res => {
+                    tracing::error!(message = "Too many responses for node_key", count=?res.len());
+                    continue;
+                }
+            };
+            let node_key = query_response.get("node_key");
+            let uid = query_response.get("uid");
+            let (node_key, uid) = match (node_key, uid) {
+                (Some(node_key), Some(uid)) => (node_key, uid),
+                (missing_key, missing_uid) => {
+                    tracing::error!(message="Unable to retrieve node_key and uid", node_key=?missing_key, uid=?missing_uid);
+                    continue;
+                }
+            };
+            let uid = u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex");
+            resolved_nodes.insert(node_key.to_owned(), uid);
+        }
+
+        resolved_nodes
+    }
+}
+
+/// Accumulates the facets already stored on each edge into the facets being written, as writing
+/// an edge replaces all of its facets
+async fn accumulate_stored_facets(
+    txn: &mut (impl Query + Send),
+    edges: &mut [UidEdge],
+) -> Result<(), anyhow::Error> {
+    let query_blocks: String = edges
+        .iter()
+        .enumerate()
+        .map(|(i, edge)| {
+            format!(
+                "q{}(func: uid(0x{:x})) {{ {} @facets(first_seen, last_seen, count) @filter(uid(0x{:x})) {{ uid }} }}\n",
+                i, edge.from_uid, edge.edge_name, edge.to_uid
+            )
+        })
+        .collect();
+    let query = format!("{{\n{}}}", query_blocks);
+
+    let query_responses = txn.query(query).await?;
+    let query_responses: HashMap<String, Vec<serde_json::Value>> =
+        serde_json::from_slice(&query_responses.json)?;
+
+    for (query_name, query_response) in query_responses.into_iter() {
+        let edge = query_name
+            .trim_start_matches('q')
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| edges.get_mut(i));
+        let edge = match edge {
+            Some(edge) => edge,
+            None => {
+                tracing::error!(message="Unexpected query in response", query_name=?query_name);
+                continue;
+            }
+        };
+        let stored_facets = query_response
+            .iter()
+            .filter_map(|node| node.get(edge.edge_name.as_str()))
+            .flat_map(edge_targets)
+            .find_map(|to_node| extract_edge_facets(to_node, &edge.edge_name));
+        if let (Some(facets), Some(stored_facets)) = (edge.facets.as_mut(), stored_facets) {
+            facets.accumulate(&stored_facets);
+        }
+    }
+    Ok(())
+}
+
+pub struct UpsertErrorHandler {}
+
+impl futures_retry::ErrorHandler<anyhow::Error> for UpsertErrorHandler {
+    type OutError = anyhow::Error;
+
+    fn handle(&mut self, attempt: usize, e: anyhow::Error) -> RetryPolicy<Self::OutError> {
+        let attempt = attempt as u64;
+        tracing::warn!(
+            message="Failed to enforce transaction",
+            error=?e,
+            attempt=?attempt,
+        );
+        match attempt {
+            0..=5 => RetryPolicy::Repeat,
+            t @ 6..=20 => RetryPolicy::WaitRetry(std::time::Duration::from_millis(10 * t as u64)),
+            21..=u64::MAX => RetryPolicy::ForwardError(e),
+        }
+    }
+}
+
+fn gen_node_key_query(node_key: &str) -> QueryBlock {
+    QueryBlockBuilder::default()
+        .query_type(QueryBlockType::query())
+        .root_filter(Condition::EQ(
+            "node_key".to_string(),
+            ConditionValue::string(node_key),
+        ))
+        .predicates(vec![
+            Predicate::Field(Field::new("uid")),
+            Predicate::Field(Field::new("node_key")),
+        ])
+        .first(1)
+        .build()
+        .unwrap()
+}
+
+fn uid_from_uids(
+    node_key: &str,
+    key_to_query_name: &HashMap<String, String>,
+    uids: &HashMap<String, String>,
+) -> Option<u64> {
+    let query_name = key_to_query_name.get(node_key)?;
+    let uid = uids.get(query_name)?;
+    Some(u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex"))
+}
+
+/// The nodes an edge points to, whether it's a list of nodes or a single node
+fn edge_targets(edge: &serde_json::Value) -> Vec<&serde_json::Value> {
+    match edge {
+        serde_json::Value::Array(to_nodes) => to_nodes.iter().collect(),
+        to_node => vec![to_node],
+    }
+}
+
+fn extract_uid(node: &serde_json::Value) -> Option<u64> {
+    let uid = node.get("uid")?.as_str()?;
+    Some(u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex"))
+}
+
+/// Dgraph returns the facets of an edge on the node it points to, as `<edge_name>|<facet>`
+fn extract_edge_facets(to_node: &serde_json::Value, edge_name: &str) -> Option<EdgeFacets> {
+    let facet = |facet_name: &str| {
+        to_node
+            .get(&format!("{}|{}", edge_name, facet_name))?
+            .as_u64()
+    };
+    let facets = EdgeFacets {
+        first_seen: facet("first_seen").map(|prop| DecrementOnlyUintProp { prop }),
+        last_seen: facet("last_seen").map(|prop| IncrementOnlyUintProp { prop }),
+        count: facet("count").map(|prop| IncrementOnlyUintProp { prop }),
+    };
+    if facets == EdgeFacets::default() {
+        None
+    } else {
+        Some(facets)
+    }
+}
+
+/// The JSON mutation for an edge, with its facets set as Dgraph facets
+fn edge_mutation(edge: &UidEdge) -> serde_json::Value {
+    let mut to_node = serde_json::Map::new();
+    to_node.insert("uid".to_string(), format!("0x{:x}", edge.to_uid).into());
+    if let Some(facets) = edge.facets {
+        let facets = [
+            ("first_seen", facets.first_seen.map(|facet| facet.prop)),
+            ("last_seen", facets.last_seen.map(|facet| facet.prop)),
+            ("count", facets.count.map(|facet| facet.prop)),
+        ];
+        for (facet_name, value) in facets.iter() {
+            if let Some(value) = value {
+                to_node.insert(
+                    format!("{}|{}", edge.edge_name, facet_name),
+                    (*value).into(),
+                );
+            }
+        }
+    }
+
+    let mut from_node = serde_json::Map::new();
+    from_node.insert("uid".to_string(), format!("0x{:x}", edge.from_uid).into());
+    from_node.insert(
+        edge.edge_name.clone(),
+        serde_json::Value::Array(vec![to_node.into()]),
+    );
+    from_node.into()
+}
+
+fn extract_node_key_map_uid(
+    dgraph_response: &serde_json::Value,
+    node_key_map_to_uid: &mut HashMap<String, u64>,
+) {
+    let query_responses = dgraph_response.as_object().expect("Invalid response");
+
+    for query_response in query_responses.values() {
+        let query_response = query_response.as_array().expect("Invalid response");
+        for query_response in query_response {
+            let uid = query_response
+                .get("uid")
+                .expect("uid")
+                .as_str()
+                .expect("uid");
+            let node_key = query_response
+                .get("node_key")
+                .expect("node_key")
+                .as_str()
+                .expect("node_key");
+
+            // dgraph uids are hex encoded as '0x1b'
+            let uid = u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex");
+            node_key_map_to_uid.insert(node_key.to_owned(), uid);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use grapl_graph_descriptions::graph_description::{
+    EdgeFacets,
+    IdentifiedNode,
+};
+
+/// An edge between two nodes that have already been stored
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UidEdge {
+    pub from_uid: u64,
+    pub to_uid: u64,
+    pub edge_name: String,
+    pub facets: Option<EdgeFacets>,
+}
+
+/// Where the graph-merger writes merged graphs to
+///
+/// Failures are retried within the store, so nothing is surfaced to the caller. Nodes and edges
+/// that still couldn't be written are left out of the returned uids.
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    /// Creates any nodes that aren't stored yet and merges the properties of those that are,
+    /// according to each property's merge semantics. Returns the uid of every node it upserted,
+    /// keyed by node_key.
+    async fn upsert_nodes(&self, nodes: Vec<IdentifiedNode>) -> HashMap<String, u64>;
+
+    /// Adds the edges between the stored nodes. The facets of edges that are already stored are
+    /// accumulated, so the counts of their observations are added together.
+    async fn upsert_edges(&self, edges: Vec<UidEdge>);
+
+    /// Removes the edges between the stored nodes, along with their facets
+    async fn delete_edges(&self, edges: Vec<UidEdge>);
+
+    /// Looks up the uids that each edge points to from the stored node, keyed by the node's uid
+    /// and the edge name. Edges that aren't stored are left out.
+    async fn lookup_edges(&self, edges: &[(u64, String)]) -> HashMap<(u64, String), Vec<u64>>;
+
+    /// Looks up the uids of stored nodes, keyed by node_key. Nodes that aren't stored are
+    /// left out.
+    async fn lookup_node_keys(&self, node_keys: &[&String]) -> HashMap<String, u64>;
+}
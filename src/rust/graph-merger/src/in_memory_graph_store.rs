@@ -0,0 +1,163 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use grapl_graph_descriptions::graph_description::{
+    EdgeFacets,
+    IdentifiedNode,
+    MergedNode,
+};
+
+use crate::graph_store::{
+    GraphStore,
+    UidEdge,
+};
+
+#[derive(Default)]
+struct InMemoryGraph {
+    nodes: HashMap<String, MergedNode>,
+    edges: HashMap<(u64, String), BTreeMap<u64, Option<EdgeFacets>>>,
+    last_uid: u64,
+}
+
+/// Holds merged graphs in memory, for tests and local runs that have no Dgraph to write to
+///
+/// Clones share the same graph.
+#[derive(Clone, Default)]
+pub struct InMemoryGraphStore {
+    graph: Arc<Mutex<InMemoryGraph>>,
+}
+
+impl InMemoryGraphStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stored node, with its merged properties
+    pub fn node(&self, node_key: &str) -> Option<MergedNode> {
+        let graph = self.graph.lock().unwrap();
+        graph.nodes.get(node_key).cloned()
+    }
+
+    /// The uids that the edge points to from the node with `from_uid`
+    pub fn edges(&self, from_uid: u64, edge_name: &str) -> Vec<u64> {
+        let graph = self.graph.lock().unwrap();
+        graph
+            .edges
+            .get(&(from_uid, edge_name.to_owned()))
+            .map(|to_uids| to_uids.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// The facets of the edge from the node with `from_uid` to the node with `to_uid`
+    pub fn edge_facets(&self, from_uid: u64, edge_name: &str, to_uid: u64) -> Option<EdgeFacets> {
+        let graph = self.graph.lock().unwrap();
+        *graph
+            .edges
+            .get(&(from_uid, edge_name.to_owned()))?
+            .get(&to_uid)?
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.graph.lock().unwrap().nodes.len()
+    }
+}
+
+#[async_trait]
+impl GraphStore for InMemoryGraphStore {
+    async fn upsert_nodes(&self, nodes: Vec<IdentifiedNode>) -> HashMap<String, u64> {
+        let mut graph = self.graph.lock().unwrap();
+        let mut node_key_to_uid = HashMap::with_capacity(nodes.len());
+        for node in nodes {
+            let node_key = node.node_key.clone();
+            let uid = match graph.nodes.get_mut(&node_key) {
+                Some(stored) => {
+                    stored.merge(&MergedNode::from(node, stored.uid));
+                    stored.uid
+                }
+                None => {
+                    graph.last_uid += 1;
+                    let uid = graph.last_uid;
+                    graph
+                        .nodes
+                        .insert(node_key.clone(), MergedNode::from(node, uid));
+                    uid
+                }
+            };
+            node_key_to_uid.insert(node_key, uid);
+        }
+        node_key_to_uid
+    }
+
+    async fn upsert_edges(&self, edges: Vec<UidEdge>) {
+        let mut graph = self.graph.lock().unwrap();
+        for UidEdge {
+            from_uid,
+            to_uid,
+            edge_name,
+            facets,
+        } in edges
+        {
+            let stored_facets = graph
+                .edges
+                .entry((from_uid, edge_name))
+                .or_default()
+                .entry(to_uid)
+                .or_default();
+            match (stored_facets.as_mut(), facets) {
+                (Some(stored_facets), Some(facets)) => stored_facets.accumulate(&facets),
+                (None, facets) => *stored_facets = facets,
+                (Some(_), None) => {}
+            }
+        }
+    }
+
+    async fn delete_edges(&self, edges: Vec<UidEdge>) {
+        let mut graph = self.graph.lock().unwrap();
+        for UidEdge {
+            from_uid,
+            to_uid,
+            edge_name,
+            ..
+        } in edges
+        {
+            let key = (from_uid, edge_name);
+            if let Some(to_uids) = graph.edges.get_mut(&key) {
+                to_uids.remove(&to_uid);
+                if to_uids.is_empty() {
+                    graph.edges.remove(&key);
+                }
+            }
+        }
+    }
+
+    async fn lookup_edges(&self, edges: &[(u64, String)]) -> HashMap<(u64, String), Vec<u64>> {
+        let graph = self.graph.lock().unwrap();
+        edges
+            .iter()
+            .filter_map(|key| {
+                let to_uids = graph.edges.get(key)?;
+                Some((key.clone(), to_uids.keys().copied().collect()))
+            })
+            .collect()
+    }
+
+    async fn lookup_node_keys(&self, node_keys: &[&String]) -> HashMap<String, u64> {
+        let graph = self.graph.lock().unwrap();
+        node_keys
+            .iter()
+            .filter_map(|node_key| {
+                let node = graph.nodes.get(node_key.as_str())?;
+                Some((node.node_key.clone(), node.uid))
+            })
+            .collect()
+    }
+}
@@ -1,7 +1,11 @@
 #![allow(unused)]
 #![allow(unused_imports)]
 #![allow(dead_code)]
-pub mod reverse_resolver;
+pub mod deployed_schemas;
+pub mod dgraph_graph_store;
+pub mod graph_store;
+pub mod in_memory_graph_store;
+pub mod schema_registry;
 pub mod service;
 pub mod upsert_util;
 pub mod upserter;
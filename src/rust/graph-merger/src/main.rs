@@ -1,7 +1,11 @@
 #![allow(unused)]
 #![allow(dead_code)]
 
-pub mod reverse_resolver;
+pub mod deployed_schemas;
+pub mod dgraph_graph_store;
+pub mod graph_store;
+pub mod in_memory_graph_store;
+pub mod schema_registry;
 pub mod service;
 pub mod upsert_util;
 pub mod upserter;
@@ -10,6 +14,7 @@ use std::{
     collections::HashMap,
     fmt::Debug,
     io::Stdout,
+    str::FromStr,
     sync::{
         Arc,
         Mutex,
@@ -33,11 +38,9 @@ use failure::{
 };
 use graph_merger_lib;
 use grapl_config::{
-    env_helpers::{
-        s3_event_emitters_from_env,
-        FromEnv,
-    },
-    event_caches,
+    env_helpers::FromEnv,
+    event_cache,
+    service::run_service,
 };
 use grapl_graph_descriptions::graph_description::{
     Edge,
@@ -72,7 +75,6 @@ use rusoto_dynamodb::{
     KeysAndAttributes,
 };
 use rusoto_s3::S3Client;
-use rusoto_sqs::SqsClient;
 use serde::{
     Deserialize,
     Serialize,
@@ -91,8 +93,6 @@ use sqs_executor::{
         CompletedEvents,
         EventHandler,
     },
-    make_ten,
-    s3_event_retriever::S3PayloadRetriever,
 };
 use tracing::{
     error,
@@ -101,14 +101,13 @@ use tracing::{
 };
 
 use crate::{
-    reverse_resolver::{
-        get_r_edges_from_dynamodb,
-        ReverseEdgeResolver,
-    },
+    dgraph_graph_store::DgraphGraphStore,
+    schema_registry::SchemaRegistry,
     service::{
         time_based_key_fn,
         GraphMerger,
     },
+    upserter::ToOneEdgePolicy,
 };
 
 #[tracing::instrument]
@@ -116,55 +115,31 @@ async fn handler() -> Result<(), Box<dyn std::error::Error>> {
     let (env, _guard) = grapl_config::init_grapl_env!();
     info!("Starting graph-merger");
 
-    let sqs_client = SqsClient::from_env();
-
-    let cache = &mut event_caches(&env).await;
+    let cache = event_cache(&env).await;
 
     let mg_alphas = grapl_config::mg_alphas();
+    tracing::debug!(
+        mg_alphas=?&mg_alphas,
+        "Connecting to mg_alphas"
+    );
+    let schema_registry = SchemaRegistry::from_env().await?;
+    let to_one_edge_policy = ToOneEdgePolicy::from_str(&grapl_config::to_one_edge_policy())?;
+    let graph_merger = GraphMerger::new(
+        DgraphGraphStore::new(mg_alphas),
+        schema_registry,
+        to_one_edge_policy,
+        grapl_config::enforce_graph_schema(),
+        MetricReporter::new(&env.service_name),
+        cache.clone(),
+    );
 
-    // todo: the intitializer should give a cache to each service
-    let graph_merger = &mut make_ten(async {
-        let mg_alphas_copy = mg_alphas.clone();
-        tracing::debug!(
-            mg_alphas=?&mg_alphas_copy,
-            "Connecting to mg_alphas"
-        );
-        let dynamo = DynamoDbClient::from_env();
-        let reverse_edge_resolver =
-            ReverseEdgeResolver::new(dynamo, MetricReporter::new(&env.service_name), 1000);
-        GraphMerger::new(
-            mg_alphas_copy,
-            reverse_edge_resolver,
-            MetricReporter::new(&env.service_name),
-            cache[0].clone(),
-        )
-    })
-    .await;
-
-    let serializer = &mut make_ten(async { MergedGraphSerializer::default() }).await;
-
-    let s3_emitter = &mut s3_event_emitters_from_env(&env, time_based_key_fn).await;
-
-    let s3_payload_retriever = &mut make_ten(async {
-        S3PayloadRetriever::new(
-            |region_str| grapl_config::env_helpers::init_s3_client(&region_str),
-            ProtoDecoder::default(),
-            MetricReporter::new(&env.service_name),
-        )
-    })
-    .await;
-
-    info!("Starting process_loop");
-    sqs_executor::process_loop(
-        grapl_config::source_queue_url(),
-        grapl_config::dead_letter_queue_url(),
+    run_service(
+        &env,
         cache,
-        sqs_client.clone(),
         graph_merger,
-        s3_payload_retriever,
-        s3_emitter,
-        serializer,
-        MetricReporter::new(&env.service_name),
+        ProtoDecoder::default(),
+        MergedGraphSerializer::default(),
+        time_based_key_fn,
     )
     .await;
 
@@ -0,0 +1,632 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use endpoint_plugin::ENDPOINT_GRAPHQL;
+use graphql_parser::schema::parse_schema;
+use grapl_graph_descriptions::{
+    schema::{
+        GraphSchema,
+        PropertyType,
+    },
+    Edge,
+    IdentifiedGraph,
+    Property,
+};
+use grapl_graphql_codegen::{
+    conflict_resolution::ConflictResolution,
+    edge::Edge as SchemaEdge,
+    edge_rel::EdgeRel,
+    node_type::parse_into_node_types,
+    predicate_type::PredicateType,
+};
+use rusoto_dynamodb::DynamoDbClient;
+use tracing::warn;
+
+use crate::deployed_schemas::{
+    DeployedSchemas,
+    DeployedSchemasError,
+    PropPrimitive,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SchemaRegistryError {
+    #[error(transparent)]
+    DeployedSchemas(#[from] DeployedSchemasError),
+    #[error("GRAPL_SCHEMA_TABLE and GRAPL_SCHEMA_PROPERTIES_TABLE must be set together")]
+    MissingSchemaTable,
+    #[error("Failed to parse schemas: {0}")]
+    Parse(String),
+    #[error("Edge {edge_name} on {node_type} points to undefined node type {target_type}")]
+    UndefinedTargetType {
+        node_type: String,
+        edge_name: String,
+        target_type: String,
+    },
+    #[error("Edge {edge_name} on {node_type} is defined more than once")]
+    ConflictingEdge {
+        node_type: String,
+        edge_name: String,
+    },
+}
+
+/// How a predicate is stored
+#[derive(Clone, Copy, Debug)]
+pub enum PredicateSchema {
+    /// A predicate defined in GraphQL, which declares how its values are merged
+    Defined {
+        predicate_type: PredicateType,
+        conflict_resolution: ConflictResolution,
+    },
+    /// A predicate that was only deployed to the schema tables, which record its primitive type
+    Deployed {
+        primitive: PropPrimitive,
+        is_set: bool,
+    },
+}
+
+impl PredicateSchema {
+    /// The type of property this predicate is stored as, if there's a property type for it.
+    /// Deployed predicates may be stored as any property type of their primitive type.
+    pub fn property_type(&self) -> Option<PropertyType> {
+        let (predicate_type, conflict_resolution) = match *self {
+            Self::Defined {
+                predicate_type,
+                conflict_resolution,
+            } => (predicate_type, conflict_resolution),
+            Self::Deployed { .. } => return None,
+        };
+        PropertyType::of_predicate(predicate_type, conflict_resolution)
+    }
+
+    /// Whether the property is of the kind this predicate is stored as
+    pub fn accepts(&self, property: &Property) -> bool {
+        let property_type = PropertyType::of(property);
+        match *self {
+            Self::Defined { .. } => self.property_type() == Some(property_type),
+            Self::Deployed { primitive, is_set } => {
+                prop_primitive(property_type) == (primitive, is_set)
+            }
+        }
+    }
+}
+
+/// The primitive type grapl_analyzerlib records for properties of the property type, and
+/// whether it's a set
+fn prop_primitive(property_type: PropertyType) -> (PropPrimitive, bool) {
+    match property_type {
+        PropertyType::ImmutableUint
+        | PropertyType::IncrementOnlyUint
+        | PropertyType::DecrementOnlyUint
+        | PropertyType::ImmutableInt
+        | PropertyType::IncrementOnlyInt
+        | PropertyType::DecrementOnlyInt
+        | PropertyType::ImmutableTimestamp
+        | PropertyType::IncrementOnlyTimestamp
+        | PropertyType::DecrementOnlyTimestamp => (PropPrimitive::Int, false),
+        PropertyType::ImmutableStr | PropertyType::ImmutableBytes => (PropPrimitive::Str, false),
+        PropertyType::ImmutableBool => (PropPrimitive::Bool, false),
+        PropertyType::IncrementOnlyFloat | PropertyType::DecrementOnlyFloat => {
+            (PropPrimitive::Float, false)
+        }
+        PropertyType::StrSet => (PropPrimitive::Str, true),
+    }
+}
+
+/// An edge from a node type, in either the direction it was declared in or in reverse
+#[derive(Clone, Debug)]
+pub struct EdgeSchema {
+    pub edge_name: String,
+    pub reverse_edge_name: String,
+    pub target_type_name: String,
+    pub relationship: EdgeRel,
+}
+
+impl EdgeSchema {
+    fn same_as(&self, other: &Self) -> bool {
+        self.edge_name == other.edge_name
+            && self.reverse_edge_name == other.reverse_edge_name
+            && self.target_type_name == other.target_type_name
+            && self.relationship == other.relationship
+    }
+}
+
+impl From<SchemaEdge> for EdgeSchema {
+    fn from(edge: SchemaEdge) -> Self {
+        Self {
+            edge_name: edge.edge_name,
+            reverse_edge_name: edge.reverse_edge_name,
+            target_type_name: edge.target_type_name,
+            relationship: edge.relationship,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NodeSchema {
+    pub predicates: HashMap<String, PredicateSchema>,
+    /// Both the edges declared on the node type and the reverse of those declared on others
+    pub edges: HashMap<String, EdgeSchema>,
+}
+
+/// A way in which a subgraph doesn't conform to the schemas
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    #[error("Node {node_key} has undefined type {node_type}")]
+    UnknownNodeType { node_key: String, node_type: String },
+    #[error("Predicate {predicate_name} is not defined on {node_type}, for node {node_key}")]
+    UnknownPredicate {
+        node_key: String,
+        node_type: String,
+        predicate_name: String,
+    },
+    #[error(
+        "Predicate {predicate_name} on {node_type} node {node_key} has the wrong property type"
+    )]
+    PredicateTypeMismatch {
+        node_key: String,
+        node_type: String,
+        predicate_name: String,
+    },
+    #[error("Edge {edge_name} is not defined on {node_type}, for node {from_node_key}")]
+    UnknownEdge {
+        from_node_key: String,
+        node_type: String,
+        edge_name: String,
+    },
+    #[error("Edge {edge_name} from {from_node_key} must point to a {expected_type}, not {to_node_key}, a {to_node_type}")]
+    EdgeTargetMismatch {
+        from_node_key: String,
+        to_node_key: String,
+        edge_name: String,
+        expected_type: String,
+        to_node_type: String,
+    },
+    #[error("Edge {edge_name} from {from_node_key} is to-one, but points to both {to_node_key} and {conflicting_node_key}")]
+    ToOneEdgeConflict {
+        from_node_key: String,
+        edge_name: String,
+        to_node_key: String,
+        conflicting_node_key: String,
+    },
+}
+
+impl SchemaViolation {
+    /// Names the kind of violation, for tagging metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::UnknownNodeType { .. } => "unknown_node_type",
+            Self::UnknownPredicate { .. } => "unknown_predicate",
+            Self::PredicateTypeMismatch { .. } => "predicate_type_mismatch",
+            Self::UnknownEdge { .. } => "unknown_edge",
+            Self::EdgeTargetMismatch { .. } => "edge_target_mismatch",
+            Self::ToOneEdgeConflict { .. } => "to_one_edge_conflict",
+        }
+    }
+}
+
+/// The node types, predicates and edges defined by Grapl's GraphQL schemas, as parsed by
+/// `grapl-graphql-codegen`, along with those provisioned into the schema tables
+#[derive(Clone, Debug, Default)]
+pub struct SchemaRegistry {
+    node_types: Arc<HashMap<String, NodeSchema>>,
+}
+
+impl SchemaRegistry {
+    /// Loads the endpoint schema, along with the schemas that have been provisioned into
+    /// `GRAPL_SCHEMA_TABLE` and `GRAPL_SCHEMA_PROPERTIES_TABLE` when they're set.
+    ///
+    /// The schema tables are read once, so plugins deployed later are picked up when the
+    /// graph-merger restarts.
+    pub async fn from_env() -> Result<Self, SchemaRegistryError> {
+        let schema_registry = Self::endpoint()?;
+        let schema_table_name = std::env::var("GRAPL_SCHEMA_TABLE");
+        let schema_properties_table_name = std::env::var("GRAPL_SCHEMA_PROPERTIES_TABLE");
+        match (schema_table_name, schema_properties_table_name) {
+            (Ok(schema_table_name), Ok(schema_properties_table_name)) => {
+                let deployed_schemas = DeployedSchemas::scan(
+                    &DynamoDbClient::from_env(),
+                    &schema_table_name,
+                    &schema_properties_table_name,
+                )
+                .await?;
+                Ok(schema_registry.with_deployed(&deployed_schemas))
+            }
+            (Err(_), Err(_)) => Ok(schema_registry),
+            _ => Err(SchemaRegistryError::MissingSchemaTable),
+        }
+    }
+
+    /// Loads the endpoint plugin's schema, `endpoint_plugin::ENDPOINT_GRAPHQL`
+    pub fn endpoint() -> Result<Self, SchemaRegistryError> {
+        Self::from_schema(ENDPOINT_GRAPHQL)
+    }
+
+    pub fn from_schema(schema: &str) -> Result<Self, SchemaRegistryError> {
+        let document =
+            parse_schema::<&str>(schema).map_err(|e| SchemaRegistryError::Parse(e.to_string()))?;
+        let parsed_node_types = parse_into_node_types(&document)
+            .map_err(|e| SchemaRegistryError::Parse(e.to_string()))?;
+
+        let mut node_types: HashMap<String, NodeSchema> = HashMap::new();
+        let mut edges = vec![];
+        for node_type in parsed_node_types {
+            let node_schema = node_types.entry(node_type.type_name).or_default();
+            for predicate in node_type.predicates {
+                node_schema.predicates.insert(
+                    predicate.predicate_name,
+                    PredicateSchema::Defined {
+                        predicate_type: predicate.predicate_type,
+                        conflict_resolution: predicate.conflict_resolution,
+                    },
+                );
+            }
+            edges.extend(node_type.edges);
+        }
+
+        for edge in edges {
+            let reverse_edge = edge.clone().reverse();
+            for edge in vec![edge, reverse_edge] {
+                if !node_types.contains_key(&edge.target_type_name) {
+                    return Err(SchemaRegistryError::UndefinedTargetType {
+                        node_type: edge.source_type_name,
+                        edge_name: edge.edge_name,
+                        target_type: edge.target_type_name,
+                    });
+                }
+                let node_type = edge.source_type_name.clone();
+                let node_schema = node_types.entry(node_type.clone()).or_default();
+                let edge_schema = EdgeSchema::from(edge);
+                match node_schema.edges.get(&edge_schema.edge_name) {
+                    Some(defined) if !defined.same_as(&edge_schema) => {
+                        return Err(SchemaRegistryError::ConflictingEdge {
+                            node_type,
+                            edge_name: edge_schema.edge_name,
+                        })
+                    }
+                    Some(_) => {}
+                    None => {
+                        node_schema
+                            .edges
+                            .insert(edge_schema.edge_name.clone(), edge_schema);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            node_types: Arc::new(node_types),
+        })
+    }
+
+    /// Adds the node types, predicates and edges of the schema tables. Those already defined in
+    /// GraphQL take precedence, since the schema tables don't record how predicates are merged.
+    /// Deployed edges that conflict with defined ones, or whose reverse or target type isn't
+    /// known, are skipped.
+    pub fn with_deployed(self, deployed_schemas: &DeployedSchemas) -> Self {
+        let mut node_types = (*self.node_types).clone();
+        for deployed in deployed_schemas.node_types.iter() {
+            let node_schema = node_types.entry(deployed.node_type.clone()).or_default();
+            for property in deployed.properties.iter() {
+                node_schema
+                    .predicates
+                    .entry(property.name.clone())
+                    .or_insert(PredicateSchema::Deployed {
+                        primitive: property.primitive,
+                        is_set: property.is_set,
+                    });
+            }
+        }
+
+        for deployed in deployed_schemas.node_types.iter() {
+            for edge in deployed.edges.iter() {
+                let edge_relationship = match deployed_schemas
+                    .edge_relationships
+                    .get(&edge.edge_name)
+                {
+                    Some(edge_relationship) => edge_relationship,
+                    None => {
+                        warn!(message="Skipping deployed edge without a reverse", node_type=%deployed.node_type, edge_name=%edge.edge_name);
+                        continue;
+                    }
+                };
+                if !node_types.contains_key(&edge.target_type_name) {
+                    warn!(message="Skipping deployed edge to an undefined node type", node_type=%deployed.node_type, edge_name=%edge.edge_name, target_type=%edge.target_type_name);
+                    continue;
+                }
+
+                let forward = EdgeSchema {
+                    edge_name: edge.edge_name.clone(),
+                    reverse_edge_name: edge_relationship.reverse_edge_name.clone(),
+                    target_type_name: edge.target_type_name.clone(),
+                    relationship: edge_relationship.relationship,
+                };
+                let reverse = EdgeSchema {
+                    edge_name: edge_relationship.reverse_edge_name.clone(),
+                    reverse_edge_name: edge.edge_name.clone(),
+                    target_type_name: deployed.node_type.clone(),
+                    relationship: edge_relationship.relationship.reverse(),
+                };
+                let directions = [
+                    (deployed.node_type.as_str(), forward),
+                    (edge.target_type_name.as_str(), reverse),
+                ];
+                let conflicts =
+                    directions.iter().any(|(node_type, edge_schema)| {
+                        match node_types[*node_type].edges.get(&edge_schema.edge_name) {
+                            Some(defined) => !defined.same_as(edge_schema),
+                            None => false,
+                        }
+                    });
+                if conflicts {
+                    warn!(message="Skipping deployed edge that conflicts with its definition", node_type=%deployed.node_type, edge_name=%edge.edge_name);
+                    continue;
+                }
+
+                for (node_type, edge_schema) in directions.iter() {
+                    node_types
+                        .get_mut(*node_type)
+                        .expect("node type")
+                        .edges
+                        .entry(edge_schema.edge_name.clone())
+                        .or_insert_with(|| edge_schema.clone());
+                }
+            }
+        }
+
+        Self {
+            node_types: Arc::new(node_types),
+        }
+    }
+
+    pub fn node_type(&self, node_type: &str) -> Option<&NodeSchema> {
+        self.node_types.get(node_type)
+    }
+
+    /// The schemas as a `GraphSchema`, for validating subgraphs without changing them.
+    /// Predicates whose type has no property type can't be given a value, so they're left out,
+    /// as are deployed predicates, which don't have a single property type.
+    pub fn graph_schema(&self) -> GraphSchema {
+        let mut graph_schema = GraphSchema::new();
+        for (node_type, node_schema) in self.node_types.iter() {
+            graph_schema = graph_schema.with_node_type(node_type.as_str());
+            for (predicate_name, predicate_schema) in node_schema.predicates.iter() {
+                if let Some(property_type) = predicate_schema.property_type() {
+                    graph_schema = graph_schema.with_property(
+                        node_type.as_str(),
+                        predicate_name.as_str(),
+                        property_type,
+                    );
+                }
+            }
+            // The reverse of every edge is defined on its target type already
+            for edge_schema in node_schema.edges.values() {
+                graph_schema = graph_schema.with_edge(
+                    node_type.as_str(),
+                    edge_schema.edge_name.as_str(),
+                    edge_schema.target_type_name.as_str(),
+                    edge_schema.reverse_edge_name.as_str(),
+                );
+            }
+        }
+        graph_schema
+    }
+
+    pub fn edge(&self, node_type: &str, edge_name: &str) -> Option<&EdgeSchema> {
+        self.node_type(node_type)?.edges.get(edge_name)
+    }
+
+    /// The schema of an edge in the subgraph, found by the type of the node it's from, or by its
+    /// name alone when that node isn't part of the subgraph
+    pub fn graph_edge(&self, graph: &IdentifiedGraph, edge: &Edge) -> Option<&EdgeSchema> {
+        match graph.nodes.get(&edge.from_node_key) {
+            Some(from_node) => self.edge(&from_node.node_type, &edge.edge_name),
+            None => self.edge_by_name(&edge.edge_name),
+        }
+    }
+
+    /// The edge's schema when its source node type isn't known, as long as every node type that
+    /// defines the edge agrees on it
+    fn edge_by_name(&self, edge_name: &str) -> Option<&EdgeSchema> {
+        let mut edge_schemas = self
+            .node_types
+            .values()
+            .filter_map(|node_schema| node_schema.edges.get(edge_name));
+        let edge_schema = edge_schemas.next()?;
+        if edge_schemas.all(|other| other.same_as(edge_schema)) {
+            Some(edge_schema)
+        } else {
+            None
+        }
+    }
+
+    /// Checks the subgraph against the schemas, and adds the reverse of every edge defined in them.
+    ///
+    /// Properties of the wrong type, edges to the wrong type of node, and edges that would give
+    /// a node more than one target for a to-one edge are removed, keeping the first target.
+    /// Node types, predicates and edges that aren't defined at all are left in place, without
+    /// reverse edges. Every violation is returned.
+    ///
+    /// Edges are checked in order of the node they're from, then in the order they were added,
+    /// so the same subgraph always keeps the same to-one targets.
+    pub fn conform(&self, graph: &mut IdentifiedGraph) -> Vec<SchemaViolation> {
+        let mut violations = vec![];
+        self.conform_nodes(graph, &mut violations);
+
+        let edges = self.conform_edges(graph, &mut violations);
+        graph.edges.clear();
+        for edge in edges {
+            // An edge and the reverse it was given describe the same observation, so their facets
+            // are merged rather than accumulated
+            let edge_list = &mut graph
+                .edges
+                .entry(edge.from_node_key.clone())
+                .or_default()
+                .edges;
+            match edge_list.iter_mut().find(|e| e.is_same_edge(&edge)) {
+                Some(existing) => {
+                    if let Some(facets) = edge.facets {
+                        existing.facets.get_or_insert(facets).merge(&facets);
+                    }
+                }
+                None => edge_list.push(edge),
+            }
+        }
+        violations
+    }
+
+    fn conform_nodes(&self, graph: &mut IdentifiedGraph, violations: &mut Vec<SchemaViolation>) {
+        for node in graph.nodes.values_mut() {
+            let node_schema = match self.node_type(&node.node_type) {
+                Some(node_schema) => node_schema,
+                None => {
+                    violations.push(SchemaViolation::UnknownNodeType {
+                        node_key: node.node_key.clone(),
+                        node_type: node.node_type.clone(),
+                    });
+                    continue;
+                }
+            };
+
+            let (node_key, node_type) = (&node.node_key, &node.node_type);
+            node.properties.retain(|predicate_name, property| {
+                let predicate_schema = match node_schema.predicates.get(predicate_name) {
+                    Some(predicate_schema) => predicate_schema,
+                    None => {
+                        violations.push(SchemaViolation::UnknownPredicate {
+                            node_key: node_key.clone(),
+                            node_type: node_type.clone(),
+                            predicate_name: predicate_name.clone(),
+                        });
+                        return true;
+                    }
+                };
+                match &property.property {
+                    Some(property) if predicate_schema.accepts(property) => true,
+                    _ => {
+                        violations.push(SchemaViolation::PredicateTypeMismatch {
+                            node_key: node_key.clone(),
+                            node_type: node_type.clone(),
+                            predicate_name: predicate_name.clone(),
+                        });
+                        false
+                    }
+                }
+            });
+        }
+    }
+
+    fn conform_edges(
+        &self,
+        graph: &IdentifiedGraph,
+        violations: &mut Vec<SchemaViolation>,
+    ) -> Vec<Edge> {
+        let node_type = |node_key: &str| graph.nodes.get(node_key).map(|n| n.node_type.as_str());
+
+        // Edges are checked against the schema of the node type they're from, or by their name
+        // alone when that node isn't part of the subgraph
+        let mut edges = vec![];
+        let mut edge_lists: Vec<_> = graph.edges.iter().collect();
+        edge_lists.sort_by_key(|(from_node_key, _)| *from_node_key);
+        for edge in edge_lists
+            .into_iter()
+            .flat_map(|(_, edge_list)| edge_list.edges.iter())
+        {
+            let edge_schema = match node_type(&edge.from_node_key) {
+                Some(from_type) => match self.node_type(from_type) {
+                    Some(node_schema) => {
+                        let edge_schema = node_schema.edges.get(&edge.edge_name);
+                        if edge_schema.is_none() {
+                            violations.push(SchemaViolation::UnknownEdge {
+                                from_node_key: edge.from_node_key.clone(),
+                                node_type: from_type.to_owned(),
+                                edge_name: edge.edge_name.clone(),
+                            });
+                        }
+                        edge_schema
+                    }
+                    // The node has already been reported
+                    None => None,
+                },
+                None => self.edge_by_name(&edge.edge_name),
+            };
+
+            if let (Some(edge_schema), Some(to_type)) = (edge_schema, node_type(&edge.to_node_key))
+            {
+                if edge_schema.target_type_name != to_type {
+                    violations.push(SchemaViolation::EdgeTargetMismatch {
+                        from_node_key: edge.from_node_key.clone(),
+                        to_node_key: edge.to_node_key.clone(),
+                        edge_name: edge.edge_name.clone(),
+                        expected_type: edge_schema.target_type_name.clone(),
+                        to_node_type: to_type.to_owned(),
+                    });
+                    continue;
+                }
+            }
+            edges.push((edge, edge_schema));
+        }
+
+        // An edge and its reverse are kept or removed together
+        let mut to_one_targets: HashMap<(&str, &str), &str> = HashMap::new();
+        let mut conformed = Vec::with_capacity(edges.len() * 2);
+        for (edge, edge_schema) in edges {
+            let edge_schema = match edge_schema {
+                Some(edge_schema) => edge_schema,
+                None => {
+                    conformed.push(edge.clone());
+                    continue;
+                }
+            };
+
+            let directions = [
+                (
+                    edge_schema.relationship.to_one(),
+                    edge.from_node_key.as_str(),
+                    edge_schema.edge_name.as_str(),
+                    edge.to_node_key.as_str(),
+                ),
+                (
+                    edge_schema.relationship.reverse().to_one(),
+                    edge.to_node_key.as_str(),
+                    edge_schema.reverse_edge_name.as_str(),
+                    edge.from_node_key.as_str(),
+                ),
+            ];
+            let conflict = directions.iter().filter(|(to_one, ..)| *to_one).find_map(
+                |(_, from, edge_name, to)| {
+                    let target = to_one_targets.get(&(*from, *edge_name))?;
+                    if target == to {
+                        None
+                    } else {
+                        Some(SchemaViolation::ToOneEdgeConflict {
+                            from_node_key: from.to_string(),
+                            edge_name: edge_name.to_string(),
+                            to_node_key: target.to_string(),
+                            conflicting_node_key: to.to_string(),
+                        })
+                    }
+                },
+            );
+            if let Some(conflict) = conflict {
+                violations.push(conflict);
+                continue;
+            }
+
+            for (to_one, from, edge_name, to) in directions.iter() {
+                if *to_one {
+                    to_one_targets.insert((from, edge_name), to);
+                }
+            }
+            conformed.push(edge.clone());
+            conformed.push(Edge {
+                from_node_key: edge.to_node_key.clone(),
+                to_node_key: edge.from_node_key.clone(),
+                edge_name: edge_schema.reverse_edge_name.clone(),
+                facets: edge.facets,
+            });
+        }
+        conformed
+    }
+}
@@ -1,5 +1,8 @@
 use std::{
-    collections::HashMap,
+    collections::{
+        HashMap,
+        HashSet,
+    },
     fmt::Debug,
     io::Stdout,
     sync::{
@@ -23,20 +26,18 @@ use failure::{
     bail,
     Error,
 };
-use grapl_config::{
-    env_helpers::{
-        s3_event_emitters_from_env,
-        FromEnv,
+use grapl_config::env_helpers::FromEnv;
+use grapl_graph_descriptions::{
+    graph_description::{
+        Edge,
+        EdgeList,
+        IdentifiedGraph,
+        IdentifiedNode,
+        MergedGraph,
+        MergedNode,
     },
-    event_caches,
-};
-use grapl_graph_descriptions::graph_description::{
-    Edge,
-    EdgeList,
-    IdentifiedGraph,
-    IdentifiedNode,
-    MergedGraph,
-    MergedNode,
+    schema::GraphSchema,
+    MergeError,
 };
 use grapl_observe::{
     dgraph_reporter::DgraphMetricReporter,
@@ -82,7 +83,6 @@ use sqs_executor::{
         CompletedEvents,
         EventHandler,
     },
-    make_ten,
     s3_event_retriever::S3PayloadRetriever,
 };
 use tracing::{
@@ -92,55 +92,155 @@ use tracing::{
 };
 
 use crate::{
-    reverse_resolver,
-    reverse_resolver::{
-        get_r_edges_from_dynamodb,
-        ReverseEdgeResolver,
+    graph_store::GraphStore,
+    schema_registry::{
+        SchemaRegistry,
+        SchemaViolation,
     },
     upsert_util,
     upserter,
+    upserter::{
+        EdgeConflict,
+        ToOneEdgePolicy,
+    },
 };
 
 #[derive(Clone)]
-pub struct GraphMerger<CacheT: Cache> {
-    mg_client: Arc<DgraphClient>,
-    reverse_edge_resolver: ReverseEdgeResolver,
+pub struct GraphMerger<GraphStoreT: GraphStore, CacheT: Cache> {
+    graph_store: GraphStoreT,
+    schema_registry: SchemaRegistry,
+    to_one_edge_policy: ToOneEdgePolicy,
+    /// Subgraphs that don't conform to this schema are rejected rather than conformed to it
+    enforced_schema: Option<GraphSchema>,
     metric_reporter: MetricReporter<Stdout>,
     cache: CacheT,
 }
 
-impl<CacheT: Cache> GraphMerger<CacheT> {
+impl<GraphStoreT: GraphStore, CacheT: Cache> GraphMerger<GraphStoreT, CacheT> {
     pub fn new(
-        mg_alphas: Vec<String>,
-        reverse_edge_resolver: ReverseEdgeResolver,
+        graph_store: GraphStoreT,
+        schema_registry: SchemaRegistry,
+        to_one_edge_policy: ToOneEdgePolicy,
+        enforce_schema: bool,
         metric_reporter: MetricReporter<Stdout>,
         cache: CacheT,
     ) -> Self {
-        let mg_client = DgraphClient::new(mg_alphas).expect("Failed to create dgraph client.");
-
+        let enforced_schema = if enforce_schema {
+            Some(schema_registry.graph_schema())
+        } else {
+            None
+        };
         Self {
-            mg_client: Arc::new(mg_client),
-            reverse_edge_resolver,
+            graph_store,
+            schema_registry,
+            to_one_edge_policy,
+            enforced_schema,
             metric_reporter,
             cache,
         }
     }
+
+    fn report_predicate_cache_hits(&mut self, cache_hit: usize, cache_miss: usize) {
+        self.metric_reporter
+            .counter(
+                "graph_merger.predicate_cache.hit.count",
+                cache_hit as f64,
+                None,
+                &[],
+            )
+            .unwrap_or_else(|e| error!("failed to report predicate_cache.hit.count: {:?}", e));
+        self.metric_reporter
+            .counter(
+                "graph_merger.predicate_cache.miss.count",
+                cache_miss as f64,
+                None,
+                &[],
+            )
+            .unwrap_or_else(|e| error!("failed to report predicate_cache.miss.count: {:?}", e));
+    }
+
+    fn report_schema_violations(&mut self, violations: &[SchemaViolation]) {
+        if violations.is_empty() {
+            return;
+        }
+        warn!(
+            message = "Subgraph does not conform to the schema",
+            violations = violations.len(),
+        );
+
+        let mut violation_counts: HashMap<&'static str, usize> = HashMap::new();
+        for violation in violations {
+            tracing::debug!(message="Schema violation", violation=%violation);
+            *violation_counts.entry(violation.kind()).or_default() += 1;
+        }
+        for (kind, count) in violation_counts {
+            self.metric_reporter
+                .counter(
+                    "graph_merger.schema_violation.count",
+                    count as f64,
+                    None,
+                    &[tag("violation", kind)],
+                )
+                .unwrap_or_else(|e| error!("failed to report schema_violation.count: {:?}", e));
+        }
+    }
+
+    fn report_rejected_subgraph(&mut self) {
+        self.metric_reporter
+            .counter("graph_merger.rejected_subgraph.count", 1.0, None, &[])
+            .unwrap_or_else(|e| error!("failed to report rejected_subgraph.count: {:?}", e));
+    }
+
+    fn report_edge_conflicts(&mut self, edge_conflicts: &[EdgeConflict]) {
+        let mut conflict_counts: HashMap<&str, usize> = HashMap::new();
+        for edge_conflict in edge_conflicts {
+            warn!(
+                message="Incoming to-one edge conflicts with the stored edge",
+                policy=?self.to_one_edge_policy,
+                from_node_key=%edge_conflict.from_node_key,
+                edge_name=%edge_conflict.edge_name,
+                to_node_key=%edge_conflict.to_node_key,
+                stored_to_uids=?edge_conflict.stored_to_uids,
+            );
+            *conflict_counts
+                .entry(edge_conflict.edge_name.as_str())
+                .or_default() += 1;
+        }
+        for (edge_name, count) in conflict_counts {
+            self.metric_reporter
+                .counter(
+                    "graph_merger.to_one_edge_conflict.count",
+                    count as f64,
+                    None,
+                    &[tag("edge_name", edge_name)],
+                )
+                .unwrap_or_else(|e| error!("failed to report to_one_edge_conflict.count: {:?}", e));
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum GraphMergerError {
     #[error("UnexpectedError")]
     Unexpected(String),
+    #[error("MergeError")]
+    MergeError(#[from] MergeError),
+    #[error("Subgraph violates the schema in {0} ways")]
+    SchemaViolations(usize),
 }
 
 impl CheckedError for GraphMergerError {
     fn error_type(&self) -> Recoverable {
-        Recoverable::Transient
+        match self {
+            Self::Unexpected(_) => Recoverable::Transient,
+            Self::MergeError(_) => Recoverable::Persistent,
+            Self::SchemaViolations(_) => Recoverable::Persistent,
+        }
     }
 }
 
 #[async_trait]
-impl<CacheT: Cache> EventHandler for GraphMerger<CacheT> {
+impl<GraphStoreT: GraphStore, CacheT: Cache> EventHandler for GraphMerger<GraphStoreT, CacheT> {
     type InputEvent = IdentifiedGraph;
     type OutputEvent = MergedGraph;
     type Error = GraphMergerError;
@@ -162,38 +262,64 @@ impl<CacheT: Cache> EventHandler for GraphMerger<CacheT> {
             edges=?subgraph.edges.len(),
         );
 
-        let uncached_nodes = subgraph.nodes.into_iter().map(|(_, n)| n);
-        let mut uncached_edges: Vec<_> = subgraph
-            .edges
-            .into_iter()
-            .flat_map(|e| e.1.into_vec())
-            .collect();
-        let reverse = self
-            .reverse_edge_resolver
-            .resolve_reverse_edges(uncached_edges.clone())
-            .await
-            .map_err(Err)?;
+        if let Err(e) = subgraph.check_properties() {
+            error!(message="Subgraph has a predicate that can't be merged", error=%e);
+            return Err(Err(e.into()));
+        }
 
-        uncached_edges.extend_from_slice(&reverse[..]);
+        if let Some(enforced_schema) = &self.enforced_schema {
+            let violations = enforced_schema.validate_identified(&subgraph);
+            if !violations.is_empty() {
+                for violation in violations.iter() {
+                    warn!(message="Rejecting subgraph that violates the schema", violation=%violation);
+                }
+                self.report_rejected_subgraph();
+                return Err(Err(GraphMergerError::SchemaViolations(violations.len())));
+            }
+        }
 
-        let mut merged_graph = MergedGraph::new();
-        let mut uncached_subgraph = IdentifiedGraph::new();
+        let mut uncached_subgraph = subgraph;
+        let violations = self.schema_registry.conform(&mut uncached_subgraph);
+        self.report_schema_violations(&violations);
 
-        for node in uncached_nodes {
-            uncached_subgraph.add_node(node);
-        }
+        let mut merged_graph = MergedGraph::new();
 
-        for edge in uncached_edges {
-            uncached_subgraph.add_edge(edge.edge_name, edge.from_node_key, edge.to_node_key);
-        }
+        // Immutable predicates that were already written can't change, so they aren't written again
+        let predicate_identities: Vec<Vec<u8>> = uncached_subgraph
+            .nodes
+            .values()
+            .flat_map(IdentifiedNode::get_cache_identities_for_predicates)
+            .collect();
+        let uncached_predicates: HashSet<Vec<u8>> = self
+            .cache
+            .filter_cached(&predicate_identities)
+            .await
+            .into_iter()
+            .collect();
+        let cached_predicates: HashSet<Vec<u8>> = predicate_identities
+            .into_iter()
+            .filter(|identity| !uncached_predicates.contains(identity))
+            .collect();
+        self.report_predicate_cache_hits(cached_predicates.len(), uncached_predicates.len());
 
-        upserter::GraphMergeHelper {}
-            .upsert_into(
-                self.mg_client.clone(),
-                &uncached_subgraph,
-                &mut merged_graph,
-            )
+        let edge_conflicts = upserter::GraphMergeHelper::with_cached_predicates(cached_predicates)
+            .with_to_one_edges(self.schema_registry.clone(), self.to_one_edge_policy)
+            .upsert_into(&self.graph_store, &uncached_subgraph, &mut merged_graph)
             .await;
+        self.report_edge_conflicts(&edge_conflicts);
+
+        // Only predicates of nodes that were merged have been committed
+        let committed_predicates: Vec<Vec<u8>> = uncached_subgraph
+            .nodes
+            .values()
+            .filter(|node| merged_graph.nodes.contains_key(&node.node_key))
+            .flat_map(IdentifiedNode::get_cache_identities_for_predicates)
+            .filter(|identity| uncached_predicates.contains(identity))
+            .collect();
+        self.cache
+            .store_all(&committed_predicates)
+            .await
+            .unwrap_or_else(|e| error!(message="Failed to cache merged predicates", error=?e));
 
         Ok(merged_graph)
     }
@@ -5,13 +5,21 @@ use grapl_graph_descriptions::{
     node_property::Property,
 };
 use node_property::Property::{
+    DecrementOnlyFloat as ProtoDecrementOnlyFloatProp,
     DecrementOnlyInt as ProtoDecrementOnlyIntProp,
+    DecrementOnlyTimestamp as ProtoDecrementOnlyTimestampProp,
     DecrementOnlyUint as ProtoDecrementOnlyUintProp,
+    ImmutableBool as ProtoImmutableBoolProp,
+    ImmutableBytes as ProtoImmutableBytesProp,
     ImmutableInt as ProtoImmutableIntProp,
     ImmutableStr as ProtoImmutableStrProp,
+    ImmutableTimestamp as ProtoImmutableTimestampProp,
     ImmutableUint as ProtoImmutableUintProp,
+    IncrementOnlyFloat as ProtoIncrementOnlyFloatProp,
     IncrementOnlyInt as ProtoIncrementOnlyIntProp,
+    IncrementOnlyTimestamp as ProtoIncrementOnlyTimestampProp,
     IncrementOnlyUint as ProtoIncrementOnlyUintProp,
+    StrSet as ProtoStrSetProp,
 };
 
 pub struct Escaped(String);
@@ -50,8 +58,10 @@ fn escape_quote(s: &str) -> Escaped {
     Escaped(output)
 }
 
-fn escape_prop(node_property: &NodeProperty) -> Escaped {
-    match &node_property.property {
+/// The values to write for a property. Sets have a value per string, a property without a value
+/// has none, and every other property has exactly one.
+fn escape_prop(node_property: &NodeProperty) -> Vec<Escaped> {
+    let value = match &node_property.property {
         Some(ProtoIncrementOnlyIntProp(i)) => escape_quote(&i.to_string()),
         Some(ProtoDecrementOnlyIntProp(i)) => escape_quote(&i.to_string()),
         Some(ProtoImmutableIntProp(i)) => escape_quote(&i.to_string()),
@@ -59,7 +69,55 @@ fn escape_prop(node_property: &NodeProperty) -> Escaped {
         Some(ProtoDecrementOnlyUintProp(i)) => escape_quote(&i.to_string()),
         Some(ProtoImmutableUintProp(i)) => escape_quote(&i.to_string()),
         Some(ProtoImmutableStrProp(s)) => escape_quote(s.as_inner()),
-        None => panic!("todo"),
+        Some(ProtoImmutableBoolProp(b)) => escape_quote(&b.to_string()),
+        Some(ProtoIncrementOnlyFloatProp(f)) => escape_quote(&f.to_string()),
+        Some(ProtoDecrementOnlyFloatProp(f)) => escape_quote(&f.to_string()),
+        // Dgraph has no bytes type, so they're stored as a hex string
+        Some(ProtoImmutableBytesProp(b)) => escape_quote(&b.to_string()),
+        // Timestamps are stored as ints, like the timestamps written as uints
+        Some(ProtoImmutableTimestampProp(t)) => escape_quote(&t.to_string()),
+        Some(ProtoIncrementOnlyTimestampProp(t)) => escape_quote(&t.to_string()),
+        Some(ProtoDecrementOnlyTimestampProp(t)) => escape_quote(&t.to_string()),
+        Some(ProtoStrSetProp(s)) => return s.as_inner().iter().map(|s| escape_quote(s)).collect(),
+        // Graphs are checked for properties without values before they're merged
+        None => return vec![],
+    };
+    vec![value]
+}
+
+/// Returns a Dgraph filter that matches a node whose currently stored value for `prop_name`
+/// should be kept rather than overwritten by `prop_value`.
+///
+/// This mirrors `Property::merge_property` - increment-only properties keep the larger value,
+/// decrement-only properties keep the smaller value, and immutable properties keep whichever
+/// value was written first. Sets are unioned, so there's no filter: the stored strings are kept
+/// and the new ones are added alongside them.
+pub(crate) fn existing_value_wins_filter(
+    prop_name: &str,
+    node_property: &NodeProperty,
+    prop_value: &Escaped,
+) -> Option<String> {
+    match &node_property.property {
+        Some(ProtoIncrementOnlyIntProp(_))
+        | Some(ProtoIncrementOnlyUintProp(_))
+        | Some(ProtoIncrementOnlyFloatProp(_))
+        | Some(ProtoIncrementOnlyTimestampProp(_)) => {
+            Some(format!("ge({}, {})", prop_name, prop_value))
+        }
+        Some(ProtoDecrementOnlyIntProp(_))
+        | Some(ProtoDecrementOnlyUintProp(_))
+        | Some(ProtoDecrementOnlyFloatProp(_))
+        | Some(ProtoDecrementOnlyTimestampProp(_)) => {
+            Some(format!("le({}, {})", prop_name, prop_value))
+        }
+        Some(ProtoStrSetProp(_)) => None,
+        Some(ProtoImmutableIntProp(_))
+        | Some(ProtoImmutableUintProp(_))
+        | Some(ProtoImmutableStrProp(_))
+        | Some(ProtoImmutableBoolProp(_))
+        | Some(ProtoImmutableBytesProp(_))
+        | Some(ProtoImmutableTimestampProp(_))
+        | None => Some(format!("has({})", prop_name)),
     }
 }
 
@@ -93,7 +151,13 @@ pub(crate) fn build_upserts(
             message="generating upsert quads for predicate",
             predicate_name=?prop_name,
         );
-        let prop_value = escape_prop(prop);
+        let prop_values = escape_prop(prop);
+        // An empty set has nothing to add
+        let prop_value = match prop_values.first() {
+            Some(prop_value) => prop_value,
+            None => continue,
+        };
+        let merge_filter = existing_value_wins_filter(&prop_name, prop, prop_value);
         let (next_query, muts) = gen_node_property_upsert_quads(
             query_param,
             predicate_param as u128,
@@ -101,7 +165,8 @@ pub(crate) fn build_upserts(
             &node_key,
             &node_type,
             &prop_name,
-            &prop_value,
+            &prop_values,
+            merge_filter.as_deref(),
         );
         inner_queries.push_str(&next_query);
         inner_queries.push('\n');
@@ -161,41 +226,65 @@ pub(crate) fn gen_node_property_upsert_quads(
     node_key: &Escaped,
     node_type: &str,
     prop_name: &str,
-    prop_value: &Escaped,
+    prop_values: &[Escaped],
+    merge_filter: Option<&str>,
 ) -> (String, [dgraph_tonic::Mutation; 2]) {
-    // let mut node_query_name = format!("pred_query_{}_{}_{}", prop_name, query_param, predicate_param);
     let mut mu_0 = dgraph_tonic::Mutation::new();
 
-    let mut inner_query = format!(
-        r#"
-            var(func: uid({creation_var_name}), first: 1)
-    "#,
-        creation_var_name = creation_var_name,
-    );
-
-    // If the node exists, set the predicate. Currently 'last write wins'.
-    let mut mu_0_n_quads = format!(
-        r#"uid({creation_var_name}) <{prop_name}> {prop_value} ."#,
-        creation_var_name = creation_var_name,
-        prop_name = prop_name,
-        prop_value = prop_value,
-    );
+    // If the node exists, set the predicate unless the existing value wins the merge
+    let mu_0_n_quads: String = prop_values
+        .iter()
+        .map(|prop_value| {
+            format!(
+                "uid({creation_var_name}) <{prop_name}> {prop_value} .\n",
+                creation_var_name = creation_var_name,
+                prop_name = prop_name,
+                prop_value = prop_value,
+            )
+        })
+        .collect();
 
     mu_0.set_set_nquads(mu_0_n_quads);
-    mu_0.set_cond(format!(
-        "@if(eq(len({creation_var_name}), 1))",
-        creation_var_name = creation_var_name
-    ));
+
+    // Binds the node only if its stored value should win the merge
+    let mut inner_query = String::new();
+    match merge_filter {
+        Some(merge_filter) => {
+            let predicate_var_name = format!("pred_{}_{}", query_param, predicate_param);
+            inner_query = format!(
+                r#"
+            {predicate_var_name} as var(func: uid({creation_var_name}), first: 1) @filter({merge_filter})
+    "#,
+                predicate_var_name = predicate_var_name,
+                creation_var_name = creation_var_name,
+                merge_filter = merge_filter,
+            );
+            mu_0.set_cond(format!(
+                "@if(eq(len({creation_var_name}), 1) AND eq(len({predicate_var_name}), 0))",
+                creation_var_name = creation_var_name,
+                predicate_var_name = predicate_var_name,
+            ));
+        }
+        None => mu_0.set_cond(format!(
+            "@if(eq(len({creation_var_name}), 1))",
+            creation_var_name = creation_var_name,
+        )),
+    }
 
     let mut mu_1 = dgraph_tonic::Mutation::new();
 
     // condition if the node does not exist
-    let mut mu_1_n_quads = format!(
-        concat!(r#"_:{creation_var_name} <{prop_name}> {prop_value} ."#,),
-        creation_var_name = creation_var_name,
-        prop_name = prop_name,
-        prop_value = prop_value,
-    );
+    let mu_1_n_quads: String = prop_values
+        .iter()
+        .map(|prop_value| {
+            format!(
+                "_:{creation_var_name} <{prop_name}> {prop_value} .\n",
+                creation_var_name = creation_var_name,
+                prop_name = prop_name,
+                prop_value = prop_value,
+            )
+        })
+        .collect();
 
     mu_1.set_set_nquads(mu_1_n_quads);
     mu_1.set_cond(format!(
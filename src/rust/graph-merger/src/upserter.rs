@@ -1,162 +1,163 @@
 use std::{
-    collections::HashMap,
-    sync::Arc,
-};
-
-use dgraph_query_lib::{
-    condition::{
-        Condition,
-        ConditionValue,
-    },
-    mutation::{
-        Mutation,
-        MutationBuilder,
-        MutationPredicateValue,
-        MutationUID,
-        MutationUnit,
-    },
-    predicate::{
-        Field,
-        Predicate,
+    collections::{
+        HashMap,
+        HashSet,
     },
-    query::QueryBuilder,
-    queryblock::{
-        QueryBlock,
-        QueryBlockBuilder,
-        QueryBlockType,
-    },
-    upsert::{
-        Upsert,
-        UpsertBlock,
-    },
-    ToQueryString,
-};
-use dgraph_tonic::{
-    Client as DgraphClient,
-    Mutate,
-    Mutation as DgraphMutation,
-    MutationResponse,
-    Query,
-};
-use futures::StreamExt;
-use futures_retry::{
-    FutureRetry,
-    RetryPolicy,
+    str::FromStr,
 };
+
 use grapl_graph_descriptions::{
     graph_description::*,
-    node_property::Property,
     MergedGraph,
 };
-use grapl_utils::iter_ext::GraplIterExt;
 // use grapl_graph_descriptions::Edge;
 // use grapl_graph_descriptions::EdgeList;
 // use grapl_graph_descriptions::MergedNode;
 // use grapl_graph_descriptions::IdentifiedNode;
 pub use node_property::Property::{
+    DecrementOnlyFloat as ProtoDecrementOnlyFloatProp,
     DecrementOnlyInt as ProtoDecrementOnlyIntProp,
+    DecrementOnlyTimestamp as ProtoDecrementOnlyTimestampProp,
     DecrementOnlyUint as ProtoDecrementOnlyUintProp,
+    ImmutableBool as ProtoImmutableBoolProp,
+    ImmutableBytes as ProtoImmutableBytesProp,
     ImmutableInt as ProtoImmutableIntProp,
     ImmutableStr as ProtoImmutableStrProp,
+    ImmutableTimestamp as ProtoImmutableTimestampProp,
     ImmutableUint as ProtoImmutableUintProp,
+    IncrementOnlyFloat as ProtoIncrementOnlyFloatProp,
     IncrementOnlyInt as ProtoIncrementOnlyIntProp,
+    IncrementOnlyTimestamp as ProtoIncrementOnlyTimestampProp,
     IncrementOnlyUint as ProtoIncrementOnlyUintProp,
+    StrSet as ProtoStrSetProp,
+};
+
+use crate::{
+    graph_store::{
+        GraphStore,
+        UidEdge,
+    },
+    schema_registry::SchemaRegistry,
 };
 
-use crate::upsert_util;
+/// How an incoming to-one edge is merged when the node already has a different target stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToOneEdgePolicy {
+    /// The stored edge is kept, and the incoming edge and its reverse are dropped
+    Reject,
+    /// The stored edge and its reverse are removed, and the incoming edge is written
+    Replace,
+}
+
+impl Default for ToOneEdgePolicy {
+    fn default() -> Self {
+        Self::Reject
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid to-one edge policy {0:?}, expected \"reject\" or \"replace\"")]
+pub struct InvalidToOneEdgePolicy(pub String);
 
-const DGRAPH_CONCURRENCY_UPSERTS: usize = 8;
-// DGraph Live Loader uses a size of 1,000 elements and they claim this has relatively good performance
-const DGRAPH_UPSERT_CHUNK_SIZE: usize = 1024;
+impl FromStr for ToOneEdgePolicy {
+    type Err = InvalidToOneEdgePolicy;
 
-pub struct GraphMergeHelper {}
+    fn from_str(input: &str) -> Result<ToOneEdgePolicy, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "reject" => Ok(ToOneEdgePolicy::Reject),
+            "replace" => Ok(ToOneEdgePolicy::Replace),
+            _ => Err(InvalidToOneEdgePolicy(input.to_owned())),
+        }
+    }
+}
+
+/// An incoming to-one edge that points to a different node than the one already stored
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeConflict {
+    pub from_node_key: String,
+    pub edge_name: String,
+    pub to_node_key: String,
+    pub stored_to_uids: Vec<u64>,
+}
+
+#[derive(Default)]
+pub struct GraphMergeHelper {
+    /// Cache identities of immutable predicates that are already stored, which aren't written
+    /// again. They're still included in the merged graph.
+    cached_predicates: HashSet<Vec<u8>>,
+    /// Used to tell which edges are to-one. Without it, every edge is added to those stored.
+    schema_registry: Option<SchemaRegistry>,
+    to_one_edge_policy: ToOneEdgePolicy,
+}
 
 impl GraphMergeHelper {
+    pub fn with_cached_predicates(cached_predicates: HashSet<Vec<u8>>) -> Self {
+        Self {
+            cached_predicates,
+            ..Self::default()
+        }
+    }
+
+    /// Merges the edges the schemas define as to-one according to `to_one_edge_policy`
+    pub fn with_to_one_edges(
+        mut self,
+        schema_registry: SchemaRegistry,
+        to_one_edge_policy: ToOneEdgePolicy,
+    ) -> Self {
+        self.schema_registry = Some(schema_registry);
+        self.to_one_edge_policy = to_one_edge_policy;
+        self
+    }
+
+    /// The node's properties, other than those that are already stored
+    fn uncached_properties(&self, node: &IdentifiedNode) -> HashMap<String, NodeProperty> {
+        node.properties
+            .iter()
+            .filter(|(predicate_name, _)| {
+                match node.get_cache_identity_for_predicate(predicate_name) {
+                    Some(identity) => !self.cached_predicates.contains(&identity),
+                    None => true,
+                }
+            })
+            .map(|(predicate_name, property)| (predicate_name.clone(), property.clone()))
+            .collect()
+    }
+
+    /// Returns the incoming to-one edges that conflicted with those already stored
     pub async fn upsert_into(
         &self,
-        dgraph_client: Arc<DgraphClient>,
+        graph_store: &impl GraphStore,
         identified_graph: &IdentifiedGraph,
         merged_graph: &mut MergedGraph,
-    ) {
+    ) -> Vec<EdgeConflict> {
         let node_key_map_to_uid = self
-            .upsert_nodes(dgraph_client.clone(), identified_graph, merged_graph)
-            .await;
-        self.upsert_edges(dgraph_client, &identified_graph, node_key_map_to_uid)
+            .upsert_nodes(graph_store, identified_graph, merged_graph)
             .await;
+        self.upsert_edges(
+            graph_store,
+            identified_graph,
+            node_key_map_to_uid,
+            merged_graph,
+        )
+        .await
     }
 
     async fn upsert_nodes(
         &self,
-        dgraph_client: Arc<DgraphClient>,
+        graph_store: &impl GraphStore,
         identified_graph: &IdentifiedGraph,
         merged_graph: &mut MergedGraph,
     ) -> HashMap<String, u64> {
-        let mut key_to_query_name = HashMap::new();
-        let mut node_upserts = Vec::with_capacity(identified_graph.nodes.len());
-        for (unique_id, node) in identified_graph.nodes.values().enumerate() {
-            let (query, upserts) = upsert_util::build_upserts(
-                unique_id as u128,
-                &node.node_key,
-                &node.node_type,
-                &node.properties,
-                &mut key_to_query_name,
-            );
-            node_upserts.push((query, upserts));
-        }
-
-        tracing::info!(message = "Upserting nodes", count = node_upserts.len());
-        let responses: Vec<dgraph_tonic::Response> = futures::stream::iter(
-            node_upserts
-                .into_iter()
-                .chunks_owned(DGRAPH_UPSERT_CHUNK_SIZE),
-        )
-        .map(move |upsert_chunk| {
-            let mut combined_query = String::new();
-            let mut all_mutations = Vec::new();
-            for (query_block, mutations) in upsert_chunk.iter() {
-                combined_query.push_str(&query_block);
-                all_mutations.extend_from_slice(mutations);
-            }
-
-            let combined_query = format!(
-                r"
-            {{
-                    {}
-            }}
-            ",
-                combined_query
-            );
-
-            tracing::debug!(message="Generated query for upsert", combined_query=?combined_query);
-
-            let dgraph_client = dgraph_client.clone();
-            Self::enforce_transaction(move || {
-                let mut txn = dgraph_client.new_mutated_txn();
-                txn.upsert_and_commit_now(combined_query.clone(), all_mutations.clone())
+        let nodes = identified_graph
+            .nodes
+            .values()
+            .map(|node| IdentifiedNode {
+                node_key: node.node_key.clone(),
+                node_type: node.node_type.clone(),
+                properties: self.uncached_properties(node),
             })
-        })
-        .buffer_unordered(DGRAPH_CONCURRENCY_UPSERTS)
-        .collect::<Vec<_>>()
-        .await;
-
-        let mut node_key_map_to_uid = HashMap::new();
-        let mut uids = HashMap::new();
-        for response in responses.iter() {
-            let query_responses: serde_json::Value = match serde_json::from_slice(&response.json) {
-                Ok(response) => response,
-                Err(e) => {
-                    tracing::error!(message="Failed to parse JSON response for upsert", error=?e);
-                    continue;
-                }
-            };
-            tracing::debug!(
-                message="Received upsert response",
-                query_response=?query_responses,
-                uids=?response.uids,
-            );
-            uids.extend(response.uids.clone());
-            extract_node_key_map_uid(&query_responses, &mut node_key_map_to_uid);
-        }
+            .collect();
+        let node_key_map_to_uid = graph_store.upsert_nodes(nodes).await;
 
         for node in identified_graph.nodes.values() {
             let IdentifiedNode {
@@ -165,12 +166,8 @@ impl GraphMergeHelper {
                 properties,
             } = node.to_owned();
 
-            let uid = node_key_map_to_uid
-                .get(&node_key)
-                .copied()
-                .or_else(|| uid_from_uids(&node_key, &key_to_query_name, &uids));
-            let uid = match uid {
-                Some(uid) => uid,
+            let uid = match node_key_map_to_uid.get(&node_key) {
+                Some(uid) => *uid,
                 None => {
                     tracing::error!(
                         message="Failed to retrieve uid associated with node_key",
@@ -192,31 +189,24 @@ impl GraphMergeHelper {
 
     async fn upsert_edges(
         &self,
-        dgraph_client: Arc<DgraphClient>,
+        graph_store: &impl GraphStore,
         identified_graph: &IdentifiedGraph,
         mut node_key_to_uid: HashMap<String, u64>,
-    ) {
+        merged_graph: &mut MergedGraph,
+    ) -> Vec<EdgeConflict> {
         let all_edges: Vec<_> = identified_graph
             .edges
             .iter()
             .flat_map(|(_, EdgeList { edges })| edges)
-            .map(
-                |Edge {
-                     from_node_key,
-                     to_node_key,
-                     edge_name,
-                 }| {
-                    (
-                        from_node_key.clone(),
-                        to_node_key.clone(),
-                        edge_name.clone(),
-                    )
-                },
-            )
             .collect();
 
         let mut unresolved = vec![];
-        for (from_node_key, to_node_key, edge_name) in all_edges.iter() {
+        for Edge {
+            from_node_key,
+            to_node_key,
+            ..
+        } in all_edges.iter()
+        {
             if !node_key_to_uid.contains_key(from_node_key) {
                 unresolved.push(from_node_key);
             }
@@ -227,204 +217,157 @@ impl GraphMergeHelper {
         unresolved.sort_unstable();
         unresolved.dedup();
         if !unresolved.is_empty() {
-            let m = query_for_node_keys(dgraph_client.clone(), &unresolved[..]).await;
+            let m = graph_store.lookup_node_keys(&unresolved[..]).await;
             for (node_key, uid) in m.into_iter() {
                 node_key_to_uid.insert(node_key, uid);
             }
         }
 
-        let mut mutations = Vec::with_capacity(all_edges.len());
-        for items in all_edges.into_iter().chunks_owned(DGRAPH_UPSERT_CHUNK_SIZE) {
-            let mut mutation_units = vec![];
-
-            for (from_node_key, to_node_key, edge_name) in items.iter() {
-                let from_uid = node_key_to_uid.get(from_node_key);
-                let to_uid = node_key_to_uid.get(to_node_key);
-                let (from_uid, to_uid) = match (from_uid, to_uid) {
-                    (Some(from_uid), Some(to_uid)) => (*from_uid, *to_uid),
-                    (from_uid, to_uid) => {
-                        tracing::error!(
-                            message="Could not retrieve uids",
-                            from_uid=?from_uid,
-                            to_uid=?to_uid,
-                            from_node_key=?from_node_key,
-                            to_node_key=?to_node_key,
-                        );
-                        continue;
-                    }
-                };
+        let mut uid_edges = Vec::with_capacity(all_edges.len());
+        let mut to_one_edges = vec![];
+        for edge in all_edges.into_iter() {
+            let Edge {
+                from_node_key,
+                to_node_key,
+                edge_name,
+                facets,
+            } = edge;
+            let from_uid = node_key_to_uid.get(from_node_key);
+            let to_uid = node_key_to_uid.get(to_node_key);
+            let (from_uid, to_uid) = match (from_uid, to_uid) {
+                (Some(from_uid), Some(to_uid)) => (*from_uid, *to_uid),
+                (from_uid, to_uid) => {
+                    tracing::error!(
+                        message="Could not retrieve uids",
+                        from_uid=?from_uid,
+                        to_uid=?to_uid,
+                        from_node_key=?from_node_key,
+                        to_node_key=?to_node_key,
+                    );
+                    continue;
+                }
+            };
+            let uid_edge = UidEdge {
+                from_uid,
+                to_uid,
+                edge_name: edge_name.clone(),
+                facets: *facets,
+            };
 
-                let (from_uid, to_uid) = (from_uid.to_string(), to_uid.to_string());
-                let mutation_unit = MutationUnit::new(MutationUID::uid(&from_uid)).predicate(
-                    &edge_name,
-                    MutationPredicateValue::Edges(vec![MutationUID::uid(&to_uid)]),
-                );
-                mutation_units.push(mutation_unit);
+            let edge_schema = self
+                .schema_registry
+                .as_ref()
+                .and_then(|schema_registry| schema_registry.graph_edge(identified_graph, edge));
+            if let Some(edge_schema) = edge_schema {
+                if edge_schema.relationship.to_one() {
+                    to_one_edges.push((edge, uid_edge.clone(), &edge_schema.reverse_edge_name));
+                }
             }
-            let mutation = MutationBuilder::default()
-                .set(mutation_units)
-                .build()
-                .unwrap();
-            mutations.push(mutation);
+            uid_edges.push(uid_edge);
         }
 
-        futures::stream::iter(mutations.into_iter())
-            .map(|mutation| {
-                let dgraph_client = dgraph_client.clone();
-                Self::enforce_transaction(move || {
-                    let mut dgraph_mutation = dgraph_tonic::Mutation::new();
-                    dgraph_mutation.set_set_json(&mutation.set).unwrap_or_else(
-                        |e| tracing::error!(message="Failed to set json for mutation", error=?e),
-                    );
-
-                    let mut txn = dgraph_client.new_mutated_txn();
-                    txn.mutate_and_commit_now(dgraph_mutation.clone())
-                })
-            })
-            .buffer_unordered(DGRAPH_CONCURRENCY_UPSERTS)
-            .collect::<Vec<_>>()
+        let conflicts = self
+            .merge_to_one_edges(graph_store, to_one_edges, &mut uid_edges)
             .await;
-    }
 
-    async fn enforce_transaction<Factory, Txn>(f: Factory) -> dgraph_tonic::Response
-    where
-        Factory: FnMut() -> Txn + 'static + Unpin,
-        Txn: std::future::Future<Output = Result<dgraph_tonic::Response, anyhow::Error>>,
-    {
-        let handle_upsert_err = UpsertErrorHandler {};
-        let (response, attempts) = FutureRetry::new(f, handle_upsert_err)
-            .await
-            .expect("Surfaced an error despite retry strategy while performing an upsert.");
-
-        tracing::info!(message = "Performed upsert", attempts = attempts);
-
-        response
-    }
-}
-
-pub struct UpsertErrorHandler {}
-
-impl futures_retry::ErrorHandler<anyhow::Error> for UpsertErrorHandler {
-    type OutError = anyhow::Error;
-
-    fn handle(&mut self, attempt: usize, e: anyhow::Error) -> RetryPolicy<Self::OutError> {
-        let attempt = attempt as u64;
-        tracing::warn!(
-            message="Failed to enforce transaction",
-            error=?e,
-            attempt=?attempt,
-        );
-        match attempt {
-            0..=5 => RetryPolicy::Repeat,
-            t @ 6..=20 => RetryPolicy::WaitRetry(std::time::Duration::from_millis(10 * t as u64)),
-            21..=u64::MAX => RetryPolicy::ForwardError(e),
+        // Only the edges that are written are part of the merged graph
+        let uid_to_node_key: HashMap<u64, &String> = node_key_to_uid
+            .iter()
+            .map(|(node_key, uid)| (*uid, node_key))
+            .collect();
+        for uid_edge in uid_edges.iter() {
+            if let (Some(from_node_key), Some(to_node_key)) = (
+                uid_to_node_key.get(&uid_edge.from_uid),
+                uid_to_node_key.get(&uid_edge.to_uid),
+            ) {
+                merged_graph.add_merged_edge(MergedEdge {
+                    from_uid: uid_edge.from_uid.to_string(),
+                    from_node_key: from_node_key.to_string(),
+                    to_uid: uid_edge.to_uid.to_string(),
+                    to_node_key: to_node_key.to_string(),
+                    edge_name: uid_edge.edge_name.clone(),
+                    facets: uid_edge.facets,
+                });
+            }
         }
-    }
-}
-
-async fn query_for_node_keys(
-    dgraph_client: Arc<DgraphClient>,
-    node_keys: &[&String],
-) -> HashMap<String, u64> {
-    let mut resolved_nodes = HashMap::new();
 
-    let mut query_blocks = Vec::with_capacity(node_keys.len());
-    for node_key in node_keys.iter() {
-        let query_block = gen_node_key_query(node_key);
-        query_blocks.push(query_block);
+        graph_store.upsert_edges(uid_edges).await;
+        conflicts
     }
 
-    let query = QueryBuilder::default()
-        .query_blocks(query_blocks)
-        .build()
-        .unwrap();
-
-    let mut txn = dgraph_client.new_read_only_txn();
-    let query_responses = txn
-        .query(query.to_query_string())
-        .await
-        .expect("query failed");
-
-    let query_responses: HashMap<String, Vec<HashMap<String, String>>> =
-        serde_json::from_slice(&query_responses.json).expect("response failed to parse");
+    /// Finds the to-one edges that already point to other nodes, then either drops them from
+    /// `uid_edges` or removes the stored ones, along with their reverse edges
+    async fn merge_to_one_edges(
+        &self,
+        graph_store: &impl GraphStore,
+        to_one_edges: Vec<(&Edge, UidEdge, &String)>,
+        uid_edges: &mut Vec<UidEdge>,
+    ) -> Vec<EdgeConflict> {
+        if to_one_edges.is_empty() {
+            return vec![];
+        }
+        let edge_keys: Vec<(u64, String)> = to_one_edges
+            .iter()
+            .map(|(_, uid_edge, _)| (uid_edge.from_uid, uid_edge.edge_name.clone()))
+            .collect();
+        let stored_edges = graph_store.lookup_edges(&edge_keys).await;
+
+        let mut conflicts = vec![];
+        let mut rejected_edges = HashSet::new();
+        let mut replaced_edges = vec![];
+        for (edge, uid_edge, reverse_edge_name) in to_one_edges {
+            let stored_to_uids =
+                match stored_edges.get(&(uid_edge.from_uid, uid_edge.edge_name.clone())) {
+                    Some(stored_to_uids) if !stored_to_uids.contains(&uid_edge.to_uid) => {
+                        stored_to_uids
+                    }
+                    _ => continue,
+                };
+            conflicts.push(EdgeConflict {
+                from_node_key: edge.from_node_key.clone(),
+                edge_name: edge.edge_name.clone(),
+                to_node_key: edge.to_node_key.clone(),
+                stored_to_uids: stored_to_uids.clone(),
+            });
 
-    for (_, query_response) in query_responses.into_iter() {
-        let query_response = match query_response.as_slice() {
-            [query_response] => query_response,
-            [] => {
-                tracing::error!(message = "Empty response for node_key");
-                continue;
-            }
-            res => {
-                tracing::error!(message = "Too many responses for node_key", count=?res.len());
-                continue;
-            }
-        };
-        let node_key = query_response.get("node_key");
-        let uid = query_response.get("uid");
-        let (node_key, uid) = match (node_key, uid) {
-            (Some(node_key), Some(uid)) => (node_key, uid),
-            (missing_key, missing_uid) => {
-                tracing::error!(message="Unable to retrieve node_key and uid", node_key=?missing_key, uid=?missing_uid);
-                continue;
+            match self.to_one_edge_policy {
+                ToOneEdgePolicy::Reject => {
+                    rejected_edges.insert((
+                        uid_edge.to_uid,
+                        uid_edge.from_uid,
+                        reverse_edge_name.clone(),
+                    ));
+                    rejected_edges.insert((uid_edge.from_uid, uid_edge.to_uid, uid_edge.edge_name));
+                }
+                ToOneEdgePolicy::Replace => {
+                    for stored_to_uid in stored_to_uids.iter().copied() {
+                        replaced_edges.push(UidEdge {
+                            from_uid: uid_edge.from_uid,
+                            to_uid: stored_to_uid,
+                            edge_name: uid_edge.edge_name.clone(),
+                            facets: None,
+                        });
+                        replaced_edges.push(UidEdge {
+                            from_uid: stored_to_uid,
+                            to_uid: uid_edge.from_uid,
+                            edge_name: reverse_edge_name.clone(),
+                            facets: None,
+                        });
+                    }
+                }
             }
-        };
-        let uid = u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex");
-        resolved_nodes.insert(node_key.to_owned(), uid);
-    }
-
-    resolved_nodes
-}
-
-fn gen_node_key_query(node_key: &str) -> QueryBlock {
-    QueryBlockBuilder::default()
-        .query_type(QueryBlockType::query())
-        .root_filter(Condition::EQ(
-            "node_key".to_string(),
-            ConditionValue::string(node_key),
-        ))
-        .predicates(vec![
-            Predicate::Field(Field::new("uid")),
-            Predicate::Field(Field::new("node_key")),
-        ])
-        .first(1)
-        .build()
-        .unwrap()
-}
-
-fn uid_from_uids(
-    node_key: &str,
-    key_to_query_name: &HashMap<String, String>,
-    uids: &HashMap<String, String>,
-) -> Option<u64> {
-    let query_name = key_to_query_name.get(node_key)?;
-    let uid = uids.get(query_name)?;
-    Some(u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex"))
-}
-
-fn extract_node_key_map_uid(
-    dgraph_response: &serde_json::Value,
-    node_key_map_to_uid: &mut HashMap<String, u64>,
-) {
-    let query_responses = dgraph_response.as_object().expect("Invalid response");
-
-    for query_response in query_responses.values() {
-        let query_response = query_response.as_array().expect("Invalid response");
-        for query_response in query_response {
-            let uid = query_response
-                .get("uid")
-                .expect("uid")
-                .as_str()
-                .expect("uid");
-            let node_key = query_response
-                .get("node_key")
-                .expect("node_key")
-                .as_str()
-                .expect("node_key");
+        }
 
-            // dgraph uids are hex encoded as '0x1b'
-            let uid = u64::from_str_radix(&uid[2..], 16).expect("uid is not valid hex");
-            node_key_map_to_uid.insert(node_key.to_owned(), uid);
+        uid_edges.retain(|uid_edge| {
+            !rejected_edges.contains(&(
+                uid_edge.from_uid,
+                uid_edge.to_uid,
+                uid_edge.edge_name.clone(),
+            ))
+        });
+        if !replaced_edges.is_empty() {
+            graph_store.delete_edges(replaced_edges).await;
         }
+        conflicts
     }
 }
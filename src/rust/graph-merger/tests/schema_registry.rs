@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+
+use graph_merger_lib::{
+    deployed_schemas::{
+        DeployedSchemas,
+        Item,
+    },
+    schema_registry::{
+        SchemaRegistry,
+        SchemaViolation,
+    },
+};
+use grapl_graph_descriptions::*;
+use rusoto_dynamodb::AttributeValue;
+
+fn process(node_key: &str, process_id: u64) -> IdentifiedNode {
+    let mut properties = HashMap::new();
+    properties.insert(
+        "process_id".to_string(),
+        ImmutableUintProp { prop: process_id }.into(),
+    );
+    IdentifiedNode {
+        node_key: node_key.to_string(),
+        node_type: "Process".to_string(),
+        properties,
+    }
+}
+
+fn file(node_key: &str) -> IdentifiedNode {
+    IdentifiedNode {
+        node_key: node_key.to_string(),
+        node_type: "File".to_string(),
+        properties: HashMap::new(),
+    }
+}
+
+fn edges(graph: &IdentifiedGraph, from_node_key: &str, edge_name: &str) -> Vec<String> {
+    let mut to_node_keys: Vec<String> = graph
+        .edges
+        .get(from_node_key)
+        .map(|edge_list| edge_list.edges.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|edge| edge.edge_name == edge_name)
+        .map(|edge| edge.to_node_key.clone())
+        .collect();
+    to_node_keys.sort();
+    to_node_keys
+}
+
+#[test]
+fn endpoint_schema_defines_reverse_edges() {
+    let schema_registry = SchemaRegistry::endpoint().expect("endpoint schema");
+
+    let children = schema_registry.edge("Process", "children").unwrap();
+    assert_eq!(children.reverse_edge_name, "parent");
+    assert_eq!(children.target_type_name, "Process");
+    assert!(children.relationship.to_many());
+
+    let parent = schema_registry.edge("Process", "parent").unwrap();
+    assert_eq!(parent.reverse_edge_name, "children");
+    assert!(parent.relationship.to_one());
+
+    let bin_file = schema_registry.edge("Process", "bin_file").unwrap();
+    assert!(bin_file.relationship.to_one());
+    let spawned_from = schema_registry.edge("File", "spawned_from").unwrap();
+    assert_eq!(spawned_from.target_type_name, "Process");
+    assert!(spawned_from.relationship.to_many());
+}
+
+#[test]
+fn extensions_add_edges_to_existing_types() {
+    let schema = format!(
+        "{}\n{}",
+        endpoint_plugin::ENDPOINT_GRAPHQL,
+        r#"
+        type ProcessExtension @grapl(extends: "Process") {
+            opened_files: [File!] @edge(reverse: "opened_by", reverse_relationship: "ToMany"),
+        }
+        "#
+    );
+    let schema_registry = SchemaRegistry::from_schema(&schema).expect("extended schema");
+
+    assert!(schema_registry.edge("Process", "children").is_some());
+    let opened_by = schema_registry.edge("File", "opened_by").unwrap();
+    assert_eq!(opened_by.reverse_edge_name, "opened_files");
+}
+
+#[test]
+fn conform_adds_reverse_edges() {
+    let schema_registry = SchemaRegistry::endpoint().unwrap();
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(process("parent", 1));
+    graph.add_node(process("child", 2));
+    graph.add_node(file("bin"));
+    graph.add_edge(
+        "children".to_string(),
+        "parent".to_string(),
+        "child".to_string(),
+    );
+    graph.add_edge(
+        "bin_file".to_string(),
+        "child".to_string(),
+        "bin".to_string(),
+    );
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert!(violations.is_empty(), "{:?}", violations);
+    assert_eq!(edges(&graph, "parent", "children"), vec!["child"]);
+    assert_eq!(edges(&graph, "child", "parent"), vec!["parent"]);
+    assert_eq!(edges(&graph, "child", "bin_file"), vec!["bin"]);
+    assert_eq!(edges(&graph, "bin", "spawned_from"), vec!["child"]);
+}
+
+#[test]
+fn conform_resolves_edges_from_nodes_outside_the_graph() {
+    let schema_registry = SchemaRegistry::endpoint().unwrap();
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(process("child", 2));
+    graph.add_edge(
+        "children".to_string(),
+        "parent".to_string(),
+        "child".to_string(),
+    );
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert!(violations.is_empty(), "{:?}", violations);
+    assert_eq!(edges(&graph, "child", "parent"), vec!["parent"]);
+}
+
+#[test]
+fn conform_drops_mistyped_properties() {
+    let schema_registry = SchemaRegistry::endpoint().unwrap();
+    let mut node = process("process", 1);
+    node.properties.insert(
+        "process_name".to_string(),
+        IncrementOnlyUintProp { prop: 1 }.into(),
+    );
+    node.properties.insert(
+        "undefined_predicate".to_string(),
+        ImmutableStrProp {
+            prop: "kept".to_string(),
+        }
+        .into(),
+    );
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(node);
+
+    let mut violations = schema_registry.conform(&mut graph);
+    violations.sort_by_key(|violation| violation.kind());
+
+    assert_eq!(
+        violations,
+        vec![
+            SchemaViolation::PredicateTypeMismatch {
+                node_key: "process".to_string(),
+                node_type: "Process".to_string(),
+                predicate_name: "process_name".to_string(),
+            },
+            SchemaViolation::UnknownPredicate {
+                node_key: "process".to_string(),
+                node_type: "Process".to_string(),
+                predicate_name: "undefined_predicate".to_string(),
+            },
+        ]
+    );
+    let properties = &graph.nodes["process"].properties;
+    assert!(properties.contains_key("process_id"));
+    assert!(properties.contains_key("undefined_predicate"));
+    assert!(!properties.contains_key("process_name"));
+}
+
+#[test]
+fn conform_drops_edges_to_the_wrong_node_type() {
+    let schema_registry = SchemaRegistry::endpoint().unwrap();
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(process("parent", 1));
+    graph.add_node(file("file"));
+    graph.add_edge(
+        "children".to_string(),
+        "parent".to_string(),
+        "file".to_string(),
+    );
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind(), "edge_target_mismatch");
+    assert!(edges(&graph, "parent", "children").is_empty());
+    assert!(edges(&graph, "file", "parent").is_empty());
+}
+
+#[test]
+fn conform_keeps_the_first_target_of_to_one_edges() {
+    let schema_registry = SchemaRegistry::endpoint().unwrap();
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(process("first_parent", 1));
+    graph.add_node(process("second_parent", 2));
+    graph.add_node(process("child", 3));
+    graph.add_edge(
+        "parent".to_string(),
+        "child".to_string(),
+        "first_parent".to_string(),
+    );
+    graph.add_edge(
+        "children".to_string(),
+        "second_parent".to_string(),
+        "child".to_string(),
+    );
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert_eq!(
+        violations,
+        vec![SchemaViolation::ToOneEdgeConflict {
+            from_node_key: "child".to_string(),
+            edge_name: "parent".to_string(),
+            to_node_key: "first_parent".to_string(),
+            conflicting_node_key: "second_parent".to_string(),
+        }]
+    );
+    assert_eq!(edges(&graph, "child", "parent"), vec!["first_parent"]);
+    assert_eq!(edges(&graph, "first_parent", "children"), vec!["child"]);
+    assert!(edges(&graph, "second_parent", "children").is_empty());
+}
+
+#[test]
+fn conform_keeps_undefined_edges_without_reverses() {
+    let schema_registry = SchemaRegistry::endpoint().unwrap();
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(process("process", 1));
+    graph.add_node(file("file"));
+    graph.add_edge(
+        "undefined_edge".to_string(),
+        "process".to_string(),
+        "file".to_string(),
+    );
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert_eq!(
+        violations,
+        vec![SchemaViolation::UnknownEdge {
+            from_node_key: "process".to_string(),
+            node_type: "Process".to_string(),
+            edge_name: "undefined_edge".to_string(),
+        }]
+    );
+    assert_eq!(edges(&graph, "process", "undefined_edge"), vec!["file"]);
+    assert!(!graph.edges.contains_key("file"));
+}
+
+#[test]
+fn conform_accepts_bool_float_bytes_set_and_timestamp_predicates() {
+    let schema = format!(
+        "{}\n{}",
+        endpoint_plugin::ENDPOINT_GRAPHQL,
+        r#"
+        type ProcessExtension @grapl(extends: "Process") {
+            is_elevated: Boolean @immutable,
+            max_cpu: Float @increment_only,
+            image_hash: Bytes @immutable,
+            arguments: [String] @union,
+            first_scanned: Timestamp @decrement_only,
+        }
+        "#
+    );
+    let schema_registry = SchemaRegistry::from_schema(&schema).expect("extended schema");
+    let mut node = process("process", 1);
+    node.properties.insert(
+        "is_elevated".to_string(),
+        ImmutableBoolProp { prop: true }.into(),
+    );
+    node.properties.insert(
+        "max_cpu".to_string(),
+        IncrementOnlyFloatProp { prop: 0.5 }.into(),
+    );
+    node.properties.insert(
+        "image_hash".to_string(),
+        ImmutableBytesProp { prop: vec![0xab] }.into(),
+    );
+    node.properties.insert(
+        "arguments".to_string(),
+        StrSetProp::from(vec!["-c", "ls"]).into(),
+    );
+    node.properties.insert(
+        "first_scanned".to_string(),
+        DecrementOnlyTimestampProp::from(1_563_991_660_514).into(),
+    );
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(node);
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert!(violations.is_empty(), "{:?}", violations);
+    assert_eq!(graph.nodes["process"].properties.len(), 6);
+}
+
+#[test]
+fn graph_schema_validates_without_conforming() {
+    let registry = SchemaRegistry::endpoint().expect("endpoint schema");
+    let graph_schema = registry.graph_schema();
+
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(process("parent", 1));
+    graph.add_node(process("child", 2));
+    graph.add_node(file("bin"));
+    graph.add_edge("children", "parent", "child");
+    graph.add_edge("bin_file", "child", "bin");
+    assert_eq!(graph_schema.validate_identified(&graph), vec![]);
+
+    graph.add_edge("bin_file", "parent", "child");
+    let original = graph.clone();
+    assert_eq!(
+        graph_schema.validate_identified(&graph),
+        vec![schema::SchemaViolation::EdgeTargetMismatch {
+            from_node_key: "parent".to_string(),
+            to_node_key: "child".to_string(),
+            edge_name: "bin_file".to_string(),
+            expected_type: "File".to_string(),
+            to_node_type: "Process".to_string(),
+        }]
+    );
+    assert_eq!(graph, original);
+}
+
+fn string_value(s: &str) -> AttributeValue {
+    AttributeValue {
+        s: Some(s.to_string()),
+        ..Default::default()
+    }
+}
+
+/// An item of the schema table, as grapl_analyzerlib's provisioner writes it
+fn schema_item(f_edge: &str, r_edge: &str, relationship: u8) -> Item {
+    let mut item = Item::new();
+    item.insert("f_edge".to_string(), string_value(f_edge));
+    item.insert("r_edge".to_string(), string_value(r_edge));
+    item.insert(
+        "relationship".to_string(),
+        AttributeValue {
+            n: Some(relationship.to_string()),
+            ..Default::default()
+        },
+    );
+    item
+}
+
+/// An item of the schema properties table, as grapl_analyzerlib's provisioner writes it
+fn schema_properties_item(node_type: &str, properties: &[(&str, &str, bool)]) -> Item {
+    let properties = properties
+        .iter()
+        .map(|(name, primitive, is_set)| {
+            let mut property = HashMap::new();
+            property.insert("name".to_string(), string_value(name));
+            property.insert("primitive".to_string(), string_value(primitive));
+            property.insert(
+                "is_set".to_string(),
+                AttributeValue {
+                    bool: Some(*is_set),
+                    ..Default::default()
+                },
+            );
+            AttributeValue {
+                m: Some(property),
+                ..Default::default()
+            }
+        })
+        .collect();
+    let mut type_definition = HashMap::new();
+    type_definition.insert(
+        "properties".to_string(),
+        AttributeValue {
+            l: Some(properties),
+            ..Default::default()
+        },
+    );
+
+    let mut item = Item::new();
+    item.insert("node_type".to_string(), string_value(node_type));
+    item.insert(
+        "type_definition".to_string(),
+        AttributeValue {
+            m: Some(type_definition),
+            ..Default::default()
+        },
+    );
+    item.insert("display_property".to_string(), string_value("dgraph_type"));
+    item
+}
+
+fn deployed_schemas() -> DeployedSchemas {
+    DeployedSchemas::from_items(
+        &[
+            // EdgeRelationship.ManyToOne and OneToMany
+            schema_item("instance_asset", "aws_instances", 4),
+            schema_item("aws_instances", "instance_asset", 2),
+            // Conflicts with the endpoint schema's `children`
+            schema_item("child_processes", "children", 2),
+            schema_item("children", "child_processes", 4),
+        ],
+        &[
+            schema_properties_item(
+                "AwsInstance",
+                &[
+                    ("instance_id", "Str", false),
+                    ("security_groups", "Str", true),
+                    ("launch_count", "Int", false),
+                    ("instance_asset", "Asset", false),
+                ],
+            ),
+            schema_properties_item(
+                "Process",
+                &[
+                    ("process_name", "Str", false),
+                    ("integrity_level", "Str", false),
+                    ("child_processes", "Process", true),
+                ],
+            ),
+        ],
+    )
+}
+
+#[test]
+fn deployed_schemas_add_node_types_and_edges() {
+    let schema_registry = SchemaRegistry::endpoint()
+        .expect("endpoint schema")
+        .with_deployed(&deployed_schemas());
+
+    let instance_asset = schema_registry
+        .edge("AwsInstance", "instance_asset")
+        .unwrap();
+    assert_eq!(instance_asset.reverse_edge_name, "aws_instances");
+    assert_eq!(instance_asset.target_type_name, "Asset");
+    assert!(instance_asset.relationship.to_one());
+
+    let aws_instances = schema_registry.edge("Asset", "aws_instances").unwrap();
+    assert_eq!(aws_instances.target_type_name, "AwsInstance");
+    assert!(aws_instances.relationship.to_many());
+
+    // The endpoint schema's edges are kept
+    assert!(schema_registry.edge("Process", "child_processes").is_none());
+    assert_eq!(
+        schema_registry
+            .edge("Process", "children")
+            .unwrap()
+            .reverse_edge_name,
+        "parent"
+    );
+}
+
+#[test]
+fn conform_checks_deployed_predicates_by_primitive_type() {
+    let schema_registry = SchemaRegistry::endpoint()
+        .expect("endpoint schema")
+        .with_deployed(&deployed_schemas());
+
+    let mut properties = HashMap::new();
+    properties.insert(
+        "instance_id".to_string(),
+        ImmutableStrProp::from("i-0123").into(),
+    );
+    properties.insert(
+        "security_groups".to_string(),
+        StrSetProp::from(vec!["sg-0123".to_string()]).into(),
+    );
+    properties.insert(
+        "launch_count".to_string(),
+        IncrementOnlyUintProp { prop: 2 }.into(),
+    );
+    let instance = IdentifiedNode {
+        node_key: "instance".to_string(),
+        node_type: "AwsInstance".to_string(),
+        properties,
+    };
+    let mut process = process("process", 1);
+    process.properties.insert(
+        "integrity_level".to_string(),
+        ImmutableUintProp { prop: 3 }.into(),
+    );
+
+    let mut graph = IdentifiedGraph::new();
+    graph.add_node(instance);
+    graph.add_node(process);
+    graph.add_node(IdentifiedNode {
+        node_key: "asset".to_string(),
+        node_type: "Asset".to_string(),
+        properties: HashMap::new(),
+    });
+    graph.add_edge("instance_asset", "instance", "asset");
+
+    let violations = schema_registry.conform(&mut graph);
+
+    assert_eq!(
+        violations,
+        vec![SchemaViolation::PredicateTypeMismatch {
+            node_key: "process".to_string(),
+            node_type: "Process".to_string(),
+            predicate_name: "integrity_level".to_string(),
+        }]
+    );
+    assert_eq!(graph.nodes["instance"].properties.len(), 3);
+    assert_eq!(edges(&graph, "asset", "aws_instances"), vec!["instance"]);
+}
@@ -34,7 +34,10 @@ pub mod test {
         Client as DgraphClient,
         Query,
     };
-    use graph_merger_lib::upserter::GraphMergeHelper;
+    use graph_merger_lib::{
+        dgraph_graph_store::DgraphGraphStore,
+        upserter::GraphMergeHelper,
+    };
     use grapl_graph_descriptions::*;
 
     async fn query_for_uid(dgraph_client: Arc<DgraphClient>, node_key: &str) -> u64 {
@@ -230,8 +233,12 @@ pub mod test {
             "example-node-key".to_string(),
         );
 
-        GraphMergeHelper {}
-            .upsert_into(dgraph_client.clone(), &identified_graph, &mut merged_graph)
+        GraphMergeHelper::default()
+            .upsert_into(
+                &DgraphGraphStore::from_client(dgraph_client.clone()),
+                &identified_graph,
+                &mut merged_graph,
+            )
             .await;
 
         let node_uid_0 = query_for_uid(dgraph_client.clone(), "example-node-key").await;
@@ -307,8 +314,12 @@ pub mod test {
                     identified_graph.add_node(n0);
                     let mut merged_graph = MergedGraph::new();
 
-                    GraphMergeHelper {}
-                        .upsert_into(dgraph_client.clone(), &identified_graph, &mut merged_graph)
+                    GraphMergeHelper::default()
+                        .upsert_into(
+                            &DgraphGraphStore::from_client(dgraph_client.clone()),
+                            &identified_graph,
+                            &mut merged_graph,
+                        )
                         .await;
                     merged_graph
                 }
@@ -382,8 +393,12 @@ pub mod test {
         identified_graph.add_node(n0);
         let mut merged_graph = MergedGraph::new();
 
-        GraphMergeHelper {}
-            .upsert_into(dgraph_client.clone(), &identified_graph, &mut merged_graph)
+        GraphMergeHelper::default()
+            .upsert_into(
+                &DgraphGraphStore::from_client(dgraph_client.clone()),
+                &identified_graph,
+                &mut merged_graph,
+            )
             .await;
 
         // If we query for multiple nodes by node_key we should only ever receive one
@@ -423,4 +438,517 @@ pub mod test {
         assert_eq!(example_name, "test_upsert_multifield");
         Ok(())
     }
+
+    async fn upsert_node(dgraph_client: Arc<DgraphClient>, node: IdentifiedNode) {
+        let mut identified_graph = IdentifiedGraph::new();
+        identified_graph.add_node(node);
+        let mut merged_graph = MergedGraph::new();
+
+        GraphMergeHelper::default()
+            .upsert_into(
+                &DgraphGraphStore::from_client(dgraph_client),
+                &identified_graph,
+                &mut merged_graph,
+            )
+            .await;
+    }
+
+    async fn query_for_predicates(
+        dgraph_client: Arc<DgraphClient>,
+        node_key: &str,
+        predicates: &[&str],
+    ) -> serde_json::Value {
+        let mut query_predicates = vec![Predicate::Field(Field::new("uid"))];
+        query_predicates.extend(
+            predicates
+                .iter()
+                .map(|predicate| Predicate::Field(Field::new(predicate))),
+        );
+        let query_block = QueryBlockBuilder::default()
+            .query_type(QueryBlockType::query())
+            .root_filter(Condition::EQ(
+                "node_key".to_string(),
+                ConditionValue::string(node_key),
+            ))
+            .predicates(query_predicates)
+            .first(1)
+            .build()
+            .unwrap();
+
+        let query = QueryBuilder::default()
+            .query_blocks(vec![query_block])
+            .build()
+            .unwrap();
+
+        let mut txn = dgraph_client.new_read_only_txn();
+        let response = txn
+            .query(query.to_query_string())
+            .await
+            .expect("query failed");
+
+        let m: HashMap<String, Vec<serde_json::Value>> =
+            serde_json::from_slice(&response.json).expect("response failed to parse");
+        let mut m = m.into_iter().next().unwrap().1;
+        debug_assert_eq!(m.len(), 1);
+        m.remove(0)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_upsert_respects_merge_semantics() -> Result<(), Box<dyn std::error::Error>> {
+        init_test_env();
+        let mg_alpha = grapl_config::mg_alphas()
+            .pop()
+            .expect("Dgraph Alpha not specified.");
+
+        let dgraph_client = DgraphClient::new(mg_alpha).expect("Failed to create dgraph client.");
+        let dgraph_client = std::sync::Arc::new(dgraph_client);
+
+        let node_key = "test_upsert_respects_merge_semantics-example-node-key";
+        let make_node = |example_id: u64, example_name: &str| {
+            let mut properties = HashMap::new();
+            properties.insert(
+                "example_id".to_string(),
+                IncrementOnlyUintProp { prop: example_id }.into(),
+            );
+            properties.insert(
+                "example_name".to_string(),
+                ImmutableStrProp {
+                    prop: example_name.to_string(),
+                }
+                .into(),
+            );
+            IdentifiedNode {
+                node_key: node_key.to_string(),
+                node_type: "ExampleNode".to_string(),
+                properties,
+            }
+        };
+
+        // The second upsert is "older" - it must neither lower the increment-only
+        // property nor overwrite the immutable one
+        upsert_node(dgraph_client.clone(), make_node(10, "first")).await;
+        upsert_node(dgraph_client.clone(), make_node(5, "second")).await;
+
+        let node = query_for_predicates(
+            dgraph_client.clone(),
+            node_key,
+            &["example_id", "example_name"],
+        )
+        .await;
+        assert_eq!(node["example_id"], 10);
+        assert_eq!(node["example_name"], "first");
+
+        // A larger value still wins
+        upsert_node(dgraph_client.clone(), make_node(20, "third")).await;
+
+        let node = query_for_predicates(
+            dgraph_client.clone(),
+            node_key,
+            &["example_id", "example_name"],
+        )
+        .await;
+        assert_eq!(node["example_id"], 20);
+        assert_eq!(node["example_name"], "first");
+        Ok(())
+    }
+}
+
+pub mod in_memory_test {
+    use std::collections::HashMap;
+
+    use graph_merger_lib::{
+        in_memory_graph_store::InMemoryGraphStore,
+        schema_registry::SchemaRegistry,
+        upserter::{
+            EdgeConflict,
+            GraphMergeHelper,
+            InvalidToOneEdgePolicy,
+            ToOneEdgePolicy,
+        },
+    };
+    use grapl_graph_descriptions::*;
+
+    fn example_node(node_key: &str, example_name: &str) -> IdentifiedNode {
+        let mut properties = HashMap::new();
+        properties.insert(
+            "example_name".to_string(),
+            ImmutableStrProp {
+                prop: example_name.to_string(),
+            }
+            .into(),
+        );
+        IdentifiedNode {
+            node_key: node_key.to_string(),
+            node_type: "ExampleNode".to_string(),
+            properties,
+        }
+    }
+
+    async fn upsert_graph(
+        graph_store: &InMemoryGraphStore,
+        identified_graph: &IdentifiedGraph,
+    ) -> MergedGraph {
+        let mut merged_graph = MergedGraph::new();
+        GraphMergeHelper::default()
+            .upsert_into(graph_store, identified_graph, &mut merged_graph)
+            .await;
+        merged_graph
+    }
+
+    async fn upsert_node(graph_store: &InMemoryGraphStore, node: IdentifiedNode) -> MergedGraph {
+        let mut identified_graph = IdentifiedGraph::new();
+        identified_graph.add_node(node);
+        upsert_graph(graph_store, &identified_graph).await
+    }
+
+    #[tokio::test]
+    async fn test_upsert_edge_and_retrieve() {
+        let graph_store = InMemoryGraphStore::new();
+
+        let mut identified_graph = IdentifiedGraph::new();
+        identified_graph.add_node(example_node("example-node-key", "foobar"));
+        identified_graph.add_node(example_node("someother-node-key", "baz"));
+        identified_graph.add_edge(
+            "to_many_edge".to_string(),
+            "example-node-key".to_string(),
+            "someother-node-key".to_string(),
+        );
+        identified_graph.add_edge(
+            "to_single_edge".to_string(),
+            "someother-node-key".to_string(),
+            "example-node-key".to_string(),
+        );
+
+        let merged_graph = upsert_graph(&graph_store, &identified_graph).await;
+
+        let node_uid_0 = graph_store.node("example-node-key").expect("node 0").uid;
+        let node_uid_1 = graph_store.node("someother-node-key").expect("node 1").uid;
+        assert_ne!(node_uid_0, node_uid_1);
+        assert_eq!(merged_graph.nodes["example-node-key"].uid, node_uid_0);
+        assert_eq!(merged_graph.nodes["someother-node-key"].uid, node_uid_1);
+
+        assert_eq!(
+            graph_store.edges(node_uid_0, "to_many_edge"),
+            vec![node_uid_1]
+        );
+        assert_eq!(
+            graph_store.edges(node_uid_1, "to_single_edge"),
+            vec![node_uid_0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_edge_to_stored_node() {
+        let graph_store = InMemoryGraphStore::new();
+        upsert_node(&graph_store, example_node("stored-node-key", "stored")).await;
+
+        // The edge's destination isn't part of this graph, so its uid has to be looked up
+        let mut identified_graph = IdentifiedGraph::new();
+        identified_graph.add_node(example_node("new-node-key", "new"));
+        identified_graph.add_edge(
+            "to_many_edge".to_string(),
+            "new-node-key".to_string(),
+            "stored-node-key".to_string(),
+        );
+        upsert_graph(&graph_store, &identified_graph).await;
+
+        let stored_uid = graph_store
+            .node("stored-node-key")
+            .expect("stored node")
+            .uid;
+        let new_uid = graph_store.node("new-node-key").expect("new node").uid;
+        assert_eq!(graph_store.edges(new_uid, "to_many_edge"), vec![stored_uid]);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_idempotency() {
+        let graph_store = InMemoryGraphStore::new();
+        let node_key = "test_upsert_idempotency-example-node-key";
+
+        let merged_graphs = futures::future::join_all(
+            (0..10).map(|_| upsert_node(&graph_store, example_node(node_key, "foobar"))),
+        )
+        .await;
+
+        let uid = graph_store.node(node_key).expect("node").uid;
+        for merged_graph in merged_graphs {
+            assert_eq!(merged_graph.nodes.len(), 1);
+            assert_eq!(merged_graph.nodes[node_key].uid, uid);
+        }
+        assert_eq!(graph_store.node_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_multifield() {
+        let graph_store = InMemoryGraphStore::new();
+        let node_key = "test_upsert_multifield-example-node-key";
+        upsert_node(
+            &graph_store,
+            example_node(node_key, "test_upsert_multifield"),
+        )
+        .await;
+
+        let node = graph_store.node(node_key).expect("node");
+        assert_eq!(node.properties.len(), 1);
+        assert_eq!(
+            node.properties["example_name"],
+            ImmutableStrProp {
+                prop: "test_upsert_multifield".to_string()
+            }
+            .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_respects_merge_semantics() {
+        let graph_store = InMemoryGraphStore::new();
+        let node_key = "test_upsert_respects_merge_semantics-example-node-key";
+        let make_node = |example_id: u64, example_name: &str| {
+            let mut node = example_node(node_key, example_name);
+            node.properties.insert(
+                "example_id".to_string(),
+                IncrementOnlyUintProp { prop: example_id }.into(),
+            );
+            node
+        };
+
+        // The second upsert is "older" - it must neither lower the increment-only
+        // property nor overwrite the immutable one
+        upsert_node(&graph_store, make_node(10, "first")).await;
+        upsert_node(&graph_store, make_node(5, "second")).await;
+
+        let node = graph_store.node(node_key).expect("node");
+        assert_eq!(
+            node.properties["example_id"],
+            IncrementOnlyUintProp { prop: 10 }.into()
+        );
+        assert_eq!(
+            node.properties["example_name"],
+            ImmutableStrProp {
+                prop: "first".to_string()
+            }
+            .into()
+        );
+
+        // A larger value still wins
+        upsert_node(&graph_store, make_node(20, "third")).await;
+
+        let node = graph_store.node(node_key).expect("node");
+        assert_eq!(
+            node.properties["example_id"],
+            IncrementOnlyUintProp { prop: 20 }.into()
+        );
+        assert_eq!(
+            node.properties["example_name"],
+            ImmutableStrProp {
+                prop: "first".to_string()
+            }
+            .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_predicates_are_merged_but_not_stored() {
+        let graph_store = InMemoryGraphStore::new();
+        let node = example_node("test_cached_predicates-example-node-key", "cached");
+        let cached_predicates = node.get_cache_identities_for_predicates();
+
+        let mut identified_graph = IdentifiedGraph::new();
+        identified_graph.add_node(node.clone());
+        let mut merged_graph = MergedGraph::new();
+        GraphMergeHelper::with_cached_predicates(cached_predicates.into_iter().collect())
+            .upsert_into(&graph_store, &identified_graph, &mut merged_graph)
+            .await;
+
+        let stored = graph_store.node(&node.node_key).expect("node");
+        assert!(stored.properties.is_empty());
+        assert_eq!(
+            merged_graph.nodes[&node.node_key].properties,
+            node.properties
+        );
+    }
+
+    /// Merges `parent_key` as the parent of `child_key`, along with the reverse edge
+    async fn upsert_parent(
+        graph_store: &InMemoryGraphStore,
+        to_one_edge_policy: ToOneEdgePolicy,
+        parent_key: &str,
+        child_key: &str,
+    ) -> Vec<EdgeConflict> {
+        let schema_registry = SchemaRegistry::endpoint().expect("endpoint schema");
+        let mut identified_graph = IdentifiedGraph::new();
+        for node_key in &[parent_key, child_key] {
+            identified_graph.add_node(IdentifiedNode {
+                node_key: node_key.to_string(),
+                node_type: "Process".to_string(),
+                properties: HashMap::new(),
+            });
+        }
+        identified_graph.add_edge(
+            "children".to_string(),
+            parent_key.to_string(),
+            child_key.to_string(),
+        );
+        schema_registry.conform(&mut identified_graph);
+
+        GraphMergeHelper::default()
+            .with_to_one_edges(schema_registry, to_one_edge_policy)
+            .upsert_into(graph_store, &identified_graph, &mut MergedGraph::new())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_to_one_edge_conflict_rejected() {
+        let graph_store = InMemoryGraphStore::new();
+        let conflicts =
+            upsert_parent(&graph_store, ToOneEdgePolicy::Reject, "parent-0", "child").await;
+        assert!(conflicts.is_empty());
+        // The same edge again isn't a conflict
+        let conflicts =
+            upsert_parent(&graph_store, ToOneEdgePolicy::Reject, "parent-0", "child").await;
+        assert!(conflicts.is_empty());
+
+        let conflicts =
+            upsert_parent(&graph_store, ToOneEdgePolicy::Reject, "parent-1", "child").await;
+
+        let parent_0 = graph_store.node("parent-0").expect("parent-0").uid;
+        let parent_1 = graph_store.node("parent-1").expect("parent-1").uid;
+        let child = graph_store.node("child").expect("child").uid;
+        assert_eq!(
+            conflicts,
+            vec![EdgeConflict {
+                from_node_key: "child".to_string(),
+                edge_name: "parent".to_string(),
+                to_node_key: "parent-1".to_string(),
+                stored_to_uids: vec![parent_0],
+            }]
+        );
+        assert_eq!(graph_store.edges(child, "parent"), vec![parent_0]);
+        assert_eq!(graph_store.edges(parent_0, "children"), vec![child]);
+        assert!(graph_store.edges(parent_1, "children").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_to_one_edge_conflict_replaced() {
+        let graph_store = InMemoryGraphStore::new();
+        upsert_parent(&graph_store, ToOneEdgePolicy::Replace, "parent-0", "child").await;
+
+        let conflicts =
+            upsert_parent(&graph_store, ToOneEdgePolicy::Replace, "parent-1", "child").await;
+
+        let parent_0 = graph_store.node("parent-0").expect("parent-0").uid;
+        let parent_1 = graph_store.node("parent-1").expect("parent-1").uid;
+        let child = graph_store.node("child").expect("child").uid;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(graph_store.edges(child, "parent"), vec![parent_1]);
+        assert_eq!(graph_store.edges(parent_1, "children"), vec![child]);
+        assert!(graph_store.edges(parent_0, "children").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_to_many_edges_are_added() {
+        let graph_store = InMemoryGraphStore::new();
+        upsert_parent(&graph_store, ToOneEdgePolicy::Reject, "parent", "child-0").await;
+        upsert_parent(&graph_store, ToOneEdgePolicy::Reject, "parent", "child-1").await;
+
+        let parent = graph_store.node("parent").expect("parent").uid;
+        assert_eq!(graph_store.edges(parent, "children").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_written_edges_are_merged() {
+        let graph_store = InMemoryGraphStore::new();
+        let schema_registry = SchemaRegistry::endpoint().expect("endpoint schema");
+        let mut identified_graph = IdentifiedGraph::new();
+        for node_key in &["parent", "child"] {
+            identified_graph.add_node(IdentifiedNode {
+                node_key: node_key.to_string(),
+                node_type: "Process".to_string(),
+                properties: HashMap::new(),
+            });
+        }
+        identified_graph.add_edge(
+            "children".to_string(),
+            "parent".to_string(),
+            "child".to_string(),
+        );
+        schema_registry.conform(&mut identified_graph);
+
+        let mut merged_graph = MergedGraph::new();
+        GraphMergeHelper::default()
+            .with_to_one_edges(schema_registry, ToOneEdgePolicy::Reject)
+            .upsert_into(&graph_store, &identified_graph, &mut merged_graph)
+            .await;
+
+        let parent = graph_store.node("parent").expect("parent").uid;
+        let child = graph_store.node("child").expect("child").uid;
+        let children = &merged_graph.edges["parent"].edges;
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].edge_name, "children");
+        assert_eq!(children[0].to_node_key, "child");
+        assert_eq!(children[0].from_uid, parent.to_string());
+        assert_eq!(children[0].to_uid, child.to_string());
+        let parents = &merged_graph.edges["child"].edges;
+        assert_eq!(parents.len(), 1);
+        assert_eq!(parents[0].edge_name, "parent");
+        assert_eq!(parents[0].to_node_key, "parent");
+    }
+
+    /// Merges a `children` edge from `parent` to `child` observed at each of the timestamps
+    async fn upsert_children(graph_store: &InMemoryGraphStore, observations: &[u64]) {
+        let schema_registry = SchemaRegistry::endpoint().expect("endpoint schema");
+        let mut identified_graph = IdentifiedGraph::new();
+        for node_key in &["parent", "child"] {
+            identified_graph.add_node(IdentifiedNode {
+                node_key: node_key.to_string(),
+                node_type: "Process".to_string(),
+                properties: HashMap::new(),
+            });
+        }
+        for timestamp in observations {
+            identified_graph.add_edge_with_facets(
+                "children".to_string(),
+                "parent".to_string(),
+                "child".to_string(),
+                EdgeFacets::seen_at(*timestamp),
+            );
+        }
+        schema_registry.conform(&mut identified_graph);
+        upsert_graph(graph_store, &identified_graph).await;
+    }
+
+    #[tokio::test]
+    async fn test_edge_facets_are_merged() {
+        let graph_store = InMemoryGraphStore::new();
+        upsert_children(&graph_store, &[10, 15]).await;
+        upsert_children(&graph_store, &[20]).await;
+
+        let parent = graph_store.node("parent").expect("parent").uid;
+        let child = graph_store.node("child").expect("child").uid;
+        // Observations from separate subgraphs are counted together
+        let expected = EdgeFacets {
+            first_seen: Some(DecrementOnlyUintProp { prop: 10 }),
+            last_seen: Some(IncrementOnlyUintProp { prop: 20 }),
+            count: Some(IncrementOnlyUintProp { prop: 3 }),
+        };
+        assert_eq!(
+            graph_store.edge_facets(parent, "children", child),
+            Some(expected)
+        );
+        // The reverse edge carries the same facets
+        assert_eq!(
+            graph_store.edge_facets(child, "parent", parent),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_to_one_edge_policy_from_str() {
+        assert_eq!("Replace".parse(), Ok(ToOneEdgePolicy::Replace));
+        assert_eq!("reject".parse(), Ok(ToOneEdgePolicy::Reject));
+        assert_eq!(
+            "overwrite".parse::<ToOneEdgePolicy>(),
+            Err(InvalidToOneEdgePolicy("overwrite".to_owned()))
+        );
+    }
 }
@@ -13,8 +13,8 @@ use rusoto_dynamodb::DynamoDbClient;
 use rusoto_s3::S3Client;
 use rusoto_sqs::SqsClient;
 use sqs_executor::{
-    make_ten,
     s3_event_emitter::S3EventEmitter,
+    sqs_event_source::SqsEventSource,
 };
 
 use crate::ServiceEnv;
@@ -252,12 +252,11 @@ where
     )
 }
 
-pub async fn s3_event_emitters_from_env<F>(
-    env: &ServiceEnv,
-    key_fn: F,
-) -> [S3EventEmitter<S3Client, F>; 10]
-where
-    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
-{
-    make_ten(async { s3_event_emitter_from_env(env, key_fn) }).await
+pub fn sqs_event_source_from_env(env: &ServiceEnv) -> SqsEventSource<SqsClient> {
+    SqsEventSource::new(
+        SqsClient::from_env(),
+        crate::source_queue_url(),
+        crate::dead_letter_queue_url(),
+        MetricReporter::new(&env.service_name),
+    )
 }
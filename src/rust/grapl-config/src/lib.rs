@@ -16,13 +16,14 @@ use rusoto_sqs::{
     Sqs,
 };
 use sqs_executor::{
-    make_ten,
+    executor_config::ExecutorConfig,
     redis_cache::RedisCache,
 };
 use tracing::debug;
 use tracing_subscriber::EnvFilter;
 
 pub mod env_helpers;
+pub mod service;
 
 #[macro_export]
 macro_rules! init_grapl_env {
@@ -71,8 +72,32 @@ pub async fn event_cache(env: &ServiceEnv) -> RedisCache {
     .expect("Could not create redis client")
 }
 
-pub async fn event_caches(env: &ServiceEnv) -> [RedisCache; 10] {
-    make_ten(event_cache(env)).await
+/// How many messages a service handles concurrently, from `WORKER_COUNT`, and how many it may
+/// have received but not yet finished, from `MAX_IN_FLIGHT_MESSAGES`
+pub fn executor_config() -> ExecutorConfig {
+    let default_config = ExecutorConfig::default();
+    let worker_count =
+        parse_env_var::<usize>("WORKER_COUNT").unwrap_or_else(|| default_config.worker_count());
+    let config = ExecutorConfig::new(worker_count);
+
+    match parse_env_var::<usize>("MAX_IN_FLIGHT_MESSAGES") {
+        Some(max_in_flight) => config.with_max_in_flight(max_in_flight),
+        None => config,
+    }
+}
+
+/// Parses the variable when it's set. A value that's set but can't be parsed panics, rather than
+/// quietly falling back to a default.
+fn parse_env_var<T>(name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = std::env::var(name).ok()?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(e) => panic!("Invalid {} {:?}: {}", name, value, e),
+    }
 }
 
 pub fn dest_bucket() -> String {
@@ -121,6 +146,18 @@ pub fn retry_queue_url() -> String {
     std::env::var("RETRY_QUEUE_URL").expect("RETRY_QUEUE_URL")
 }
 
+pub fn source_dir() -> String {
+    std::env::var("SOURCE_DIR").expect("SOURCE_DIR")
+}
+
+pub fn dest_dir() -> String {
+    std::env::var("DEST_DIR").expect("DEST_DIR")
+}
+
+pub fn dead_letter_dir() -> String {
+    std::env::var("DEAD_LETTER_DIR").expect("DEAD_LETTER_DIR")
+}
+
 pub fn mg_alphas() -> Vec<String> {
     return std::env::var("MG_ALPHAS")
         .expect("MG_ALPHAS")
@@ -234,3 +271,16 @@ pub fn asset_id_mappings_table_name() -> String {
 pub fn source_compression() -> String {
     std::env::var("SOURCE_COMPRESSION").unwrap_or(String::from("Zstd"))
 }
+
+pub fn to_one_edge_policy() -> String {
+    std::env::var("TO_ONE_EDGE_POLICY").unwrap_or(String::from("Reject"))
+}
+
+/// Whether the graph-merger rejects subgraphs that don't conform to the schemas, from
+/// `ENFORCE_GRAPH_SCHEMA`
+pub fn enforce_graph_schema() -> bool {
+    std::env::var("ENFORCE_GRAPH_SCHEMA")
+        .ok()
+        .and_then(|enforce| enforce.parse::<bool>().ok())
+        .unwrap_or(false)
+}
@@ -0,0 +1,229 @@
+use std::{
+    fmt::Debug,
+    str::FromStr,
+};
+
+use grapl_observe::metric_reporter::MetricReporter;
+use sqs_executor::{
+    cache::Cache,
+    completion_event_serializer::CompletionEventSerializer,
+    directory_event_emitter::DirectoryEventEmitter,
+    directory_event_source::DirectoryEventSource,
+    errors::CheckedError,
+    event_decoder::PayloadDecoder,
+    event_handler::EventHandler,
+    inline_payload_retriever::InlinePayloadRetriever,
+    s3_event_retriever::S3PayloadRetriever,
+};
+use tracing::info;
+
+use crate::{
+    env_helpers::{
+        init_s3_client,
+        s3_event_emitter_from_env,
+        sqs_event_source_from_env,
+    },
+    ServiceEnv,
+};
+
+/// Where a service receives events from and emits its results to, from `EVENT_SOURCE`
+///
+/// * `sqs` (the default) - notifications from `SOURCE_QUEUE_URL` of payloads in S3, with
+///   results written to `DEST_BUCKET_NAME`
+/// * `directory` - files in `SOURCE_DIR`, with results written to `DEST_DIR` and dead letters
+///   moved to `DEAD_LETTER_DIR`
+/// * `kafka` - messages on `KAFKA_SOURCE_TOPIC`, with results produced to `KAFKA_DEST_TOPIC` and
+///   dead letters to `KAFKA_DEAD_LETTER_TOPIC`. Only available with the `kafka` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventSourceKind {
+    Sqs,
+    Directory,
+    Kafka,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown event source: {0}")]
+pub struct UnknownEventSource(String);
+
+impl FromStr for EventSourceKind {
+    type Err = UnknownEventSource;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sqs" => Ok(Self::Sqs),
+            "directory" => Ok(Self::Directory),
+            "kafka" => Ok(Self::Kafka),
+            other => Err(UnknownEventSource(other.to_owned())),
+        }
+    }
+}
+
+pub fn event_source_kind() -> EventSourceKind {
+    std::env::var("EVENT_SOURCE")
+        .map(|event_source| EventSourceKind::from_str(&event_source).expect("EVENT_SOURCE"))
+        .unwrap_or(EventSourceKind::Sqs)
+}
+
+/// Runs `process_loop` for the event source configured by `EVENT_SOURCE`, decoding each payload
+/// with the decoder and emitting the serialized results under keys from the key function
+pub async fn run_service<
+    CacheT,
+    EventHandlerT,
+    InputEventT,
+    OutputEventT,
+    HandlerErrorT,
+    DecoderT,
+    DecoderErrorT,
+    SerializerT,
+    SerializerErrorT,
+    KeyFnT,
+>(
+    env: &ServiceEnv,
+    cache: CacheT,
+    event_handler: EventHandlerT,
+    decoder: DecoderT,
+    serializer: SerializerT,
+    key_fn: KeyFnT,
+) where
+    CacheT: Cache + Clone + Send + Sync + 'static,
+    EventHandlerT: EventHandler<InputEvent = InputEventT, OutputEvent = OutputEventT, Error = HandlerErrorT>
+        + Clone
+        + Send
+        + 'static,
+    InputEventT: Send + 'static,
+    OutputEventT: Clone + Send + Sync + 'static,
+    HandlerErrorT: CheckedError + Debug + Send + Sync + 'static,
+    DecoderT:
+        PayloadDecoder<InputEventT, DecoderError = DecoderErrorT> + Clone + Send + Sync + 'static,
+    DecoderErrorT: CheckedError + Send + 'static,
+    SerializerT: CompletionEventSerializer<
+            CompletedEvent = OutputEventT,
+            Output = Vec<u8>,
+            Error = SerializerErrorT,
+        > + Clone
+        + Send
+        + 'static,
+    SerializerErrorT: CheckedError + Debug + Send + Sync + 'static,
+    KeyFnT: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    let event_source_kind = event_source_kind();
+    info!(event_source=?event_source_kind, "Starting process_loop");
+
+    match event_source_kind {
+        EventSourceKind::Sqs => {
+            sqs_executor::process_loop(
+                sqs_event_source_from_env(env),
+                cache,
+                event_handler,
+                S3PayloadRetriever::new(
+                    |region_str| init_s3_client(&region_str),
+                    decoder,
+                    MetricReporter::new(&env.service_name),
+                ),
+                s3_event_emitter_from_env(env, key_fn),
+                serializer,
+                crate::executor_config(),
+                MetricReporter::new(&env.service_name),
+            )
+            .await
+        }
+        EventSourceKind::Directory => {
+            sqs_executor::process_loop(
+                DirectoryEventSource::new(crate::source_dir(), crate::dead_letter_dir()),
+                cache,
+                event_handler,
+                InlinePayloadRetriever::new(decoder),
+                DirectoryEventEmitter::new(crate::dest_dir(), key_fn),
+                serializer,
+                crate::executor_config(),
+                MetricReporter::new(&env.service_name),
+            )
+            .await
+        }
+        #[cfg(feature = "kafka")]
+        EventSourceKind::Kafka => {
+            sqs_executor::process_loop(
+                kafka::kafka_event_source_from_env(env),
+                cache,
+                event_handler,
+                InlinePayloadRetriever::new(decoder),
+                kafka::kafka_event_emitter_from_env(key_fn),
+                serializer,
+                crate::executor_config(),
+                MetricReporter::new(&env.service_name),
+            )
+            .await
+        }
+        #[cfg(not(feature = "kafka"))]
+        EventSourceKind::Kafka => {
+            panic!("EVENT_SOURCE is kafka, but this service was built without the kafka feature")
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use rdkafka::{
+        consumer::{
+            Consumer,
+            StreamConsumer,
+        },
+        producer::FutureProducer,
+        ClientConfig,
+    };
+    use sqs_executor::{
+        kafka_event_emitter::KafkaEventEmitter,
+        kafka_event_source::KafkaEventSource,
+    };
+
+    use crate::ServiceEnv;
+
+    fn kafka_client_config() -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config.set(
+            "bootstrap.servers",
+            &std::env::var("KAFKA_BOOTSTRAP_SERVERS").expect("KAFKA_BOOTSTRAP_SERVERS"),
+        );
+        config
+    }
+
+    fn kafka_producer() -> FutureProducer {
+        kafka_client_config()
+            .create()
+            .expect("Failed to create Kafka producer")
+    }
+
+    /// Consumes `KAFKA_SOURCE_TOPIC` as the `KAFKA_CONSUMER_GROUP` group, which defaults to the
+    /// service's name
+    pub(super) fn kafka_event_source_from_env(env: &ServiceEnv) -> KafkaEventSource {
+        let source_topic = std::env::var("KAFKA_SOURCE_TOPIC").expect("KAFKA_SOURCE_TOPIC");
+        let consumer_group =
+            std::env::var("KAFKA_CONSUMER_GROUP").unwrap_or_else(|_| env.service_name.clone());
+
+        let consumer: StreamConsumer = kafka_client_config()
+            .set("group.id", &consumer_group)
+            .set("enable.auto.offset.store", "false")
+            .create()
+            .expect("Failed to create Kafka consumer");
+        consumer
+            .subscribe(&[source_topic.as_str()])
+            .expect("Failed to subscribe to KAFKA_SOURCE_TOPIC");
+
+        KafkaEventSource::new(
+            consumer,
+            kafka_producer(),
+            std::env::var("KAFKA_DEAD_LETTER_TOPIC").expect("KAFKA_DEAD_LETTER_TOPIC"),
+        )
+    }
+
+    pub(super) fn kafka_event_emitter_from_env<F>(key_fn: F) -> KafkaEventEmitter<F>
+    where
+        F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+    {
+        KafkaEventEmitter::new(
+            kafka_producer(),
+            std::env::var("KAFKA_DEST_TOPIC").expect("KAFKA_DEST_TOPIC"),
+            key_fn,
+        )
+    }
+}
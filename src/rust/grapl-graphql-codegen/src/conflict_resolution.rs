@@ -12,6 +12,7 @@ use crate::{
         PSEUDO_KEY,
         STATIC_ID,
         TERMINATE_TIME,
+        UNION,
     },
     errors::CodeGenError,
 };
@@ -27,6 +28,8 @@ pub enum ConflictResolution {
     IncrementOnly,
     /// Given two values, choose the lesser of thet two
     DecrementOnly,
+    /// Given two sets, keep every value from both
+    Union,
 }
 
 impl ConflictResolution {
@@ -35,6 +38,7 @@ impl ConflictResolution {
             Self::Immutable => true,
             Self::IncrementOnly => false,
             Self::DecrementOnly => false,
+            Self::Union => false,
         }
     }
 
@@ -48,6 +52,7 @@ impl ConflictResolution {
             INCREMENT_ONLY => Some(ConflictResolution::IncrementOnly),
             DECREMENT_ONLY => Some(ConflictResolution::DecrementOnly),
             IMMUTABLE => Some(ConflictResolution::Immutable),
+            UNION => Some(ConflictResolution::Union),
             _ => None,
         }
     }
@@ -62,6 +67,7 @@ mod tests {
         assert_eq!(ConflictResolution::Immutable.implies_cacheable(), true);
         assert_eq!(ConflictResolution::IncrementOnly.implies_cacheable(), false);
         assert_eq!(ConflictResolution::DecrementOnly.implies_cacheable(), false);
+        assert_eq!(ConflictResolution::Union.implies_cacheable(), false);
     }
 }
 
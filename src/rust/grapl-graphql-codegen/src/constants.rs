@@ -8,10 +8,15 @@ pub const TERMINATE_TIME: &'static str = "terminate_time";
 pub const IMMUTABLE: &'static str = "immutable";
 pub const INCREMENT_ONLY: &'static str = "increment_only";
 pub const DECREMENT_ONLY: &'static str = "decrement_only";
+pub const UNION: &'static str = "union";
 
 pub const STRING: &'static str = "String";
 pub const INT: &'static str = "Int";
 pub const UINT: &'static str = "UInt";
+pub const BOOLEAN: &'static str = "Boolean";
+pub const FLOAT: &'static str = "Float";
+pub const BYTES: &'static str = "Bytes";
+pub const TIMESTAMP: &'static str = "Timestamp";
 
 pub const SESSION_ALGORITHM: &'static str = "session";
 pub const STATIC_ALGORITHM: &'static str = "static";
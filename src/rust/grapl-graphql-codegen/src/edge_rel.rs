@@ -6,11 +6,19 @@ use crate::errors::CodeGenError;
 
 /// EdgeRel describes the bi-directional relationship of an edge in terms of
 /// whether the edge points to one or many nodes
-#[derive(Copy, Debug, Clone)]
+///
+/// Relationships are named from the sources to the targets, as grapl_analyzerlib's
+/// `EdgeRelationship` names them: a `ManyToOne` edge points each of many sources at one
+/// target, so the edge itself is to-one and its reverse is to-many.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
 pub enum EdgeRel {
+    /// A to-one edge whose reverse is to-one
     OneToOne,
+    /// A to-many edge whose reverse is to-one
     OneToMany,
+    /// A to-one edge whose reverse is to-many
     ManyToOne,
+    /// A to-many edge whose reverse is to-many
     ManyToMany,
 }
 
@@ -95,12 +103,101 @@ impl<'a> TryFrom<&Field<'a, &'a str>> for EdgeRel {
             },
         };
 
+        // The relationship is named from the sources to the targets, so a to-one edge whose
+        // reverse is to-many is ManyToOne. grapl_analyzerlib reads the relationship the same way,
+        // so the generated Python only marks to-many edges as `is_to_many`.
         match (forward_rel, reverse_rel) {
             ("ToOne", "ToOne") => Ok(EdgeRel::OneToOne),
-            ("ToOne", "ToMany") => Ok(EdgeRel::OneToMany),
-            ("ToMany", "ToOne") => Ok(EdgeRel::ManyToOne),
+            ("ToOne", "ToMany") => Ok(EdgeRel::ManyToOne),
+            ("ToMany", "ToOne") => Ok(EdgeRel::OneToMany),
             ("ToMany", "ToMany") => Ok(EdgeRel::ManyToMany),
             (_, _) => unreachable!(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use graphql_parser::schema::{
+        parse_schema,
+        Definition,
+        TypeDefinition,
+    };
+
+    use super::*;
+    use crate::edge::Edge;
+
+    fn edge_rels(schema: &str) -> Vec<(String, EdgeRel)> {
+        let document = parse_schema::<&str>(schema).expect("schema");
+        let mut edge_rels = vec![];
+        for definition in document.definitions.iter() {
+            if let Definition::TypeDefinition(TypeDefinition::Object(object)) = definition {
+                for field in object.fields.iter() {
+                    edge_rels.push((field.name.to_string(), EdgeRel::try_from(field).unwrap()));
+                }
+            }
+        }
+        edge_rels
+    }
+
+    #[test]
+    fn edge_rel_from_field() {
+        let edge_rels = edge_rels(
+            r#"
+            type Process @grapl(identity_algorithm: "session") {
+                binary_file: File! @edge(reverse: "executed_as_processes", reverse_relationship: "ToMany"),
+                created_files: [File!] @edge(reverse: "creator", reverse_relationship: "ToOne"),
+                read_files: [File!] @edge(reverse: "readers", reverse_relationship: "ToMany"),
+                lock_file: File @edge(reverse: "locked_by", reverse_relationship: "ToOne"),
+            }
+            "#,
+        );
+        let to_one: Vec<_> = edge_rels
+            .iter()
+            .map(|(edge_name, edge_rel)| (edge_name.as_str(), edge_rel.to_one()))
+            .collect();
+        assert_eq!(
+            to_one,
+            vec![
+                ("binary_file", true),
+                ("created_files", false),
+                ("read_files", false),
+                ("lock_file", true),
+            ]
+        );
+        assert!(matches!(edge_rels[0].1, EdgeRel::ManyToOne));
+        assert!(matches!(edge_rels[1].1, EdgeRel::OneToMany));
+        assert!(matches!(edge_rels[2].1, EdgeRel::ManyToMany));
+        assert!(matches!(edge_rels[3].1, EdgeRel::OneToOne));
+    }
+
+    #[test]
+    fn generated_python_marks_only_to_many_edges_as_to_many() {
+        let document = parse_schema::<&str>(
+            r#"
+            type Process @grapl(identity_algorithm: "session") {
+                binary_file: File! @edge(reverse: "executed_as_processes", reverse_relationship: "ToMany"),
+                created_files: [File!] @edge(reverse: "creator", reverse_relationship: "ToOne"),
+            }
+            "#,
+        )
+        .expect("schema");
+        let mut relationships = vec![];
+        for definition in document.definitions.iter() {
+            if let Definition::TypeDefinition(TypeDefinition::Object(object)) = definition {
+                for field in object.fields.iter() {
+                    let edge = Edge::try_from((object.name.to_string(), field)).unwrap();
+                    relationships.push(edge.generate_edge_relationship());
+                }
+            }
+        }
+
+        // EdgeRelationship.ManyToOne.is_to_many() is False, and OneToMany.is_to_many() is True
+        assert!(relationships[0].contains(
+            "EdgeT(ProcessSchema, FileSchema, grapl_analyzerlib.node_types.EdgeRelationship.ManyToOne)"
+        ));
+        assert!(relationships[1].contains(
+            "EdgeT(ProcessSchema, FileSchema, grapl_analyzerlib.node_types.EdgeRelationship.OneToMany)"
+        ));
+    }
+}
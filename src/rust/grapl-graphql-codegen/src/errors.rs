@@ -1,6 +1,9 @@
 use graphql_parser::schema::Directive;
 
-use crate::node_type::MergeFailure;
+use crate::{
+    node_type::MergeFailure,
+    predicate_type::PredicateType,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum CodeGenError<'a> {
@@ -22,6 +25,10 @@ pub enum CodeGenError<'a> {
     MissingGraplDirectiveArguments {
         directives: Vec<Directive<'a, &'a str>>,
     },
+    #[error("Unsupported predicate type: {0}")]
+    UnsupportedPredicateType(&'a str),
+    #[error("Unsupported set of {0:?}, only sets of strings are supported")]
+    UnsupportedSetType(PredicateType),
     #[error("Failed to extend node schema")]
     MergeFailure(#[from] MergeFailure),
 }
@@ -72,11 +72,25 @@ impl NodePredicate {
         comparisons
     }
 
+    // The analyzerlib's range comparisons are integer only, so floats are compared by equality
+    pub fn generate_python_float_comparisons(&self) -> String {
+        r#"        eq: Optional["grapl_analyzerlib.comparators.FloatOrNot"] = None,"#.to_string()
+    }
+
+    pub fn generate_python_bool_comparisons(&self) -> String {
+        r#"        eq: Optional[bool] = None,"#.to_string()
+    }
+
     pub fn generate_python_query_comparisons(&self) -> String {
         match self.predicate_type {
             PredicateType::String => self.generate_python_str_comparisons(),
             PredicateType::I64 => self.generate_python_int_comparisons(),
             PredicateType::U64 => self.generate_python_int_comparisons(),
+            PredicateType::Bool => self.generate_python_bool_comparisons(),
+            PredicateType::F64 => self.generate_python_float_comparisons(),
+            PredicateType::Bytes => self.generate_python_str_comparisons(),
+            PredicateType::StrSet => self.generate_python_str_comparisons(),
+            PredicateType::Timestamp => self.generate_python_int_comparisons(),
         }
     }
 
@@ -94,7 +108,7 @@ impl NodePredicate {
         query_def = query_def + "                \"" + &self.predicate_name + "\",\n";
 
         match self.predicate_type {
-            PredicateType::String => {
+            PredicateType::String | PredicateType::Bytes | PredicateType::StrSet => {
                 query_def += "                eq=eq,\n";
                 query_def += "                contains=contains,\n";
                 query_def += "                starts_with=starts_with,\n";
@@ -102,13 +116,16 @@ impl NodePredicate {
                 query_def += "                regexp=regexp,\n";
                 query_def += "                distance_lt=distance_lt\n";
             }
-            PredicateType::U64 | PredicateType::I64 => {
+            PredicateType::U64 | PredicateType::I64 | PredicateType::Timestamp => {
                 query_def += "                eq=eq,\n";
                 query_def += "                gt=gt,\n";
                 query_def += "                ge=ge,\n";
                 query_def += "                lt=lt,\n";
                 query_def += "                le=le,\n";
             }
+            PredicateType::Bool | PredicateType::F64 => {
+                query_def += "                eq=eq,\n";
+            }
         }
         query_def += "            )\n";
         query_def += "        )\n";
@@ -120,7 +137,8 @@ impl NodePredicate {
         let mut get_method = String::with_capacity(512);
 
         let predicate_name = self.predicate_name.as_str();
-        let py_ty = self.predicate_type.into_python_primitive_type();
+        let py_ty = self.predicate_type.into_python_type();
+        let get_method_name = self.predicate_type.into_python_get_method();
         let cached = self
             .conflict_resolution
             .implies_cacheable()
@@ -133,8 +151,8 @@ impl NodePredicate {
             + "\n";
         get_method = get_method
             + &format!(
-                r#"        return self.get_{}("{}", cached=cached)"#,
-                py_ty, predicate_name
+                r#"        return self.{}("{}", cached=cached)"#,
+                get_method_name, predicate_name
             )
             + "\n\n";
 
@@ -172,7 +190,7 @@ pub fn is_nullable<'a>(field_type: &Type<'a, &'a str>) -> bool {
     match field_type {
         Type::NonNullType(_) => false,
         Type::NamedType(_) => true,
-        Type::ListType(_) => panic!("ListType not supported"),
+        Type::ListType(_) => true,
     }
 }
 
@@ -196,4 +214,21 @@ mod tests {
             expected_str
         );
     }
+
+    #[test]
+    fn generate_viewable_get_str_set_predicate_method() {
+        let expected_str = "    def get_arguments(self, cached: bool = False) -> Optional[Set[str]]:\n        return self.get_str_set(\"arguments\", cached=cached)\n\n";
+        let node_predicate = NodePredicate {
+            predicate_name: String::from("arguments"),
+            description: None,
+            predicate_type: PredicateType::StrSet,
+            conflict_resolution: ConflictResolution::Union,
+            identity_predicate_type: None,
+            nullable: true,
+        };
+        assert_eq!(
+            node_predicate.generate_viewable_get_predicate_method(),
+            expected_str
+        );
+    }
 }
@@ -23,7 +23,7 @@ use crate::{
 // Python Queryable generation
 pub fn generate_parameter_from_predicate(predicate: &NodePredicate) -> String {
     let parameter_name = predicate.predicate_name.as_str();
-    let parameter_ty = predicate.predicate_type.into_python_primitive_type();
+    let parameter_ty = predicate.predicate_type.into_python_type();
     format!(
         r#"{}: Optional["{}"] = None,"#,
         parameter_name, parameter_ty
@@ -115,6 +115,7 @@ impl NodeType {
             ));
         }
         self.predicates.extend(other.predicates.into_iter());
+        self.edges.extend(other.edges.into_iter());
 
         Ok(())
     }
@@ -525,7 +526,7 @@ pub fn parse_into_node_types<'a>(
             NodeTypeOrExtension::NodeType(node_type) => node_types.push(node_type),
             NodeTypeOrExtension::NodeExtension(node_extension) => {
                 node_extensions
-                    .entry(type_name.to_string())
+                    .entry(node_extension.extends_type.clone())
                     .or_insert(vec![])
                     .push(node_extension);
             }
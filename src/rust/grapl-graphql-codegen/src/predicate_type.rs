@@ -7,8 +7,12 @@ use graphql_parser::schema::Type;
 
 use crate::{
     constants::{
+        BOOLEAN,
+        BYTES,
+        FLOAT,
         INT,
         STRING,
+        TIMESTAMP,
         UINT,
     },
     errors::CodeGenError,
@@ -20,6 +24,14 @@ pub enum PredicateType {
     String,
     I64,
     U64,
+    Bool,
+    F64,
+    /// Stored as a hex string, as Dgraph has no bytes type
+    Bytes,
+    /// A set of strings, declared as `[String]`
+    StrSet,
+    /// Milliseconds since the unix epoch, stored as an int like Grapl's other timestamps
+    Timestamp,
 }
 
 // Python code generation
@@ -29,6 +41,11 @@ impl PredicateType {
             PredicateType::String => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Str, False)",
             PredicateType::I64 => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Int, False)",
             PredicateType::U64 => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Int, False)",
+            PredicateType::Bool => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Bool, False)",
+            PredicateType::F64 => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Float, False)",
+            PredicateType::Bytes => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Str, False)",
+            PredicateType::StrSet => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Str, True)",
+            PredicateType::Timestamp => "grapl_analyzerlib.node_types.PropType(grapl_analyzerlib.node_types.PropPrimitive.Int, False)",
         }.to_string()
     }
 
@@ -37,15 +54,41 @@ impl PredicateType {
             PredicateType::String => "str",
             PredicateType::I64 => "int",
             PredicateType::U64 => "int",
+            PredicateType::Bool => "bool",
+            PredicateType::F64 => "float",
+            PredicateType::Bytes => "str",
+            PredicateType::StrSet => "str",
+            PredicateType::Timestamp => "int",
         }
         .to_string()
     }
 
+    /// The Python type of the predicate's value, which is a set of its primitive type for sets
+    pub fn into_python_type(self) -> String {
+        match self {
+            PredicateType::StrSet => "Set[str]".to_string(),
+            _ => self.into_python_primitive_type(),
+        }
+    }
+
+    /// The Viewable method that gets the predicate's value
+    pub fn into_python_get_method(self) -> String {
+        match self {
+            PredicateType::StrSet => "get_str_set".to_string(),
+            _ => format!("get_{}", self.into_python_primitive_type()),
+        }
+    }
+
     pub fn into_python_primitive_type_or_not(self) -> String {
         match self {
             PredicateType::String => "StrOrNot",
             PredicateType::I64 => "IntOrNot",
             PredicateType::U64 => "IntOrNot",
+            PredicateType::Bool => "bool",
+            PredicateType::F64 => "FloatOrNot",
+            PredicateType::Bytes => "StrOrNot",
+            PredicateType::StrSet => "StrOrNot",
+            PredicateType::Timestamp => "IntOrNot",
         }
         .to_string()
     }
@@ -57,17 +100,21 @@ impl<'a> TryFrom<&Type<'a, &'a str>> for PredicateType {
     #[tracing::instrument]
     fn try_from(value: &Type<'a, &'a str>) -> Result<Self, Self::Error> {
         match value {
-            Type::NamedType(value) => {
-                match *value {
-                    STRING => Ok(PredicateType::String),
-                    INT => Ok(PredicateType::I64),
-                    UINT => Ok(PredicateType::U64),
-                    // todo: error
-                    unsupported => panic!("Unsupported type: {}", unsupported),
-                }
-            }
+            Type::NamedType(value) => match *value {
+                STRING => Ok(PredicateType::String),
+                INT => Ok(PredicateType::I64),
+                UINT => Ok(PredicateType::U64),
+                BOOLEAN => Ok(PredicateType::Bool),
+                FLOAT => Ok(PredicateType::F64),
+                BYTES => Ok(PredicateType::Bytes),
+                TIMESTAMP => Ok(PredicateType::Timestamp),
+                unsupported => Err(CodeGenError::UnsupportedPredicateType(unsupported)),
+            },
             Type::NonNullType(ref value) => value.as_ref().try_into(),
-            Type::ListType(_) => todo!("We don't currently support sets of values in Grapl"),
+            Type::ListType(ref value) => match value.as_ref().try_into()? {
+                PredicateType::String => Ok(PredicateType::StrSet),
+                unsupported => Err(CodeGenError::UnsupportedSetType(unsupported)),
+            },
         }
     }
 }
@@ -90,5 +137,52 @@ mod tests {
             PredicateType::U64.into_python_primitive_type_or_not(),
             "IntOrNot"
         );
+        assert_eq!(
+            PredicateType::F64.into_python_primitive_type_or_not(),
+            "FloatOrNot"
+        );
+    }
+
+    #[test]
+    fn test_predicate_type_from_graphql() {
+        let parse = |field_type: &str| {
+            let schema = format!("type Node {{ field: {} }}", field_type);
+            let document = graphql_parser::parse_schema::<&str>(&schema).unwrap();
+            match &document.definitions[0] {
+                graphql_parser::schema::Definition::TypeDefinition(
+                    graphql_parser::schema::TypeDefinition::Object(object),
+                ) => PredicateType::try_from(&object.fields[0].field_type).unwrap(),
+                _ => unreachable!(),
+            }
+        };
+
+        assert!(matches!(parse("Boolean!"), PredicateType::Bool));
+        assert!(matches!(parse("Float"), PredicateType::F64));
+        assert!(matches!(parse("Bytes"), PredicateType::Bytes));
+        assert!(matches!(parse("[String!]"), PredicateType::StrSet));
+        assert!(matches!(parse("[String]!"), PredicateType::StrSet));
+        assert!(matches!(parse("Timestamp!"), PredicateType::Timestamp));
+    }
+
+    #[test]
+    fn test_unsupported_predicate_types_are_errors() {
+        let parse = |field_type: &str| {
+            let schema = format!("type Node {{ field: {} }}", field_type);
+            let document = graphql_parser::parse_schema::<&str>(&schema).unwrap();
+            match &document.definitions[0] {
+                graphql_parser::schema::Definition::TypeDefinition(
+                    graphql_parser::schema::TypeDefinition::Object(object),
+                ) => PredicateType::try_from(&object.fields[0].field_type)
+                    .unwrap_err()
+                    .to_string(),
+                _ => unreachable!(),
+            }
+        };
+
+        assert_eq!(parse("Date"), "Unsupported predicate type: Date");
+        assert_eq!(
+            parse("[Int]"),
+            "Unsupported set of I64, only sets of strings are supported"
+        );
     }
 }
@@ -53,22 +53,33 @@ impl CheckedError for PayloadDecompressionError {
     }
 }
 
-pub fn maybe_decompress(input: &[u8]) -> Result<Vec<u8>, PayloadDecompressionError> {
-    let value = grapl_config::source_compression();
-    match PayloadDecompression::from_str(value.as_str()).expect("PayloadDecompression") {
-        PayloadDecompression::Gzip => {
-            let mut decoder = GzDecoder::new(input)?;
-            let mut decoded_data = Vec::with_capacity(input.len());
+impl PayloadDecompression {
+    /// Reads the compression of payloads from `SOURCE_COMPRESSION`
+    pub fn from_env() -> Self {
+        let value = grapl_config::source_compression();
+        PayloadDecompression::from_str(value.as_str()).expect("PayloadDecompression")
+    }
 
-            decoder.read_to_end(&mut decoded_data)?;
+    pub fn decompress(&self, input: &[u8]) -> Result<Vec<u8>, PayloadDecompressionError> {
+        match self {
+            PayloadDecompression::Gzip => {
+                let mut decoder = GzDecoder::new(input)?;
+                let mut decoded_data = Vec::with_capacity(input.len());
 
-            Ok(decoded_data)
-        }
-        PayloadDecompression::None => Ok(input.to_vec()),
-        PayloadDecompression::Zstd => {
-            let body = Cursor::new(input);
+                decoder.read_to_end(&mut decoded_data)?;
 
-            zstd::stream::decode_all(body).map_err(|e| e.into())
+                Ok(decoded_data)
+            }
+            PayloadDecompression::None => Ok(input.to_vec()),
+            PayloadDecompression::Zstd => {
+                let body = Cursor::new(input);
+
+                zstd::stream::decode_all(body).map_err(|e| e.into())
+            }
         }
     }
 }
+
+pub fn maybe_decompress(input: &[u8]) -> Result<Vec<u8>, PayloadDecompressionError> {
+    PayloadDecompression::from_env().decompress(input)
+}
@@ -7,7 +7,10 @@ use sqs_executor::{
     event_decoder::PayloadDecoder,
 };
 
-use crate::decoder::decompress::PayloadDecompressionError;
+use crate::decoder::decompress::{
+    PayloadDecompression,
+    PayloadDecompressionError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum JsonDecoderError {
@@ -26,8 +29,23 @@ impl CheckedError for JsonDecoderError {
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct JsonDecoder;
+#[derive(Debug, Clone)]
+pub struct JsonDecoder {
+    decompression: PayloadDecompression,
+}
+
+impl JsonDecoder {
+    pub fn new(decompression: PayloadDecompression) -> Self {
+        Self { decompression }
+    }
+}
+
+impl Default for JsonDecoder {
+    /// Decompresses payloads as `SOURCE_COMPRESSION` says
+    fn default() -> Self {
+        Self::new(PayloadDecompression::from_env())
+    }
+}
 
 impl<D> PayloadDecoder<D> for JsonDecoder
 where
@@ -35,7 +53,7 @@ where
 {
     type DecoderError = JsonDecoderError;
     fn decode(&mut self, body: Vec<u8>) -> Result<D, Self::DecoderError> {
-        let decompressed = super::decompress::maybe_decompress(body.as_slice())?;
+        let decompressed = self.decompression.decompress(body.as_slice())?;
 
         serde_json::from_slice(&decompressed).map_err(|e| e.into())
     }
@@ -3,6 +3,7 @@ pub mod zstd_proto_graph;
 pub use zstd_proto_graph::{
     GraphDescriptionSerializer,
     GraphDescriptionSerializerError,
+    GraphSerializerError,
     IdentifiedGraphSerializer,
     IdentifiedGraphSerializerError,
     MergedGraphSerializer,
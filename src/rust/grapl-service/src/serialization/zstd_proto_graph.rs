@@ -1,11 +1,39 @@
 use grapl_graph_descriptions::graph_description::*;
 use prost::EncodeError;
-use sqs_executor::completion_event_serializer::CompletionEventSerializer;
+use sqs_executor::{
+    completion_event_serializer::CompletionEventSerializer,
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+};
 use tracing::{
     debug,
     error,
 };
 
+/// The error shared by the graph serializers.
+///
+/// Serializing is deterministic, so the same events would fail again and every error is
+/// persistent.
+#[derive(thiserror::Error, Debug)]
+pub enum GraphSerializerError {
+    #[error("IO")]
+    Io(#[from] std::io::Error),
+    #[error("EncodeError")]
+    EncodeError(#[from] EncodeError),
+}
+
+impl CheckedError for GraphSerializerError {
+    fn error_type(&self) -> Recoverable {
+        Recoverable::Persistent
+    }
+}
+
+pub type GraphDescriptionSerializerError = GraphSerializerError;
+pub type IdentifiedGraphSerializerError = GraphSerializerError;
+pub type MergedGraphSerializerError = GraphSerializerError;
+
 /// Takes a subgraph generated by a Generator and serializes it so it can be returned by a
 /// lambda as an execution result.
 #[derive(Clone, Debug, Default)]
@@ -20,14 +48,6 @@ impl GraphDescriptionSerializer {
     }
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum GraphDescriptionSerializerError {
-    #[error("IO")]
-    Io(#[from] std::io::Error),
-    #[error("EncodeError")]
-    EncodeError(#[from] EncodeError),
-}
-
 impl CompletionEventSerializer for GraphDescriptionSerializer {
     type CompletedEvent = GraphDescription;
     type Output = Vec<u8>;
@@ -90,14 +110,6 @@ impl CompletionEventSerializer for GraphDescriptionSerializer {
     }
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum IdentifiedGraphSerializerError {
-    #[error("IO")]
-    Io(#[from] std::io::Error),
-    #[error("EncodeError")]
-    EncodeError(#[from] EncodeError),
-}
-
 #[derive(Clone, Debug, Default)]
 pub struct IdentifiedGraphSerializer {
     proto: Vec<u8>,
@@ -171,14 +183,6 @@ impl CompletionEventSerializer for IdentifiedGraphSerializer {
     }
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum MergedGraphSerializerError {
-    #[error("IO")]
-    Io(#[from] std::io::Error),
-    #[error("EncodeError")]
-    EncodeError(#[from] EncodeError),
-}
-
 #[derive(Clone, Debug, Default)]
 pub struct MergedGraphSerializer {
     proto: Vec<u8>,
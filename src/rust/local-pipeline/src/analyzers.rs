@@ -0,0 +1,61 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use analyzer_dispatcher_lib::AnalyzerLister;
+use async_trait::async_trait;
+use failure::{
+    format_err,
+    Error,
+};
+
+/// Lists analyzers from a local directory laid out like the analyzers bucket, so every file
+/// under the directory is dispatched to by its path relative to the directory. Without a
+/// directory no analyzers are dispatched to.
+#[derive(Debug, Clone, Default)]
+pub struct LocalAnalyzerLister {
+    analyzers_dir: Option<PathBuf>,
+}
+
+impl LocalAnalyzerLister {
+    pub fn new(analyzers_dir: Option<PathBuf>) -> Self {
+        Self { analyzers_dir }
+    }
+}
+
+#[async_trait]
+impl AnalyzerLister for LocalAnalyzerLister {
+    async fn list_analyzers(&self) -> Result<Vec<Result<String, Error>>, Error> {
+        let analyzers_dir = match self.analyzers_dir {
+            Some(ref analyzers_dir) => analyzers_dir,
+            None => return Ok(vec![]),
+        };
+
+        let mut keys = Vec::new();
+        let mut dirs = vec![analyzers_dir.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    dirs.push(path);
+                } else {
+                    keys.push(analyzer_key(analyzers_dir, &path));
+                }
+            }
+        }
+
+        keys.sort_by_key(|key| key.as_ref().ok().cloned());
+
+        Ok(keys)
+    }
+}
+
+fn analyzer_key(analyzers_dir: &Path, path: &Path) -> Result<String, Error> {
+    let key = path.strip_prefix(analyzers_dir)?;
+    key.to_str()
+        .map(str::to_owned)
+        .ok_or_else(|| format_err!("Analyzer path is not valid utf-8: {:?}", key))
+}
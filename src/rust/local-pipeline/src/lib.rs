@@ -0,0 +1,284 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use analyzer_dispatcher_lib::AnalyzerDispatcher;
+use generic_subgraph_generator_lib::generator::GenericSubgraphGenerator;
+use graph_merger_lib::{
+    dgraph_graph_store::DgraphGraphStore,
+    graph_store::GraphStore,
+    in_memory_graph_store::InMemoryGraphStore,
+    schema_registry::{
+        SchemaRegistry,
+        SchemaRegistryError,
+    },
+    service::GraphMerger,
+    upserter::{
+        InvalidToOneEdgePolicy,
+        ToOneEdgePolicy,
+    },
+};
+use grapl_graph_descriptions::graph_description::{
+    GraphDescription,
+    MergedGraph,
+};
+use grapl_observe::metric_reporter::MetricReporter;
+use grapl_service::decoder::{
+    decompress::PayloadDecompression,
+    JsonDecoder,
+};
+use node_identifier::{
+    assetdb::AssetIdentifier,
+    dynamic_sessiondb::NodeDescriptionIdentifier,
+    in_memory_assetdb::InMemoryAssetIdDb,
+    in_memory_mappingdb::InMemoryMappingDb,
+    in_memory_sessiondb::InMemorySessionDb,
+    NodeIdentifier,
+};
+use osquery_generator_lib::{
+    generator::OSQueryGenerator,
+    metrics::OSQueryGeneratorMetrics,
+    parsers::{
+        OSQueryRoutes,
+        OSQueryRoutesError,
+    },
+    serialization::OSQueryDecoder,
+};
+use sqs_executor::{
+    cache::NopCache,
+    event_decoder::PayloadDecoder,
+    event_handler::EventHandler,
+};
+use sysmon_generator_lib::{
+    generator::SysmonGenerator,
+    metrics::SysmonGeneratorMetrics,
+    serialization::SysmonDecoder,
+};
+use tokio::{
+    sync::mpsc::channel,
+    task::JoinHandle,
+};
+use tracing::info;
+
+use crate::{
+    analyzers::LocalAnalyzerLister,
+    report::PipelineReport,
+    source::{
+        read_log_files,
+        LogSource,
+    },
+    stage::run_stage,
+};
+
+pub mod analyzers;
+pub mod report;
+pub mod source;
+pub mod stage;
+
+#[derive(Debug, Clone)]
+pub struct LocalPipelineConfig {
+    pub service_name: String,
+    pub log_source: LogSource,
+    pub log_paths: Vec<PathBuf>,
+    /// How the log files are compressed
+    pub decompression: PayloadDecompression,
+    /// A directory laid out like the analyzers bucket. No analyzers are dispatched to without one.
+    pub analyzers_dir: Option<PathBuf>,
+    /// How many events may be waiting between any two stages
+    pub channel_capacity: usize,
+    /// Merge graphs in memory rather than into the configured `MG_ALPHAS`
+    pub in_memory_graph: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LocalPipelineError {
+    #[error("Pipeline stage {0} failed to complete: {1}")]
+    StageFailed(&'static str, tokio::task::JoinError),
+    #[error(transparent)]
+    OSQueryRoutes(#[from] OSQueryRoutesError),
+    #[error(transparent)]
+    SchemaRegistry(#[from] SchemaRegistryError),
+    #[error(transparent)]
+    ToOneEdgePolicy(#[from] InvalidToOneEdgePolicy),
+}
+
+/**
+    Runs the generator for the configured log source, the node-identifier, the graph-merger and
+    the analyzer-dispatcher in this process. Events are passed between the services over bounded
+    in-memory channels instead of SQS queues and S3 buckets.
+
+    Sessions, static mappings and asset ids are held in memory for the duration of the run, so
+    node identification needs no DynamoDB. Merged graphs are written to the configured
+    `MG_ALPHAS`, unless `in_memory_graph` is set.
+*/
+pub async fn run_local_pipeline(
+    config: LocalPipelineConfig,
+) -> Result<PipelineReport, LocalPipelineError> {
+    info!(message="Starting local pipeline", log_source=?config.log_source, files=?config.log_paths.len());
+
+    match config.log_source {
+        LogSource::Sysmon => {
+            let generator = SysmonGenerator::new(
+                NopCache {},
+                SysmonGeneratorMetrics::new(&config.service_name),
+            );
+            let decoder = SysmonDecoder::new(config.decompression.clone());
+            run_pipeline(&config, generator, decoder).await
+        }
+        LogSource::OSQuery => {
+            let metrics = OSQueryGeneratorMetrics::new(&config.service_name);
+            let decoder = OSQueryDecoder::new(
+                OSQueryRoutes::from_env()?,
+                metrics.clone(),
+                config.decompression.clone(),
+            );
+            let generator = OSQueryGenerator::new(NopCache {}, metrics);
+            run_pipeline(&config, generator, decoder).await
+        }
+        LogSource::Generic => {
+            let generator = GenericSubgraphGenerator::new(NopCache {});
+            let decoder = JsonDecoder::new(config.decompression.clone());
+            run_pipeline(&config, generator, decoder).await
+        }
+    }
+}
+
+async fn run_pipeline<GeneratorT, DecoderT>(
+    config: &LocalPipelineConfig,
+    generator: GeneratorT,
+    decoder: DecoderT,
+) -> Result<PipelineReport, LocalPipelineError>
+where
+    GeneratorT: EventHandler<OutputEvent = GraphDescription> + Send + 'static,
+    GeneratorT::InputEvent: Send + 'static,
+    DecoderT: PayloadDecoder<GeneratorT::InputEvent>,
+{
+    // The graph-merger's configuration is checked before any stage is started
+    let schema_registry = SchemaRegistry::from_env().await?;
+    let to_one_edge_policy = ToOneEdgePolicy::from_str(&grapl_config::to_one_edge_policy())?;
+
+    let capacity = config.channel_capacity.max(1);
+    let (log_tx, log_rx) = channel(capacity);
+    let (generated_tx, generated_rx) = channel(capacity);
+    let (identified_tx, identified_rx) = channel(capacity);
+    let (merged_tx, mut merged_rx) = channel::<MergedGraph>(capacity);
+    let (undispatched_tx, undispatched_rx) = channel(capacity);
+    let (dispatched_tx, mut dispatched_rx) = channel(capacity);
+
+    let generator = tokio::spawn(run_stage("generator", generator, log_rx, generated_tx));
+    let node_identifier = tokio::spawn(run_stage(
+        "node-identifier",
+        init_node_identifier(&config.service_name),
+        generated_rx,
+        identified_tx,
+    ));
+    let graph_merger = if config.in_memory_graph {
+        tokio::spawn(run_stage(
+            "graph-merger",
+            init_graph_merger(
+                &config.service_name,
+                InMemoryGraphStore::new(),
+                schema_registry,
+                to_one_edge_policy,
+            ),
+            identified_rx,
+            merged_tx,
+        ))
+    } else {
+        tokio::spawn(run_stage(
+            "graph-merger",
+            init_graph_merger(
+                &config.service_name,
+                DgraphGraphStore::new(grapl_config::mg_alphas()),
+                schema_registry,
+                to_one_edge_policy,
+            ),
+            identified_rx,
+            merged_tx,
+        ))
+    };
+    let analyzer_dispatcher = tokio::spawn(run_stage(
+        "analyzer-dispatcher",
+        AnalyzerDispatcher::new(LocalAnalyzerLister::new(config.analyzers_dir.clone())),
+        undispatched_rx,
+        dispatched_tx,
+    ));
+
+    // Every merged graph is recorded on its way to the analyzer-dispatcher
+    let merged_graph = tokio::spawn(async move {
+        let mut merged_graph = MergedGraph::new();
+        while let Some(merged) = merged_rx.recv().await {
+            merged_graph.merge(&merged);
+            if undispatched_tx.send(merged).await.is_err() {
+                break;
+            }
+        }
+        merged_graph
+    });
+
+    let dispatched = tokio::spawn(async move {
+        let mut dispatched = BTreeMap::new();
+        while let Some(dispatch_events) = dispatched_rx.recv().await {
+            for dispatch_event in dispatch_events {
+                *dispatched
+                    .entry(dispatch_event.key().to_owned())
+                    .or_insert(0) += 1;
+            }
+        }
+        dispatched
+    });
+
+    // Dropping the sender once every file is read lets each stage drain and exit in turn
+    let files = read_log_files(&config.log_paths, decoder, log_tx).await;
+
+    Ok(PipelineReport {
+        files,
+        generator: join_stage("generator", generator).await?,
+        node_identifier: join_stage("node-identifier", node_identifier).await?,
+        graph_merger: join_stage("graph-merger", graph_merger).await?,
+        analyzer_dispatcher: join_stage("analyzer-dispatcher", analyzer_dispatcher).await?,
+        merged_graph: join_stage("merged-graph", merged_graph).await?,
+        dispatched: join_stage("dispatched", dispatched).await?,
+    })
+}
+
+async fn join_stage<T>(
+    stage_name: &'static str,
+    stage: JoinHandle<T>,
+) -> Result<T, LocalPipelineError> {
+    stage
+        .await
+        .map_err(|e| LocalPipelineError::StageFailed(stage_name, e))
+}
+
+fn init_node_identifier(
+    service_name: &str,
+) -> NodeIdentifier<NopCache, InMemorySessionDb, InMemoryMappingDb, InMemoryAssetIdDb> {
+    let dynamic_identifier = NodeDescriptionIdentifier::new(
+        InMemorySessionDb::new(),
+        InMemoryMappingDb::new(),
+        AssetIdentifier::new(InMemoryAssetIdDb::new()),
+        MetricReporter::new(service_name),
+        true,
+    );
+
+    NodeIdentifier::new(dynamic_identifier, true, NopCache {})
+}
+
+fn init_graph_merger<GraphStoreT: GraphStore>(
+    service_name: &str,
+    graph_store: GraphStoreT,
+    schema_registry: SchemaRegistry,
+    to_one_edge_policy: ToOneEdgePolicy,
+) -> GraphMerger<GraphStoreT, NopCache> {
+    GraphMerger::new(
+        graph_store,
+        schema_registry,
+        to_one_edge_policy,
+        grapl_config::enforce_graph_schema(),
+        MetricReporter::new(service_name),
+        NopCache {},
+    )
+}
@@ -0,0 +1,93 @@
+use std::{
+    path::PathBuf,
+    str::FromStr,
+};
+
+use grapl_service::decoder::decompress::PayloadDecompression;
+use local_pipeline_lib::{
+    run_local_pipeline,
+    source::LogSource,
+    LocalPipelineConfig,
+};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "local-pipeline",
+    about = "Runs log files through the Grapl pipeline in a single process"
+)]
+struct Opt {
+    /// The kind of logs being read: sysmon, osquery or generic
+    #[structopt(short = "s", long = "source")]
+    log_source: LogSource,
+
+    /// Directory laid out like the analyzers bucket, merged graphs are dispatched to every
+    /// analyzer in it
+    #[structopt(
+        short = "a",
+        long = "analyzers",
+        parse(from_os_str),
+        env = "LOCAL_ANALYZERS_DIR"
+    )]
+    analyzers_dir: Option<PathBuf>,
+
+    /// Write every merged node and edge to this file, as json
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// How many events may be waiting between any two stages of the pipeline
+    #[structopt(long = "channel-capacity", default_value = "10")]
+    channel_capacity: usize,
+
+    /// Merge graphs in memory instead of writing them to MG_ALPHAS
+    #[structopt(long = "in-memory-graph")]
+    in_memory_graph: bool,
+
+    /// How the log files are compressed: none, gzip or zstd. Unlike the payloads the
+    /// generators read from S3, local log files are usually not compressed
+    #[structopt(
+        long = "compression",
+        default_value = "none",
+        parse(try_from_str = parse_compression)
+    )]
+    compression: PayloadDecompression,
+
+    /// Log files to run through the pipeline
+    #[structopt(parse(from_os_str), required = true)]
+    log_paths: Vec<PathBuf>,
+}
+
+fn parse_compression(input: &str) -> Result<PayloadDecompression, String> {
+    PayloadDecompression::from_str(input).map_err(|()| {
+        format!(
+            "Unknown compression: {}. Expected one of none, gzip, zstd",
+            input
+        )
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (env, _guard) = grapl_config::init_grapl_env!();
+    let opt = Opt::from_args();
+
+    let report = run_local_pipeline(LocalPipelineConfig {
+        service_name: env.service_name,
+        log_source: opt.log_source,
+        log_paths: opt.log_paths,
+        decompression: opt.compression,
+        analyzers_dir: opt.analyzers_dir,
+        channel_capacity: opt.channel_capacity,
+        in_memory_graph: opt.in_memory_graph,
+    })
+    .await?;
+
+    print!("{}", report);
+
+    if let Some(output) = opt.output {
+        std::fs::write(&output, serde_json::to_vec_pretty(&report.merged_graph)?)?;
+        println!("wrote merged graph to {}", output.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+};
+
+use grapl_graph_descriptions::graph_description::MergedGraph;
+use serde::Serialize;
+
+use crate::{
+    source::FileReport,
+    stage::StageReport,
+};
+
+/// Everything that happened during a run of the local pipeline, along with the union of every
+/// graph that was merged
+#[derive(Debug, Default, Serialize)]
+pub struct PipelineReport {
+    pub files: Vec<FileReport>,
+    pub generator: StageReport,
+    pub node_identifier: StageReport,
+    pub graph_merger: StageReport,
+    pub analyzer_dispatcher: StageReport,
+    pub merged_graph: MergedGraph,
+    /// The number of times a merged graph was dispatched to each analyzer
+    pub dispatched: BTreeMap<String, usize>,
+}
+
+impl PipelineReport {
+    /// The number of merged nodes of each node type
+    pub fn merged_node_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for node in self.merged_graph.nodes.values() {
+            *counts.entry(node.node_type.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The number of merged edges of each edge name
+    pub fn merged_edge_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for edge in self
+            .merged_graph
+            .edges
+            .values()
+            .flat_map(|edges| &edges.edges)
+        {
+            *counts.entry(edge.edge_name.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "files:")?;
+        for file in &self.files {
+            match file.error {
+                Some(ref error) => writeln!(f, "  {} (failed: {})", file.path.display(), error)?,
+                None => writeln!(f, "  {}", file.path.display())?,
+            }
+        }
+
+        writeln!(f, "stages:")?;
+        for (stage_name, stage) in &[
+            ("generator", &self.generator),
+            ("node-identifier", &self.node_identifier),
+            ("graph-merger", &self.graph_merger),
+            ("analyzer-dispatcher", &self.analyzer_dispatcher),
+        ] {
+            writeln!(
+                f,
+                "  {}: received={} succeeded={} partially_failed={} failed={}",
+                stage_name, stage.received, stage.succeeded, stage.partially_failed, stage.failed
+            )?;
+        }
+
+        writeln!(f, "merged nodes: {}", self.merged_graph.nodes.len())?;
+        for (node_type, count) in self.merged_node_counts() {
+            writeln!(f, "  {}: {}", node_type, count)?;
+        }
+
+        let edge_counts = self.merged_edge_counts();
+        writeln!(f, "merged edges: {}", edge_counts.values().sum::<usize>())?;
+        for (edge_name, count) in edge_counts {
+            writeln!(f, "  {}: {}", edge_name, count)?;
+        }
+
+        writeln!(f, "dispatched:")?;
+        for (analyzer, count) in &self.dispatched {
+            writeln!(f, "  {}: {}", analyzer, count)?;
+        }
+
+        Ok(())
+    }
+}
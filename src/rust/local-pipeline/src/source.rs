@@ -0,0 +1,98 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+
+use serde::Serialize;
+use sqs_executor::event_decoder::PayloadDecoder;
+use tokio::sync::mpsc::Sender;
+use tracing::{
+    error,
+    info,
+};
+
+/// The kinds of logs the pipeline can generate graphs from, one per generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogSource {
+    Sysmon,
+    OSQuery,
+    Generic,
+}
+
+impl FromStr for LogSource {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "sysmon" => Ok(LogSource::Sysmon),
+            "osquery" => Ok(LogSource::OSQuery),
+            "generic" => Ok(LogSource::Generic),
+            _ => Err(format!(
+                "Unknown log source: {}. Expected one of sysmon, osquery, generic",
+                input
+            )),
+        }
+    }
+}
+
+/// Whether a log file could be read and decoded into events for the generator
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub error: Option<String>,
+}
+
+/// Reads each log file from disk and decodes it the same way the generator services decode
+/// payloads retrieved from S3. Every file is handed to the generator as a single event batch.
+pub async fn read_log_files<DecoderT, InputEventT>(
+    log_paths: &[PathBuf],
+    mut decoder: DecoderT,
+    output: Sender<InputEventT>,
+) -> Vec<FileReport>
+where
+    DecoderT: PayloadDecoder<InputEventT>,
+{
+    let mut file_reports = Vec::with_capacity(log_paths.len());
+
+    for path in log_paths {
+        let error = match read_log_file(path, &mut decoder).await {
+            Ok(events) => {
+                info!(message="Read log file", path=?path);
+                if output.send(events).await.is_err() {
+                    error!(message = "Generator has exited, not reading remaining log files");
+                    file_reports.push(FileReport {
+                        path: path.clone(),
+                        error: Some("Generator exited before the file was handled".to_owned()),
+                    });
+                    break;
+                }
+                None
+            }
+            Err(e) => {
+                error!(message="Failed to read log file", path=?path, error=?e);
+                Some(e)
+            }
+        };
+
+        file_reports.push(FileReport {
+            path: path.clone(),
+            error,
+        });
+    }
+
+    file_reports
+}
+
+async fn read_log_file<DecoderT, InputEventT>(
+    path: &Path,
+    decoder: &mut DecoderT,
+) -> Result<InputEventT, String>
+where
+    DecoderT: PayloadDecoder<InputEventT>,
+{
+    let body = tokio::fs::read(path).await.map_err(|e| e.to_string())?;
+    decoder.decode(body).map_err(|e| e.to_string())
+}
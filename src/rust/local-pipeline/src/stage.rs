@@ -0,0 +1,89 @@
+use serde::Serialize;
+use sqs_executor::{
+    errors::CheckedError,
+    event_handler::{
+        CompletedEvents,
+        EventHandler,
+    },
+};
+use tokio::sync::mpsc::{
+    Receiver,
+    Sender,
+};
+use tracing::{
+    error,
+    info,
+    warn,
+};
+
+/// How many events a stage of the pipeline handled, and how that went
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StageReport {
+    pub received: usize,
+    pub succeeded: usize,
+    pub partially_failed: usize,
+    pub failed: usize,
+}
+
+/**
+    Drives an `EventHandler` the way the sqs-executor would, except that events are read from and
+    written to in-memory channels rather than queues and buckets.
+
+    Successful and partially successful outputs are both forwarded to the next stage, mirroring
+    the partial emission in `process_message`. The stage exits once its input channel is closed.
+*/
+pub async fn run_stage<EventHandlerT>(
+    stage_name: &'static str,
+    mut event_handler: EventHandlerT,
+    mut input: Receiver<EventHandlerT::InputEvent>,
+    output: Sender<EventHandlerT::OutputEvent>,
+) -> StageReport
+where
+    EventHandlerT: EventHandler + Send,
+    EventHandlerT::InputEvent: Send,
+{
+    let mut report = StageReport::default();
+
+    while let Some(event) = input.recv().await {
+        report.received += 1;
+
+        let mut completed = CompletedEvents::default();
+        let output_event = match event_handler.handle_event(event, &mut completed).await {
+            Ok(output_event) => {
+                report.succeeded += 1;
+                output_event
+            }
+            Err(Ok((partial, e))) => {
+                warn!(
+                    message="EventHandler partially failed",
+                    stage=stage_name,
+                    error=?e,
+                    recoverable=?e.error_type()
+                );
+                report.partially_failed += 1;
+                partial
+            }
+            Err(Err(e)) => {
+                error!(
+                    message="EventHandler failed",
+                    stage=stage_name,
+                    error=?e,
+                    recoverable=?e.error_type()
+                );
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        if output.send(output_event).await.is_err() {
+            warn!(
+                message = "Downstream stage has exited, stopping",
+                stage = stage_name
+            );
+            break;
+        }
+    }
+
+    info!(message="Stage complete", stage=stage_name, report=?report);
+    report
+}
@@ -0,0 +1,156 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    path::PathBuf,
+};
+
+use grapl_graph_descriptions::graph_description::MergedGraph;
+use grapl_service::decoder::decompress::PayloadDecompression;
+use local_pipeline_lib::{
+    run_local_pipeline,
+    source::LogSource,
+    stage::StageReport,
+    LocalPipelineConfig,
+};
+
+const HOST: &str = "ip-172-31-46-189.us-east-2.compute.internal";
+
+/// The processes in `process_create.zstd`, all of which are children of kthreadd (pid 2)
+const CHILD_PIDS: &[&str] = &["543", "6", "684", "690", "7", "8", "822", "872", "9", "900"];
+
+fn assert_succeeded(stage_name: &str, stage: &StageReport) {
+    assert_eq!(
+        (
+            stage.received,
+            stage.succeeded,
+            stage.partially_failed,
+            stage.failed
+        ),
+        (1, 1, 0, 0),
+        "unexpected counts for {}: {:?}",
+        stage_name,
+        stage
+    );
+}
+
+/// The value of the property on the merged node, by the node's key
+fn property(merged_graph: &MergedGraph, node_key: &str, name: &str) -> Option<String> {
+    merged_graph.nodes[node_key]
+        .properties
+        .get(name)
+        .map(|property| property.to_string())
+}
+
+/// The (from, to) node keys of every merged edge with the name
+fn edges<'a>(merged_graph: &'a MergedGraph, edge_name: &str) -> Vec<(&'a str, &'a str)> {
+    merged_graph
+        .edges
+        .values()
+        .flat_map(|edges| &edges.edges)
+        .filter(|edge| edge.edge_name == edge_name)
+        .map(|edge| (edge.from_node_key.as_str(), edge.to_node_key.as_str()))
+        .collect()
+}
+
+#[tokio::test]
+async fn osquery_sample_runs_through_the_pipeline() {
+    let report = run_local_pipeline(LocalPipelineConfig {
+        service_name: "local-pipeline-test".to_owned(),
+        log_source: LogSource::OSQuery,
+        log_paths: vec![PathBuf::from(
+            "../generators/osquery-generator/sample_data/process_create.zstd",
+        )],
+        decompression: PayloadDecompression::Zstd,
+        analyzers_dir: None,
+        channel_capacity: 1,
+        in_memory_graph: true,
+    })
+    .await
+    .expect("local pipeline failed");
+
+    assert_eq!(report.files.len(), 1);
+    assert_eq!(report.files[0].error, None);
+    assert_succeeded("generator", &report.generator);
+    assert_succeeded("node-identifier", &report.node_identifier);
+    assert_succeeded("graph-merger", &report.graph_merger);
+    assert_succeeded("analyzer-dispatcher", &report.analyzer_dispatcher);
+    assert!(report.dispatched.is_empty());
+
+    let merged_graph = &report.merged_graph;
+    let node_counts = report.merged_node_counts();
+    assert_eq!(
+        node_counts.keys().copied().collect::<Vec<_>>(),
+        vec!["Asset", "Process"]
+    );
+    assert_eq!(node_counts["Asset"], 1);
+
+    let asset_key = merged_graph
+        .nodes
+        .values()
+        .find(|node| node.node_type == "Asset")
+        .map(|node| node.node_key.as_str())
+        .expect("missing asset");
+    assert_eq!(
+        property(merged_graph, asset_key, "hostname").as_deref(),
+        Some(HOST)
+    );
+
+    let pids: BTreeMap<&str, String> = merged_graph
+        .nodes
+        .values()
+        .filter(|node| node.node_type == "Process")
+        .map(|node| {
+            let pid = property(merged_graph, &node.node_key, "process_id").expect("missing pid");
+            (node.node_key.as_str(), pid)
+        })
+        .collect();
+    let mut expected_pids: BTreeSet<&str> = CHILD_PIDS.iter().copied().collect();
+    expected_pids.insert("2");
+    assert_eq!(
+        pids.values().map(String::as_str).collect::<BTreeSet<_>>(),
+        expected_pids
+    );
+
+    // Every sampled process is a child of pid 2
+    let children: BTreeSet<&str> = edges(merged_graph, "children")
+        .into_iter()
+        .map(|(parent, child)| {
+            assert_eq!(pids[parent], "2");
+            pids[child].as_str()
+        })
+        .collect();
+    assert_eq!(children, CHILD_PIDS.iter().copied().collect());
+    let parents: BTreeSet<&str> = edges(merged_graph, "parent")
+        .into_iter()
+        .map(|(child, parent)| {
+            assert_eq!(pids[parent], "2");
+            pids[child].as_str()
+        })
+        .collect();
+    assert_eq!(parents, children);
+
+    // And every process, parent included, is on the asset
+    let asset_processes: BTreeSet<&str> = edges(merged_graph, "asset_processes")
+        .into_iter()
+        .map(|(asset, process)| {
+            assert_eq!(asset, asset_key);
+            pids[process].as_str()
+        })
+        .collect();
+    assert_eq!(asset_processes, expected_pids);
+    assert_eq!(
+        edges(merged_graph, "process_asset").len(),
+        report.merged_node_counts()["Process"]
+    );
+
+    assert_eq!(
+        report
+            .merged_edge_counts()
+            .keys()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec!["asset_processes", "children", "parent", "process_asset"]
+    );
+}
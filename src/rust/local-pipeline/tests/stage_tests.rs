@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use local_pipeline_lib::stage::run_stage;
+use sqs_executor::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_handler::{
+        CompletedEvents,
+        EventHandler,
+    },
+};
+use tokio::sync::mpsc::channel;
+
+#[derive(thiserror::Error, Debug)]
+#[error("HalvingError")]
+struct HalvingError;
+
+impl CheckedError for HalvingError {
+    fn error_type(&self) -> Recoverable {
+        Recoverable::Persistent
+    }
+}
+
+/// Halves even numbers, partially fails on odd numbers, and fails entirely on zero
+#[derive(Clone)]
+struct Halver;
+
+#[async_trait]
+impl EventHandler for Halver {
+    type InputEvent = u64;
+    type OutputEvent = u64;
+    type Error = HalvingError;
+
+    async fn handle_event(
+        &mut self,
+        input: u64,
+        _completed: &mut CompletedEvents,
+    ) -> Result<Self::OutputEvent, Result<(Self::OutputEvent, Self::Error), Self::Error>> {
+        match input {
+            0 => Err(Err(HalvingError)),
+            n if n % 2 == 0 => Ok(n / 2),
+            n => Err(Ok((n / 2, HalvingError))),
+        }
+    }
+}
+
+#[tokio::test]
+async fn stage_forwards_successful_and_partial_outputs() {
+    let (input_tx, input_rx) = channel(1);
+    let (output_tx, mut output_rx) = channel(1);
+
+    let stage = tokio::spawn(run_stage("halver", Halver, input_rx, output_tx));
+
+    let outputs = tokio::spawn(async move {
+        let mut outputs = Vec::new();
+        while let Some(output) = output_rx.recv().await {
+            outputs.push(output);
+        }
+        outputs
+    });
+
+    for input in [4, 0, 7, 10].iter().copied() {
+        input_tx.send(input).await.expect("stage exited early");
+    }
+    drop(input_tx);
+
+    let report = stage.await.expect("stage panicked");
+    assert_eq!(report.received, 4);
+    assert_eq!(report.succeeded, 2);
+    assert_eq!(report.partially_failed, 1);
+    assert_eq!(report.failed, 1);
+
+    assert_eq!(outputs.await.expect("collector panicked"), vec![2, 3, 5]);
+}
+
+#[tokio::test]
+async fn stage_stops_when_downstream_exits() {
+    let (input_tx, input_rx) = channel(4);
+    let (output_tx, output_rx) = channel(1);
+    drop(output_rx);
+
+    for input in [2, 4, 6].iter().copied() {
+        input_tx.send(input).await.expect("stage exited early");
+    }
+    drop(input_tx);
+
+    let report = run_stage("halver", Halver, input_rx, output_tx).await;
+    assert_eq!(report.received, 1);
+}
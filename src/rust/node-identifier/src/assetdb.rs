@@ -0,0 +1,331 @@
+use async_trait::async_trait;
+use failure::{
+    bail,
+    Error,
+};
+use grapl_graph_descriptions::graph_description::{
+    id_strategy,
+    NodeDescription,
+};
+use hmap::hmap;
+use rusoto_dynamodb::{
+    AttributeValue,
+    DynamoDb,
+    PutItemInput,
+    QueryInput,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tracing::{
+    info,
+    warn,
+};
+
+/// The node properties that can identify the host a node was observed on,
+/// in order of how stable they are as an identity.
+const HOST_ID_PROPERTIES: &[&str] = &["sensor_id", "asset_id", "hostname", "host_ip"];
+
+/// An identifier that refers to a single asset at a given point in time. Hostnames and
+/// ips can be reassigned, so they only map to an asset_id for a range of time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostId {
+    SensorId(String),
+    AssetId(String),
+    Hostname(String),
+    Ip(String),
+}
+
+impl HostId {
+    pub fn from_property(prop_name: &str, value: String) -> Option<Self> {
+        match prop_name {
+            "sensor_id" => Some(HostId::SensorId(value)),
+            "asset_id" => Some(HostId::AssetId(value)),
+            "hostname" => Some(HostId::Hostname(value)),
+            "host_ip" => Some(HostId::Ip(value)),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            HostId::SensorId(id) | HostId::AssetId(id) | HostId::Hostname(id) | HostId::Ip(id) => {
+                id
+            }
+        }
+    }
+
+    /// The key that the identifier's mappings are stored under
+    pub fn pseudo_key(&self) -> String {
+        match self {
+            HostId::SensorId(id) => format!("sensor_id:{}", id),
+            HostId::AssetId(id) => format!("asset_id:{}", id),
+            HostId::Hostname(id) => format!("hostname:{}", id),
+            HostId::Ip(id) => format!("host_ip:{}", id),
+        }
+    }
+}
+
+/// Stores which asset_id each host identifier mapped to over time. `AssetIdDb` stores the
+/// mappings in DynamoDB, and `InMemoryAssetIdDb` keeps them in process.
+#[async_trait]
+pub trait AssetIdStore: Send + Sync {
+    /// Finds the asset_id that `host_id` mapped to at `timestamp`. If the host_id was
+    /// first seen after `timestamp` the earliest mapping is used instead.
+    async fn resolve_asset_id(
+        &self,
+        host_id: &HostId,
+        timestamp: u64,
+    ) -> Result<Option<String>, Error>;
+
+    /// Maps `host_id` to `asset_id` from `timestamp` onwards
+    async fn create_mapping(
+        &self,
+        host_id: &HostId,
+        asset_id: String,
+        timestamp: u64,
+    ) -> Result<(), Error>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetIdMapping {
+    pub pseudo_key: String,
+    pub c_timestamp: u64,
+    pub asset_id: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssetIdDb<D>
+where
+    D: DynamoDb,
+{
+    dynamo: D,
+    table_name: String,
+}
+
+impl<D> AssetIdDb<D>
+where
+    D: DynamoDb,
+{
+    pub fn new(dynamo: D, table_name: impl Into<String>) -> Self {
+        Self {
+            dynamo,
+            table_name: table_name.into(),
+        }
+    }
+
+    async fn query_mapping(
+        &self,
+        host_id: &HostId,
+        timestamp: u64,
+        before: bool,
+    ) -> Result<Option<AssetIdMapping>, Error> {
+        let key_condition_expression = if before {
+            "pseudo_key = :pseudo_key AND c_timestamp <= :c_timestamp"
+        } else {
+            "pseudo_key = :pseudo_key AND c_timestamp >= :c_timestamp"
+        };
+
+        let query = QueryInput {
+            consistent_read: Some(true),
+            limit: Some(1),
+            scan_index_forward: Some(!before),
+            table_name: self.table_name.clone(),
+            key_condition_expression: Some(key_condition_expression.into()),
+            expression_attribute_values: Some(hmap! {
+                ":pseudo_key".to_owned() => AttributeValue {
+                    s: host_id.pseudo_key().into(),
+                    ..Default::default()
+                },
+                ":c_timestamp".to_owned() => AttributeValue {
+                    n: timestamp.to_string().into(),
+                    ..Default::default()
+                }
+            }),
+            ..Default::default()
+        };
+
+        let res = self.dynamo.query(query).await?;
+
+        match res.items.as_deref() {
+            None | Some([]) => Ok(None),
+            Some([item]) => Ok(Some(serde_dynamodb::from_hashmap(item.clone())?)),
+            Some(_) => bail!("Unexpected number of items returned"),
+        }
+    }
+}
+
+#[async_trait]
+impl<D> AssetIdStore for AssetIdDb<D>
+where
+    D: DynamoDb,
+{
+    #[tracing::instrument(skip(self), err)]
+    async fn resolve_asset_id(
+        &self,
+        host_id: &HostId,
+        timestamp: u64,
+    ) -> Result<Option<String>, Error> {
+        if let Some(mapping) = self.query_mapping(host_id, timestamp, true).await? {
+            return Ok(Some(mapping.asset_id));
+        }
+
+        let mapping = self.query_mapping(host_id, timestamp, false).await?;
+        Ok(mapping.map(|mapping| mapping.asset_id))
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn create_mapping(
+        &self,
+        host_id: &HostId,
+        asset_id: String,
+        timestamp: u64,
+    ) -> Result<(), Error> {
+        let mapping = AssetIdMapping {
+            pseudo_key: host_id.pseudo_key(),
+            c_timestamp: timestamp,
+            asset_id,
+        };
+
+        let put_req = PutItemInput {
+            item: serde_dynamodb::to_hashmap(&mapping)?,
+            table_name: self.table_name.clone(),
+            ..Default::default()
+        };
+
+        self.dynamo.put_item(put_req).await?;
+
+        Ok(())
+    }
+}
+
+/**
+    The `AssetIdentifier` resolves the host identifiers on a node (sensor ids, asset ids,
+    hostnames, ips) into one canonical asset_id.
+
+    Every identifier observed alongside a canonical asset_id is recorded as a mapping, so that
+    a host that is later seen under a new ip or hostname still resolves to the same asset.
+*/
+#[derive(Debug, Clone)]
+pub struct AssetIdentifier<A>
+where
+    A: AssetIdStore,
+{
+    asset_id_db: A,
+}
+
+impl<A> AssetIdentifier<A>
+where
+    A: AssetIdStore,
+{
+    pub fn new(asset_id_db: A) -> Self {
+        Self { asset_id_db }
+    }
+
+    /// Resolves the canonical asset_id for `node`, recording mappings for any host
+    /// identifiers on the node that were not yet known. An asset_id already on the node is kept,
+    /// and the node's other identifiers are mapped to it.
+    ///
+    /// Identifiers are resolved as of the node's session timestamps, or as of `event_timestamp`
+    /// for nodes without any, such as statically identified ones.
+    #[tracing::instrument(skip(self, node), fields(node_key=?node.node_key), err)]
+    pub async fn attribute_asset_id(
+        &self,
+        node: &NodeDescription,
+        event_timestamp: u64,
+    ) -> Result<String, Error> {
+        let host_ids = host_ids_from_node(node);
+        if host_ids.is_empty() {
+            bail!(
+                "Node {} requires an asset_id but has no host identifiers",
+                node.node_key
+            );
+        }
+
+        let timestamp = session_timestamp(node).unwrap_or(event_timestamp);
+
+        // An asset_id the node already carries is canonical, rather than whatever it maps to
+        let mut asset_id: Option<String> = host_ids.iter().find_map(|host_id| match host_id {
+            HostId::AssetId(asset_id) => Some(asset_id.clone()),
+            _ => None,
+        });
+        let mut unmapped = Vec::with_capacity(host_ids.len());
+        for host_id in host_ids {
+            match self
+                .asset_id_db
+                .resolve_asset_id(&host_id, timestamp)
+                .await?
+            {
+                Some(mapped) => match asset_id {
+                    None => asset_id = Some(mapped),
+                    // The identifier has since been reassigned, e.g. a DHCP lease changing hands,
+                    // so it gets remapped from this point in time onwards
+                    Some(ref asset_id) if *asset_id != mapped => {
+                        warn!(
+                            message="Host identifier maps to a different asset_id. Remapping.",
+                            host_id=?host_id,
+                            mapped_asset_id=?mapped,
+                            asset_id=?asset_id,
+                        );
+                        unmapped.push(host_id);
+                    }
+                    Some(_) => (),
+                },
+                None => unmapped.push(host_id),
+            }
+        }
+
+        // None of the host identifiers were known, so the most stable one becomes canonical
+        let asset_id = match asset_id {
+            Some(asset_id) => asset_id,
+            None => {
+                info!(message = "Creating new asset_id");
+                unmapped[0].value().to_owned()
+            }
+        };
+
+        for host_id in unmapped {
+            self.asset_id_db
+                .create_mapping(&host_id, asset_id.clone(), timestamp)
+                .await?;
+        }
+
+        Ok(asset_id)
+    }
+}
+
+/// Collects the host identifiers present on the node, most stable first
+fn host_ids_from_node(node: &NodeDescription) -> Vec<HostId> {
+    HOST_ID_PROPERTIES
+        .iter()
+        .filter_map(|prop_name| {
+            let value = node.properties.get(*prop_name)?.as_immutable_str()?;
+            if value.as_inner().is_empty() {
+                return None;
+            }
+            HostId::from_property(prop_name, value.as_inner().to_owned())
+        })
+        .collect()
+}
+
+/// The time at which the node's session was observed, if it's identified by a session
+fn session_timestamp(node: &NodeDescription) -> Option<u64> {
+    node.id_strategy
+        .iter()
+        .filter_map(|strategy| match strategy.strategy.as_ref() {
+            Some(id_strategy::Strategy::Session(session)) => Some(session),
+            _ => None,
+        })
+        .filter_map(|session| {
+            [
+                session.create_time,
+                session.last_seen_time,
+                session.terminate_time,
+            ]
+            .iter()
+            .copied()
+            .find(|timestamp| *timestamp != 0)
+        })
+        .next()
+}
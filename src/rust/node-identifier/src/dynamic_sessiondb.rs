@@ -1,8 +1,16 @@
-use std::collections::{
-    HashMap,
-    HashSet,
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    io::Stdout,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
+use async_trait::async_trait;
 use failure::{
     bail,
     Error,
@@ -12,6 +20,10 @@ use grapl_graph_descriptions::graph_description::{
     Session as SessionStrategy,
     *,
 };
+use grapl_observe::metric_reporter::{
+    tag,
+    MetricReporter,
+};
 use rusoto_dynamodb::{
     AttributeValue,
     DynamoDb,
@@ -33,10 +45,23 @@ use tracing::{
 };
 
 use crate::{
-    sessiondb::SessionDb,
+    assetdb::{
+        AssetIdStore,
+        AssetIdentifier,
+    },
+    sessiondb::SessionStore,
     sessions::UnidSession,
 };
 
+/// Stores direct mappings from a pseudo key to the node key it resolves to. `DynamicMappingDb`
+/// stores the mappings in DynamoDB, and `InMemoryMappingDb` keeps them in process.
+#[async_trait]
+pub trait MappingStore: Send + Sync {
+    async fn direct_map(&self, input: &str) -> Result<Option<String>, Error>;
+
+    async fn create_mapping(&self, input: String, maps_to: String) -> Result<(), Error>;
+}
+
 #[derive(Debug, Clone)]
 pub struct DynamicMappingDb<D>
 where
@@ -63,9 +88,15 @@ where
     pub fn new(dyn_mapping_db: D) -> Self {
         Self { dyn_mapping_db }
     }
+}
 
+#[async_trait]
+impl<D> MappingStore for DynamicMappingDb<D>
+where
+    D: DynamoDb,
+{
     #[tracing::instrument(skip(self))]
-    pub async fn direct_map(&self, input: &str) -> Result<Option<String>, Error> {
+    async fn direct_map(&self, input: &str) -> Result<Option<String>, Error> {
         let mut key: HashMap<String, AttributeValue> = HashMap::new();
 
         key.insert(
@@ -95,7 +126,7 @@ where
     }
 
     #[tracing::instrument(skip(self), err)]
-    pub async fn create_mapping(&self, input: String, maps_to: String) -> Result<(), Error> {
+    async fn create_mapping(&self, input: String, maps_to: String) -> Result<(), Error> {
         let mapping = DirectMapping {
             pseudo_key: input,
             mapping: maps_to,
@@ -114,27 +145,37 @@ where
 }
 
 #[derive(Debug, Clone)]
-pub struct NodeDescriptionIdentifier<D>
+pub struct NodeDescriptionIdentifier<S, M, A>
 where
-    D: DynamoDb,
+    S: SessionStore,
+    M: MappingStore,
+    A: AssetIdStore,
 {
-    dyn_session_db: SessionDb<D>,
-    dyn_mapping_db: DynamicMappingDb<D>,
+    dyn_session_db: S,
+    dyn_mapping_db: M,
+    asset_identifier: AssetIdentifier<A>,
+    metric_reporter: MetricReporter<Stdout>,
     should_guess: bool,
 }
 
-impl<D> NodeDescriptionIdentifier<D>
+impl<S, M, A> NodeDescriptionIdentifier<S, M, A>
 where
-    D: DynamoDb,
+    S: SessionStore,
+    M: MappingStore,
+    A: AssetIdStore,
 {
     pub fn new(
-        dyn_session_db: SessionDb<D>,
-        dyn_mapping_db: DynamicMappingDb<D>,
+        dyn_session_db: S,
+        dyn_mapping_db: M,
+        asset_identifier: AssetIdentifier<A>,
+        metric_reporter: MetricReporter<Stdout>,
         should_guess: bool,
     ) -> Self {
         Self {
             dyn_session_db,
             dyn_mapping_db,
+            asset_identifier,
+            metric_reporter,
             should_guess,
         }
     }
@@ -144,11 +185,13 @@ where
         &self,
         node: &mut NodeDescription,
         strategy: &SessionStrategy,
+        event_timestamp: u64,
     ) -> Result<String, Error> {
         let mut primary_key = String::with_capacity(32);
 
         if strategy.primary_key_requires_asset_id {
-            panic!("asset_id resolution is currently not supported")
+            let asset_id = self.attribute_asset_id(node, event_timestamp).await?;
+            primary_key.push_str(&asset_id);
         }
 
         for prop_name in &strategy.primary_key_properties {
@@ -164,11 +207,27 @@ where
         }
 
         // Push node type, as a natural partition
-        primary_key.push_str(&node.node_key);
+        primary_key.push_str(&node.node_type);
 
         Ok(primary_key)
     }
 
+    /// Resolves the node's canonical asset_id and stores it as the node's `asset_id` property,
+    /// so that nodes observed on the same asset under different hostnames or ips share an identity
+    #[tracing::instrument(skip(self, node), err)]
+    async fn attribute_asset_id(
+        &self,
+        node: &mut NodeDescription,
+        event_timestamp: u64,
+    ) -> Result<String, Error> {
+        let asset_id = self
+            .asset_identifier
+            .attribute_asset_id(node, event_timestamp)
+            .await?;
+        node.set_property("asset_id", ImmutableStrProp::from(asset_id.as_str()));
+        Ok(asset_id)
+    }
+
     /// Because statically identified nodes are uniquely identifiable based on their static properties
     /// we can avoid fetching from dynamodb and calculate a node key by hashing the properties deterministically
     #[tracing::instrument(skip(self, node, strategy), err)]
@@ -179,6 +238,14 @@ where
     ) -> Result<String, Error> {
         let mut hasher = Sha256::new();
 
+        // The asset_id has already been resolved into the node's properties
+        if strategy.primary_key_requires_asset_id {
+            match node.properties.get("asset_id") {
+                Some(asset_id) => hasher.update(asset_id.to_string().as_bytes()),
+                None => bail!("Node is missing resolved asset_id for identity"),
+            }
+        }
+
         // first, let's sort the properties, so we get a consistent ordering for hashing
         let mut sorted_key_properties = strategy.primary_key_properties.clone();
         sorted_key_properties.sort();
@@ -203,39 +270,73 @@ where
         &self,
         node: NodeDescription,
         strategy: &SessionStrategy,
+        event_timestamp: u64,
     ) -> Result<NodeDescription, Error> {
         let mut attributed_node = node.clone();
 
         let primary_key = self
-            .primary_session_key(&mut attributed_node, strategy)
+            .primary_session_key(&mut attributed_node, strategy, event_timestamp)
             .await?;
 
         let created_time = strategy.create_time;
         let last_seen_time = strategy.last_seen_time;
+        let terminate_time = strategy.terminate_time;
 
-        let unid = match (created_time != 0, last_seen_time != 0) {
-            (true, _) => UnidSession {
-                pseudo_key: primary_key,
+        let unid = match (created_time != 0, last_seen_time != 0, terminate_time != 0) {
+            (true, _, _) => UnidSession {
+                pseudo_key: primary_key.clone(),
                 timestamp: created_time,
                 is_creation: true,
+                is_termination: false,
             },
-            (_, true) => UnidSession {
-                pseudo_key: primary_key,
+            (_, true, _) => UnidSession {
+                pseudo_key: primary_key.clone(),
                 timestamp: last_seen_time,
                 is_creation: false,
+                is_termination: false,
+            },
+            (_, _, true) => UnidSession {
+                pseudo_key: primary_key.clone(),
+                timestamp: terminate_time,
+                is_creation: false,
+                is_termination: true,
             },
             _ => bail!(
-                "Terminating sessions not yet supported: {:?} {:?}",
+                "Session strategy has no timestamps: {:?} {:?}",
                 node.properties,
                 &strategy,
             ),
         };
+        let is_termination = unid.is_termination;
 
         let session_id = self
             .dyn_session_db
             .handle_unid_session(unid, self.should_guess)
             .await?;
 
+        // If the node was identified by an earlier timestamp but also terminated,
+        // close the session it was attributed to
+        if terminate_time != 0 && !is_termination {
+            let termination = UnidSession {
+                pseudo_key: primary_key,
+                timestamp: terminate_time,
+                is_creation: false,
+                is_termination: true,
+            };
+            let terminated_session_id = self
+                .dyn_session_db
+                .handle_unid_session(termination, self.should_guess)
+                .await?;
+
+            if terminated_session_id != session_id {
+                warn!(
+                    message="Termination was attributed to a different session",
+                    session_id=?session_id,
+                    terminated_session_id=?terminated_session_id,
+                );
+            }
+        }
+
         attributed_node.node_key = session_id;
 
         Ok(attributed_node)
@@ -246,39 +347,86 @@ where
         &self,
         mut node: NodeDescription,
         strategy: &Static,
+        event_timestamp: u64,
     ) -> Result<NodeDescription, Error> {
+        if strategy.primary_key_requires_asset_id {
+            self.attribute_asset_id(&mut node, event_timestamp).await?;
+        }
+
         let static_node_key = self.get_static_node_key(&node, &strategy)?;
         node.set_key(static_node_key);
 
         Ok(node)
     }
 
+    /// Attributes the node with the first of its strategies that the node has all of the
+    /// primary key properties for, so that a strategy relying on properties that only some
+    /// datasources provide can fall back to a more widely available one.
+    ///
+    /// `event_timestamp` is when the node's graph was observed, see `event_timestamp`.
     #[tracing::instrument(skip(self, node), err)]
     pub async fn attribute_dynamic_node(
         &self,
         node: &NodeDescription,
+        event_timestamp: u64,
     ) -> Result<NodeDescription, Error> {
-        let mut attributed_node = node.clone();
-        let strategy = &node.id_strategy[0];
+        let attributed_node = node.clone();
+
+        let (strategy_index, strategy) = match applicable_strategy(node) {
+            Some(strategy) => strategy,
+            None => {
+                self.report_strategy("none", None);
+                bail!(
+                    "Node {} has no applicable identity strategy out of {}",
+                    node.node_key,
+                    node.id_strategy.len()
+                )
+            }
+        };
 
-        match strategy.strategy.as_ref().unwrap() {
+        if strategy_index > 0 {
+            info!(
+                message = "Falling back to identity strategy",
+                strategy_index = strategy_index
+            );
+        }
+
+        let attributed_node = match strategy {
             id_strategy::Strategy::Session(ref strategy) => {
                 info!("Attributing dynamic node via session");
-                attributed_node = self
-                    .attribute_dynamic_session(attributed_node, &strategy)
-                    .await?;
+                self.report_strategy("session", Some(strategy_index));
+                self.attribute_dynamic_session(attributed_node, &strategy, event_timestamp)
+                    .await?
             }
             id_strategy::Strategy::Static(ref strategy) => {
                 info!("Attributing dynamic node via static mapping");
-                attributed_node = self
-                    .attribute_static_mapping(attributed_node, &strategy)
-                    .await?;
+                self.report_strategy("static", Some(strategy_index));
+                self.attribute_static_mapping(attributed_node, &strategy, event_timestamp)
+                    .await?
             }
-        }
+        };
 
         Ok(attributed_node)
     }
 
+    fn report_strategy(&self, strategy: &str, strategy_index: Option<usize>) {
+        let strategy_index = strategy_index
+            .map(|index| index.to_string())
+            .unwrap_or_else(|| "none".to_owned());
+        self.metric_reporter
+            .clone()
+            .counter(
+                "node_identifier.strategy.count",
+                1.0,
+                0.10,
+                &[
+                    tag("strategy", strategy),
+                    tag("strategy_index", strategy_index.as_str()),
+                ],
+            )
+            .unwrap_or_else(|e| warn!(message="Metric failed.", error=?e));
+    }
+
     #[tracing::instrument(skip(self, unid_graph, _unid_id_map))]
     pub async fn attribute_dynamic_nodes(
         &self,
@@ -286,6 +434,7 @@ where
         _unid_id_map: &mut HashMap<String, String>,
     ) -> Result<GraphDescription, GraphDescription> {
         let mut unid_id_map = HashMap::new();
+        let event_timestamp = event_timestamp(&unid_graph);
         let mut dead_nodes: HashSet<&str> = HashSet::new();
         let mut output_graph = GraphDescription::new();
         output_graph.edges = unid_graph.edges;
@@ -293,7 +442,7 @@ where
         for node in unid_graph.nodes.values() {
             let span = trace_span!("dynamic attribution loop", node_key=?node.node_key);
             let _enter = span.enter();
-            let new_node = match self.attribute_dynamic_node(&node).await {
+            let new_node = match self.attribute_dynamic_node(&node, event_timestamp).await {
                 Ok(node) => node,
                 Err(e) => {
                     warn!(message="Failed to attribute dynamic node", error=?e);
@@ -317,3 +466,110 @@ where
         }
     }
 }
+
+/// Groups nodes that may be attributed to the same session timeline. Nodes with the same
+/// pseudo key always share a group key, since it is built from the same properties minus the
+/// resolved asset_id. Statically identified nodes never touch the session timeline, so they
+/// have no group.
+pub fn session_group_key(node: &NodeDescription) -> Option<String> {
+    let strategy = match applicable_strategy(node)? {
+        (_, id_strategy::Strategy::Session(strategy)) => strategy,
+        (_, id_strategy::Strategy::Static(_)) => return None,
+    };
+
+    let mut group_key = node.node_type.clone();
+    for prop_name in &strategy.primary_key_properties {
+        if strategy.primary_key_requires_asset_id && prop_name == "asset_id" {
+            continue;
+        }
+        if let Some(prop_val) = node.properties.get(prop_name) {
+            group_key.push_str(&prop_val.to_string());
+        }
+    }
+
+    Some(group_key)
+}
+
+/// Orders nodes within a session group so that each timeline is built up in time order, with
+/// creation events ahead of any other event at the same timestamp.
+pub fn session_order_key(node: &NodeDescription) -> (u64, bool) {
+    match applicable_strategy(node) {
+        Some((_, id_strategy::Strategy::Session(strategy))) => {
+            let timestamp = [
+                strategy.create_time,
+                strategy.last_seen_time,
+                strategy.terminate_time,
+            ]
+            .iter()
+            .copied()
+            .find(|timestamp| *timestamp != 0)
+            .unwrap_or_default();
+            (timestamp, strategy.create_time == 0)
+        }
+        _ => (0, true),
+    }
+}
+
+/// When the graph's events were observed, in milliseconds: the latest of the session timestamps
+/// and edge sightings in the graph. Statically identified nodes carry no timestamps of their own,
+/// so their asset_ids are resolved as of this time. Graphs without any timestamps fall back to
+/// the current time.
+pub fn event_timestamp(graph: &GraphDescription) -> u64 {
+    let session_timestamps = graph
+        .nodes
+        .values()
+        .flat_map(|node| node.id_strategy.iter())
+        .filter_map(|strategy| match strategy.strategy.as_ref() {
+            Some(id_strategy::Strategy::Session(session)) => Some(
+                session
+                    .create_time
+                    .max(session.last_seen_time)
+                    .max(session.terminate_time),
+            ),
+            _ => None,
+        });
+    let edge_timestamps = graph
+        .edges
+        .values()
+        .flat_map(|edge_list| edge_list.edges.iter())
+        .filter_map(|edge| Some(edge.facets.as_ref()?.last_seen.as_ref()?.prop));
+
+    session_timestamps
+        .chain(edge_timestamps)
+        .max()
+        .filter(|timestamp| *timestamp != 0)
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|now| now.as_millis() as u64)
+                .unwrap_or_default()
+        })
+}
+
+fn applicable_strategy(node: &NodeDescription) -> Option<(usize, &id_strategy::Strategy)> {
+    node.id_strategy
+        .iter()
+        .enumerate()
+        .filter_map(|(index, strategy)| strategy.strategy.as_ref().map(|s| (index, s)))
+        .find(|(_, strategy)| strategy_applies(node, strategy))
+}
+
+/// Whether the node has every property the strategy derives its identity from.
+/// When the strategy resolves the asset_id itself it is not required up front.
+fn strategy_applies(node: &NodeDescription, strategy: &id_strategy::Strategy) -> bool {
+    let (primary_key_properties, requires_asset_id) = match strategy {
+        id_strategy::Strategy::Session(strategy) => (
+            &strategy.primary_key_properties,
+            strategy.primary_key_requires_asset_id,
+        ),
+        id_strategy::Strategy::Static(strategy) => (
+            &strategy.primary_key_properties,
+            strategy.primary_key_requires_asset_id,
+        ),
+    };
+
+    primary_key_properties
+        .iter()
+        .filter(|prop_name| !(requires_asset_id && prop_name.as_str() == "asset_id"))
+        .all(|prop_name| node.properties.contains_key(prop_name))
+}
@@ -0,0 +1,70 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use failure::Error;
+
+use crate::assetdb::{
+    AssetIdStore,
+    HostId,
+};
+
+type MappingKey = (String, u64);
+
+/// An `AssetIdStore` backed by an in-process map, keyed the same way as the DynamoDB table
+/// (`pseudo_key`, `c_timestamp`). Useful for local pipelines and for testing asset
+/// attribution without a database.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryAssetIdDb {
+    mappings: Arc<Mutex<BTreeMap<MappingKey, String>>>,
+}
+
+impl InMemoryAssetIdDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn key_range(pseudo_key: &str, start: u64, end: u64) -> std::ops::RangeInclusive<MappingKey> {
+    (pseudo_key.to_owned(), start)..=(pseudo_key.to_owned(), end)
+}
+
+#[async_trait]
+impl AssetIdStore for InMemoryAssetIdDb {
+    async fn resolve_asset_id(
+        &self,
+        host_id: &HostId,
+        timestamp: u64,
+    ) -> Result<Option<String>, Error> {
+        let pseudo_key = host_id.pseudo_key();
+        let mappings = self.mappings.lock().unwrap();
+
+        let before = mappings
+            .range(key_range(&pseudo_key, 0, timestamp))
+            .next_back();
+        let after = || {
+            mappings
+                .range(key_range(&pseudo_key, timestamp, u64::MAX))
+                .next()
+        };
+
+        Ok(before.or_else(after).map(|(_, asset_id)| asset_id.clone()))
+    }
+
+    async fn create_mapping(
+        &self,
+        host_id: &HostId,
+        asset_id: String,
+        timestamp: u64,
+    ) -> Result<(), Error> {
+        let mut mappings = self.mappings.lock().unwrap();
+        mappings.insert((host_id.pseudo_key(), timestamp), asset_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use failure::Error;
+
+use crate::dynamic_sessiondb::MappingStore;
+
+/// A `MappingStore` backed by an in-process map. Useful for local pipelines and for testing
+/// identification without a database.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryMappingDb {
+    mappings: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl InMemoryMappingDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MappingStore for InMemoryMappingDb {
+    async fn direct_map(&self, input: &str) -> Result<Option<String>, Error> {
+        let mappings = self.mappings.lock().unwrap();
+        Ok(mappings.get(input).cloned())
+    }
+
+    async fn create_mapping(&self, input: String, maps_to: String) -> Result<(), Error> {
+        let mut mappings = self.mappings.lock().unwrap();
+        mappings.insert(input, maps_to);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use failure::{
+    bail,
+    Error,
+};
+
+use crate::{
+    sessiondb::SessionStore,
+    sessions::*,
+};
+
+type SessionKey = (String, u64);
+
+/// A `SessionStore` backed by an in-process map, keyed the same way as the DynamoDB table
+/// (`pseudo_key`, `create_time`). Useful for local pipelines and for testing session
+/// attribution without a database.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySessionDb {
+    sessions: Arc<Mutex<BTreeMap<SessionKey, Session>>>,
+}
+
+impl InMemorySessionDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All sessions for `pseudo_key`, ordered by create_time
+    pub fn sessions_for(&self, pseudo_key: &str) -> Vec<Session> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .range(key_range(pseudo_key, 0, u64::MAX))
+            .map(|(_, session)| session.clone())
+            .collect()
+    }
+}
+
+fn key_range(pseudo_key: &str, start: u64, end: u64) -> std::ops::RangeInclusive<SessionKey> {
+    (pseudo_key.to_owned(), start)..=(pseudo_key.to_owned(), end)
+}
+
+fn session_key(session: &Session) -> SessionKey {
+    (session.pseudo_key.clone(), session.create_time)
+}
+
+/// Looks up the stored copy of `session`, failing if it has been modified since it was read
+fn check_version<'a>(
+    sessions: &'a mut BTreeMap<SessionKey, Session>,
+    session: &Session,
+) -> Result<&'a mut Session, Error> {
+    match sessions.get_mut(&session_key(session)) {
+        Some(stored) if stored.version == session.version => Ok(stored),
+        Some(stored) => bail!(
+            "Session version mismatch. expected: {} actual: {}",
+            session.version,
+            stored.version
+        ),
+        None => bail!("Session does not exist: {}", session.session_id),
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionDb {
+    async fn find_first_session_after(&self, unid: &UnidSession) -> Result<Option<Session>, Error> {
+        let sessions = self.sessions.lock().unwrap();
+        Ok(sessions
+            .range(key_range(&unid.pseudo_key, unid.timestamp, u64::MAX))
+            .next()
+            .map(|(_, session)| session.clone()))
+    }
+
+    async fn find_last_session_before(&self, unid: &UnidSession) -> Result<Option<Session>, Error> {
+        let sessions = self.sessions.lock().unwrap();
+        Ok(sessions
+            .range(key_range(&unid.pseudo_key, 0, unid.timestamp))
+            .next_back()
+            .map(|(_, session)| session.clone()))
+    }
+
+    async fn update_session_create_time(
+        &self,
+        session: &Session,
+        new_time: u64,
+        is_canon: bool,
+    ) -> Result<(), Error> {
+        let mut sessions = self.sessions.lock().unwrap();
+        check_version(&mut sessions, session)?;
+
+        let mut new_session = sessions.remove(&session_key(session)).unwrap();
+        new_session.create_time = new_time;
+        new_session.is_create_canon = is_canon;
+        new_session.version += 1;
+        sessions.insert(session_key(&new_session), new_session);
+
+        Ok(())
+    }
+
+    async fn make_create_time_canonical(&self, session: &Session) -> Result<(), Error> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let stored = check_version(&mut sessions, session)?;
+        stored.is_create_canon = true;
+        stored.version += 1;
+
+        Ok(())
+    }
+
+    async fn update_session_end_time(
+        &self,
+        session: &Session,
+        new_time: u64,
+        is_canon: bool,
+    ) -> Result<(), Error> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let stored = check_version(&mut sessions, session)?;
+        stored.end_time = new_time;
+        stored.is_end_canon = is_canon;
+        stored.version += 1;
+
+        Ok(())
+    }
+
+    async fn create_session(&self, session: &Session) -> Result<(), Error> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.insert(session_key(session), session.clone());
+
+        Ok(())
+    }
+
+    async fn delete_session(&self, session: &Session) -> Result<(), Error> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.remove(&session_key(session));
+
+        Ok(())
+    }
+}
@@ -1,21 +1,36 @@
 // #![allow(unused_must_use)]
 
-use std::collections::HashMap;
+use std::collections::{
+    BTreeMap,
+    HashMap,
+};
 
+use assetdb::{
+    AssetIdDb,
+    AssetIdStore,
+    AssetIdentifier,
+};
 use async_trait::async_trait;
 use dynamic_sessiondb::{
+    event_timestamp,
+    session_group_key,
+    session_order_key,
     DynamicMappingDb,
+    MappingStore,
     NodeDescriptionIdentifier,
 };
 use failure::Error;
+use futures::{
+    stream,
+    StreamExt,
+};
 use grapl_config::{
-    env_helpers::{
-        s3_event_emitters_from_env,
-        FromEnv,
-    },
-    event_caches,
+    env_helpers::FromEnv,
+    event_cache,
+    service::run_service,
 };
 use grapl_graph_descriptions::graph_description::{
+    Edge,
     GraphDescription,
     IdentifiedGraph,
     IdentifiedNode,
@@ -26,18 +41,17 @@ use grapl_service::{
     decoder::ProtoDecoder,
     serialization::IdentifiedGraphSerializer,
 };
-use grapl_utils::rusoto_ext::dynamodb::GraplDynamoDbClientExt;
 use rusoto_dynamodb::DynamoDbClient;
-use rusoto_sqs::SqsClient;
-use sessiondb::SessionDb;
+use sessiondb::{
+    SessionDb,
+    SessionStore,
+};
 use sqs_executor::{
     cache::Cache,
     event_handler::{
         CompletedEvents,
         EventHandler,
     },
-    make_ten,
-    s3_event_retriever::S3PayloadRetriever,
     time_based_key_fn,
 };
 use tap::tap::TapOptional;
@@ -48,11 +62,18 @@ use tracing::{
 
 use crate::error::NodeIdentifierError;
 
+pub mod assetdb;
 pub mod dynamic_sessiondb;
 mod error;
+pub mod in_memory_assetdb;
+pub mod in_memory_mappingdb;
+pub mod in_memory_sessiondb;
 pub mod sessiondb;
 pub mod sessions;
 
+/// The number of groups of nodes that are identified concurrently within a single graph
+const DEFAULT_IDENTIFICATION_CONCURRENCY: usize = 16;
+
 /**
     The `NodeIdentifier` takes in graphs of previously unidentified nodes and performs identification
     based on the configured strategies for that node type.
@@ -63,48 +84,64 @@ pub mod sessions;
     * [Static](`grapl_graph_descriptions::graph_description::Static`) - strategy used for nodes with canonical and unique identifiers (e.g. aws events)
 */
 #[derive(Clone)]
-pub struct NodeIdentifier<D, CacheT>
+pub struct NodeIdentifier<CacheT, S, M, A>
 where
-    D: GraplDynamoDbClientExt,
     CacheT: Cache,
+    S: SessionStore,
+    M: MappingStore,
+    A: AssetIdStore,
 {
-    dynamic_identifier: NodeDescriptionIdentifier<D>,
-    node_id_db: D,
+    dynamic_identifier: NodeDescriptionIdentifier<S, M, A>,
     should_default: bool,
     cache: CacheT,
+    identification_concurrency: usize,
 }
 
-impl<D, CacheT> NodeIdentifier<D, CacheT>
+impl<CacheT, S, M, A> NodeIdentifier<CacheT, S, M, A>
 where
-    D: GraplDynamoDbClientExt,
     CacheT: Cache,
+    S: SessionStore,
+    M: MappingStore,
+    A: AssetIdStore,
 {
     pub fn new(
-        dynamic_identifier: NodeDescriptionIdentifier<D>,
-        node_id_db: D,
+        dynamic_identifier: NodeDescriptionIdentifier<S, M, A>,
         should_default: bool,
         cache: CacheT,
     ) -> Self {
         Self {
             dynamic_identifier,
-            node_id_db,
             should_default,
             cache,
+            identification_concurrency: DEFAULT_IDENTIFICATION_CONCURRENCY,
         }
     }
 
+    /// Sets how many groups of nodes may be identified at once
+    pub fn with_identification_concurrency(mut self, identification_concurrency: usize) -> Self {
+        self.identification_concurrency = identification_concurrency.max(1);
+        self
+    }
+
     // todo: We should be yielding IdentifiedNode's here
     #[tracing::instrument(fields(node_key=?node.node_key), skip(self, node))]
-    async fn attribute_node_key(&self, node: &NodeDescription) -> Result<IdentifiedNode, Error> {
+    async fn attribute_node_key(
+        &self,
+        node: &NodeDescription,
+        event_timestamp: u64,
+    ) -> Result<IdentifiedNode, Error> {
         let new_node = self
             .dynamic_identifier
-            .attribute_dynamic_node(&node)
+            .attribute_dynamic_node(&node, event_timestamp)
             .await?;
         Ok(new_node.into())
     }
 
     /// Performs batch identification of unidentified nodes into identified nodes.
     ///
+    /// Nodes that may share a session timeline are identified one after another, in time order, while
+    /// independent groups of nodes are identified concurrently.
+    ///
     /// A map of unidentified node keys to identified node keys will be returned in addition to the
     /// last error, if any, that occurred while identifying nodes.
     #[tracing::instrument(skip(self, unidentified_subgraph, identified_graph))]
@@ -115,10 +152,26 @@ where
     ) -> (HashMap<String, String>, Option<failure::Error>) {
         let mut identified_nodekey_map = HashMap::new();
         let mut attribution_failure = None;
+        let event_timestamp = event_timestamp(unidentified_subgraph);
+
+        let attributions: Vec<_> = stream::iter(group_by_session(unidentified_subgraph))
+            .map(|group| async move {
+                // Nodes within a group may share a session timeline, so they're identified in order
+                let mut attributions = Vec::with_capacity(group.len());
+                for (unidentified_node_key, unidentified_node) in group {
+                    let identified_node = self
+                        .attribute_node_key(unidentified_node, event_timestamp)
+                        .await;
+                    attributions.push((unidentified_node_key, identified_node));
+                }
+                attributions
+            })
+            .buffer_unordered(self.identification_concurrency)
+            .collect()
+            .await;
 
-        // new method
-        for (unidentified_node_key, unidentified_node) in unidentified_subgraph.nodes.iter() {
-            let identified_node = match self.attribute_node_key(&unidentified_node).await {
+        for (unidentified_node_key, identified_node) in attributions.into_iter().flatten() {
+            let identified_node = match identified_node {
                 Ok(identified_node) => identified_node,
                 Err(e) => {
                     warn!(
@@ -185,26 +238,54 @@ where
                                 to_key=?edge.to_node_key
                             )
                         )
-                        .map(|identified_to_edge| (identified_to_edge, &edge.edge_name))
+                        .map(|identified_to_edge| (identified_to_edge, edge))
                 });
 
             // add all identified edges into the `identified_graph`
-            for (identified_to_key, edge_name) in identified_edges {
-                identified_graph.add_edge(
-                    edge_name.to_owned(),
-                    identified_from_key.to_owned(),
-                    identified_to_key.to_owned(),
-                );
+            for (identified_to_key, edge) in identified_edges {
+                identified_graph.insert_edge(Edge {
+                    from_node_key: identified_from_key.to_owned(),
+                    to_node_key: identified_to_key.to_owned(),
+                    edge_name: edge.edge_name.to_owned(),
+                    facets: edge.facets,
+                });
             }
         }
     }
 }
 
+/// Splits the graph's nodes into groups that can be identified independently of one another.
+/// Nodes that may be attributed to the same session are grouped together and ordered by time,
+/// every other node is identified on its own.
+fn group_by_session(graph: &GraphDescription) -> Vec<Vec<(&String, &NodeDescription)>> {
+    let mut session_groups: BTreeMap<String, Vec<(&String, &NodeDescription)>> = BTreeMap::new();
+    let mut groups = Vec::with_capacity(graph.nodes.len());
+
+    for (node_key, node) in graph.nodes.iter() {
+        match session_group_key(node) {
+            Some(group_key) => session_groups
+                .entry(group_key)
+                .or_default()
+                .push((node_key, node)),
+            None => groups.push(vec![(node_key, node)]),
+        }
+    }
+
+    for (_, mut group) in session_groups {
+        group.sort_by_key(|(_, node)| session_order_key(node));
+        groups.push(group);
+    }
+
+    groups
+}
+
 #[async_trait]
-impl<D, CacheT> EventHandler for NodeIdentifier<D, CacheT>
+impl<CacheT, S, M, A> EventHandler for NodeIdentifier<CacheT, S, M, A>
 where
-    D: GraplDynamoDbClientExt,
     CacheT: Cache,
+    S: SessionStore,
+    M: MappingStore,
+    A: AssetIdStore,
 {
     type InputEvent = GraphDescription;
     type OutputEvent = IdentifiedGraph;
@@ -291,56 +372,36 @@ where
 
 pub async fn handler(should_default: bool) -> Result<(), Box<dyn std::error::Error>> {
     let (env, _guard) = grapl_config::init_grapl_env!();
-    let source_queue_url = grapl_config::source_queue_url();
 
-    tracing::info!(
-        source_queue_url=?source_queue_url,
-        env=?env,
-        "handler_init"
-    );
+    tracing::info!(env=?env, "handler_init");
 
-    let sqs_client = SqsClient::from_env();
-    let cache = &mut event_caches(&env).await;
-    let serializer = &mut make_ten(async { IdentifiedGraphSerializer::default() }).await;
-    let s3_emitter = &mut s3_event_emitters_from_env(&env, time_based_key_fn).await;
-
-    let s3_payload_retriever = &mut make_ten(async {
-        S3PayloadRetriever::new(
-            |region_str| grapl_config::env_helpers::init_s3_client(&region_str),
-            ProtoDecoder::default(),
-            MetricReporter::new(&env.service_name),
-        )
-    })
-    .await;
+    let cache = event_cache(&env).await;
 
     let dynamo = DynamoDbClient::from_env();
     let dyn_session_db = SessionDb::new(dynamo.clone(), grapl_config::dynamic_session_table_name());
     let dyn_mapping_db = DynamicMappingDb::new(dynamo.clone());
+    let asset_identifier = AssetIdentifier::new(AssetIdDb::new(
+        dynamo,
+        grapl_config::asset_id_mappings_table_name(),
+    ));
+
+    let dyn_node_identifier = NodeDescriptionIdentifier::new(
+        dyn_session_db,
+        dyn_mapping_db,
+        asset_identifier,
+        MetricReporter::new(&env.service_name),
+        should_default,
+    );
 
-    let dyn_node_identifier =
-        NodeDescriptionIdentifier::new(dyn_session_db, dyn_mapping_db, should_default);
+    let node_identifier = NodeIdentifier::new(dyn_node_identifier, should_default, cache.clone());
 
-    let node_identifier = &mut make_ten(async {
-        NodeIdentifier::new(
-            dyn_node_identifier,
-            dynamo,
-            should_default,
-            cache[0].to_owned(),
-        )
-    })
-    .await;
-
-    info!("Starting process_loop");
-    sqs_executor::process_loop(
-        grapl_config::source_queue_url(),
-        std::env::var("DEAD_LETTER_QUEUE_URL").expect("DEAD_LETTER_QUEUE_URL"),
+    run_service(
+        &env,
         cache,
-        sqs_client.clone(),
         node_identifier,
-        s3_payload_retriever,
-        s3_emitter,
-        serializer,
-        MetricReporter::new(&env.service_name),
+        ProtoDecoder::default(),
+        IdentifiedGraphSerializer::default(),
+        time_based_key_fn,
     )
     .await;
 
@@ -1,5 +1,9 @@
-use std::convert::TryFrom;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+};
 
+use async_trait::async_trait;
 use failure::{
     bail,
     Error,
@@ -8,7 +12,6 @@ use hmap::hmap;
 use rusoto_core::RusotoError;
 use rusoto_dynamodb::{
     AttributeValue,
-    AttributeValueUpdate,
     Delete,
     DeleteItemInput,
     DynamoDb,
@@ -27,6 +30,273 @@ use uuid::Uuid;
 
 use crate::sessions::*;
 
+/// The storage operations that session identification is built on. Implementors only need to
+/// provide the primitive lookups and updates - the logic for attributing an `UnidSession` to a
+/// session is shared by every store.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Finds the earliest session for the pseudo key where `session.create_time >= unid.timestamp`
+    async fn find_first_session_after(&self, unid: &UnidSession) -> Result<Option<Session>, Error>;
+
+    /// Finds the latest session for the pseudo key where `session.create_time <= unid.timestamp`
+    async fn find_last_session_before(&self, unid: &UnidSession) -> Result<Option<Session>, Error>;
+
+    async fn update_session_create_time(
+        &self,
+        session: &Session,
+        new_time: u64,
+        is_canon: bool,
+    ) -> Result<(), Error>;
+
+    async fn make_create_time_canonical(&self, session: &Session) -> Result<(), Error>;
+
+    /// Fails if the session's version has changed since it was read
+    async fn update_session_end_time(
+        &self,
+        session: &Session,
+        new_time: u64,
+        is_canon: bool,
+    ) -> Result<(), Error>;
+
+    async fn create_session(&self, session: &Session) -> Result<(), Error>;
+
+    async fn delete_session(&self, session: &Session) -> Result<(), Error>;
+
+    #[tracing::instrument(skip(self, unid), err)]
+    async fn handle_creation_event(&self, unid: UnidSession) -> Result<String, Error> {
+        info!(
+            message="Handling unid session creation",
+            pseudo_key=?unid.pseudo_key, timestamp=?unid.timestamp
+        );
+
+        // Look for first session where session.create_time >= unid.create_time
+        let session = self.find_first_session_after(&unid).await?;
+
+        if let Some(session) = session {
+            // If session.is_create_canon is false,
+            // This means that there is a 'Guessed' session in the future,
+            // and we should consider this the canonical ID for that session
+            if !session.is_create_canon && session.create_time != unid.timestamp {
+                info!(message = "Extending session create_time");
+                self.update_session_create_time(&session, unid.timestamp, true)
+                    .await?;
+                return Ok(session.session_id);
+            }
+
+            tracing::debug!(
+                "UNID: {} - {} - {}",
+                unid.pseudo_key,
+                unid.timestamp,
+                unid.pseudo_key
+            );
+
+            // If the timestamps are the same, we've found the session_id
+            // No need to update the database here - it's already canonical,
+            // with an accurate timestamp
+            if skewed_cmp(unid.timestamp, session.create_time) {
+                info!(message = "Found existing session with exact create time");
+                return Ok(session.session_id);
+            }
+
+            // We should never be looking at a case where the query returned
+            // a create_time less than the unid.timestamp
+            if unid.timestamp > session.create_time {
+                bail!(
+                    "unid.timestamp > session.create_time {} {}",
+                    unid.timestamp,
+                    session.create_time
+                );
+            }
+        }
+
+        // Look for last session where session.create_time <= unid.create_time
+        let session = self.find_last_session_before(&unid).await?;
+
+        if let Some(session) = session {
+            // If session.end_time >= unid.create_time (indicates overlapping sessions, error)
+            // This will correct that session so that it does not overlap anymore.
+            if session.end_time >= unid.timestamp {
+                warn!(
+                    "Found session created before new session. Fixing overlapping end_time.
+                    {:?}
+                    {:?}
+                ",
+                    session, unid
+                );
+                // if session.end_time is NOT canonical, we can update it
+                if !session.is_end_canon {
+                    let new_end_time =
+                        std::cmp::max(session.create_time, unid.timestamp.saturating_sub(100));
+                    self.update_session_end_time(&session, new_end_time, false)
+                        .await?;
+                } else {
+                    warn!(message = "Overlapping session has a canonical end_time. Not fixing.");
+                }
+            }
+        }
+
+        // Create new session, return new session id
+        let session = Session {
+            session_id: Uuid::new_v4().to_string(),
+            create_time: unid.timestamp,
+            end_time: unid.timestamp + 101,
+            is_create_canon: true,
+            is_end_canon: false,
+            version: 0,
+            pseudo_key: unid.pseudo_key,
+        };
+
+        info!(message = "Creating session");
+        self.create_session(&session).await?;
+        Ok(session.session_id)
+    }
+
+    #[tracing::instrument(skip(self, unid), err)]
+    async fn handle_last_seen(
+        &self,
+        unid: UnidSession,
+        should_default: bool,
+    ) -> Result<String, Error> {
+        info!(
+            message="Handling unid session",
+            pseudo_key=?unid.pseudo_key, timestamp=?unid.timestamp
+        );
+
+        // Look for session where session.create_time <= unid.create_time <= session.end_time
+        // Look for last session where session.create_time <= unid.create_time
+        let session = self.find_last_session_before(&unid).await?;
+        if let Some(session) = session {
+            if unid.timestamp < session.end_time || skewed_cmp(unid.timestamp, session.end_time) {
+                info!(message = "Identified session because it fell within a timeline.");
+                return Ok(session.session_id);
+            }
+
+            // A canonical end_time means the session has terminated, so we only
+            // extend sessions that have not yet been observed to end
+            if !session.is_end_canon {
+                info!(message = "Updating session end_time.");
+                self.update_session_end_time(&session, unid.timestamp, false)
+                    .await?;
+
+                return Ok(session.session_id);
+            }
+        }
+
+        let session = self.find_first_session_after(&unid).await?;
+        if let Some(session) = session {
+            if !session.is_create_canon {
+                info!(message = "Found a later, non canonical session. Extending create_time.");
+
+                self.update_session_create_time(&session, unid.timestamp, false)
+                    .await?;
+                return Ok(session.session_id);
+            }
+        }
+
+        if should_default {
+            info!(message = "Defaulting and creating new session.");
+            let session_id = Uuid::new_v4().to_string();
+            let session = Session {
+                session_id: session_id.clone(),
+                create_time: unid.timestamp,
+                end_time: unid.timestamp + 101,
+                is_create_canon: false,
+                is_end_canon: false,
+                version: 0,
+                pseudo_key: unid.pseudo_key,
+            };
+            self.create_session(&session).await?;
+
+            Ok(session_id)
+        } else {
+            warn!(message = "Could not attribute session. Not defaulting.");
+            bail!(
+                "Could not attribute session. should_default {}. Not defaulting.",
+                should_default
+            )
+        }
+    }
+
+    #[tracing::instrument(skip(self, unid), err)]
+    async fn handle_termination_event(
+        &self,
+        unid: UnidSession,
+        should_default: bool,
+    ) -> Result<String, Error> {
+        info!(
+            message="Handling unid session termination",
+            pseudo_key=?unid.pseudo_key, timestamp=?unid.timestamp
+        );
+
+        // Look for last session where session.create_time <= unid.timestamp
+        let session = self.find_last_session_before(&unid).await?;
+
+        if let Some(session) = session {
+            if !session.is_end_canon {
+                info!(message = "Terminating session. Making end_time canonical.");
+                self.update_session_end_time(&session, unid.timestamp, true)
+                    .await?;
+                return Ok(session.session_id);
+            }
+
+            // The session has already been terminated, this is either the same
+            // termination or one that belongs to a later session
+            if skewed_cmp(unid.timestamp, session.end_time) {
+                info!(message = "Found existing session with exact end time");
+                return Ok(session.session_id);
+            }
+
+            if unid.timestamp < session.end_time {
+                bail!(
+                    "unid.timestamp < session.end_time for terminated session {} {}",
+                    unid.timestamp,
+                    session.end_time
+                );
+            }
+        }
+
+        // Any session created after this termination can not be the one that terminated,
+        // so the session's creation was never observed
+        if should_default {
+            info!(message = "Defaulting and creating new terminated session.");
+            let session = Session {
+                session_id: Uuid::new_v4().to_string(),
+                create_time: unid.timestamp,
+                end_time: unid.timestamp,
+                is_create_canon: false,
+                is_end_canon: true,
+                version: 0,
+                pseudo_key: unid.pseudo_key,
+            };
+            self.create_session(&session).await?;
+
+            Ok(session.session_id)
+        } else {
+            warn!(message = "Could not attribute terminated session. Not defaulting.");
+            bail!(
+                "Could not attribute terminated session. should_default {}. Not defaulting.",
+                should_default
+            )
+        }
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    async fn handle_unid_session(
+        &self,
+        mut unid: UnidSession,
+        should_default: bool,
+    ) -> Result<String, Error> {
+        unid.timestamp = shave_int(unid.timestamp, 1);
+        if unid.is_creation {
+            self.handle_creation_event(unid).await
+        } else if unid.is_termination {
+            self.handle_termination_event(unid, should_default).await
+        } else {
+            self.handle_last_seen(unid, should_default).await
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionDb<D>
 where
@@ -46,12 +316,15 @@ where
             table_name: table_name.into(),
         }
     }
+}
 
+#[async_trait]
+impl<D> SessionStore for SessionDb<D>
+where
+    D: DynamoDb,
+{
     #[tracing::instrument(skip(self, unid), err)]
-    pub async fn find_first_session_after(
-        &self,
-        unid: &UnidSession,
-    ) -> Result<Option<Session>, Error> {
+    async fn find_first_session_after(&self, unid: &UnidSession) -> Result<Option<Session>, Error> {
         info!(message="Finding first session after", table_name=?&self.table_name);
         let query = QueryInput {
             consistent_read: Some(true),
@@ -90,10 +363,7 @@ where
     }
 
     #[tracing::instrument(skip(self, unid), err)]
-    pub async fn find_last_session_before(
-        &self,
-        unid: &UnidSession,
-    ) -> Result<Option<Session>, Error> {
+    async fn find_last_session_before(&self, unid: &UnidSession) -> Result<Option<Session>, Error> {
         info!(message = "Finding last session before");
         let query = QueryInput {
             consistent_read: Some(true),
@@ -134,7 +404,7 @@ where
     // new create_time
     // This method assumes that the `session` passed in has already been modified
     #[tracing::instrument(skip(self, session), err)]
-    pub async fn update_session_create_time(
+    async fn update_session_create_time(
         &self,
         session: &Session,
         new_time: u64,
@@ -188,107 +458,24 @@ where
     }
 
     #[tracing::instrument(skip(self, session), err)]
-    pub async fn make_create_time_canonical(&self, session: &Session) -> Result<(), Error> {
-        info!(message = "Updating session end time");
-        // Use version as a constraint
-        let upd_req = UpdateItemInput {
-            key: hmap! {
-                "pseudo_key".to_owned() => AttributeValue {
-                    s: session.pseudo_key.clone().into(),
-                    ..Default::default()
-                },
-                "create_time".to_owned() => AttributeValue {
-                    n: session.create_time.to_string().into(),
-                    ..Default::default()
-                }
-            },
-            attribute_updates: Some(hmap! {
-                "is_create_canon".to_owned() => AttributeValueUpdate {
-                    value: Some(AttributeValue {
-                            bool: true.into(),
-                            ..Default::default()
-                        }),
-                    ..Default::default()
-                },
-                "version".to_owned() => AttributeValueUpdate {
-                    value: Some(AttributeValue {
-                        n: (session.version + 1).to_string().into(),
-                        ..Default::default()
-                    }),
-                    ..Default::default()
-                }
-            }),
-            table_name: self.table_name.clone(),
-            condition_expression: Some("version = :version".into()),
-            expression_attribute_values: Some(hmap! {
-                ":version".to_owned() => AttributeValue {
-                    n: session.version.to_string().into(),
-                    ..Default::default()
-                }
-            }),
-            ..Default::default()
-        };
+    async fn make_create_time_canonical(&self, session: &Session) -> Result<(), Error> {
+        info!(message = "Making session create time canonical");
+        let upd_req = create_time_canonical_update(&self.table_name, session);
 
         self.dynamo.update_item(upd_req).await?;
 
         Ok(())
     }
 
-    // Update version, and use it as a constraint
     #[tracing::instrument(skip(self, session), err)]
-    pub async fn update_session_end_time(
+    async fn update_session_end_time(
         &self,
         session: &Session,
         new_time: u64,
         is_canon: bool,
     ) -> Result<(), Error> {
         info!(message = "Updating session end time");
-        // Use version as a constraint
-        let upd_req = UpdateItemInput {
-            key: hmap! {
-                "pseudo_key".to_owned() => AttributeValue {
-                    s: session.pseudo_key.clone().into(),
-                    ..Default::default()
-                },
-                "create_time".to_owned() => AttributeValue {
-                    n: session.create_time.to_string().into(),
-                    ..Default::default()
-                }
-            },
-            attribute_updates: Some(hmap! {
-                "end_time".to_owned() => AttributeValueUpdate {
-                    value: Some(AttributeValue {
-                        n: new_time.to_string().into(),
-                        ..Default::default()
-                    }),
-                    ..Default::default()
-                },
-                "is_end_canon".to_owned() => AttributeValueUpdate {
-                    value: Some(AttributeValue {
-                            bool: is_canon.into(),
-                            ..Default::default()
-                        }),
-                    ..Default::default()
-                },
-                "version".to_owned() => AttributeValueUpdate {
-                    value: Some(AttributeValue {
-                        n: (session.version + 1).to_string().into(),
-                        ..Default::default()
-                    }),
-                    ..Default::default()
-
-                }
-            }),
-            table_name: self.table_name.clone(),
-            condition_expression: Some("version = :version".into()),
-            expression_attribute_values: Some(hmap! {
-                ":version".to_owned() => AttributeValue {
-                    n: session.version.to_string().into(),
-                    ..Default::default()
-                }
-            }),
-            ..Default::default()
-        };
+        let upd_req = session_end_time_update(&self.table_name, session, new_time, is_canon);
 
         self.dynamo.update_item(upd_req).await?;
 
@@ -296,7 +483,7 @@ where
     }
 
     #[tracing::instrument(skip(self, session), err)]
-    pub async fn create_session(&self, session: &Session) -> Result<(), Error> {
+    async fn create_session(&self, session: &Session) -> Result<(), Error> {
         let put_req = PutItemInput {
             item: serde_dynamodb::to_hashmap(session).unwrap(),
             table_name: self.table_name.clone(),
@@ -309,7 +496,7 @@ where
     }
 
     #[tracing::instrument(skip(self, session), err)]
-    pub async fn delete_session(&self, session: &Session) -> Result<(), Error> {
+    async fn delete_session(&self, session: &Session) -> Result<(), Error> {
         let del_req = DeleteItemInput {
             key: hmap! {
                 "pseudo_key".to_owned() => AttributeValue {
@@ -328,165 +515,98 @@ where
         self.dynamo.delete_item(del_req).await?;
         Ok(())
     }
+}
 
-    #[tracing::instrument(skip(self, unid), err)]
-    pub async fn handle_creation_event(&self, unid: UnidSession) -> Result<String, Error> {
-        info!(
-            message="Handling unid session creation",
-            pseudo_key=?unid.pseudo_key, timestamp=?unid.timestamp
-        );
-
-        // Look for first session where session.create_time >= unid.create_time
-        let session = self.find_first_session_after(&unid).await?;
-
-        if let Some(session) = session {
-            // If session.is_create_canon is false,
-            // This means that there is a 'Guessed' session in the future,
-            // and we should consider this the canonical ID for that session
-            if !session.is_create_canon && session.create_time != unid.timestamp {
-                info!(message = "Extending session create_time");
-                self.update_session_create_time(&session, unid.timestamp, true)
-                    .await?;
-                return Ok(session.session_id);
-            }
-
-            tracing::debug!(
-                "UNID: {} - {} - {}",
-                unid.pseudo_key,
-                unid.timestamp,
-                unid.pseudo_key
-            );
-
-            // If the timestamps are the same, we've found the session_id
-            // No need to update the database here - it's already canonical,
-            // with an accurate timestamp
-            if skewed_cmp(unid.timestamp, session.create_time) {
-                info!(message = "Found existing session with exact create time");
-                return Ok(session.session_id);
-            }
-
-            // We should never be looking at a case where the query returned
-            // a create_time less than the unid.timestamp
-            if unid.timestamp > session.create_time {
-                bail!(
-                    "unid.timestamp > session.create_time {} {}",
-                    unid.timestamp,
-                    session.create_time
-                );
-            }
-        }
-
-        // Look for last session where session.create_time <= unid.create_time
-        let session = self.find_last_session_before(&unid).await?;
-
-        if let Some(session) = session {
-            // If session.end_time >= unid.create_time (indicates overlapping sessions, error)
-            // This will correct that session so that it does not overlap anymore.
-            if session.end_time >= unid.timestamp {
-                warn!(
-                    "Found session created before new session. Fixing overlapping end_time.
-                    {:?}
-                    {:?}
-                ",
-                    session, unid
-                );
-                // if session.end_time is NOT canonical, we can update it
-                //                self.update_session_end_time(&session, unid.timestamp - 100, session.is_end_canon)?;
-            }
-        }
-
-        // Create new session, return new session id
-        let session = Session {
-            session_id: Uuid::new_v4().to_string(),
-            create_time: unid.timestamp,
-            end_time: unid.timestamp + 101,
-            is_create_canon: true,
-            is_end_canon: false,
-            version: 0,
-            pseudo_key: unid.pseudo_key,
-        };
-
-        info!(message = "Creating session");
-        self.create_session(&session).await?;
-        Ok(session.session_id)
-    }
-
-    #[tracing::instrument(skip(self, unid), err)]
-    pub async fn handle_last_seen(
-        &self,
-        unid: UnidSession,
-        should_default: bool,
-    ) -> Result<String, Error> {
-        info!(
-            message="Handling unid session",
-            pseudo_key=?unid.pseudo_key, timestamp=?unid.timestamp
-        );
-
-        // Look for session where session.create_time <= unid.create_time <= session.end_time
-        // Look for last session where session.create_time <= unid.create_time
-        let session = self.find_last_session_before(&unid).await?;
-        if let Some(mut session) = session {
-            if unid.timestamp < session.end_time || skewed_cmp(unid.timestamp, session.end_time) {
-                info!(message = "Identified session because it fell within a timeline.");
-                return Ok(session.session_id);
+/// Builds the update that marks a session's create time as canonical, conditional on the
+/// session not having been modified since it was read
+pub fn create_time_canonical_update(table_name: &str, session: &Session) -> UpdateItemInput {
+    versioned_update(
+        table_name,
+        session,
+        "SET #is_create_canon = :is_create_canon",
+        hmap! {
+            "#is_create_canon".to_owned() => "is_create_canon".to_owned()
+        },
+        hmap! {
+            ":is_create_canon".to_owned() => AttributeValue {
+                bool: true.into(),
+                ..Default::default()
             }
+        },
+    )
+}
 
-            if !session.is_end_canon {
-                session.end_time = unid.timestamp;
-                info!(message = "Updating session end_time.");
-                //                self.update_session_end_time(&session, unid.timestamp, false)?;
-
-                return Ok(session.session_id);
+/// Builds the update that sets a session's end time, conditional on the session not having been
+/// modified since it was read
+pub fn session_end_time_update(
+    table_name: &str,
+    session: &Session,
+    new_time: u64,
+    is_canon: bool,
+) -> UpdateItemInput {
+    versioned_update(
+        table_name,
+        session,
+        "SET #end_time = :end_time, #is_end_canon = :is_end_canon",
+        hmap! {
+            "#end_time".to_owned() => "end_time".to_owned(),
+            "#is_end_canon".to_owned() => "is_end_canon".to_owned()
+        },
+        hmap! {
+            ":end_time".to_owned() => AttributeValue {
+                n: new_time.to_string().into(),
+                ..Default::default()
+            },
+            ":is_end_canon".to_owned() => AttributeValue {
+                bool: is_canon.into(),
+                ..Default::default()
             }
-        }
+        },
+    )
+}
 
-        let session = self.find_first_session_after(&unid).await?;
-        if let Some(session) = session {
-            if !session.is_create_canon {
-                info!(message = "Found a later, non canonical session. Extending create_time.");
+// DynamoDB rejects requests that mix the legacy `attribute_updates` with expressions, so the
+// version check and the updates are both written as expressions
+fn versioned_update(
+    table_name: &str,
+    session: &Session,
+    set_expression: &str,
+    mut names: HashMap<String, String>,
+    mut values: HashMap<String, AttributeValue>,
+) -> UpdateItemInput {
+    names.insert("#version".to_owned(), "version".to_owned());
+    values.insert(
+        ":version".to_owned(),
+        AttributeValue {
+            n: session.version.to_string().into(),
+            ..Default::default()
+        },
+    );
+    values.insert(
+        ":next_version".to_owned(),
+        AttributeValue {
+            n: (session.version + 1).to_string().into(),
+            ..Default::default()
+        },
+    );
 
-                self.update_session_create_time(&session, unid.timestamp, false)
-                    .await?;
-                return Ok(session.session_id);
+    UpdateItemInput {
+        key: hmap! {
+            "pseudo_key".to_owned() => AttributeValue {
+                s: session.pseudo_key.clone().into(),
+                ..Default::default()
+            },
+            "create_time".to_owned() => AttributeValue {
+                n: session.create_time.to_string().into(),
+                ..Default::default()
             }
-        }
-
-        if should_default {
-            info!(message = "Defaulting and creating new session.");
-            let session_id = Uuid::new_v4().to_string();
-            let session = Session {
-                session_id: session_id.clone(),
-                create_time: unid.timestamp,
-                end_time: unid.timestamp + 101,
-                is_create_canon: false,
-                is_end_canon: false,
-                version: 0,
-                pseudo_key: unid.pseudo_key,
-            };
-            self.create_session(&session).await?;
-
-            Ok(session_id)
-        } else {
-            warn!(message = "Could not attribute session. Not defaulting.");
-            bail!(
-                "Could not attribute session. should_default {}. Not defaulting.",
-                should_default
-            )
-        }
-    }
-
-    #[tracing::instrument(skip(self), err)]
-    pub async fn handle_unid_session(
-        &self,
-        mut unid: UnidSession,
-        should_default: bool,
-    ) -> Result<String, Error> {
-        unid.timestamp = shave_int(unid.timestamp, 1);
-        if unid.is_creation {
-            self.handle_creation_event(unid).await
-        } else {
-            self.handle_last_seen(unid, should_default).await
-        }
+        },
+        table_name: table_name.to_owned(),
+        update_expression: Some(format!("{}, #version = :next_version", set_expression)),
+        condition_expression: Some("#version = :version".into()),
+        expression_attribute_names: Some(names),
+        expression_attribute_values: Some(values),
+        ..Default::default()
     }
 }
 
@@ -15,7 +15,8 @@ use serde::{
 pub struct UnidSession {
     pub pseudo_key: String,
     pub timestamp: u64,
-    pub is_creation: bool, // Is this a creation event
+    pub is_creation: bool,    // Is this a creation event
+    pub is_termination: bool, // Is this a termination event
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
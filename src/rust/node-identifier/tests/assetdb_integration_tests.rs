@@ -0,0 +1,165 @@
+#![cfg(feature = "integration")]
+
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
+use grapl_config::env_helpers::FromEnv;
+use grapl_graph_descriptions::graph_description::*;
+use node_identifier::assetdb::{
+    AssetIdDb,
+    AssetIdStore,
+    AssetIdentifier,
+    HostId,
+};
+use quickcheck_macros::quickcheck;
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeDefinition,
+    CreateTableError,
+    CreateTableInput,
+    CreateTableOutput,
+    DeleteTableInput,
+    DynamoDb,
+    DynamoDbClient,
+    KeySchemaElement,
+    ProvisionedThroughput,
+};
+use tokio::runtime::Runtime;
+
+async fn try_create_table(
+    dynamo: &impl DynamoDb,
+    table_name: String,
+) -> Result<CreateTableOutput, RusotoError<CreateTableError>> {
+    dynamo
+        .create_table(CreateTableInput {
+            table_name,
+            attribute_definitions: vec![
+                AttributeDefinition {
+                    attribute_name: "pseudo_key".into(),
+                    attribute_type: "S".into(),
+                },
+                AttributeDefinition {
+                    attribute_name: "c_timestamp".into(),
+                    attribute_type: "N".into(),
+                },
+            ],
+            key_schema: vec![
+                KeySchemaElement {
+                    attribute_name: "pseudo_key".into(),
+                    key_type: "HASH".into(),
+                },
+                KeySchemaElement {
+                    attribute_name: "c_timestamp".into(),
+                    key_type: "RANGE".into(),
+                },
+            ],
+            provisioned_throughput: Some(ProvisionedThroughput {
+                read_capacity_units: 3,
+                write_capacity_units: 3,
+            }),
+            ..Default::default()
+        })
+        .await
+}
+
+fn create_or_empty_table(dynamo: &impl DynamoDb, table_name: impl Into<String>) {
+    let runtime = Runtime::new().unwrap();
+    let table_name = table_name.into();
+
+    let _ = runtime.block_on(dynamo.delete_table(DeleteTableInput {
+        table_name: table_name.clone(),
+    }));
+
+    std::thread::sleep(Duration::from_millis(250));
+
+    while let Err(_e) = runtime.block_on(try_create_table(dynamo, table_name.clone())) {
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+fn host_node(properties: &[(&str, &str)], last_seen_time: u64) -> NodeDescription {
+    let mut node = NodeDescription {
+        properties: HashMap::new(),
+        node_key: "node_key".to_owned(),
+        node_type: "Process".to_owned(),
+        id_strategy: vec![Session {
+            primary_key_properties: vec!["process_id".to_owned()],
+            primary_key_requires_asset_id: true,
+            create_time: 0,
+            last_seen_time,
+            terminate_time: 0,
+        }
+        .into()],
+    };
+    for (name, value) in properties {
+        node.set_property(*name, ImmutableStrProp::from(*value));
+    }
+    node
+}
+
+// Given a mapping from hostname 'H' to asset_id 'A' at time 'X'
+// When resolving 'H' at time 'Y' where 'Y' > 'X'
+// Then 'A' should be returned
+#[quickcheck]
+fn resolve_existing_mapping(hostname: String, asset_id: String) {
+    let runtime = Runtime::new().unwrap();
+    let table_name = "asset_id_mappings_resolve_existing_mapping";
+    let dynamo = DynamoDbClient::from_env();
+
+    create_or_empty_table(&dynamo, table_name);
+
+    let asset_id_db = AssetIdDb::new(dynamo, table_name);
+    let host_id = HostId::Hostname(hostname);
+
+    runtime
+        .block_on(asset_id_db.create_mapping(&host_id, asset_id.clone(), 1_544_301_484_600))
+        .expect("Failed to create mapping");
+
+    let resolved = runtime
+        .block_on(asset_id_db.resolve_asset_id(&host_id, 1_544_301_484_700))
+        .expect("Failed to resolve asset_id");
+
+    assert_eq!(resolved, Some(asset_id));
+}
+
+// Given a host first seen with a hostname and an ip
+// When the host is later seen with the same hostname and a new ip
+// Then both observations should resolve to the same asset_id
+#[test]
+fn new_ip_resolves_to_existing_asset() {
+    let runtime = Runtime::new().unwrap();
+    let table_name = "asset_id_mappings_new_ip_resolves_to_existing_asset";
+    let dynamo = DynamoDbClient::from_env();
+
+    create_or_empty_table(&dynamo, table_name);
+
+    let asset_identifier = AssetIdentifier::new(AssetIdDb::new(dynamo, table_name));
+
+    let first = host_node(
+        &[("hostname", "host-a"), ("host_ip", "10.0.0.1")],
+        1_544_301_484_600,
+    );
+    let first_asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&first, 0))
+        .expect("Failed to attribute asset_id");
+
+    let second = host_node(
+        &[("hostname", "host-a"), ("host_ip", "10.0.0.2")],
+        1_544_301_485_600,
+    );
+    let second_asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&second, 0))
+        .expect("Failed to attribute asset_id");
+
+    assert_eq!(first_asset_id, second_asset_id);
+
+    // The new ip alone now resolves to the asset as well
+    let third = host_node(&[("host_ip", "10.0.0.2")], 1_544_301_486_600);
+    let third_asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&third, 0))
+        .expect("Failed to attribute asset_id");
+
+    assert_eq!(first_asset_id, third_asset_id);
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use grapl_graph_descriptions::graph_description::*;
+use grapl_observe::metric_reporter::MetricReporter;
+use node_identifier::{
+    assetdb::AssetIdentifier,
+    dynamic_sessiondb::NodeDescriptionIdentifier,
+    in_memory_assetdb::InMemoryAssetIdDb,
+    in_memory_mappingdb::InMemoryMappingDb,
+    in_memory_sessiondb::InMemorySessionDb,
+};
+use tokio::runtime::Runtime;
+
+const BASE_TIMESTAMP: u64 = 1_544_301_484_600;
+
+fn identifier() -> NodeDescriptionIdentifier<InMemorySessionDb, InMemoryMappingDb, InMemoryAssetIdDb>
+{
+    NodeDescriptionIdentifier::new(
+        InMemorySessionDb::new(),
+        InMemoryMappingDb::new(),
+        AssetIdentifier::new(InMemoryAssetIdDb::new()),
+        MetricReporter::new("identity_strategy_tests"),
+        false,
+    )
+}
+
+/// A process node with the strategies `ProcessNode::identity_strategies` provides: its
+/// process_guid, falling back to its asset_id and process_id session
+fn process_node(
+    process_guid: Option<&str>,
+    process_id: u64,
+    create_time: u64,
+    terminate_time: u64,
+) -> NodeDescription {
+    let mut node = NodeDescription {
+        properties: HashMap::new(),
+        node_key: uuid::Uuid::new_v4().to_string(),
+        node_type: "Process".to_owned(),
+        id_strategy: vec![
+            Static {
+                primary_key_properties: vec!["process_guid".to_owned()],
+                primary_key_requires_asset_id: false,
+            }
+            .into(),
+            Session {
+                primary_key_properties: vec!["asset_id".to_owned(), "process_id".to_owned()],
+                primary_key_requires_asset_id: false,
+                create_time,
+                last_seen_time: 0,
+                terminate_time,
+            }
+            .into(),
+        ],
+    };
+    node.set_property("asset_id", ImmutableStrProp::from("asset-a"));
+    node.set_property("process_id", ImmutableUintProp::from(process_id));
+    if let Some(process_guid) = process_guid {
+        node.set_property("process_guid", ImmutableStrProp::from(process_guid));
+    }
+    node
+}
+
+// Given a process created and later terminated, both observed with its process_guid
+// When a later process reuses the process_id under a new process_guid
+// Then the first two observations should share a node key, and the new process should not
+#[test]
+fn processes_are_identified_by_process_guid() {
+    let runtime = Runtime::new().unwrap();
+    let identifier = identifier();
+    let identify = |node: NodeDescription| {
+        runtime
+            .block_on(identifier.attribute_dynamic_node(&node, BASE_TIMESTAMP))
+            .expect("Failed to attribute node")
+            .node_key
+    };
+
+    let created = identify(process_node(Some("guid-a"), 100, BASE_TIMESTAMP, 0));
+    let terminated = identify(process_node(Some("guid-a"), 100, 0, BASE_TIMESTAMP + 1_000));
+    let reused = identify(process_node(Some("guid-b"), 100, BASE_TIMESTAMP + 2_000, 0));
+
+    assert_eq!(created, terminated);
+    assert_ne!(created, reused);
+}
+
+// Given process observations without a process_guid
+// When they are identified
+// Then they should fall back to their session, and not collide with guid-identified processes
+#[test]
+fn processes_without_process_guid_fall_back_to_sessions() {
+    let runtime = Runtime::new().unwrap();
+    let identifier = identifier();
+    let identify = |node: NodeDescription| {
+        runtime
+            .block_on(identifier.attribute_dynamic_node(&node, BASE_TIMESTAMP))
+            .expect("Failed to attribute node")
+            .node_key
+    };
+
+    let guid_identified = identify(process_node(Some("guid-a"), 100, BASE_TIMESTAMP, 0));
+    let created = identify(process_node(None, 100, BASE_TIMESTAMP, 0));
+    let terminated = identify(process_node(None, 100, 0, BASE_TIMESTAMP + 1_000));
+
+    assert_eq!(created, terminated);
+    assert_ne!(created, guid_identified);
+}
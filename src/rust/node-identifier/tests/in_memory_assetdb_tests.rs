@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use grapl_graph_descriptions::graph_description::*;
+use node_identifier::{
+    assetdb::{
+        AssetIdStore,
+        AssetIdentifier,
+        HostId,
+    },
+    in_memory_assetdb::InMemoryAssetIdDb,
+};
+use quickcheck_macros::quickcheck;
+use tokio::runtime::Runtime;
+
+fn static_host_node(properties: &[(&str, &str)]) -> NodeDescription {
+    let mut node = NodeDescription {
+        properties: HashMap::new(),
+        node_key: "node_key".to_owned(),
+        node_type: "File".to_owned(),
+        id_strategy: vec![Static {
+            primary_key_properties: vec!["file_path".to_owned()],
+            primary_key_requires_asset_id: true,
+        }
+        .into()],
+    };
+    for (name, value) in properties {
+        node.set_property(*name, ImmutableStrProp::from(*value));
+    }
+    node
+}
+
+fn host_node(properties: &[(&str, &str)], last_seen_time: u64) -> NodeDescription {
+    let mut node = NodeDescription {
+        properties: HashMap::new(),
+        node_key: "node_key".to_owned(),
+        node_type: "Process".to_owned(),
+        id_strategy: vec![Session {
+            primary_key_properties: vec!["process_id".to_owned()],
+            primary_key_requires_asset_id: true,
+            create_time: 0,
+            last_seen_time,
+            terminate_time: 0,
+        }
+        .into()],
+    };
+    for (name, value) in properties {
+        node.set_property(*name, ImmutableStrProp::from(*value));
+    }
+    node
+}
+
+// Given a mapping from hostname 'H' to asset_id 'A' at time 'X'
+// When resolving 'H' at time 'Y' where 'Y' > 'X'
+// Then 'A' should be returned
+#[quickcheck]
+fn resolve_existing_mapping(hostname: String, asset_id: String) {
+    let runtime = Runtime::new().unwrap();
+    let asset_id_db = InMemoryAssetIdDb::new();
+    let host_id = HostId::Hostname(hostname);
+
+    runtime
+        .block_on(asset_id_db.create_mapping(&host_id, asset_id.clone(), 1_544_301_484_600))
+        .expect("Failed to create mapping");
+
+    let resolved = runtime
+        .block_on(asset_id_db.resolve_asset_id(&host_id, 1_544_301_484_700))
+        .expect("Failed to resolve asset_id");
+
+    assert_eq!(resolved, Some(asset_id));
+}
+
+// Given an ip mapped to asset_id 'A' at time 'X' and to asset_id 'B' at time 'Z'
+// When resolving the ip before 'X', between 'X' and 'Z', and after 'Z'
+// Then the earliest mapping, 'A' and 'B' should be returned respectively
+#[test]
+fn resolve_mapping_at_time() {
+    let runtime = Runtime::new().unwrap();
+    let asset_id_db = InMemoryAssetIdDb::new();
+    let host_id = HostId::Ip("10.0.0.1".to_owned());
+
+    runtime
+        .block_on(asset_id_db.create_mapping(&host_id, "asset-a".to_owned(), 1_544_301_484_600))
+        .expect("Failed to create mapping");
+    runtime
+        .block_on(asset_id_db.create_mapping(&host_id, "asset-b".to_owned(), 1_544_301_486_600))
+        .expect("Failed to create mapping");
+
+    let resolve = |timestamp| {
+        runtime
+            .block_on(asset_id_db.resolve_asset_id(&host_id, timestamp))
+            .expect("Failed to resolve asset_id")
+    };
+
+    assert_eq!(resolve(1_544_301_483_600), Some("asset-a".to_owned()));
+    assert_eq!(resolve(1_544_301_485_600), Some("asset-a".to_owned()));
+    assert_eq!(resolve(1_544_301_487_600), Some("asset-b".to_owned()));
+    assert_eq!(
+        runtime
+            .block_on(asset_id_db.resolve_asset_id(&HostId::Ip("10.0.0.2".to_owned()), 0))
+            .expect("Failed to resolve asset_id"),
+        None
+    );
+}
+
+// Given a host first seen with a hostname and an ip
+// When the host is later seen with the same hostname and a new ip
+// Then both observations should resolve to the same asset_id
+#[test]
+fn new_ip_resolves_to_existing_asset() {
+    let runtime = Runtime::new().unwrap();
+    let asset_identifier = AssetIdentifier::new(InMemoryAssetIdDb::new());
+
+    let first = host_node(
+        &[("hostname", "host-a"), ("host_ip", "10.0.0.1")],
+        1_544_301_484_600,
+    );
+    let first_asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&first, 0))
+        .expect("Failed to attribute asset_id");
+
+    let second = host_node(
+        &[("hostname", "host-a"), ("host_ip", "10.0.0.2")],
+        1_544_301_485_600,
+    );
+    let second_asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&second, 0))
+        .expect("Failed to attribute asset_id");
+
+    assert_eq!(first_asset_id, second_asset_id);
+
+    // The new ip alone now resolves to the asset as well
+    let third = host_node(&[("host_ip", "10.0.0.2")], 1_544_301_486_600);
+    let third_asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&third, 0))
+        .expect("Failed to attribute asset_id");
+
+    assert_eq!(first_asset_id, third_asset_id);
+}
+
+// Given a node that already carries asset_id 'A' and a hostname mapped to asset_id 'B'
+// When attributing the node's asset_id
+// Then 'A' should be kept, and the hostname remapped to it
+#[test]
+fn existing_asset_id_is_kept() {
+    let runtime = Runtime::new().unwrap();
+    let asset_id_db = InMemoryAssetIdDb::new();
+    let asset_identifier = AssetIdentifier::new(asset_id_db.clone());
+    let hostname = HostId::Hostname("host-a".to_owned());
+
+    runtime
+        .block_on(asset_id_db.create_mapping(&hostname, "asset-b".to_owned(), 1_544_301_484_600))
+        .expect("Failed to create mapping");
+
+    let node = host_node(
+        &[("asset_id", "asset-a"), ("hostname", "host-a")],
+        1_544_301_485_600,
+    );
+    let asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&node, 0))
+        .expect("Failed to attribute asset_id");
+
+    assert_eq!(asset_id, "asset-a");
+    assert_eq!(
+        runtime
+            .block_on(asset_id_db.resolve_asset_id(&hostname, 1_544_301_485_600))
+            .expect("Failed to resolve asset_id"),
+        Some("asset-a".to_owned())
+    );
+}
+
+// Given an ip mapped to asset_id 'A' at time 'X' and to asset_id 'B' at time 'Z'
+// When attributing a statically identified node observed between 'X' and 'Z'
+// Then 'A' should be returned
+#[test]
+fn static_nodes_resolve_at_the_event_timestamp() {
+    let runtime = Runtime::new().unwrap();
+    let asset_id_db = InMemoryAssetIdDb::new();
+    let asset_identifier = AssetIdentifier::new(asset_id_db.clone());
+    let host_ip = HostId::Ip("10.0.0.1".to_owned());
+
+    runtime
+        .block_on(asset_id_db.create_mapping(&host_ip, "asset-a".to_owned(), 1_544_301_484_600))
+        .expect("Failed to create mapping");
+    runtime
+        .block_on(asset_id_db.create_mapping(&host_ip, "asset-b".to_owned(), 1_544_301_486_600))
+        .expect("Failed to create mapping");
+
+    let node = static_host_node(&[("file_path", "/tmp/a"), ("host_ip", "10.0.0.1")]);
+    let asset_id = runtime
+        .block_on(asset_identifier.attribute_asset_id(&node, 1_544_301_485_600))
+        .expect("Failed to attribute asset_id");
+
+    assert_eq!(asset_id, "asset-a");
+}
@@ -0,0 +1,142 @@
+use node_identifier::{
+    in_memory_sessiondb::InMemorySessionDb,
+    sessiondb::SessionStore,
+    sessions::UnidSession,
+};
+use quickcheck_macros::quickcheck;
+use tokio::runtime::Runtime;
+
+const BASE_TIMESTAMP: u64 = 1_544_301_484_600;
+
+// Session timestamps are shaved to the hundreds, so keep generated timestamps aligned
+fn timestamp(offset: u32) -> u64 {
+    BASE_TIMESTAMP + (offset as u64 * 100)
+}
+
+fn unid(pseudo_key: &str, timestamp: u64, is_creation: bool, is_termination: bool) -> UnidSession {
+    UnidSession {
+        pseudo_key: pseudo_key.to_owned(),
+        timestamp,
+        is_creation,
+        is_termination,
+    }
+}
+
+// Given an empty timeline
+// When the same canonical creation event comes in twice
+// Then both events should be attributed to the same session
+#[quickcheck]
+fn repeated_creation_is_idempotent(pseudo_key: String, offset: u32) {
+    let runtime = Runtime::new().unwrap();
+    let session_db = InMemorySessionDb::new();
+    let create_time = timestamp(offset);
+
+    let first = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, create_time, true, false), false),
+        )
+        .expect("Failed to handle unid");
+    let second = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, create_time, true, false), false),
+        )
+        .expect("Failed to handle unid");
+
+    assert_eq!(first, second);
+    assert_eq!(session_db.sessions_for(&pseudo_key).len(), 1);
+}
+
+// Given a timeline with a single canonical session
+// When a last seen event comes in that falls within that session
+// Then the event should be attributed to that session
+#[quickcheck]
+fn last_seen_within_session(pseudo_key: String, offset: u32, seen_after: u8) {
+    let runtime = Runtime::new().unwrap();
+    let session_db = InMemorySessionDb::new();
+    let create_time = timestamp(offset);
+
+    let created = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, create_time, true, false), false),
+        )
+        .expect("Failed to handle unid");
+
+    let last_seen_time = create_time + (seen_after % 100) as u64;
+    let seen = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, last_seen_time, false, false), false),
+        )
+        .expect("Failed to handle unid");
+
+    assert_eq!(created, seen);
+}
+
+// Given a timeline with a single canonical session
+// When a termination event comes in, followed by a later last seen event
+// Then the termination should close the session, and the later event should be
+//      attributed to a new session
+#[quickcheck]
+fn terminated_session_is_not_extended(pseudo_key: String, offset: u32, lifetime: u16) {
+    let runtime = Runtime::new().unwrap();
+    let session_db = InMemorySessionDb::new();
+    let create_time = timestamp(offset);
+    let terminate_time = create_time + 200 + (lifetime as u64 * 100);
+
+    let created = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, create_time, true, false), false),
+        )
+        .expect("Failed to handle unid");
+    let terminated = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, terminate_time, false, true), false),
+        )
+        .expect("Failed to handle unid");
+
+    assert_eq!(created, terminated);
+
+    let sessions = session_db.sessions_for(&pseudo_key);
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].is_end_canon);
+    assert_eq!(sessions[0].end_time, terminate_time);
+
+    let seen = runtime
+        .block_on(session_db.handle_unid_session(
+            unid(&pseudo_key, terminate_time + 10_000, false, false),
+            true,
+        ))
+        .expect("Failed to handle unid");
+
+    assert_ne!(created, seen);
+    assert_eq!(session_db.sessions_for(&pseudo_key).len(), 2);
+}
+
+// Given an empty timeline
+// When a last seen event comes in with 'should_default' true, followed by an earlier
+//      canonical creation event
+// Then the creation event should extend the guessed session rather than create a new one
+#[quickcheck]
+fn creation_extends_guessed_session(pseudo_key: String, offset: u32, seen_after: u16) {
+    let runtime = Runtime::new().unwrap();
+    let session_db = InMemorySessionDb::new();
+    let create_time = timestamp(offset);
+    let last_seen_time = create_time + 200 + (seen_after as u64 * 100);
+
+    let guessed = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, last_seen_time, false, false), true),
+        )
+        .expect("Failed to handle unid");
+    let created = runtime
+        .block_on(
+            session_db.handle_unid_session(unid(&pseudo_key, create_time, true, false), false),
+        )
+        .expect("Failed to handle unid");
+
+    assert_eq!(guessed, created);
+
+    let sessions = session_db.sessions_for(&pseudo_key);
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].is_create_canon);
+    assert_eq!(sessions[0].create_time, create_time);
+}
@@ -0,0 +1,113 @@
+use grapl_graph_descriptions::graph_description::{
+    GraphDescription,
+    ImmutableStrProp,
+    NodeDescription,
+    Session,
+    Static,
+};
+use node_identifier::dynamic_sessiondb::{
+    event_timestamp,
+    session_group_key,
+    session_order_key,
+};
+use quickcheck_macros::quickcheck;
+
+fn process_node(node_key: &str, process_id: &str, session: Session) -> NodeDescription {
+    let mut node = NodeDescription {
+        node_key: node_key.to_owned(),
+        node_type: "Process".to_owned(),
+        id_strategy: vec![session.into()],
+        ..Default::default()
+    };
+    node.set_property("process_id", ImmutableStrProp::from(process_id));
+    node.set_property("hostname", ImmutableStrProp::from("host"));
+    node
+}
+
+fn session(create_time: u64, last_seen_time: u64, terminate_time: u64) -> Session {
+    Session {
+        primary_key_properties: vec!["process_id".to_owned()],
+        primary_key_requires_asset_id: true,
+        create_time,
+        last_seen_time,
+        terminate_time,
+    }
+}
+
+// Given two nodes with the same primary key properties but different node keys
+// Then both nodes should be identified within the same group
+#[quickcheck]
+fn same_primary_key_shares_group(process_id: String, create_time: u64, last_seen_time: u64) {
+    let created = process_node("a", &process_id, session(create_time, 0, 0));
+    let seen = process_node("b", &process_id, session(0, last_seen_time, 0));
+
+    assert!(session_group_key(&created).is_some());
+    assert_eq!(session_group_key(&created), session_group_key(&seen));
+}
+
+// Given two nodes with different primary key properties
+// Then the nodes should be identified in different groups
+#[quickcheck]
+fn different_primary_key_separate_groups(process_id: String, other_process_id: String) {
+    if process_id == other_process_id {
+        return;
+    }
+
+    let node = process_node("a", &process_id, session(1, 0, 0));
+    let other = process_node("b", &other_process_id, session(1, 0, 0));
+
+    assert_ne!(session_group_key(&node), session_group_key(&other));
+}
+
+// Given a statically identified node
+// Then the node should not be part of any session group
+#[test]
+fn static_nodes_are_ungrouped() {
+    let mut node = NodeDescription {
+        node_key: "a".to_owned(),
+        node_type: "File".to_owned(),
+        id_strategy: vec![Static {
+            primary_key_properties: vec!["file_path".to_owned()],
+            primary_key_requires_asset_id: false,
+        }
+        .into()],
+        ..Default::default()
+    };
+    node.set_property("file_path", ImmutableStrProp::from("/bin/sh"));
+
+    assert_eq!(session_group_key(&node), None);
+}
+
+// Given a creation event and a last seen event at the same timestamp
+// Then the creation event should be ordered first
+#[quickcheck]
+fn creation_ordered_before_last_seen(timestamp: u64) {
+    let timestamp = timestamp.max(1);
+    let created = process_node("a", "1", session(timestamp, 0, 0));
+    let seen = process_node("b", "1", session(0, timestamp, 0));
+    let terminated = process_node("c", "1", session(0, 0, timestamp));
+
+    assert!(session_order_key(&created) < session_order_key(&seen));
+    assert_eq!(session_order_key(&seen), session_order_key(&terminated));
+}
+
+// Given a graph of a statically identified node and two process sessions
+// Then the graph's event timestamp should be the latest session timestamp
+#[test]
+fn event_timestamp_is_the_latest_session_timestamp() {
+    let mut graph = GraphDescription::new();
+    graph.add_node(process_node("a", "1", session(1_544_301_484_600, 0, 0)));
+    graph.add_node(process_node("b", "2", session(0, 1_544_301_485_600, 0)));
+    graph.add_node(NodeDescription {
+        node_key: "c".to_owned(),
+        node_type: "File".to_owned(),
+        id_strategy: vec![Static {
+            primary_key_properties: vec!["file_path".to_owned()],
+            primary_key_requires_asset_id: false,
+        }
+        .into()],
+        ..Default::default()
+    });
+
+    assert_eq!(event_timestamp(&graph), 1_544_301_485_600);
+}
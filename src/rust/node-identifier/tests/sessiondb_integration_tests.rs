@@ -4,7 +4,10 @@ use std::time::Duration;
 
 use grapl_config::env_helpers::FromEnv;
 use node_identifier::{
-    sessiondb::SessionDb,
+    sessiondb::{
+        SessionDb,
+        SessionStore,
+    },
     sessions::{
         Session,
         UnidSession,
@@ -93,6 +96,7 @@ fn canon_create_on_empty_timeline(asset_id: String, pid: u64) {
         pseudo_key: format!("{}{}", asset_id, pid),
         timestamp: 1544301484600,
         is_creation: true,
+        is_termination: false,
     };
 
     let session_id = runtime
@@ -139,6 +143,7 @@ fn canon_create_update_existing_non_canon_create(asset_id: String, pid: u64) {
         pseudo_key: format!("{}{}", asset_id, pid),
         timestamp: 1_544_301_484_500,
         is_creation: true,
+        is_termination: false,
     };
 
     let session_id = runtime
@@ -185,6 +190,7 @@ fn noncanon_create_update_existing_non_canon_create(asset_id: String, pid: u64)
         pseudo_key: format!("{}{}", asset_id, pid),
         timestamp: 1_544_301_484_500,
         is_creation: false,
+        is_termination: false,
     };
 
     let session_id = runtime
@@ -212,6 +218,7 @@ fn noncanon_create_on_empty_timeline_with_default(asset_id: String, pid: u64) {
         pseudo_key: format!("{}{}", asset_id, pid),
         timestamp: 1_544_301_484_500,
         is_creation: false,
+        is_termination: false,
     };
 
     let session_id = runtime
@@ -238,6 +245,7 @@ fn noncanon_create_on_empty_timeline_without_default() {
         pseudo_key: "asset_id_a1234".into(),
         timestamp: 1_544_301_484_500,
         is_creation: false,
+        is_termination: false,
     };
 
     let session_id = runtime.block_on(session_db.handle_unid_session(unid, false));
@@ -276,6 +284,7 @@ fn update_end_time(asset_id: String, pid: u64) {
         pseudo_key: format!("{}{}", asset_id, pid),
         timestamp: 1_544_301_484_800,
         is_creation: false,
+        is_termination: false,
     };
 
     let session_id = runtime
@@ -284,3 +293,100 @@ fn update_end_time(asset_id: String, pid: u64) {
 
     assert_eq!(session_id, "SessionId");
 }
+
+// Given a timeline with a single session, where that session has a non canon
+//      end time 'X'
+// When a termination event comes in with a timestamp of 'Y'
+//      where 'Y' > 'X'
+// Then the session should be terminated, and later events should not be
+//      attributed to it
+#[quickcheck]
+fn terminate_existing_session(asset_id: String, pid: u64) {
+    let runtime = Runtime::new().unwrap();
+    let table_name = "process_history_terminate_existing_session";
+    let dynamo = DynamoDbClient::from_env();
+
+    create_or_empty_table(&dynamo, table_name);
+
+    let session_db = SessionDb::new(dynamo, table_name);
+
+    let session = Session {
+        pseudo_key: format!("{}{}", asset_id, pid),
+        create_time: 1_544_301_484_600,
+        is_create_canon: true,
+        session_id: "SessionId".into(),
+        is_end_canon: false,
+        end_time: 1_544_301_484_700,
+        version: 0,
+    };
+
+    runtime
+        .block_on(session_db.create_session(&session))
+        .expect("Failed to create session");
+
+    let unid = UnidSession {
+        pseudo_key: format!("{}{}", asset_id, pid),
+        timestamp: 1_544_301_484_900,
+        is_creation: false,
+        is_termination: true,
+    };
+
+    let session_id = runtime
+        .block_on(session_db.handle_unid_session(unid, false))
+        .expect("Failed to handle unid");
+
+    assert_eq!(session_id, "SessionId");
+
+    // An event after the termination must not land in the dead session
+    let unid = UnidSession {
+        pseudo_key: format!("{}{}", asset_id, pid),
+        timestamp: 1_544_301_485_900,
+        is_creation: false,
+        is_termination: false,
+    };
+
+    let session_id = runtime
+        .block_on(session_db.handle_unid_session(unid, true))
+        .expect("Failed to handle unid");
+
+    assert_ne!(session_id, "SessionId");
+}
+
+// Given an empty timeline
+// When a termination event comes in and 'should_default' is true
+// Then create a new session with a canonical end time, which a later
+//      canonical creation event should be attributed to
+#[quickcheck]
+fn terminate_on_empty_timeline_with_default(asset_id: String, pid: u64) {
+    let runtime = Runtime::new().unwrap();
+    let table_name = "process_history_terminate_on_empty_timeline_with_default";
+    let dynamo = DynamoDbClient::from_env();
+
+    create_or_empty_table(&dynamo, table_name);
+
+    let session_db = SessionDb::new(dynamo, table_name);
+
+    let unid = UnidSession {
+        pseudo_key: format!("{}{}", asset_id, pid),
+        timestamp: 1_544_301_484_900,
+        is_creation: false,
+        is_termination: true,
+    };
+
+    let terminated_session_id = runtime
+        .block_on(session_db.handle_unid_session(unid, true))
+        .expect("Failed to handle unid");
+
+    let unid = UnidSession {
+        pseudo_key: format!("{}{}", asset_id, pid),
+        timestamp: 1_544_301_484_500,
+        is_creation: true,
+        is_termination: false,
+    };
+
+    let created_session_id = runtime
+        .block_on(session_db.handle_unid_session(unid, false))
+        .expect("Failed to handle unid");
+
+    assert_eq!(terminated_session_id, created_session_id);
+}
@@ -0,0 +1,97 @@
+use node_identifier::{
+    sessiondb::{
+        create_time_canonical_update,
+        session_end_time_update,
+    },
+    sessions::Session,
+};
+use rusoto_dynamodb::UpdateItemInput;
+
+fn session() -> Session {
+    Session {
+        pseudo_key: "asset_id_a1234".into(),
+        create_time: 1_544_301_484_600,
+        is_create_canon: false,
+        session_id: "SessionId".into(),
+        is_end_canon: false,
+        end_time: 1_544_301_484_700,
+        version: 3,
+    }
+}
+
+fn value_n(update: &UpdateItemInput, name: &str) -> Option<String> {
+    update
+        .expression_attribute_values
+        .as_ref()?
+        .get(name)?
+        .n
+        .clone()
+}
+
+fn value_bool(update: &UpdateItemInput, name: &str) -> Option<bool> {
+    update.expression_attribute_values.as_ref()?.get(name)?.bool
+}
+
+// Every placeholder in the update and condition expressions must be defined, and the legacy
+// `attribute_updates` must not be mixed in with them
+fn assert_well_formed(update: &UpdateItemInput) {
+    assert!(update.attribute_updates.is_none());
+
+    let names = update.expression_attribute_names.as_ref().unwrap();
+    let values = update.expression_attribute_values.as_ref().unwrap();
+    let expressions = format!(
+        "{} {}",
+        update.update_expression.as_ref().unwrap(),
+        update.condition_expression.as_ref().unwrap()
+    );
+
+    for token in expressions.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.starts_with('#') {
+            assert!(names.contains_key(token), "{} is undefined", token);
+        }
+        if token.starts_with(':') {
+            assert!(values.contains_key(token), "{} is undefined", token);
+        }
+    }
+}
+
+#[test]
+fn create_time_canonical_update_is_conditional_on_version() {
+    let update = create_time_canonical_update("process_history", &session());
+
+    assert_well_formed(&update);
+    assert_eq!(update.table_name, "process_history");
+    assert_eq!(
+        update.update_expression.as_deref(),
+        Some("SET #is_create_canon = :is_create_canon, #version = :next_version")
+    );
+    assert_eq!(
+        update.condition_expression.as_deref(),
+        Some("#version = :version")
+    );
+    assert_eq!(value_bool(&update, ":is_create_canon"), Some(true));
+    assert_eq!(value_n(&update, ":version"), Some("3".to_owned()));
+    assert_eq!(value_n(&update, ":next_version"), Some("4".to_owned()));
+}
+
+#[test]
+fn session_end_time_update_is_conditional_on_version() {
+    let update = session_end_time_update("process_history", &session(), 1_544_301_484_900, true);
+
+    assert_well_formed(&update);
+    assert_eq!(
+        update.update_expression.as_deref(),
+        Some("SET #end_time = :end_time, #is_end_canon = :is_end_canon, #version = :next_version")
+    );
+    assert_eq!(
+        update.condition_expression.as_deref(),
+        Some("#version = :version")
+    );
+    assert_eq!(
+        value_n(&update, ":end_time"),
+        Some("1544301484900".to_owned())
+    );
+    assert_eq!(value_bool(&update, ":is_end_canon"), Some(true));
+    assert_eq!(value_n(&update, ":version"), Some("3".to_owned()));
+    assert_eq!(value_n(&update, ":next_version"), Some("4".to_owned()));
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+/// How often, and how long apart, a transiently failing operation is attempted.
+///
+/// The delay before each retry grows exponentially from `base_delay` up to `max_delay`, and
+/// a random delay up to that bound is picked ("full jitter"), so that workers that failed
+/// together don't all retry together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Backoff {
+    /// `max_attempts` includes the first attempt, and is never fewer than 1
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            max_delay: max_delay.max(base_delay),
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The most that's waited before the given retry, where the first retry is attempt 1
+    pub fn max_delay_before(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// A random delay before the given retry, up to `max_delay_before(attempt)`
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        let max_delay = self.max_delay_before(attempt);
+        let max_millis = max_delay.as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=max_millis))
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(5, Duration::from_millis(100), Duration::from_secs(5))
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_emitter::EventEmitter,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DirectoryEventEmitterError {
+    #[error("IO: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl CheckedError for DirectoryEventEmitterError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::Io(_) => Recoverable::Transient,
+        }
+    }
+}
+
+/// Writes each event to its own file in the output directory.
+///
+/// The file is named by the key function, with any `/` replaced by `_`, so that the output
+/// directory can in turn be watched by a `DirectoryEventSource`. Files are written under a
+/// hidden name and renamed once complete, so they're never read partially written.
+#[derive(Clone)]
+pub struct DirectoryEventEmitter<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    output_dir: PathBuf,
+    key_fn: F,
+}
+
+impl<F> DirectoryEventEmitter<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    pub fn new(output_dir: impl Into<PathBuf>, key_fn: F) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            key_fn,
+        }
+    }
+}
+
+#[async_trait]
+impl<F> EventEmitter for DirectoryEventEmitter<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    type Event = Vec<u8>;
+    type Error = DirectoryEventEmitterError;
+
+    #[tracing::instrument(skip(self, events))]
+    async fn emit_event(&mut self, events: Vec<Self::Event>) -> Result<(), Self::Error> {
+        tokio::fs::create_dir_all(&self.output_dir).await?;
+
+        for event in events {
+            let file_name = (self.key_fn)(&event).replace('/', "_");
+            let partial_path = self.output_dir.join(format!(".{}.partial", file_name));
+            let path = self.output_dir.join(&file_name);
+
+            tracing::debug!("writing event to: {}", path.display());
+            tokio::fs::write(&partial_path, event).await?;
+            tokio::fs::rename(&partial_path, &path).await?;
+        }
+        Ok(())
+    }
+}
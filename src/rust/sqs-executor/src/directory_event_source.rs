@@ -0,0 +1,355 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+
+use async_trait::async_trait;
+use tokio::{
+    io::AsyncWriteExt,
+    task::JoinHandle,
+};
+use tracing::{
+    debug,
+    warn,
+};
+use uuid::Uuid;
+
+use crate::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_source::EventSource,
+    retriever::inline_payload_retriever::InlinePayload,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DirectoryEventSourceError {
+    #[error("IO: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl CheckedError for DirectoryEventSourceError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::Io(_) => Recoverable::Transient,
+        }
+    }
+}
+
+/// A file claimed from the watched directory, along with its contents
+#[derive(Clone, Debug)]
+pub struct FileMessage {
+    pub file_name: String,
+    pub payload: Vec<u8>,
+}
+
+impl InlinePayload for FileMessage {
+    fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+
+/// Watches a directory for new files, each file being a single message.
+///
+/// Files are claimed by moving them into an in-flight directory, so that several executors can
+/// watch the same directory, and are deleted once acked. Dead lettered files are moved to the
+/// dead letter directory, with the reason written to a hidden `.<file name>.reason` file.
+/// Hidden files are never picked up, so writers should write to a hidden file and rename it once
+/// it's complete, as `DirectoryEventEmitter` does.
+///
+/// Each claim is marked by a hidden file in the in-flight directory, which is refreshed for as
+/// long as the message is kept alive. Claims whose markers haven't been refreshed within the
+/// claim timeout were left behind by an executor that exited, and are handed back to the input
+/// directory by whichever executor receives next.
+///
+/// File names identify messages, so they're expected to be unique.
+#[derive(Clone, Debug)]
+pub struct DirectoryEventSource {
+    input_dir: PathBuf,
+    in_flight_dir: PathBuf,
+    dead_letter_dir: PathBuf,
+    batch_size: usize,
+    claim_timeout: Duration,
+    owner: String,
+}
+
+impl DirectoryEventSource {
+    pub fn new(input_dir: impl Into<PathBuf>, dead_letter_dir: impl Into<PathBuf>) -> Self {
+        let input_dir = input_dir.into();
+        Self {
+            in_flight_dir: input_dir.join(".in-flight"),
+            input_dir,
+            dead_letter_dir: dead_letter_dir.into(),
+            batch_size: 10,
+            claim_timeout: Duration::from_secs(300),
+            owner: Uuid::new_v4().to_simple().to_string(),
+        }
+    }
+
+    /// The most files that are claimed by a single receive
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// How long a claim may go without being kept alive before it's considered abandoned
+    pub fn with_claim_timeout(mut self, claim_timeout: Duration) -> Self {
+        self.claim_timeout = claim_timeout;
+        self
+    }
+
+    pub fn input_dir(&self) -> &Path {
+        &self.input_dir
+    }
+
+    fn in_flight_path(&self, message: &FileMessage) -> PathBuf {
+        self.in_flight_dir.join(&message.file_name)
+    }
+
+    fn claim_marker_path(&self, file_name: &str) -> PathBuf {
+        self.in_flight_dir
+            .join(format!(".{}.{}.claim", file_name, self.owner))
+    }
+
+    async fn remove_claim_marker(&self, file_name: &str) -> Result<(), DirectoryEventSourceError> {
+        remove_if_exists(&self.claim_marker_path(file_name)).await
+    }
+
+    /// Files whose claims haven't been kept alive within the claim timeout are handed back to
+    /// the input directory, along with any markers they've left behind
+    async fn recover_abandoned_claims(&self) -> Result<(), DirectoryEventSourceError> {
+        let mut in_flight = Vec::new();
+        // The markers of each claimed file, along with when they were last refreshed
+        let mut markers: HashMap<String, Vec<(PathBuf, SystemTime)>> = HashMap::new();
+
+        let mut entries = tokio::fs::read_dir(&self.in_flight_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = match entry.file_name().into_string() {
+                Ok(file_name) => file_name,
+                Err(_) => continue,
+            };
+
+            if !file_name.starts_with('.') {
+                in_flight.push(file_name);
+                continue;
+            }
+
+            let claimed = file_name
+                .strip_prefix('.')
+                .and_then(|name| name.strip_suffix(".claim"))
+                .and_then(|name| name.rsplit_once('.'))
+                .map(|(claimed, _owner)| claimed.to_owned());
+            // The marker may have been removed since the directory was listed
+            let modified = match entry.metadata().await {
+                Ok(metadata) => metadata.modified()?,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(claimed) = claimed {
+                markers
+                    .entry(claimed)
+                    .or_default()
+                    .push((entry.path(), modified));
+            }
+        }
+
+        let now = SystemTime::now();
+        let is_stale = |modified: &SystemTime| {
+            now.duration_since(*modified).unwrap_or_default() > self.claim_timeout
+        };
+
+        for file_name in in_flight {
+            let file_markers = markers.remove(&file_name).unwrap_or_default();
+            if file_markers.iter().any(|(_, modified)| !is_stale(modified)) {
+                continue;
+            }
+
+            debug!(message="Recovering abandoned in-flight file", file_name=?file_name);
+            match tokio::fs::rename(
+                self.in_flight_dir.join(&file_name),
+                self.input_dir.join(&file_name),
+            )
+            .await
+            {
+                Ok(()) => (),
+                // Another executor recovered the file first
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            }
+            for (marker_path, _) in file_markers {
+                remove_if_exists(&marker_path).await?;
+            }
+        }
+
+        // Markers outliving their files are left by executors that exited between the two
+        for (marker_path, modified) in markers.values().flatten() {
+            if is_stale(modified) {
+                remove_if_exists(marker_path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        file_name: String,
+    ) -> Result<Option<FileMessage>, DirectoryEventSourceError> {
+        // The marker is written before the file is moved, so a claimed file is never seen
+        // without a fresh marker
+        let claim_marker_path = self.claim_marker_path(&file_name);
+        tokio::fs::write(&claim_marker_path, b"claimed").await?;
+
+        let in_flight_path = self.in_flight_dir.join(&file_name);
+        match tokio::fs::rename(self.input_dir.join(&file_name), &in_flight_path).await {
+            Ok(()) => (),
+            // Another executor claimed the file first
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                remove_if_exists(&claim_marker_path).await?;
+                return Ok(None);
+            }
+            Err(e) => {
+                remove_if_exists(&claim_marker_path).await?;
+                return Err(e.into());
+            }
+        }
+
+        let payload = tokio::fs::read(&in_flight_path).await?;
+        Ok(Some(FileMessage { file_name, payload }))
+    }
+}
+
+/// Refreshes a claim's marker for as long as it's held
+#[derive(Debug)]
+pub struct ClaimKeepAlive {
+    task: JoinHandle<()>,
+}
+
+impl Drop for ClaimKeepAlive {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Rewrites the marker in place, so that a marker removed by an ack isn't recreated
+async fn refresh_claim_marker(claim_marker_path: &Path) -> std::io::Result<()> {
+    let mut marker = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(claim_marker_path)
+        .await?;
+    marker.write_all(b"claimed").await?;
+    Ok(())
+}
+
+async fn remove_if_exists(path: &Path) -> Result<(), DirectoryEventSourceError> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The names of the visible regular files in a directory, in order
+async fn list_files(dir: &Path) -> Result<Vec<String>, DirectoryEventSourceError> {
+    let mut file_names = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_file() {
+            continue;
+        }
+        match entry.file_name().into_string() {
+            Ok(file_name) if file_name.starts_with('.') => (),
+            Ok(file_name) => file_names.push(file_name),
+            Err(file_name) => {
+                warn!(message="Skipping file with a non utf8 name", file_name=?file_name)
+            }
+        }
+    }
+
+    file_names.sort();
+    Ok(file_names)
+}
+
+#[async_trait]
+impl EventSource for DirectoryEventSource {
+    type Message = FileMessage;
+    type InFlight = ClaimKeepAlive;
+    type Error = DirectoryEventSourceError;
+
+    async fn receive_messages(&self) -> Result<Vec<Self::Message>, Self::Error> {
+        tokio::fs::create_dir_all(&self.in_flight_dir).await?;
+        tokio::fs::create_dir_all(&self.dead_letter_dir).await?;
+
+        self.recover_abandoned_claims().await?;
+
+        let mut messages = Vec::with_capacity(self.batch_size);
+        for file_name in list_files(&self.input_dir).await? {
+            if messages.len() >= self.batch_size {
+                break;
+            }
+            if let Some(message) = self.claim(file_name).await? {
+                messages.push(message);
+            }
+        }
+
+        Ok(messages)
+    }
+
+    fn message_id(&self, message: &Self::Message) -> String {
+        message.file_name.clone()
+    }
+
+    fn keep_alive(&self, message: &Self::Message) -> Self::InFlight {
+        let claim_marker_path = self.claim_marker_path(&message.file_name);
+        let refresh_interval = self.claim_timeout / 3;
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(refresh_interval).await;
+                if let Err(e) = refresh_claim_marker(&claim_marker_path).await {
+                    warn!(message="Failed to refresh claim", error=?e);
+                    return;
+                }
+            }
+        });
+        ClaimKeepAlive { task }
+    }
+
+    async fn ack(&self, message: &Self::Message) -> Result<(), Self::Error> {
+        tokio::fs::remove_file(self.in_flight_path(message)).await?;
+        self.remove_claim_marker(&message.file_name).await
+    }
+
+    async fn release(&self, message: &Self::Message) -> Result<(), Self::Error> {
+        tokio::fs::rename(
+            self.in_flight_path(message),
+            self.input_dir.join(&message.file_name),
+        )
+        .await?;
+        self.remove_claim_marker(&message.file_name).await
+    }
+
+    async fn dead_letter(&self, message: &Self::Message, reason: &str) -> Result<(), Self::Error> {
+        tokio::fs::write(
+            self.dead_letter_dir
+                .join(format!(".{}.reason", message.file_name)),
+            reason,
+        )
+        .await?;
+        tokio::fs::rename(
+            self.in_flight_path(message),
+            self.dead_letter_dir.join(&message.file_name),
+        )
+        .await?;
+        self.remove_claim_marker(&message.file_name).await
+    }
+}
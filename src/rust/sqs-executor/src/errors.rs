@@ -45,3 +45,29 @@ where
         }
     }
 }
+
+#[derive(Error, Debug)]
+pub enum EmitCompletedError<SerializerErrorT, EmitterErrorT>
+where
+    SerializerErrorT: CheckedError,
+    EmitterErrorT: CheckedError,
+{
+    #[error("Failed to serialize completed events: {0}")]
+    SerializeError(SerializerErrorT),
+    #[error("Failed to emit completed events: {0}")]
+    EmitError(EmitterErrorT),
+}
+
+impl<SerializerErrorT, EmitterErrorT> CheckedError
+    for EmitCompletedError<SerializerErrorT, EmitterErrorT>
+where
+    SerializerErrorT: CheckedError,
+    EmitterErrorT: CheckedError,
+{
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::SerializeError(e) => e.error_type(),
+            Self::EmitError(e) => e.error_type(),
+        }
+    }
+}
@@ -1,8 +1,16 @@
 use async_trait::async_trait;
 
+use crate::errors::CheckedError;
+
 #[async_trait]
 pub trait EventEmitter {
     type Event;
-    type Error: std::fmt::Debug + Send;
+    /// Transient errors are retried by `process_loop`, persistent errors dead letter the message
+    type Error: CheckedError + std::fmt::Debug + Send;
     async fn emit_event(&mut self, completed_events: Vec<Self::Event>) -> Result<(), Self::Error>;
 }
+
+/// An `EventEmitter` that `process_loop` can hand serialized events to
+pub trait EventSink: EventEmitter<Event = Vec<u8>> + Send {}
+
+impl<T> EventSink for T where T: EventEmitter<Event = Vec<u8>> + Send {}
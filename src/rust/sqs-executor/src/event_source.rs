@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+
+use crate::errors::CheckedError;
+
+/// Where `process_loop` receives messages from, and where it reports back what happened to them.
+///
+/// A message only identifies an event, its payload is fetched by a `PayloadRetriever` with the
+/// matching `Message` type. Every received message is eventually acked, released or dead lettered.
+/// Messages are received while earlier messages are still being processed, so every method takes
+/// `&self`.
+#[async_trait]
+pub trait EventSource: Send + Sync {
+    type Message: Send + Sync + 'static;
+    /// Held for as long as a message is being processed, dropping it allows the message to
+    /// be redelivered once it's released
+    type InFlight: Send + 'static;
+    type Error: CheckedError + Send + Sync + 'static;
+
+    /// Waits for the next batch of messages, which may be empty
+    async fn receive_messages(&self) -> Result<Vec<Self::Message>, Self::Error>;
+
+    /// Identifies the message across redeliveries, this is what completed messages are cached by
+    fn message_id(&self, message: &Self::Message) -> String;
+
+    fn keep_alive(&self, message: &Self::Message) -> Self::InFlight;
+
+    /// The message was processed, or can be skipped, and must not be delivered again
+    async fn ack(&self, message: &Self::Message) -> Result<(), Self::Error>;
+
+    /// The message failed with a transient error and should be delivered again.
+    /// By default the message is left to be redelivered by the source itself.
+    async fn release(&self, _message: &Self::Message) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// The message failed with a persistent error and must not be delivered again.
+    /// The reason is kept alongside the dead lettered message.
+    async fn dead_letter(&self, message: &Self::Message, reason: &str) -> Result<(), Self::Error>;
+}
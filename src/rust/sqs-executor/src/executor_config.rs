@@ -0,0 +1,55 @@
+use crate::backoff::Backoff;
+
+/// How much work `process_loop` takes on at once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutorConfig {
+    worker_count: usize,
+    max_in_flight: usize,
+    emit_backoff: Backoff,
+}
+
+impl ExecutorConfig {
+    /// Allows twice as many messages in flight as there are workers, so that the next batch
+    /// is received while the current one is processed
+    pub fn new(worker_count: usize) -> Self {
+        let worker_count = worker_count.max(1);
+        Self {
+            worker_count,
+            max_in_flight: worker_count * 2,
+            emit_backoff: Backoff::default(),
+        }
+    }
+
+    /// The most messages that may be received but not yet acked, released or dead lettered.
+    /// This is never fewer than the number of workers.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(self.worker_count);
+        self
+    }
+
+    /// How completed events are retried when they fail to be emitted with a transient error
+    pub fn with_emit_backoff(mut self, emit_backoff: Backoff) -> Self {
+        self.emit_backoff = emit_backoff;
+        self
+    }
+
+    /// How many messages are processed concurrently, each worker having its own handler,
+    /// retriever, sink, serializer and cache
+    pub fn worker_count(&self) -> usize {
+        self.worker_count
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    pub fn emit_backoff(&self) -> &Backoff {
+        &self.emit_backoff
+    }
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
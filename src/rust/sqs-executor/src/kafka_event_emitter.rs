@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rdkafka::{
+    error::KafkaError,
+    producer::{
+        FutureProducer,
+        FutureRecord,
+    },
+};
+
+use crate::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_emitter::EventEmitter,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum KafkaEventEmitterError {
+    #[error("KafkaError: {0}")]
+    KafkaError(#[from] KafkaError),
+}
+
+impl CheckedError for KafkaEventEmitterError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::KafkaError(_) => Recoverable::Transient,
+        }
+    }
+}
+
+/// Produces each event to a Kafka topic, keyed by the key function
+#[derive(Clone)]
+pub struct KafkaEventEmitter<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    producer: FutureProducer,
+    topic: String,
+    key_fn: F,
+}
+
+impl<F> KafkaEventEmitter<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    pub fn new(producer: FutureProducer, topic: impl Into<String>, key_fn: F) -> Self {
+        Self {
+            producer,
+            topic: topic.into(),
+            key_fn,
+        }
+    }
+}
+
+#[async_trait]
+impl<F> EventEmitter for KafkaEventEmitter<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    type Event = Vec<u8>;
+    type Error = KafkaEventEmitterError;
+
+    #[tracing::instrument(skip(self, events))]
+    async fn emit_event(&mut self, events: Vec<Self::Event>) -> Result<(), Self::Error> {
+        let mut event_sends = Vec::with_capacity(events.len());
+
+        for event in events.iter() {
+            let key = (self.key_fn)(event);
+            let producer = self.producer.clone();
+            let topic = self.topic.as_str();
+            event_sends.push(async move {
+                let record = FutureRecord::to(topic).key(&key).payload(event);
+                producer
+                    .send(record, Duration::from_secs(3))
+                    .await
+                    .map(|(partition, offset)| {
+                        tracing::debug!(
+                            message="Event produced",
+                            topic=%topic,
+                            partition=?partition,
+                            offset=?offset,
+                        )
+                    })
+                    .map_err(|(e, _)| KafkaEventEmitterError::from(e))
+            });
+        }
+
+        for e in futures::future::join_all(event_sends).await {
+            e?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use rdkafka::{
+    consumer::{
+        Consumer,
+        StreamConsumer,
+    },
+    error::KafkaError,
+    message::{
+        Message,
+        OwnedHeaders,
+        OwnedMessage,
+    },
+    producer::{
+        FutureProducer,
+        FutureRecord,
+    },
+    Offset,
+    TopicPartitionList,
+};
+use tracing::debug;
+
+use crate::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_source::EventSource,
+    partition_offsets::PartitionOffsets,
+    retriever::inline_payload_retriever::InlinePayload,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum KafkaEventSourceError {
+    #[error("KafkaError: {0}")]
+    KafkaError(#[from] KafkaError),
+}
+
+impl CheckedError for KafkaEventSourceError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::KafkaError(_) => Recoverable::Transient,
+        }
+    }
+}
+
+impl InlinePayload for OwnedMessage {
+    fn payload(&self) -> &[u8] {
+        Message::payload(self).unwrap_or_default()
+    }
+}
+
+/// Consumes messages from the topics the consumer is subscribed to. Persistently failing
+/// messages are produced to a dead letter topic, with the reason in a `reason` header.
+///
+/// Acked offsets are stored and then committed by the consumer, so it must be configured with
+/// `enable.auto.offset.store=false`. Kafka tracks a single offset per partition, and messages
+/// are acked out of order, so the stored offset only ever advances past messages that have all
+/// been acked. A message that failed transiently holds its partition's offset back until the
+/// consumer restarts, when it and every later message are redelivered.
+#[derive(Clone)]
+pub struct KafkaEventSource {
+    consumer: Arc<StreamConsumer>,
+    offsets: Arc<Mutex<HashMap<(String, i32), PartitionOffsets>>>,
+    dead_letter_producer: FutureProducer,
+    dead_letter_topic: String,
+    batch_size: usize,
+    wait_time: Duration,
+}
+
+impl KafkaEventSource {
+    pub fn new(
+        consumer: StreamConsumer,
+        dead_letter_producer: FutureProducer,
+        dead_letter_topic: impl Into<String>,
+    ) -> Self {
+        Self {
+            consumer: Arc::new(consumer),
+            offsets: Arc::new(Mutex::new(HashMap::new())),
+            dead_letter_producer,
+            dead_letter_topic: dead_letter_topic.into(),
+            batch_size: 10,
+            wait_time: Duration::from_secs(20),
+        }
+    }
+
+    /// The most messages that are received in a single batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// How long to wait for the first message of a batch before returning an empty batch
+    pub fn with_wait_time(mut self, wait_time: Duration) -> Self {
+        self.wait_time = wait_time;
+        self
+    }
+}
+
+#[async_trait]
+impl EventSource for KafkaEventSource {
+    type Message = OwnedMessage;
+    type InFlight = ();
+    type Error = KafkaEventSourceError;
+
+    async fn receive_messages(&self) -> Result<Vec<Self::Message>, Self::Error> {
+        let mut messages = Vec::with_capacity(self.batch_size);
+
+        // Wait for the first message, then take whatever else is already available
+        let mut wait_time = self.wait_time;
+        while messages.len() < self.batch_size {
+            match tokio::time::timeout(wait_time, self.consumer.recv()).await {
+                Ok(message) => messages.push(message?.detach()),
+                Err(_) => break,
+            }
+            wait_time = Duration::from_millis(10);
+        }
+
+        let mut offsets = self.offsets.lock().unwrap();
+        for message in messages.iter() {
+            offsets
+                .entry((message.topic().to_owned(), message.partition()))
+                .or_default()
+                .received(message.offset());
+        }
+
+        Ok(messages)
+    }
+
+    fn message_id(&self, message: &Self::Message) -> String {
+        format!(
+            "{}/{}/{}",
+            message.topic(),
+            message.partition(),
+            message.offset()
+        )
+    }
+
+    fn keep_alive(&self, _message: &Self::Message) -> Self::InFlight {}
+
+    async fn ack(&self, message: &Self::Message) -> Result<(), Self::Error> {
+        let next_offset = self
+            .offsets
+            .lock()
+            .unwrap()
+            .entry((message.topic().to_owned(), message.partition()))
+            .or_default()
+            .completed(message.offset());
+
+        // Earlier messages in the partition are still being processed
+        let next_offset = match next_offset {
+            Some(next_offset) => next_offset,
+            None => return Ok(()),
+        };
+
+        let mut offsets = TopicPartitionList::new();
+        offsets.add_partition_offset(
+            message.topic(),
+            message.partition(),
+            Offset::Offset(next_offset),
+        )?;
+        self.consumer.store_offsets(&offsets)?;
+        Ok(())
+    }
+
+    async fn dead_letter(&self, message: &Self::Message, reason: &str) -> Result<(), Self::Error> {
+        debug!(
+            message = "Moving message to dead letter topic",
+            dead_letter_topic = self.dead_letter_topic.as_str(),
+            reason = reason,
+        );
+        let mut record = FutureRecord::to(&self.dead_letter_topic)
+            .payload(InlinePayload::payload(message))
+            .headers(OwnedHeaders::new().add("reason", reason));
+        if let Some(key) = message.key() {
+            record = record.key(key);
+        }
+
+        self.dead_letter_producer
+            .send(record, Duration::from_secs(3))
+            .await
+            .map_err(|(e, _)| e)?;
+
+        self.ack(message).await
+    }
+}
@@ -1,11 +1,10 @@
 pub mod retriever;
 
 use std::{
-    error::Error,
     fmt::Debug,
-    future::Future,
     io::Stdout,
     panic::AssertUnwindSafe,
+    sync::Arc,
     time::{
         Duration,
         SystemTime,
@@ -13,8 +12,9 @@ use std::{
     },
 };
 
-use event_emitter::EventEmitter;
+use event_emitter::EventSink;
 use event_handler::EventHandler;
+use event_source::EventSource;
 use futures_util::FutureExt;
 use grapl_observe::{
     metric_reporter::{
@@ -23,76 +23,74 @@ use grapl_observe::{
     },
     timers::TimedFutureExt,
 };
-use rusoto_s3::S3;
 use rusoto_sqs::{
     ListQueuesError,
     ListQueuesRequest,
-    Message as SqsMessage,
     Sqs,
 };
-use s3_event_emitter::S3EventEmitter;
-use s3_event_retriever::S3PayloadRetriever;
+use tokio::sync::{
+    mpsc::{
+        channel,
+        Receiver,
+        Sender,
+    },
+    Mutex,
+    OwnedSemaphorePermit,
+    Semaphore,
+};
 use tracing::{
     debug,
     error,
     info,
+    warn,
 };
 
 use crate::{
+    backoff::Backoff,
     cache::Cache,
     completion_event_serializer::CompletionEventSerializer,
     errors::{
         CheckedError,
+        EmitCompletedError,
         Recoverable,
     },
-    event_decoder::PayloadDecoder,
     event_handler::CompletedEvents,
     event_retriever::PayloadRetriever,
     event_status::EventStatus,
+    executor_config::ExecutorConfig,
 };
 
+pub mod backoff;
 pub mod cache;
 pub mod completion_event_serializer;
+pub mod directory_event_emitter;
+pub mod directory_event_source;
 pub mod errors;
 pub mod event_decoder;
 pub mod event_emitter;
 pub mod event_handler;
+pub mod event_source;
+pub mod executor_config;
+#[cfg(feature = "kafka")]
+pub mod kafka_event_emitter;
+#[cfg(feature = "kafka")]
+pub mod kafka_event_source;
+pub mod partition_offsets;
 pub use retriever::{
     event_retriever,
+    inline_payload_retriever,
     s3_event_retriever,
 };
 use rusoto_core::RusotoError;
 
-use crate::sqs_timeout_manager::keep_alive;
-
 pub mod event_status;
 pub mod key_creator;
 pub mod redis_cache;
 pub mod rusoto_helpers;
 pub mod s3_event_emitter;
+pub mod sqs_event_source;
 pub mod sqs_timeout_manager;
 
-pub async fn make_ten<F, T>(f: F) -> [T; 10]
-where
-    F: Future<Output = T>,
-    T: Clone,
-{
-    let t = f.await;
-
-    [
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t.clone(),
-        t,
-    ]
-}
-
 async fn cache_completed<CacheT>(cache: &mut CacheT, completed: &mut CompletedEvents)
 where
     CacheT: Cache + Send,
@@ -117,134 +115,170 @@ where
     });
 }
 
-#[tracing::instrument(skip(
-    next_message,
-    queue_url,
-    dead_letter_queue_url,
-    cache,
-    sqs_client,
-    event_handler,
-    s3_payload_retriever,
-    s3_emitter,
-    serializer,
-    metric_reporter,
-))]
+/// Emits the serialized events, retrying transient failures with a jittered backoff
+async fn emit_with_retry<SinkT>(
+    event_sink: &mut SinkT,
+    events: Vec<Vec<u8>>,
+    emit_backoff: &Backoff,
+    metric_reporter: &mut MetricReporter<Stdout>,
+) -> Result<(), SinkT::Error>
+where
+    SinkT: EventSink,
+{
+    let mut attempt = 0;
+    loop {
+        let e = match event_sink.emit_event(events.clone()).await {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        attempt += 1;
+        if e.is_persistent() || attempt >= emit_backoff.max_attempts() {
+            return Err(e);
+        }
+
+        let delay = emit_backoff.delay_before(attempt);
+        warn!(
+            message="Failed to emit completed events, retrying",
+            error=?e,
+            attempt=attempt,
+            delay_ms=delay.as_millis() as u64,
+        );
+        metric_reporter
+            .counter("sqs_executor.emit_event.retry", 1.0, None, &[])
+            .unwrap_or_else(
+                |e| error!(message="failed to report sqs_executor.emit_event.retry", error=?e),
+            );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Serializes the completed events and emits them to the sink
+async fn emit_completed<SinkT, OutputEventT, SerializerErrorT, CompletionEventSerializerT>(
+    completed: OutputEventT,
+    event_sink: &mut SinkT,
+    serializer: &mut CompletionEventSerializerT,
+    emit_backoff: &Backoff,
+    metric_reporter: &mut MetricReporter<Stdout>,
+) -> Result<(), EmitCompletedError<SerializerErrorT, SinkT::Error>>
+where
+    SinkT: EventSink,
+    SerializerErrorT: CheckedError,
+    CompletionEventSerializerT: CompletionEventSerializer<
+        CompletedEvent = OutputEventT,
+        Output = Vec<u8>,
+        Error = SerializerErrorT,
+    >,
+{
+    let events = serializer
+        .serialize_completed_events(&[completed])
+        .map_err(EmitCompletedError::SerializeError)?;
+
+    let result = emit_with_retry(event_sink, events, emit_backoff, metric_reporter).await;
+    metric_reporter
+        .counter(
+            "sqs_executor.emit_completed",
+            1.0,
+            None,
+            &[tag("success", result.is_ok())],
+        )
+        .unwrap_or_else(
+            |e| error!(message="failed to report sqs_executor.emit_completed", error=?e),
+        );
+    result.map_err(EmitCompletedError::EmitError)
+}
+
+#[tracing::instrument(
+    skip(
+        next_message,
+        msg_handle,
+        source,
+        cache,
+        event_handler,
+        payload_retriever,
+        event_sink,
+        serializer,
+        emit_backoff,
+        metric_reporter,
+    ),
+    fields(message_id)
+)]
 async fn process_message<
+    SourceT,
+    SinkT,
     CacheT,
-    SInit,
-    SqsT,
-    DecoderT,
-    DecoderErrorT,
+    RetrieverT,
     EventHandlerT,
     InputEventT,
     OutputEventT,
     HandlerErrorT,
     SerializerErrorT,
-    S3ClientT,
-    F,
     CompletionEventSerializerT,
 >(
-    next_message: SqsMessage,
-    queue_url: String,
-    dead_letter_queue_url: String,
+    next_message: SourceT::Message,
+    msg_handle: SourceT::InFlight,
+    source: &SourceT,
     cache: &mut CacheT,
-    sqs_client: SqsT,
     event_handler: &mut EventHandlerT,
-    s3_payload_retriever: &mut S3PayloadRetriever<
-        S3ClientT,
-        SInit,
-        DecoderT,
-        InputEventT,
-        DecoderErrorT,
-    >,
-    s3_emitter: &mut S3EventEmitter<S3ClientT, F>,
+    payload_retriever: &mut RetrieverT,
+    event_sink: &mut SinkT,
     serializer: &mut CompletionEventSerializerT,
+    emit_backoff: &Backoff,
     mut metric_reporter: MetricReporter<Stdout>,
 ) where
+    SourceT: EventSource,
+    SinkT: EventSink,
     CacheT: crate::cache::Cache + Clone + Send + Sync + 'static,
-    SInit: (Fn(String) -> S3ClientT) + Clone + Send + Sync + 'static,
-    SqsT: Sqs + Clone + Send + Sync + 'static,
-    DecoderT: PayloadDecoder<InputEventT, DecoderError = DecoderErrorT> + Clone + Send + 'static,
-    DecoderErrorT: CheckedError + Send + 'static,
+    RetrieverT: PayloadRetriever<InputEventT, Message = SourceT::Message>,
     InputEventT: Send,
     EventHandlerT:
         EventHandler<InputEvent = InputEventT, OutputEvent = OutputEventT, Error = HandlerErrorT>,
     OutputEventT: Clone + Send + Sync + 'static,
     HandlerErrorT: CheckedError + Debug + Send + Sync + 'static,
-    SerializerErrorT: Error + Debug + Send + Sync + 'static,
-    S3ClientT: S3 + Clone + Send + Sync + 'static,
-    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+    SerializerErrorT: CheckedError + Debug + Send + Sync + 'static,
     CompletionEventSerializerT: CompletionEventSerializer<
         CompletedEvent = OutputEventT,
         Output = Vec<u8>,
         Error = SerializerErrorT,
     >,
 {
-    let message_id = next_message.message_id.as_ref().unwrap().as_str();
-    let inner_loop_span = tracing::trace_span!("inner_loop_span", message_id = message_id,);
-    let _enter = inner_loop_span.enter();
+    let message_id = source.message_id(&next_message);
+    let message_id = message_id.as_str();
+    tracing::Span::current().record("message_id", &message_id);
 
     if cache.all_exist(&[message_id.to_owned()]).await {
         debug!(
             message_id = message_id,
             "Message has already been processed",
         );
-        rusoto_helpers::delete_message(
-            sqs_client.clone(),
-            queue_url.to_owned(),
-            next_message.receipt_handle.expect("missing receipt_handle"),
-            metric_reporter,
-        )
-        .await
-        .unwrap_or_else(|e| error!(message="delete_message failed", error=?e));
+        drop(msg_handle);
+        source
+            .ack(&next_message)
+            .await
+            .unwrap_or_else(|e| error!(message="ack failed", error=?e));
         return;
     }
-    debug!(message = "Retrieving payload from s3");
-    let receipt_handle = next_message
-        .receipt_handle
-        .as_ref()
-        .expect("missing receipt_handle")
-        .to_owned();
-    // Maintain an invisibility timeout for the message until we're done
-    let msg_handle = keep_alive(
-        sqs_client.clone(),
-        receipt_handle.clone(),
-        message_id.to_owned(),
-        queue_url.clone(),
-        30,
-    );
-    let payload = s3_payload_retriever.retrieve_event(&next_message).await;
+    debug!(message = "Retrieving payload");
+    let payload = payload_retriever.retrieve_event(&next_message).await;
 
     let events = match payload {
         Ok(Some(events)) => events,
         Ok(None) => {
-            rusoto_helpers::delete_message(
-                sqs_client.clone(),
-                queue_url.to_owned(),
-                receipt_handle,
-                metric_reporter.clone(),
-            );
+            drop(msg_handle);
+            source
+                .ack(&next_message)
+                .await
+                .unwrap_or_else(|e| error!(message="ack failed", error=?e));
             return;
         }
         Err(e) => {
             error!(
-                queue_url = queue_url.as_str(),
                 message_id = message_id,
                 error = e.to_string().as_str(),
                 "Failed to retrieve payload with"
             );
-            if let Recoverable::Persistent = e.error_type() {
-                rusoto_helpers::move_to_dead_letter(
-                    sqs_client.clone(),
-                    next_message.body.as_ref().unwrap(),
-                    dead_letter_queue_url,
-                    queue_url.to_owned(),
-                    receipt_handle,
-                    metric_reporter.clone(),
-                )
-                .await
-                .unwrap_or_else(|e| error!(message="move_to_dead_letter failed", error=?e));
-            }
+            drop(msg_handle);
+            reject_message(source, &next_message, e.error_type(), &e.to_string()).await;
             return;
         }
     };
@@ -272,33 +306,37 @@ async fn process_message<
 
     match processing_result {
         Ok(total) => {
-            // encode event
-            let event = serializer
-                .serialize_completed_events(&[total])
-                .expect("Serializing failed");
-            // emit event
-            // todo: we should retry event emission
-            s3_emitter
-                .emit_event(event)
-                .await
-                .expect("Failed to emit event");
+            let emitted = emit_completed(
+                total,
+                event_sink,
+                serializer,
+                emit_backoff,
+                &mut metric_reporter,
+            )
+            .await;
+            if let Err(e) = emitted {
+                error!(
+                    message="Failed to emit completed events",
+                    error=?e,
+                    recoverable=?e.error_type()
+                );
+                drop(msg_handle);
+                reject_message(source, &next_message, e.error_type(), &e.to_string()).await;
+                return;
+            }
 
             cache
-                .store(next_message.message_id.clone().unwrap().into_bytes())
+                .store(message_id.to_owned().into_bytes())
                 .await
                 .unwrap_or_else(|e| error!(message="cache.store failed", error=?e));
             cache_completed(cache, &mut completed).await;
             // ack the message - we could probably not block on this
 
-            msg_handle.stop();
-            rusoto_helpers::delete_message(
-                sqs_client.clone(),
-                queue_url.to_owned(),
-                receipt_handle,
-                metric_reporter.clone(),
-            )
-            .await
-            .unwrap_or_else(|e| error!(message="delete_message failed", error=?e));
+            drop(msg_handle);
+            source
+                .ack(&next_message)
+                .await
+                .unwrap_or_else(|e| error!(message="ack failed", error=?e));
         }
         Err(Ok((partial, e))) => {
             error!(
@@ -306,30 +344,36 @@ async fn process_message<
                 error=?e,
                 recoverable=?e.error_type()
             );
-            let event = serializer
-                .serialize_completed_events(&[partial])
-                .expect("Serializing failed");
-            // emit event
-            // todo: we should retry event emission
-            s3_emitter
-                .emit_event(event)
-                .await
-                .expect("Failed to emit event");
-
-            cache_completed(cache, &mut completed).await;
-
-            if let Recoverable::Persistent = e.error_type() {
-                msg_handle.stop();
-                rusoto_helpers::move_to_dead_letter(
-                    sqs_client.clone(),
-                    next_message.body.as_ref().unwrap(),
-                    dead_letter_queue_url,
-                    queue_url.to_owned(),
-                    receipt_handle,
-                    metric_reporter.clone(),
-                )
-                .await
-                .unwrap_or_else(|e| error!(message="move_to_dead_letter failed", error=?e));
+            let emitted = emit_completed(
+                partial,
+                event_sink,
+                serializer,
+                emit_backoff,
+                &mut metric_reporter,
+            )
+            .await;
+            drop(msg_handle);
+            match emitted {
+                Ok(()) => {
+                    cache_completed(cache, &mut completed).await;
+                    reject_message(source, &next_message, e.error_type(), &e.to_string()).await;
+                }
+                // The partial events were lost too, so whether the message is retried depends
+                // on whether they can be emitted
+                Err(emit_error) => {
+                    error!(
+                        message="Failed to emit partially completed events",
+                        error=?emit_error,
+                        recoverable=?emit_error.error_type()
+                    );
+                    reject_message(
+                        source,
+                        &next_message,
+                        emit_error.error_type(),
+                        &emit_error.to_string(),
+                    )
+                    .await;
+                }
             }
         }
         Err(Err(e)) => {
@@ -338,100 +382,90 @@ async fn process_message<
                 e,
                 e.error_type()
             );
-            if let Recoverable::Persistent = e.error_type() {
-                msg_handle.stop();
-                rusoto_helpers::move_to_dead_letter(
-                    sqs_client.clone(),
-                    next_message.body.as_ref().unwrap(),
-                    dead_letter_queue_url,
-                    queue_url.to_owned(),
-                    receipt_handle,
-                    metric_reporter.clone(),
-                )
-                .await
-                .unwrap_or_else(|e| error!(message="move_to_dead_letter failed", error=?e));
-            }
-            // should we retry? idk
-            // otherwise we can just do nothing
+            drop(msg_handle);
+            reject_message(source, &next_message, e.error_type(), &e.to_string()).await;
         }
     }
 }
 
-async fn _process_loop<
-    CacheT,
-    SInit,
-    SqsT,
-    DecoderT,
-    DecoderErrorT,
-    EventHandlerT,
-    InputEventT,
-    OutputEventT,
-    HandlerErrorT,
-    SerializerErrorT,
-    S3ClientT,
-    F,
-    CompletionEventSerializerT,
->(
-    queue_url: String,
-    dead_letter_queue_url: String,
-    cache: &mut [CacheT; 10],
-    sqs_client: SqsT,
-    event_handler: &mut [EventHandlerT; 10],
-    s3_payload_retriever: &mut [S3PayloadRetriever<S3ClientT, SInit, DecoderT, InputEventT, DecoderErrorT>;
-             10],
-    s3_emitter: &mut [S3EventEmitter<S3ClientT, F>; 10],
-    serializer: &mut [CompletionEventSerializerT; 10],
+/// Dead letters a message that failed persistently, otherwise releases it to be retried
+async fn reject_message<SourceT>(
+    source: &SourceT,
+    message: &SourceT::Message,
+    recoverable: Recoverable,
+    reason: &str,
+) where
+    SourceT: EventSource,
+{
+    match recoverable {
+        Recoverable::Persistent => source
+            .dead_letter(message, reason)
+            .await
+            .unwrap_or_else(|e| error!(message="dead_letter failed", error=?e)),
+        Recoverable::Transient => source
+            .release(message)
+            .await
+            .unwrap_or_else(|e| error!(message="release failed", error=?e)),
+    }
+}
+
+/// A received message, along with what keeps it from being redelivered and the permit it
+/// holds on the messages in flight
+struct Received<SourceT>
+where
+    SourceT: EventSource,
+{
+    message: SourceT::Message,
+    msg_handle: SourceT::InFlight,
+    permit: OwnedSemaphorePermit,
+}
+
+/// Receives messages whenever there's room for more messages in flight, so the next batch is
+/// received while earlier messages are still being processed
+async fn receive_loop<SourceT>(
+    source: Arc<SourceT>,
+    max_in_flight: usize,
+    received_tx: Sender<Received<SourceT>>,
     mut metric_reporter: MetricReporter<Stdout>,
 ) where
-    CacheT: crate::cache::Cache + Clone + Send + Sync + 'static,
-    SInit: (Fn(String) -> S3ClientT) + Clone + Send + Sync + 'static,
-    SqsT: Sqs + Clone + Send + Sync + 'static,
-    DecoderT: PayloadDecoder<InputEventT, DecoderError = DecoderErrorT> + Clone + Send + 'static,
-    DecoderErrorT: CheckedError + Send + 'static,
-    InputEventT: Send,
-    EventHandlerT:
-        EventHandler<InputEvent = InputEventT, OutputEvent = OutputEventT, Error = HandlerErrorT>,
-    OutputEventT: Clone + Send + Sync + 'static,
-    HandlerErrorT: CheckedError + Debug + Send + Sync + 'static,
-    SerializerErrorT: Error + Debug + Send + Sync + 'static,
-    S3ClientT: S3 + Clone + Send + Sync + 'static,
-    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
-    CompletionEventSerializerT: CompletionEventSerializer<
-        CompletedEvent = OutputEventT,
-        Output = Vec<u8>,
-        Error = SerializerErrorT,
-    >,
+    SourceT: EventSource + 'static,
 {
+    let in_flight = Arc::new(Semaphore::new(max_in_flight));
     let mut i = 1;
     loop {
         if i >= 15 {
             i = 2;
         }
 
-        let span = tracing::trace_span!("inner_process_loop", queue_url = queue_url.as_str(),);
-        let _enter = span.enter();
-        let message_batch = rusoto_helpers::get_message(
-            queue_url.to_string(),
-            sqs_client.clone(),
-            &mut metric_reporter,
-        )
-        .await;
+        let permit = match in_flight.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => return,
+        };
+
+        let message_batch = AssertUnwindSafe(source.receive_messages())
+            .catch_unwind()
+            .await;
 
         let message_batch = match message_batch {
-            Ok(message_batch) => {
+            Ok(Ok(message_batch)) => {
                 i = 1;
                 message_batch
             }
-            Err(e) => {
+            Ok(Err(e)) => {
                 error!(
-                    queue_url = queue_url.as_str(),
                     error = e.to_string().as_str(),
-                    "Failed to get_message from queue"
+                    "Failed to receive messages from source"
                 );
                 tokio::time::sleep(std::time::Duration::from_millis(i * 250)).await;
                 i += 1;
                 continue;
             }
+            Err(_) => {
+                error!("Receiving messages from source panicked");
+                tokio::time::sleep(std::time::Duration::from_millis(i * 250)).await;
+                i += 1;
+                continue;
+            }
         };
         let message_batch_len = message_batch.len();
 
@@ -441,138 +475,235 @@ async fn _process_loop<
             tokio::time::sleep(std::time::Duration::from_millis(250)).await;
             continue;
         }
-        let combos = message_batch
-            .into_iter()
-            .zip(&mut *event_handler)
-            .zip(&mut *s3_payload_retriever)
-            .zip(&mut *s3_emitter)
-            .zip(&mut *serializer)
-            .zip(&mut *cache);
-
-        let mut process_futs = Vec::with_capacity(10);
-
-        for combo in combos {
-            let (
-                ((((next_message, event_handler), s3_payload_retriever), s3_emitter), serializer),
-                cache,
-            ) = combo;
-            let p = process_message(
-                next_message,
-                queue_url.clone(),
-                dead_letter_queue_url.clone(),
-                cache,
-                sqs_client.clone(),
-                event_handler,
-                s3_payload_retriever,
-                s3_emitter,
-                serializer,
-                metric_reporter.clone(),
-            );
-            process_futs.push(p);
+
+        let mut permit = Some(permit);
+        for message in message_batch {
+            // Messages are kept alive from the moment they're received, as they may wait for
+            // a free worker
+            let msg_handle = source.keep_alive(&message);
+            let permit = match permit.take() {
+                Some(permit) => permit,
+                None => match in_flight.clone().acquire_owned().await {
+                    Ok(permit) => permit,
+                    Err(_) => return,
+                },
+            };
+
+            let received = Received {
+                message,
+                msg_handle,
+                permit,
+            };
+            if received_tx.send(received).await.is_err() {
+                error!("Every worker has exited, no longer receiving messages");
+                return;
+            }
         }
 
-        let all_processing = tokio::time::timeout(
+        metric_reporter
+            .gauge_notags(
+                "sqs_executor.messages_in_flight",
+                (max_in_flight - in_flight.available_permits()) as f64,
+            )
+            .unwrap_or_else(|e| {
+                error!("failed to report sqs_executor.messages_in_flight: {:?}", e)
+            });
+    }
+}
+
+/// Takes the next received message whenever it's free, so a slow message only ever holds up
+/// the worker processing it
+async fn work<
+    SourceT,
+    SinkT,
+    CacheT,
+    RetrieverT,
+    EventHandlerT,
+    InputEventT,
+    OutputEventT,
+    HandlerErrorT,
+    SerializerErrorT,
+    CompletionEventSerializerT,
+>(
+    source: Arc<SourceT>,
+    received_rx: Arc<Mutex<Receiver<Received<SourceT>>>>,
+    mut cache: CacheT,
+    mut event_handler: EventHandlerT,
+    mut payload_retriever: RetrieverT,
+    mut event_sink: SinkT,
+    mut serializer: CompletionEventSerializerT,
+    emit_backoff: Backoff,
+    mut metric_reporter: MetricReporter<Stdout>,
+) where
+    SourceT: EventSource + 'static,
+    SinkT: EventSink,
+    CacheT: crate::cache::Cache + Clone + Send + Sync + 'static,
+    RetrieverT: PayloadRetriever<InputEventT, Message = SourceT::Message> + Send,
+    InputEventT: Send,
+    EventHandlerT: EventHandler<InputEvent = InputEventT, OutputEvent = OutputEventT, Error = HandlerErrorT>
+        + Send,
+    OutputEventT: Clone + Send + Sync + 'static,
+    HandlerErrorT: CheckedError + Debug + Send + Sync + 'static,
+    SerializerErrorT: CheckedError + Debug + Send + Sync + 'static,
+    CompletionEventSerializerT: CompletionEventSerializer<
+            CompletedEvent = OutputEventT,
+            Output = Vec<u8>,
+            Error = SerializerErrorT,
+        > + Send,
+{
+    loop {
+        let received = received_rx.lock().await.recv().await;
+        let Received {
+            message,
+            msg_handle,
+            permit: _permit,
+        } = match received {
+            Some(received) => received,
+            None => return,
+        };
+
+        let processing = process_message(
+            message,
+            msg_handle,
+            &*source,
+            &mut cache,
+            &mut event_handler,
+            &mut payload_retriever,
+            &mut event_sink,
+            &mut serializer,
+            &emit_backoff,
+            metric_reporter.clone(),
+        );
+        let processing = tokio::time::timeout(
             Duration::from_secs(30 * 15),
-            futures::future::join_all(process_futs).timed(),
+            AssertUnwindSafe(processing).catch_unwind().timed(),
         );
-        match all_processing.await {
-            Ok((_r, ms)) => {
+
+        match processing.await {
+            Ok((Ok(()), ms)) => {
                 metric_reporter
-                    .histogram("sqs_executor.all_processing.ms", ms as f64, &[])
+                    .histogram("sqs_executor.process_message.ms", ms as f64, &[])
                     .unwrap_or_else(|e| {
-                        error!("failed to report sqs_executor.all_processing.ms: {:?}", e)
+                        error!("failed to report sqs_executor.process_message.ms: {:?}", e)
                     });
             }
-            Err(e) => error!("Timed out when processing messages: {:?}", e),
+            Ok((Err(e), _)) => {
+                if let Some(e) = e.downcast_ref::<Box<dyn std::error::Error + 'static>>() {
+                    error!(
+                        error = e.to_string().as_str(),
+                        "Processing message panicked"
+                    );
+                } else if let Some(e) = e.downcast_ref::<Box<dyn std::fmt::Debug>>() {
+                    error!(
+                        error = format!("{:?}", e).as_str(),
+                        "Processing message panicked"
+                    );
+                } else {
+                    error!("Unexpected error");
+                }
+            }
+            Err(e) => error!("Timed out when processing message: {:?}", e),
         };
     }
 }
 
+/// Receives messages from the source, retrieves and handles each message's events, and emits
+/// the completed events to the sink.
+///
+/// Each of the configured number of workers has its own clone of the cache, handler, retriever,
+/// sink and serializer, and takes the next received message whenever it's free. The source is
+/// polled for more messages whenever fewer than the configured maximum are in flight.
+///
+/// Emitting is retried with the configured backoff while it fails transiently. A message that
+/// fails persistently, in any step, is dead lettered along with the error it failed with.
 #[tracing::instrument(skip(
+    source,
     cache,
-    sqs_client,
     event_handler,
-    s3_payload_retriever,
-    s3_emitter,
+    payload_retriever,
+    event_sink,
     serializer,
     metric_reporter,
 ))]
 pub async fn process_loop<
+    SourceT,
+    SinkT,
     CacheT,
-    SInit,
-    SqsT,
-    DecoderT,
-    DecoderErrorT,
+    RetrieverT,
     EventHandlerT,
     InputEventT,
     OutputEventT,
     HandlerErrorT,
     SerializerErrorT,
-    S3ClientT,
-    F,
     CompletionEventSerializerT,
 >(
-    queue_url: String,
-    dead_letter_queue_url: String,
-    cache: &mut [CacheT; 10],
-    sqs_client: SqsT,
-    event_handler: &mut [EventHandlerT; 10],
-    s3_payload_retriever: &mut [S3PayloadRetriever<S3ClientT, SInit, DecoderT, InputEventT, DecoderErrorT>;
-             10],
-    s3_emitter: &mut [S3EventEmitter<S3ClientT, F>; 10],
-    serializer: &mut [CompletionEventSerializerT; 10],
+    source: SourceT,
+    cache: CacheT,
+    event_handler: EventHandlerT,
+    payload_retriever: RetrieverT,
+    event_sink: SinkT,
+    serializer: CompletionEventSerializerT,
+    config: ExecutorConfig,
     metric_reporter: MetricReporter<Stdout>,
 ) where
+    SourceT: EventSource + 'static,
+    SinkT: EventSink + Clone + 'static,
     CacheT: crate::cache::Cache + Clone + Send + Sync + 'static,
-    SInit: (Fn(String) -> S3ClientT) + Clone + Send + Sync + 'static,
-    SqsT: Sqs + Clone + Send + Sync + 'static,
-    DecoderT: PayloadDecoder<InputEventT, DecoderError = DecoderErrorT> + Clone + Send + 'static,
-    DecoderErrorT: CheckedError + Send + 'static,
-    InputEventT: Send,
-    EventHandlerT:
-        EventHandler<InputEvent = InputEventT, OutputEvent = OutputEventT, Error = HandlerErrorT>,
+    RetrieverT: PayloadRetriever<InputEventT, Message = SourceT::Message> + Clone + Send + 'static,
+    InputEventT: Send + 'static,
+    EventHandlerT: EventHandler<InputEvent = InputEventT, OutputEvent = OutputEventT, Error = HandlerErrorT>
+        + Clone
+        + Send
+        + 'static,
     OutputEventT: Clone + Send + Sync + 'static,
     HandlerErrorT: CheckedError + Debug + Send + Sync + 'static,
-    SerializerErrorT: Error + Debug + Send + Sync + 'static,
-    S3ClientT: S3 + Clone + Send + Sync + 'static,
-    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+    SerializerErrorT: CheckedError + Debug + Send + Sync + 'static,
     CompletionEventSerializerT: CompletionEventSerializer<
-        CompletedEvent = OutputEventT,
-        Output = Vec<u8>,
-        Error = SerializerErrorT,
-    >,
+            CompletedEvent = OutputEventT,
+            Output = Vec<u8>,
+            Error = SerializerErrorT,
+        > + Clone
+        + Send
+        + 'static,
 {
-    loop {
-        tracing::trace!("Outer process loop");
-        let f = _process_loop(
-            queue_url.clone(),
-            dead_letter_queue_url.clone(),
-            cache,
-            sqs_client.clone(),
-            event_handler,
-            s3_payload_retriever,
-            s3_emitter,
-            serializer,
+    info!(
+        worker_count = config.worker_count(),
+        max_in_flight = config.max_in_flight(),
+        "Starting workers"
+    );
+    let source = Arc::new(source);
+    let (received_tx, received_rx) = channel(config.max_in_flight());
+    let received_rx = Arc::new(Mutex::new(received_rx));
+
+    let mut workers = Vec::with_capacity(config.worker_count());
+    for _ in 0..config.worker_count() {
+        workers.push(tokio::spawn(work(
+            source.clone(),
+            received_rx.clone(),
+            cache.clone(),
+            event_handler.clone(),
+            payload_retriever.clone(),
+            event_sink.clone(),
+            serializer.clone(),
+            config.emit_backoff().clone(),
             metric_reporter.clone(),
-        );
-        let f = AssertUnwindSafe(f);
+        )));
+    }
 
-        if let Err(e) = f.catch_unwind().await {
-            if let Some(e) = e.downcast_ref::<Box<dyn std::error::Error + 'static>>() {
-                error!(
-                    queue_url = queue_url.as_str(),
-                    error = e.to_string().as_str(),
-                    "Processing loop panicked"
-                );
-            } else if let Some(e) = e.downcast_ref::<Box<dyn std::fmt::Debug>>() {
-                error!(
-                    error = format!("{:?}", e).as_str(),
-                    "Processing loop panicked"
-                );
-            } else {
-                error!("Unexpected error");
-            }
-            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let receiver = tokio::spawn(receive_loop(
+        source,
+        config.max_in_flight(),
+        received_tx,
+        metric_reporter,
+    ));
+
+    // Neither the workers nor the receive loop exit unless every worker has
+    if let Err(e) = receiver.await {
+        error!(error=?e, "Receive loop failed");
+    }
+    for worker in futures::future::join_all(workers).await {
+        if let Err(e) = worker {
+            error!(error=?e, "Worker failed");
         }
     }
 }
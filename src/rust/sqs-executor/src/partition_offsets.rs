@@ -0,0 +1,50 @@
+use std::collections::BTreeSet;
+
+/// Tracks which of a partition's received offsets are still being processed, so that the
+/// committed offset never moves past a message that hasn't completed.
+///
+/// Messages complete out of order when they're processed concurrently, so completing an offset
+/// only advances the commit once every earlier received offset has completed too.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartitionOffsets {
+    pending: BTreeSet<i64>,
+    next_offset: Option<i64>,
+    committed: Option<i64>,
+}
+
+impl PartitionOffsets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn received(&mut self, offset: i64) {
+        // Nothing before the first received offset needs to be committed
+        self.committed.get_or_insert(offset);
+        self.pending.insert(offset);
+        self.next_offset = Some(
+            self.next_offset
+                .map_or(offset + 1, |next| next.max(offset + 1)),
+        );
+    }
+
+    /// Returns the offset to commit, the next one to be consumed, if completing this offset
+    /// advanced it
+    pub fn completed(&mut self, offset: i64) -> Option<i64> {
+        if !self.pending.remove(&offset) {
+            return None;
+        }
+
+        let low_water_mark = match self.pending.iter().next() {
+            Some(earliest_pending) => *earliest_pending,
+            None => self.next_offset?,
+        };
+
+        match self.committed {
+            Some(committed) if committed >= low_water_mark => None,
+            _ => {
+                self.committed = Some(low_water_mark);
+                self.committed
+            }
+        }
+    }
+}
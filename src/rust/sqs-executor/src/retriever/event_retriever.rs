@@ -5,6 +5,6 @@ use crate::errors::CheckedError;
 #[async_trait]
 pub trait PayloadRetriever<T> {
     type Message;
-    type Error: CheckedError;
+    type Error: CheckedError + Send;
     async fn retrieve_event(&mut self, msg: &Self::Message) -> Result<Option<T>, Self::Error>;
 }
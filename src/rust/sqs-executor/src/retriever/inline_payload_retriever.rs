@@ -0,0 +1,73 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+
+use crate::{
+    errors::CheckedError,
+    event_decoder::PayloadDecoder,
+    PayloadRetriever,
+};
+
+/// A message that carries its payload with it, rather than pointing to where the payload is stored
+pub trait InlinePayload {
+    fn payload(&self) -> &[u8];
+}
+
+/// Decodes the payload carried by each message, for sources such as Kafka topics and directories
+pub struct InlinePayloadRetriever<M, D, E, DecoderErrorT>
+where
+    M: InlinePayload + Send + Sync + 'static,
+    D: PayloadDecoder<E, DecoderError = DecoderErrorT> + Clone + Send + 'static,
+    DecoderErrorT: CheckedError + Send + 'static,
+    E: Send + 'static,
+{
+    decoder: D,
+    phantom: PhantomData<(M, E, DecoderErrorT)>,
+}
+
+impl<M, D, E, DecoderErrorT> Clone for InlinePayloadRetriever<M, D, E, DecoderErrorT>
+where
+    M: InlinePayload + Send + Sync + 'static,
+    D: PayloadDecoder<E, DecoderError = DecoderErrorT> + Clone + Send + 'static,
+    DecoderErrorT: CheckedError + Send + 'static,
+    E: Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            decoder: self.decoder.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<M, D, E, DecoderErrorT> InlinePayloadRetriever<M, D, E, DecoderErrorT>
+where
+    M: InlinePayload + Send + Sync + 'static,
+    D: PayloadDecoder<E, DecoderError = DecoderErrorT> + Clone + Send + 'static,
+    DecoderErrorT: CheckedError + Send + 'static,
+    E: Send + 'static,
+{
+    pub fn new(decoder: D) -> Self {
+        Self {
+            decoder,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<M, D, E, DecoderErrorT> PayloadRetriever<E> for InlinePayloadRetriever<M, D, E, DecoderErrorT>
+where
+    M: InlinePayload + Send + Sync + 'static,
+    D: PayloadDecoder<E, DecoderError = DecoderErrorT> + Clone + Send + 'static,
+    DecoderErrorT: CheckedError + Send + 'static,
+    E: Send + 'static,
+{
+    type Message = M;
+    type Error = DecoderErrorT;
+
+    async fn retrieve_event(&mut self, msg: &Self::Message) -> Result<Option<E>, Self::Error> {
+        let event = self.decoder.decode(msg.payload().to_vec())?;
+        Ok(Some(event))
+    }
+}
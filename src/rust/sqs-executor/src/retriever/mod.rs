@@ -1,5 +1,7 @@
 pub mod event_retriever;
+pub mod inline_payload_retriever;
 pub mod s3_event_retriever;
 
 pub use event_retriever::PayloadRetriever;
+pub use inline_payload_retriever::InlinePayloadRetriever;
 pub use s3_event_retriever::S3PayloadRetriever;
@@ -1,4 +1,7 @@
-use std::io::Stdout;
+use std::{
+    collections::HashMap,
+    io::Stdout,
+};
 
 use grapl_observe::{
     metric_reporter::{
@@ -16,6 +19,7 @@ use rusoto_sqs::{
     DeleteMessageError as InnerDeleteMessageError,
     DeleteMessageRequest,
     Message as SqsMessage,
+    MessageAttributeValue,
     ReceiveMessageError as InnerReceiveMessageError,
     ReceiveMessageRequest,
     SendMessageRequest,
@@ -162,6 +166,7 @@ impl CheckedError for SendMessageError {
 pub fn send_message<SqsT>(
     queue_url: String,
     message_body: String,
+    message_attributes: Option<HashMap<String, MessageAttributeValue>>,
     sqs_client: SqsT,
     mut metric_reporter: MetricReporter<Stdout>,
 ) -> JoinHandle<Result<(), SendMessageError>>
@@ -176,6 +181,7 @@ where
             let res = sqs_client.send_message(SendMessageRequest {
                 queue_url: queue_url.clone(),
                 message_body: message_body.clone(),
+                message_attributes: message_attributes.clone(),
                 ..Default::default()
             });
 
@@ -318,6 +324,7 @@ pub enum MoveToDeadLetterError {
 pub async fn move_to_dead_letter<SqsT>(
     sqs_client: SqsT,
     message: &impl serde::Serialize,
+    reason: &str,
     publish_to_queue: String,
     delete_from_queue: String,
     receipt_handle: String,
@@ -329,13 +336,24 @@ where
     debug!(
         publish_to_queue = publish_to_queue.as_str(),
         delete_from_queue = delete_from_queue.as_str(),
+        reason = reason,
         "Moving message to deadletter queue"
     );
     let message = serde_json::to_string(&message);
     let message = message?;
+    let mut message_attributes = HashMap::with_capacity(1);
+    message_attributes.insert(
+        "reason".to_owned(),
+        MessageAttributeValue {
+            data_type: "String".to_owned(),
+            string_value: Some(reason.to_owned()),
+            ..Default::default()
+        },
+    );
     send_message(
         publish_to_queue,
         message,
+        Some(message_attributes),
         sqs_client.clone(),
         metric_reporter.clone(),
     )
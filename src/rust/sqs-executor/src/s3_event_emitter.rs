@@ -37,6 +37,15 @@ pub enum S3EventEmitterError {
     Timeout(#[from] Elapsed),
 }
 
+impl CheckedError for S3EventEmitterError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::PutObjectError(e) => e.error_type(),
+            Self::Timeout(_) => Recoverable::Transient,
+        }
+    }
+}
+
 pub struct S3EventEmitter<S, F>
 where
     S: Clone + S3 + Send + Sync,
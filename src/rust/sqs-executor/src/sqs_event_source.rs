@@ -0,0 +1,155 @@
+use std::io::Stdout;
+
+use async_trait::async_trait;
+use grapl_observe::metric_reporter::MetricReporter;
+use rusoto_core::RusotoError;
+use rusoto_sqs::{
+    Message as SqsMessage,
+    ReceiveMessageError,
+    Sqs,
+};
+use tokio::task::JoinError;
+
+use crate::{
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_source::EventSource,
+    rusoto_helpers::{
+        self,
+        MoveToDeadLetterError,
+    },
+    sqs_timeout_manager::{
+        keep_alive,
+        Sender,
+    },
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SqsEventSourceError {
+    #[error("ReceiveMessageError: {0}")]
+    ReceiveMessageError(#[from] RusotoError<ReceiveMessageError>),
+    #[error("DeleteMessageError: {0}")]
+    DeleteMessageError(#[from] JoinError),
+    #[error("MoveToDeadLetterError: {0}")]
+    MoveToDeadLetterError(#[from] MoveToDeadLetterError),
+    #[error("Message is missing its {0}")]
+    MissingAttribute(&'static str),
+}
+
+impl CheckedError for SqsEventSourceError {
+    fn error_type(&self) -> Recoverable {
+        match self {
+            Self::ReceiveMessageError(_) => Recoverable::Transient,
+            Self::DeleteMessageError(_) => Recoverable::Transient,
+            Self::MoveToDeadLetterError(_) => Recoverable::Transient,
+            Self::MissingAttribute(_) => Recoverable::Persistent,
+        }
+    }
+}
+
+/// Receives messages from an SQS queue. Persistently failing messages are moved to the
+/// dead letter queue, while transiently failing messages become visible again once their
+/// visibility timeout expires.
+#[derive(Clone)]
+pub struct SqsEventSource<SqsT>
+where
+    SqsT: Sqs + Clone + Send + Sync + 'static,
+{
+    sqs_client: SqsT,
+    queue_url: String,
+    dead_letter_queue_url: String,
+    metric_reporter: MetricReporter<Stdout>,
+}
+
+impl<SqsT> SqsEventSource<SqsT>
+where
+    SqsT: Sqs + Clone + Send + Sync + 'static,
+{
+    pub fn new(
+        sqs_client: SqsT,
+        queue_url: impl Into<String>,
+        dead_letter_queue_url: impl Into<String>,
+        metric_reporter: MetricReporter<Stdout>,
+    ) -> Self {
+        Self {
+            sqs_client,
+            queue_url: queue_url.into(),
+            dead_letter_queue_url: dead_letter_queue_url.into(),
+            metric_reporter,
+        }
+    }
+
+    pub fn queue_url(&self) -> &str {
+        &self.queue_url
+    }
+}
+
+fn receipt_handle(message: &SqsMessage) -> Result<String, SqsEventSourceError> {
+    message
+        .receipt_handle
+        .clone()
+        .ok_or(SqsEventSourceError::MissingAttribute("receipt_handle"))
+}
+
+#[async_trait]
+impl<SqsT> EventSource for SqsEventSource<SqsT>
+where
+    SqsT: Sqs + Clone + Send + Sync + 'static,
+{
+    type Message = SqsMessage;
+    type InFlight = Option<Sender>;
+    type Error = SqsEventSourceError;
+
+    async fn receive_messages(&self) -> Result<Vec<Self::Message>, Self::Error> {
+        let messages = rusoto_helpers::get_message(
+            self.queue_url.clone(),
+            self.sqs_client.clone(),
+            &mut self.metric_reporter.clone(),
+        )
+        .await?;
+        Ok(messages)
+    }
+
+    fn message_id(&self, message: &Self::Message) -> String {
+        message.message_id.clone().unwrap_or_default()
+    }
+
+    fn keep_alive(&self, message: &Self::Message) -> Self::InFlight {
+        // Maintain an invisibility timeout for the message until we're done
+        let receipt_handle = message.receipt_handle.clone()?;
+        Some(keep_alive(
+            self.sqs_client.clone(),
+            receipt_handle,
+            self.message_id(message),
+            self.queue_url.clone(),
+            30,
+        ))
+    }
+
+    async fn ack(&self, message: &Self::Message) -> Result<(), Self::Error> {
+        rusoto_helpers::delete_message(
+            self.sqs_client.clone(),
+            self.queue_url.clone(),
+            receipt_handle(message)?,
+            self.metric_reporter.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn dead_letter(&self, message: &Self::Message, reason: &str) -> Result<(), Self::Error> {
+        rusoto_helpers::move_to_dead_letter(
+            self.sqs_client.clone(),
+            &message.body.clone().unwrap_or_default(),
+            reason,
+            self.dead_letter_queue_url.clone(),
+            self.queue_url.clone(),
+            receipt_handle(message)?,
+            self.metric_reporter.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+}
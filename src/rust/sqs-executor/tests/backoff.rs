@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use sqs_executor::backoff::Backoff;
+
+#[test]
+fn delays_grow_exponentially_up_to_the_max() {
+    let backoff = Backoff::new(10, Duration::from_millis(100), Duration::from_secs(1));
+
+    assert_eq!(backoff.max_delay_before(1), Duration::from_millis(100));
+    assert_eq!(backoff.max_delay_before(2), Duration::from_millis(200));
+    assert_eq!(backoff.max_delay_before(4), Duration::from_millis(800));
+    assert_eq!(backoff.max_delay_before(5), Duration::from_secs(1));
+    assert_eq!(backoff.max_delay_before(64), Duration::from_secs(1));
+}
+
+#[test]
+fn delays_are_jittered_within_the_max() {
+    let backoff = Backoff::new(10, Duration::from_millis(100), Duration::from_secs(1));
+
+    for attempt in 1..10 {
+        assert!(backoff.delay_before(attempt) <= backoff.max_delay_before(attempt));
+    }
+}
+
+#[test]
+fn at_least_one_attempt_is_made() {
+    let backoff = Backoff::new(0, Duration::from_millis(100), Duration::from_secs(1));
+    assert_eq!(backoff.max_attempts(), 1);
+}
@@ -0,0 +1,124 @@
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
+
+use sqs_executor::{
+    directory_event_emitter::DirectoryEventEmitter,
+    directory_event_source::DirectoryEventSource,
+    event_emitter::EventEmitter,
+    event_source::EventSource,
+};
+
+fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("sqs-executor-tests-{}", uuid::Uuid::new_v4()))
+}
+
+fn payload_key_fn(event: &[u8]) -> String {
+    format!("events/{}", String::from_utf8_lossy(event))
+}
+
+#[tokio::test]
+async fn emitted_events_are_received_once_until_released() {
+    let root = scratch_dir();
+    let input_dir = root.join("input");
+    let dead_letter_dir = root.join("dead-letter");
+
+    let mut emitter = DirectoryEventEmitter::new(&input_dir, payload_key_fn);
+    emitter
+        .emit_event(vec![
+            b"first".to_vec(),
+            b"second".to_vec(),
+            b"third".to_vec(),
+        ])
+        .await
+        .expect("emit_event");
+
+    let source = DirectoryEventSource::new(&input_dir, &dead_letter_dir);
+    let messages = source.receive_messages().await.expect("receive_messages");
+    let payloads: Vec<&[u8]> = messages.iter().map(|m| m.payload.as_slice()).collect();
+    assert_eq!(payloads, vec![&b"first"[..], &b"second"[..], &b"third"[..]]);
+    assert_eq!(source.message_id(&messages[0]), "events_first");
+
+    // Claimed messages aren't received again
+    assert!(source.receive_messages().await.unwrap().is_empty());
+
+    source.ack(&messages[0]).await.expect("ack");
+    source.release(&messages[1]).await.expect("release");
+    source
+        .dead_letter(&messages[2], "handler failed")
+        .await
+        .expect("dead_letter");
+
+    let redelivered = source.receive_messages().await.expect("receive_messages");
+    assert_eq!(redelivered.len(), 1);
+    assert_eq!(redelivered[0].payload, b"second");
+
+    let dead_lettered = tokio::fs::read(dead_letter_dir.join("events_third"))
+        .await
+        .expect("dead lettered file");
+    assert_eq!(dead_lettered, b"third");
+    let reason = tokio::fs::read(dead_letter_dir.join(".events_third.reason"))
+        .await
+        .expect("dead letter reason");
+    assert_eq!(reason, b"handler failed");
+
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
+
+#[tokio::test]
+async fn abandoned_in_flight_files_are_recovered_by_a_new_source() {
+    let root = scratch_dir();
+    let input_dir = root.join("input");
+
+    let mut emitter = DirectoryEventEmitter::new(&input_dir, payload_key_fn);
+    emitter
+        .emit_event(vec![b"stranded".to_vec()])
+        .await
+        .expect("emit_event");
+
+    let source = DirectoryEventSource::new(&input_dir, root.join("dead-letter"))
+        .with_batch_size(1)
+        .with_claim_timeout(Duration::from_millis(100));
+    assert_eq!(source.receive_messages().await.unwrap().len(), 1);
+    drop(source);
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let source = DirectoryEventSource::new(&input_dir, root.join("dead-letter"))
+        .with_claim_timeout(Duration::from_millis(100));
+    let messages = source.receive_messages().await.expect("receive_messages");
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].payload, b"stranded");
+
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
+
+#[tokio::test]
+async fn claims_kept_alive_are_not_recovered_by_other_sources() {
+    let root = scratch_dir();
+    let input_dir = root.join("input");
+
+    let mut emitter = DirectoryEventEmitter::new(&input_dir, payload_key_fn);
+    emitter
+        .emit_event(vec![b"claimed".to_vec()])
+        .await
+        .expect("emit_event");
+
+    let source = DirectoryEventSource::new(&input_dir, root.join("dead-letter"))
+        .with_claim_timeout(Duration::from_millis(300));
+    let messages = source.receive_messages().await.expect("receive_messages");
+    assert_eq!(messages.len(), 1);
+    let _in_flight = source.keep_alive(&messages[0]);
+
+    // Well past the claim timeout, but the claim has been kept alive all along
+    tokio::time::sleep(Duration::from_millis(900)).await;
+
+    let other_source = DirectoryEventSource::new(&input_dir, root.join("dead-letter"))
+        .with_claim_timeout(Duration::from_millis(300));
+    assert!(other_source.receive_messages().await.unwrap().is_empty());
+
+    source.ack(&messages[0]).await.expect("ack");
+
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
@@ -0,0 +1,47 @@
+use sqs_executor::partition_offsets::PartitionOffsets;
+
+#[test]
+fn in_order_completions_commit_each_offset() {
+    let mut offsets = PartitionOffsets::new();
+    offsets.received(10);
+    offsets.received(11);
+
+    assert_eq!(offsets.completed(10), Some(11));
+    assert_eq!(offsets.completed(11), Some(12));
+}
+
+#[test]
+fn later_completions_wait_for_earlier_offsets() {
+    let mut offsets = PartitionOffsets::new();
+    offsets.received(10);
+    offsets.received(11);
+    offsets.received(12);
+
+    // Committing 12 or 13 here would skip offset 10 if the consumer crashed
+    assert_eq!(offsets.completed(12), None);
+    assert_eq!(offsets.completed(11), None);
+    assert_eq!(offsets.completed(10), Some(13));
+}
+
+#[test]
+fn commits_stop_at_the_earliest_pending_offset() {
+    let mut offsets = PartitionOffsets::new();
+    for offset in 10..15 {
+        offsets.received(offset);
+    }
+
+    assert_eq!(offsets.completed(10), Some(11));
+    assert_eq!(offsets.completed(13), None);
+    assert_eq!(offsets.completed(11), Some(12));
+    assert_eq!(offsets.completed(12), Some(14));
+}
+
+#[test]
+fn unknown_and_repeated_completions_are_ignored() {
+    let mut offsets = PartitionOffsets::new();
+    offsets.received(10);
+
+    assert_eq!(offsets.completed(9), None);
+    assert_eq!(offsets.completed(10), Some(11));
+    assert_eq!(offsets.completed(10), None);
+}
@@ -0,0 +1,268 @@
+use std::{
+    collections::BTreeSet,
+    path::Path,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use grapl_observe::metric_reporter::MetricReporter;
+use sqs_executor::{
+    backoff::Backoff,
+    cache::NopCache,
+    completion_event_serializer::CompletionEventSerializer,
+    directory_event_emitter::DirectoryEventEmitter,
+    directory_event_source::DirectoryEventSource,
+    errors::{
+        CheckedError,
+        Recoverable,
+    },
+    event_emitter::EventEmitter,
+    event_handler::{
+        CompletedEvents,
+        EventHandler,
+    },
+    executor_config::ExecutorConfig,
+    inline_payload_retriever::InlinePayloadRetriever,
+    process_loop,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("EchoError")]
+struct EchoError;
+
+impl CheckedError for EchoError {
+    fn error_type(&self) -> Recoverable {
+        Recoverable::Persistent
+    }
+}
+
+/// Echoes each payload back after a short delay, recording how many payloads it's
+/// handling at once across all of its clones
+#[derive(Clone, Default)]
+struct SlowEcho {
+    running: Arc<AtomicUsize>,
+    max_running: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl EventHandler for SlowEcho {
+    type InputEvent = Vec<u8>;
+    type OutputEvent = Vec<u8>;
+    type Error = EchoError;
+
+    async fn handle_event(
+        &mut self,
+        input: Vec<u8>,
+        _completed: &mut CompletedEvents,
+    ) -> Result<Self::OutputEvent, Result<(Self::OutputEvent, Self::Error), Self::Error>> {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(input)
+    }
+}
+
+/// Passes events through as they are, other than "poison", which can never be serialized
+#[derive(Clone, Default)]
+struct PassthroughSerializer;
+
+impl CompletionEventSerializer for PassthroughSerializer {
+    type CompletedEvent = Vec<u8>;
+    type Output = Vec<u8>;
+    type Error = EchoError;
+
+    fn serialize_completed_events(
+        &mut self,
+        completed_events: &[Self::CompletedEvent],
+    ) -> Result<Vec<Self::Output>, Self::Error> {
+        if completed_events.iter().any(|event| event == b"poison") {
+            return Err(EchoError);
+        }
+        Ok(completed_events.to_vec())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("FlakyError")]
+struct FlakyError;
+
+impl CheckedError for FlakyError {
+    fn error_type(&self) -> Recoverable {
+        Recoverable::Transient
+    }
+}
+
+/// Fails to emit a number of times, shared across its clones, before emitting to a directory
+#[derive(Clone)]
+struct FlakySink<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    failures_left: Arc<AtomicUsize>,
+    emitter: DirectoryEventEmitter<F>,
+}
+
+#[async_trait]
+impl<F> EventEmitter for FlakySink<F>
+where
+    F: Clone + Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    type Event = Vec<u8>;
+    type Error = FlakyError;
+
+    async fn emit_event(&mut self, events: Vec<Self::Event>) -> Result<(), Self::Error> {
+        let failed = self
+            .failures_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok();
+        if failed {
+            return Err(FlakyError);
+        }
+        self.emitter
+            .emit_event(events)
+            .await
+            .map_err(|_| FlakyError)
+    }
+}
+
+fn decode(payload: Vec<u8>) -> Result<Vec<u8>, EchoError> {
+    Ok(payload)
+}
+
+fn payload_key_fn(event: &[u8]) -> String {
+    String::from_utf8_lossy(event).into_owned()
+}
+
+async fn list_files(dir: &Path) -> BTreeSet<String> {
+    let mut file_names = BTreeSet::new();
+    if let Ok(mut entries) = tokio::fs::read_dir(dir).await {
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            let file_name = entry.file_name().into_string().unwrap();
+            if !file_name.starts_with('.') {
+                file_names.insert(file_name);
+            }
+        }
+    }
+    file_names
+}
+
+#[tokio::test]
+async fn process_loop_handles_messages_on_every_worker() {
+    let root = std::env::temp_dir().join(format!("sqs-executor-tests-{}", uuid::Uuid::new_v4()));
+    let input_dir = root.join("input");
+    let output_dir = root.join("output");
+
+    let payloads: BTreeSet<String> = (0..12).map(|i| format!("event-{:02}", i)).collect();
+    DirectoryEventEmitter::new(&input_dir, payload_key_fn)
+        .emit_event(payloads.iter().map(|p| p.clone().into_bytes()).collect())
+        .await
+        .expect("emit_event");
+
+    let handler = SlowEcho::default();
+    let max_running = handler.max_running.clone();
+
+    let executor = tokio::spawn(process_loop(
+        DirectoryEventSource::new(&input_dir, root.join("dead-letter")),
+        NopCache {},
+        handler,
+        InlinePayloadRetriever::new(decode),
+        DirectoryEventEmitter::new(&output_dir, payload_key_fn),
+        PassthroughSerializer,
+        ExecutorConfig::new(4).with_max_in_flight(8),
+        MetricReporter::new("process_loop_tests"),
+    ));
+
+    // Wait for every message to be emitted and acked
+    let mut emitted = BTreeSet::new();
+    let mut unacked = BTreeSet::new();
+    for _ in 0..100 {
+        emitted = list_files(&output_dir).await;
+        unacked = list_files(&input_dir).await;
+        unacked.extend(list_files(&input_dir.join(".in-flight")).await);
+        if emitted.len() == payloads.len() && unacked.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    executor.abort();
+
+    assert_eq!(emitted, payloads);
+    assert!(unacked.is_empty());
+    // Scheduling decides how many workers overlap, but never more than the configured count
+    let max_running = max_running.load(Ordering::SeqCst);
+    assert!(max_running <= 4, "{} handlers ran at once", max_running);
+    assert!(max_running > 1, "handlers never ran concurrently");
+
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
+
+#[tokio::test]
+async fn process_loop_retries_emitting_and_dead_letters_what_fails() {
+    let root = std::env::temp_dir().join(format!("sqs-executor-tests-{}", uuid::Uuid::new_v4()));
+    let input_dir = root.join("input");
+    let output_dir = root.join("output");
+    let dead_letter_dir = root.join("dead-letter");
+
+    DirectoryEventEmitter::new(&input_dir, payload_key_fn)
+        .emit_event(vec![b"event".to_vec(), b"poison".to_vec()])
+        .await
+        .expect("emit_event");
+
+    let sink = FlakySink {
+        failures_left: Arc::new(AtomicUsize::new(3)),
+        emitter: DirectoryEventEmitter::new(&output_dir, payload_key_fn),
+    };
+    let failures_left = sink.failures_left.clone();
+
+    let executor = tokio::spawn(process_loop(
+        DirectoryEventSource::new(&input_dir, &dead_letter_dir),
+        NopCache {},
+        SlowEcho::default(),
+        InlinePayloadRetriever::new(decode),
+        sink,
+        PassthroughSerializer,
+        ExecutorConfig::new(2).with_emit_backoff(Backoff::new(
+            5,
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+        )),
+        MetricReporter::new("process_loop_tests"),
+    ));
+
+    let mut emitted = BTreeSet::new();
+    let mut dead_lettered = BTreeSet::new();
+    for _ in 0..100 {
+        emitted = list_files(&output_dir).await;
+        dead_lettered = list_files(&dead_letter_dir).await;
+        if !emitted.is_empty() && !dead_lettered.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    executor.abort();
+
+    // The transient failures were retried rather than redelivering the message
+    assert_eq!(failures_left.load(Ordering::SeqCst), 0);
+    assert_eq!(emitted, vec!["event".to_owned()].into_iter().collect());
+    assert_eq!(
+        dead_lettered,
+        vec!["poison".to_owned()].into_iter().collect()
+    );
+
+    let reason = tokio::fs::read_to_string(dead_letter_dir.join(".poison.reason"))
+        .await
+        .expect("dead letter reason");
+    assert_eq!(reason, "Failed to serialize completed events: EchoError");
+
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
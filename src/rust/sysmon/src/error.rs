@@ -0,0 +1,34 @@
+/// Why a Sysmon event couldn't be parsed
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Malformed event XML: {0}")]
+    Xml(#[from] serde_xml_rs::Error),
+    #[error("Unsupported event id: {0}")]
+    UnsupportedEventId(u8),
+    #[error("Invalid data in event {event_id}: {source}")]
+    EventData {
+        event_id: u8,
+        #[source]
+        source: EventDataError,
+    },
+    #[error("Failed to read events: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Why an event's `EventData` couldn't be converted to the data of its event type
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum EventDataError {
+    #[error("No field: {0}")]
+    MissingField(&'static str),
+    #[error("Invalid field {field}: {value:?} ({reason})")]
+    InvalidField {
+        field: &'static str,
+        value: String,
+        reason: String,
+    },
+}
+
+/// An `EventType` that isn't one of the event's subtypes
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unknown event type: {0}")]
+pub struct UnknownEventType(pub String);
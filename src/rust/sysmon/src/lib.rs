@@ -1,4 +1,3 @@
-extern crate anyhow;
 extern crate chrono;
 #[macro_use]
 extern crate derive_is_enum_variant;
@@ -9,13 +8,11 @@ extern crate uuid;
 use std::{
     collections::HashMap,
     convert::TryFrom,
+    fmt::Display,
+    net::IpAddr,
     str::FromStr,
 };
 
-use anyhow::{
-    anyhow,
-    Result,
-};
 use chrono::prelude::*;
 use failure::_core::ops::Deref;
 use serde::{
@@ -24,21 +21,17 @@ use serde::{
     Deserializer,
 };
 
-macro_rules! get_or_err {
-    ($map:ident, $field_name:expr) => {
-        match $map.remove($field_name) {
-            Some(field) => field,
-            None => return Err(anyhow!("No field: {}", $field_name)),
-        }
-    };
+pub use crate::{
+    error::{
+        Error,
+        EventDataError,
+        UnknownEventType,
+    },
+    stream::EventStream,
+};
 
-    ($map:ident, $field_name:expr, $maperr:expr) => {
-        match $map.remove($field_name) {
-            Some(field) => field,
-            None => return Err(anyhow!("No field: {}", $field_name)).map_err($maperr),
-        }
-    };
-}
+mod error;
+mod stream;
 
 #[derive(Debug, Clone, Hash, is_enum_variant)]
 pub enum Event {
@@ -46,28 +39,119 @@ pub enum Event {
     FileCreate(FileCreateEvent),
     InboundNetwork(NetworkEvent),
     OutboundNetwork(NetworkEvent),
+    ProcessTerminate(ProcessTerminateEvent),
+    ImageLoad(ImageLoadEvent),
+    CreateRemoteThread(CreateRemoteThreadEvent),
+    ProcessAccess(ProcessAccessEvent),
+    /// Registry key and value creation, deletion, modification and renaming (12, 13 and 14)
+    Registry(RegistryEvent),
+    FileCreateStreamHash(FileCreateStreamHashEvent),
+    /// Named pipe creation and connection (17 and 18)
+    Pipe(PipeEvent),
+    DnsQuery(DnsQueryEvent),
+    /// File deletion, whether or not the file was archived (23 and 26)
+    FileDelete(FileDeleteEvent),
 }
 
 impl FromStr for Event {
-    type Err = anyhow::Error;
+    type Err = Error;
 
+    /// Parses the XML of a single event
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        serde_xml_rs::from_str::<ProcessCreateEvent>(s)
-            .map(|p| Event::ProcessCreate(p))
-            .or_else(|_| serde_xml_rs::from_str::<FileCreateEvent>(s).map(|f| Event::FileCreate(f)))
-            .or_else(|_| {
-                serde_xml_rs::from_str::<NetworkEvent>(s).map(|n| {
-                    if n.event_data.initiated {
-                        Event::OutboundNetwork(n)
-                    } else {
-                        Event::InboundNetwork(n)
-                    }
-                })
-            })
-            .map_err(|e| anyhow!("Error : {:?} {}", e, s))
+        let raw_event: RawEvent = serde_xml_rs::from_str(s)?;
+
+        Event::try_from(raw_event)
     }
 }
 
+/// An event whose data is yet to be converted to the data of its event type
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct RawEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData")]
+    pub event_data: IntermediaryEventData,
+}
+
+impl TryFrom<RawEvent> for Event {
+    type Error = Error;
+
+    /// Converts the event's data by the type its `EventID` names, as the data of many event
+    /// types overlap
+    fn try_from(raw_event: RawEvent) -> Result<Self, Self::Error> {
+        let RawEvent { system, event_data } = raw_event;
+        let event_id = system.event_id.event_id;
+
+        let event = match event_id {
+            1 => Event::ProcessCreate(ProcessCreateEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            3 => {
+                let event = NetworkEvent {
+                    system,
+                    event_data: convert_event_data(event_id, event_data)?,
+                };
+                if event.event_data.initiated {
+                    Event::OutboundNetwork(event)
+                } else {
+                    Event::InboundNetwork(event)
+                }
+            }
+            5 => Event::ProcessTerminate(ProcessTerminateEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            7 => Event::ImageLoad(ImageLoadEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            8 => Event::CreateRemoteThread(CreateRemoteThreadEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            10 => Event::ProcessAccess(ProcessAccessEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            11 => Event::FileCreate(FileCreateEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            12..=14 => Event::Registry(RegistryEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            15 => Event::FileCreateStreamHash(FileCreateStreamHashEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            17 | 18 => Event::Pipe(PipeEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            22 => Event::DnsQuery(DnsQueryEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            23 | 26 => Event::FileDelete(FileDeleteEvent {
+                system,
+                event_data: convert_event_data(event_id, event_data)?,
+            }),
+            event_id => return Err(Error::UnsupportedEventId(event_id)),
+        };
+
+        Ok(event)
+    }
+}
+
+fn convert_event_data<T>(event_id: u8, event_data: IntermediaryEventData) -> Result<T, Error>
+where
+    T: TryFrom<IntermediaryEventData, Error = EventDataError>,
+{
+    T::try_from(event_data).map_err(|source| Error::EventData { event_id, source })
+}
+
 #[derive(Debug, Deserialize, Clone, Hash)]
 pub struct Provider {
     #[serde(rename = "Name")]
@@ -227,6 +311,21 @@ impl ProcessGuid {
     }
 }
 
+impl FromStr for ProcessGuid {
+    type Err = uuid::Error;
+
+    /// Parses a GUID as Sysmon formats it, within braces
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ProcessGuid {
+            process_guid: parse_guid(s)?,
+        })
+    }
+}
+
+fn parse_guid(guid: &str) -> Result<uuid::Uuid, uuid::Error> {
+    uuid::Uuid::parse_str(guid.trim_start_matches('{').trim_end_matches('}'))
+}
+
 #[derive(Debug, Deserialize, Clone, Hash)]
 pub struct Image {
     pub image: String,
@@ -284,6 +383,16 @@ pub struct LogonGuid {
     pub logon_guid: uuid::Uuid,
 }
 
+impl FromStr for LogonGuid {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(LogonGuid {
+            logon_guid: parse_guid(s)?,
+        })
+    }
+}
+
 impl Deref for LogonGuid {
     type Target = uuid::Uuid;
 
@@ -449,148 +558,592 @@ pub struct NetworkEvent {
     pub event_data: NetworkEventData,
 }
 
-impl TryFrom<IntermediaryEventData> for ProcessCreateEventData {
-    type Error = anyhow::Error;
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct ProcessTerminateEventData {
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    pub image: Image,
+    pub user: Option<User>,
+}
 
-    fn try_from(inter: IntermediaryEventData) -> Result<Self> {
-        let mut m = HashMap::with_capacity(inter.data.len());
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct ProcessTerminateEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: ProcessTerminateEventData,
+}
 
-        for data in inter.data {
-            if let Some(value) = data.value {
-                //                if (value == "4") {
-                //                    panic!("{} {}", data.name, value.len());
-                //                }
-                m.insert(data.name, value);
-            }
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct ImageLoadEventData {
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    pub image: Image,
+    /// <Data Name="ImageLoaded">C:\Windows\System32\ntdll.dll</Data>
+    pub image_loaded: String,
+    pub file_version: Option<String>,
+    pub description: Option<String>,
+    pub product: Option<String>,
+    pub company: Option<String>,
+    pub original_file_name: Option<String>,
+    pub hashes: Option<Hashes>,
+    pub signed: Option<bool>,
+    pub signature: Option<String>,
+    pub signature_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct ImageLoadEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: ImageLoadEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct CreateRemoteThreadEventData {
+    pub utc_time: UtcTime,
+    pub source_process_guid: ProcessGuid,
+    pub source_process_id: u64,
+    pub source_image: Image,
+    pub target_process_guid: ProcessGuid,
+    pub target_process_id: u64,
+    pub target_image: Image,
+    pub new_thread_id: u64,
+    /// <Data Name="StartAddress">0x00007FFB7A0E1D50</Data>
+    pub start_address: String,
+    pub start_module: Option<String>,
+    pub start_function: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct CreateRemoteThreadEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: CreateRemoteThreadEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct ProcessAccessEventData {
+    pub utc_time: UtcTime,
+    pub source_process_guid: ProcessGuid,
+    pub source_process_id: u64,
+    pub source_thread_id: u64,
+    pub source_image: Image,
+    pub target_process_guid: ProcessGuid,
+    pub target_process_id: u64,
+    pub target_image: Image,
+    /// <Data Name="GrantedAccess">0x1010</Data>
+    pub granted_access: String,
+    pub call_trace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct ProcessAccessEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: ProcessAccessEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash, is_enum_variant)]
+pub enum RegistryEventType {
+    CreateKey,
+    DeleteKey,
+    RenameKey,
+    CreateValue,
+    DeleteValue,
+    RenameValue,
+    SetValue,
+}
+
+impl FromStr for RegistryEventType {
+    type Err = UnknownEventType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CreateKey" => Ok(RegistryEventType::CreateKey),
+            "DeleteKey" => Ok(RegistryEventType::DeleteKey),
+            "RenameKey" => Ok(RegistryEventType::RenameKey),
+            "CreateValue" => Ok(RegistryEventType::CreateValue),
+            "DeleteValue" => Ok(RegistryEventType::DeleteValue),
+            "RenameValue" => Ok(RegistryEventType::RenameValue),
+            "SetValue" => Ok(RegistryEventType::SetValue),
+            unknown => Err(UnknownEventType(unknown.to_string())),
         }
+    }
+}
 
-        let process_id = get_or_err!(m, "ProcessId");
-        let process_id: u64 = process_id.parse()?;
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct RegistryEventData {
+    pub event_type: RegistryEventType,
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    pub image: Image,
+    /// <Data Name="TargetObject">HKU\S-1-5-21-1-1000\Software\Microsoft\Windows\CurrentVersion\Run\updater</Data>
+    pub target_object: String,
+    /// The data written by a `SetValue`
+    pub details: Option<String>,
+    /// The path a `RenameKey` or `RenameValue` renamed the target to
+    pub new_name: Option<String>,
+}
 
-        let parent_process_id = get_or_err!(m, "ParentProcessId");
-        let parent_process_id: u64 = parent_process_id.parse()?;
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct RegistryEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: RegistryEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct FileCreateStreamHashEventData {
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    pub image: Image,
+    /// <Data Name="TargetFilename">C:\Users\rsmith\Downloads\setup.exe:Zone.Identifier</Data>
+    pub target_filename: String,
+    pub creation_utc_time: UtcTime,
+    pub hash: Option<Hashes>,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct FileCreateStreamHashEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: FileCreateStreamHashEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash, is_enum_variant)]
+pub enum PipeEventType {
+    CreatePipe,
+    ConnectPipe,
+}
+
+impl FromStr for PipeEventType {
+    type Err = UnknownEventType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CreatePipe" => Ok(PipeEventType::CreatePipe),
+            "ConnectPipe" => Ok(PipeEventType::ConnectPipe),
+            unknown => Err(UnknownEventType(unknown.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct PipeEventData {
+    pub event_type: PipeEventType,
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    /// <Data Name="PipeName">\MSSE-1234-server</Data>
+    pub pipe_name: String,
+    pub image: Image,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct PipeEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: PipeEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct DnsQueryEventData {
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    pub query_name: String,
+    pub query_status: String,
+    /// <Data Name="QueryResults">type:  5 example.trafficmanager.net;::ffff:93.184.216.34;</Data>
+    pub query_results: Option<String>,
+    pub image: Image,
+}
+
+impl DnsQueryEventData {
+    /// The addresses the query resolved to. Other records, such as the CNAMEs Sysmon prefixes
+    /// with their record type, are skipped.
+    pub fn resolved_addresses(&self) -> Vec<IpAddr> {
+        self.query_results
+            .iter()
+            .flat_map(|results| results.split(';'))
+            .filter_map(|result| {
+                let result = result.trim();
+                let result = result.strip_prefix("::ffff:").unwrap_or(result);
+                result.parse().ok()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct DnsQueryEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: DnsQueryEventData,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct FileDeleteEventData {
+    pub utc_time: UtcTime,
+    pub process_guid: ProcessGuid,
+    pub process_id: u64,
+    pub user: Option<User>,
+    pub image: Image,
+    pub target_filename: String,
+    pub hashes: Option<Hashes>,
+    pub is_executable: Option<bool>,
+    /// Whether Sysmon kept a copy of the file. Only `FileDelete` (23) events report this,
+    /// `FileDeleteDetected` (26) events never archive.
+    pub archived: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Clone, Hash)]
+pub struct FileDeleteEvent {
+    #[serde(rename = "System")]
+    pub system: System,
+    #[serde(rename = "EventData", deserialize_with = "from_intermediary_data")]
+    pub event_data: FileDeleteEventData,
+}
+
+impl TryFrom<IntermediaryEventData> for ProcessCreateEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
 
         Ok(ProcessCreateEventData {
             utc_time: UtcTime {
-                utc_time: get_or_err!(m, "UtcTime"),
-            },
-            process_guid: ProcessGuid {
-                process_guid: uuid::Uuid::parse_str(&get_or_err!(m, "ProcessGuid")[1..37])?,
+                utc_time: f.required("UtcTime")?,
             },
-            process_id,
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
             image: Image {
-                image: get_or_err!(m, "Image"),
+                image: f.required("Image")?,
             },
             command_line: CommandLine {
-                command_line: get_or_err!(m, "CommandLine"),
+                command_line: f.required("CommandLine")?,
             },
             current_directory: CurrentDirectory {
-                current_directory: get_or_err!(m, "CurrentDirectory"),
+                current_directory: f.required("CurrentDirectory")?,
             },
             user: User {
-                user: get_or_err!(m, "User"),
-            },
-            logon_guid: LogonGuid {
-                logon_guid: uuid::Uuid::parse_str(&get_or_err!(m, "LogonGuid")[1..37])?,
+                user: f.required("User")?,
             },
+            logon_guid: f.parse("LogonGuid")?,
             logon_id: LogonId {
-                logon_id: get_or_err!(m, "LogonId"),
+                logon_id: f.required("LogonId")?,
             },
             terminal_session_id: TerminalSessionId {
-                terminal_session_id: get_or_err!(m, "TerminalSessionId"),
+                terminal_session_id: f.required("TerminalSessionId")?,
             },
             integrity_level: IntegrityLevel {
-                integrity_level: get_or_err!(m, "IntegrityLevel"),
+                integrity_level: f.required("IntegrityLevel")?,
             },
             hashes: Hashes {
-                hashes: get_or_err!(m, "Hashes"),
-            },
-            parent_process_guid: ProcessGuid {
-                process_guid: uuid::Uuid::parse_str(&get_or_err!(m, "ParentProcessGuid")[1..37])?,
+                hashes: f.required("Hashes")?,
             },
-            parent_process_id,
+            parent_process_guid: f.parse("ParentProcessGuid")?,
+            parent_process_id: f.parse("ParentProcessId")?,
             parent_image: Image {
-                image: get_or_err!(m, "ParentImage"),
+                image: f.required("ParentImage")?,
             },
             parent_command_line: CommandLine {
-                command_line: get_or_err!(m, "ParentCommandLine"),
+                command_line: f.required("ParentCommandLine")?,
             },
         })
     }
 }
 
 impl TryFrom<IntermediaryEventData> for FileCreateEventData {
-    type Error = anyhow::Error;
+    type Error = EventDataError;
 
-    fn try_from(inter: IntermediaryEventData) -> Result<Self> {
-        let mut m = HashMap::with_capacity(inter.data.len());
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
 
-        for data in inter.data {
-            if let Some(value) = data.value {
-                m.insert(data.name, value);
-            }
-        }
+        Ok(FileCreateEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            image: Image {
+                image: f.required("Image")?,
+            },
+            creation_utc_time: UtcTime {
+                utc_time: f.required("CreationUtcTime")?,
+            },
+            target_filename: f.required("TargetFilename")?,
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for NetworkEventData {
+    type Error = EventDataError;
 
-        let process_id = get_or_err!(m, "ProcessId");
-        let process_id = process_id.parse()?;
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
 
-        Ok(FileCreateEventData {
+        Ok(NetworkEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            image: Image {
+                image: f.required("Image")?,
+            },
+            user: f.optional("User").map(|user| User { user }),
+            protocol: f.required("Protocol")?,
+            source_is_ipv6: f.required("SourceIsIpv6")?,
+            source_ip: f.required("SourceIp")?,
+            source_hostname: f.optional("SourceHostname"),
+            source_port_name: f.optional("SourcePortName"),
+            destination_is_ipv6: f.required("DestinationIsIpv6")?,
+            destination_ip: f.required("DestinationIp")?,
+            destination_hostname: f.optional("DestinationHostname"),
+            destination_port_name: f.optional("DestinationPortName"),
+            initiated: f.parse("Initiated")?,
+            source_port: f.parse("SourcePort")?,
+            destination_port: f.parse("DestinationPort")?,
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for ProcessTerminateEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(ProcessTerminateEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            image: Image {
+                image: f.required("Image")?,
+            },
+            user: f.optional("User").map(|user| User { user }),
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for ImageLoadEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(ImageLoadEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            image: Image {
+                image: f.required("Image")?,
+            },
+            image_loaded: f.required("ImageLoaded")?,
+            file_version: f.optional("FileVersion"),
+            description: f.optional("Description"),
+            product: f.optional("Product"),
+            company: f.optional("Company"),
+            original_file_name: f.optional("OriginalFileName"),
+            hashes: f.optional("Hashes").map(|hashes| Hashes { hashes }),
+            signed: f.parse_optional("Signed")?,
+            signature: f.optional("Signature"),
+            signature_status: f.optional("SignatureStatus"),
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for CreateRemoteThreadEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(CreateRemoteThreadEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            source_process_guid: f.parse("SourceProcessGuid")?,
+            source_process_id: f.parse("SourceProcessId")?,
+            source_image: Image {
+                image: f.required("SourceImage")?,
+            },
+            target_process_guid: f.parse("TargetProcessGuid")?,
+            target_process_id: f.parse("TargetProcessId")?,
+            target_image: Image {
+                image: f.required("TargetImage")?,
+            },
+            new_thread_id: f.parse("NewThreadId")?,
+            start_address: f.required("StartAddress")?,
+            start_module: f.optional("StartModule"),
+            start_function: f.optional("StartFunction"),
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for ProcessAccessEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        // Unlike every other event, ProcessAccess capitalizes its GUIDs as `GUID`
+        let source_process_guid = match f.parse_optional("SourceProcessGUID")? {
+            Some(guid) => guid,
+            None => f.parse("SourceProcessGuid")?,
+        };
+        let target_process_guid = match f.parse_optional("TargetProcessGUID")? {
+            Some(guid) => guid,
+            None => f.parse("TargetProcessGuid")?,
+        };
+
+        Ok(ProcessAccessEventData {
             utc_time: UtcTime {
-                utc_time: get_or_err!(m, "UtcTime"),
+                utc_time: f.required("UtcTime")?,
             },
-            process_guid: ProcessGuid {
-                process_guid: uuid::Uuid::parse_str(&get_or_err!(m, "ProcessGuid")[1..37])?,
+            source_process_guid,
+            source_process_id: f.parse("SourceProcessId")?,
+            source_thread_id: f.parse("SourceThreadId")?,
+            source_image: Image {
+                image: f.required("SourceImage")?,
             },
-            process_id,
+            target_process_guid,
+            target_process_id: f.parse("TargetProcessId")?,
+            target_image: Image {
+                image: f.required("TargetImage")?,
+            },
+            granted_access: f.required("GrantedAccess")?,
+            call_trace: f.optional("CallTrace"),
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for RegistryEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(RegistryEventData {
+            event_type: f.parse("EventType")?,
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
             image: Image {
-                image: get_or_err!(m, "Image"),
+                image: f.required("Image")?,
+            },
+            target_object: f.required("TargetObject")?,
+            details: f.optional("Details"),
+            new_name: f.optional("NewName"),
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for FileCreateStreamHashEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(FileCreateStreamHashEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
             },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            image: Image {
+                image: f.required("Image")?,
+            },
+            target_filename: f.required("TargetFilename")?,
             creation_utc_time: UtcTime {
-                utc_time: get_or_err!(m, "CreationUtcTime"),
+                utc_time: f.required("CreationUtcTime")?,
             },
-            target_filename: get_or_err!(m, "TargetFilename"),
+            hash: f.optional("Hash").map(|hashes| Hashes { hashes }),
         })
     }
 }
 
-impl TryFrom<IntermediaryEventData> for NetworkEventData {
-    type Error = anyhow::Error;
+impl TryFrom<IntermediaryEventData> for PipeEventData {
+    type Error = EventDataError;
 
-    fn try_from(inter: IntermediaryEventData) -> Result<Self> {
-        let mut m = HashMap::with_capacity(inter.data.len());
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
 
-        for data in inter.data {
-            if let Some(value) = data.value {
-                m.insert(data.name, value);
-            }
-        }
+        Ok(PipeEventData {
+            event_type: f.parse("EventType")?,
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
+            },
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            pipe_name: f.required("PipeName")?,
+            image: Image {
+                image: f.required("Image")?,
+            },
+        })
+    }
+}
 
-        let user = m.remove("User").map(|user| User { user });
+impl TryFrom<IntermediaryEventData> for DnsQueryEventData {
+    type Error = EventDataError;
 
-        Ok(NetworkEventData {
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(DnsQueryEventData {
             utc_time: UtcTime {
-                utc_time: get_or_err!(m, "UtcTime"),
+                utc_time: f.required("UtcTime")?,
             },
-            process_guid: ProcessGuid {
-                process_guid: uuid::Uuid::parse_str(&get_or_err!(m, "ProcessGuid")[1..37])?,
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            query_name: f.required("QueryName")?,
+            query_status: f.required("QueryStatus")?,
+            query_results: f.optional("QueryResults"),
+            image: Image {
+                image: f.required("Image")?,
+            },
+        })
+    }
+}
+
+impl TryFrom<IntermediaryEventData> for FileDeleteEventData {
+    type Error = EventDataError;
+
+    fn try_from(inter: IntermediaryEventData) -> Result<Self, Self::Error> {
+        let mut f = inter.into_fields();
+
+        Ok(FileDeleteEventData {
+            utc_time: UtcTime {
+                utc_time: f.required("UtcTime")?,
             },
-            process_id: get_or_err!(m, "ProcessId").parse()?,
+            process_guid: f.parse("ProcessGuid")?,
+            process_id: f.parse("ProcessId")?,
+            user: f.optional("User").map(|user| User { user }),
             image: Image {
-                image: get_or_err!(m, "Image"),
-            },
-            user,
-            protocol: get_or_err!(m, "Protocol"),
-            source_is_ipv6: get_or_err!(m, "SourceIsIpv6"),
-            source_ip: get_or_err!(m, "SourceIp"),
-            source_hostname: m.remove("SourceHostname"),
-            source_port_name: m.remove("SourcePortName"),
-            destination_is_ipv6: get_or_err!(m, "DestinationIsIpv6"),
-            destination_ip: get_or_err!(m, "DestinationIp"),
-            destination_hostname: m.remove("DestinationHostname"),
-            destination_port_name: m.remove("DestinationPortName"),
-            initiated: get_or_err!(m, "Initiated").parse()?,
-            source_port: get_or_err!(m, "SourcePort").parse()?,
-            destination_port: get_or_err!(m, "DestinationPort").parse()?,
+                image: f.required("Image")?,
+            },
+            target_filename: f.required("TargetFilename")?,
+            hashes: f.optional("Hashes").map(|hashes| Hashes { hashes }),
+            is_executable: f.parse_optional("IsExecutable")?,
+            archived: f.parse_optional("Archived")?,
         })
     }
 }
@@ -599,9 +1152,10 @@ fn from_intermediary_data<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
     T: TryFrom<IntermediaryEventData>,
+    T::Error: Display,
 {
     let s: IntermediaryEventData = Deserialize::deserialize(deserializer)?;
-    T::try_from(s).map_err(|_| SerdeError::custom("Failed to deserialize"))
+    T::try_from(s).map_err(SerdeError::custom)
 }
 
 #[derive(Debug, Deserialize, Clone, Hash)]
@@ -614,10 +1168,74 @@ pub struct Data {
 
 #[derive(Debug, Deserialize, Clone, Hash)]
 pub struct IntermediaryEventData {
-    #[serde(rename = "Data")]
+    #[serde(rename = "Data", default)]
     pub data: Vec<Data>,
 }
 
+impl IntermediaryEventData {
+    /// The values of the event's data by name. Data without a value are left out.
+    fn into_fields(self) -> EventDataFields {
+        EventDataFields {
+            fields: self
+                .data
+                .into_iter()
+                .filter_map(|Data { name, value }| value.map(|value| (name, value)))
+                .collect(),
+        }
+    }
+}
+
+/// The values of an event's data, taken by name as they're converted
+struct EventDataFields {
+    fields: HashMap<String, String>,
+}
+
+impl EventDataFields {
+    fn required(&mut self, field: &'static str) -> Result<String, EventDataError> {
+        self.fields
+            .remove(field)
+            .ok_or(EventDataError::MissingField(field))
+    }
+
+    fn optional(&mut self, field: &'static str) -> Option<String> {
+        self.fields.remove(field)
+    }
+
+    fn parse<T>(&mut self, field: &'static str) -> Result<T, EventDataError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.required(field)?;
+        parse_field(field, value)
+    }
+
+    fn parse_optional<T>(&mut self, field: &'static str) -> Result<Option<T>, EventDataError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.optional(field)
+            .map(|value| parse_field(field, value))
+            .transpose()
+    }
+}
+
+fn parse_field<T>(field: &'static str, value: String) -> Result<T, EventDataError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match value.parse() {
+        Ok(parsed) => Ok(parsed),
+        Err(e) => Err(EventDataError::InvalidField {
+            field,
+            reason: e.to_string(),
+            value,
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -777,4 +1395,392 @@ mod tests {
         assert!(Event::from_str(FILE_CREATE).unwrap().is_file_create());
         assert!(Event::from_str(PROCESS_CREATE).unwrap().is_process_create());
     }
+
+    /// Wraps `event_data` in an event with the given `EventID`
+    fn event_with_data(event_id: u8, event_data: &str) -> String {
+        format!(
+            r#"
+            <Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+                {}
+                <EventData>{}</EventData>
+            </Event>
+            "#,
+            HEADER.replace(
+                "<EventID>1</EventID>",
+                &format!("<EventID>{}</EventID>", event_id)
+            ),
+            event_data
+        )
+    }
+
+    const PROCESS_TERMINATE_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:13:20.895</Data>
+        <Data Name="ProcessGuid">{A23EAE89-BD56-5903-0000-0010E9D95E00}</Data>
+        <Data Name="ProcessId">6228</Data>
+        <Data Name="Image">C:\Program Files (x86)\Google\Chrome\Application\chrome.exe</Data>
+    "#;
+
+    const IMAGE_LOAD_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:45:16.662</Data>
+        <Data Name="ProcessGuid">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="ProcessId">12536</Data>
+        <Data Name="Image">C:\Windows\System32\rundll32.exe</Data>
+        <Data Name="ImageLoaded">C:\Users\rsmith\AppData\Local\Temp\payload.dll</Data>
+        <Data Name="FileVersion">-</Data>
+        <Data Name="Description">-</Data>
+        <Data Name="Product">-</Data>
+        <Data Name="Company">-</Data>
+        <Data Name="Hashes">SHA256=2B6D7F8F2A1B7C83E6A4D3D5F1B8E0F41A5C3D6B7E8F9A0B1C2D3E4F5A6B7C8D</Data>
+        <Data Name="Signed">false</Data>
+        <Data Name="Signature"></Data>
+        <Data Name="SignatureStatus">Unavailable</Data>
+    "#;
+
+    const CREATE_REMOTE_THREAD_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:46:02.113</Data>
+        <Data Name="SourceProcessGuid">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="SourceProcessId">12536</Data>
+        <Data Name="SourceImage">C:\Windows\System32\rundll32.exe</Data>
+        <Data Name="TargetProcessGuid">{A23EAE89-B357-5903-0000-0010A1B20700}</Data>
+        <Data Name="TargetProcessId">3800</Data>
+        <Data Name="TargetImage">C:\Windows\explorer.exe</Data>
+        <Data Name="NewThreadId">9016</Data>
+        <Data Name="StartAddress">0x0000000002A10000</Data>
+        <Data Name="StartModule"></Data>
+        <Data Name="StartFunction"></Data>
+    "#;
+
+    const PROCESS_ACCESS_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:47:30.421</Data>
+        <Data Name="SourceProcessGUID">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="SourceProcessId">12536</Data>
+        <Data Name="SourceThreadId">8040</Data>
+        <Data Name="SourceImage">C:\Windows\System32\rundll32.exe</Data>
+        <Data Name="TargetProcessGUID">{A23EAE89-B2F2-5903-0000-0010C3D00000}</Data>
+        <Data Name="TargetProcessId">620</Data>
+        <Data Name="TargetImage">C:\Windows\system32\lsass.exe</Data>
+        <Data Name="GrantedAccess">0x1010</Data>
+        <Data Name="CallTrace">C:\Windows\SYSTEM32\ntdll.dll+a5314|UNKNOWN(0000000002A10000)</Data>
+    "#;
+
+    const REGISTRY_SET_VALUE_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="EventType">SetValue</Data>
+        <Data Name="UtcTime">2017-04-28 22:48:11.005</Data>
+        <Data Name="ProcessGuid">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="ProcessId">12536</Data>
+        <Data Name="Image">C:\Windows\System32\rundll32.exe</Data>
+        <Data Name="TargetObject">HKU\S-1-5-21-1-1000\Software\Microsoft\Windows\CurrentVersion\Run\updater</Data>
+        <Data Name="Details">C:\Users\rsmith\AppData\Local\Temp\updater.exe</Data>
+    "#;
+
+    const REGISTRY_RENAME_KEY_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="EventType">RenameKey</Data>
+        <Data Name="UtcTime">2017-04-28 22:48:12.110</Data>
+        <Data Name="ProcessGuid">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="ProcessId">12536</Data>
+        <Data Name="Image">C:\Windows\System32\rundll32.exe</Data>
+        <Data Name="TargetObject">HKLM\System\CurrentControlSet\Services\Updater</Data>
+        <Data Name="NewName">HKLM\System\CurrentControlSet\Services\WinUpdate</Data>
+    "#;
+
+    const FILE_CREATE_STREAM_HASH_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:49:01.733</Data>
+        <Data Name="ProcessGuid">{A23EAE89-BD28-5903-0000-00102F345D00}</Data>
+        <Data Name="ProcessId">13220</Data>
+        <Data Name="Image">C:\Program Files (x86)\Google\Chrome\Application\chrome.exe</Data>
+        <Data Name="TargetFilename">C:\Users\rsmith\Downloads\setup.exe:Zone.Identifier</Data>
+        <Data Name="CreationUtcTime">2017-04-28 22:49:00.512</Data>
+        <Data Name="Hash">SHA256=EACD09517CE90D34BA562171D15AC40D302F0E691B439F91BE1B6406E25F5913</Data>
+    "#;
+
+    const PIPE_CONNECTED_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="EventType">ConnectPipe</Data>
+        <Data Name="UtcTime">2017-04-28 22:50:44.287</Data>
+        <Data Name="ProcessGuid">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="ProcessId">12536</Data>
+        <Data Name="PipeName">\MSSE-1234-server</Data>
+        <Data Name="Image">C:\Windows\System32\rundll32.exe</Data>
+    "#;
+
+    const DNS_QUERY_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:51:10.318</Data>
+        <Data Name="ProcessGuid">{A23EAE89-BD28-5903-0000-00102F345D00}</Data>
+        <Data Name="ProcessId">13220</Data>
+        <Data Name="QueryName">www.example.com</Data>
+        <Data Name="QueryStatus">0</Data>
+        <Data Name="QueryResults">type:  5 www.example.com-v4.edgesuite.net;::ffff:93.184.216.34;2606:2800:220:1:248:1893:25c8:1946;</Data>
+        <Data Name="Image">C:\Program Files (x86)\Google\Chrome\Application\chrome.exe</Data>
+    "#;
+
+    const FILE_DELETE_DATA: &str = r#"
+        <Data Name="RuleName"></Data>
+        <Data Name="UtcTime">2017-04-28 22:52:37.004</Data>
+        <Data Name="ProcessGuid">{A23EAE89-C6DC-5903-0000-0010EC4E6B00}</Data>
+        <Data Name="ProcessId">12536</Data>
+        <Data Name="User">LAB\rsmith</Data>
+        <Data Name="Image">C:\Windows\System32\rundll32.exe</Data>
+        <Data Name="TargetFilename">C:\Users\rsmith\AppData\Local\Temp\payload.dll</Data>
+        <Data Name="Hashes">SHA256=2B6D7F8F2A1B7C83E6A4D3D5F1B8E0F41A5C3D6B7E8F9A0B1C2D3E4F5A6B7C8D</Data>
+        <Data Name="IsExecutable">true</Data>
+        <Data Name="Archived">true</Data>
+    "#;
+
+    #[test]
+    fn process_terminate_event() {
+        let event = Event::from_str(&event_with_data(5, PROCESS_TERMINATE_DATA)).unwrap();
+        match event {
+            Event::ProcessTerminate(event) => assert_eq!(event.event_data.process_id, 6228),
+            event => panic!("expected ProcessTerminate, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn image_load_event() {
+        match Event::from_str(&event_with_data(7, IMAGE_LOAD_DATA)).unwrap() {
+            Event::ImageLoad(event) => {
+                assert_eq!(
+                    event.event_data.image_loaded,
+                    r"C:\Users\rsmith\AppData\Local\Temp\payload.dll"
+                );
+                assert_eq!(event.event_data.signed, Some(false));
+                assert_eq!(event.event_data.signature, None);
+            }
+            event => panic!("expected ImageLoad, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn create_remote_thread_event() {
+        match Event::from_str(&event_with_data(8, CREATE_REMOTE_THREAD_DATA)).unwrap() {
+            Event::CreateRemoteThread(event) => {
+                assert_eq!(event.event_data.source_process_id, 12536);
+                assert_eq!(event.event_data.target_process_id, 3800);
+                assert_eq!(event.event_data.start_module, None);
+            }
+            event => panic!("expected CreateRemoteThread, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn process_access_event() {
+        match Event::from_str(&event_with_data(10, PROCESS_ACCESS_DATA)).unwrap() {
+            Event::ProcessAccess(event) => {
+                assert_eq!(event.event_data.target_process_id, 620);
+                assert_eq!(event.event_data.granted_access, "0x1010");
+                assert_eq!(
+                    event
+                        .event_data
+                        .target_process_guid
+                        .process_guid
+                        .to_string(),
+                    "a23eae89-b2f2-5903-0000-0010c3d00000"
+                );
+            }
+            event => panic!("expected ProcessAccess, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn registry_events() {
+        match Event::from_str(&event_with_data(13, REGISTRY_SET_VALUE_DATA)).unwrap() {
+            Event::Registry(event) => {
+                assert!(event.event_data.event_type.is_set_value());
+                assert!(event.event_data.details.is_some());
+            }
+            event => panic!("expected Registry, got {:?}", event),
+        }
+
+        match Event::from_str(&event_with_data(14, REGISTRY_RENAME_KEY_DATA)).unwrap() {
+            Event::Registry(event) => {
+                assert!(event.event_data.event_type.is_rename_key());
+                assert_eq!(
+                    event.event_data.new_name.as_deref(),
+                    Some(r"HKLM\System\CurrentControlSet\Services\WinUpdate")
+                );
+            }
+            event => panic!("expected Registry, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn file_create_stream_hash_event() {
+        assert!(
+            Event::from_str(&event_with_data(15, FILE_CREATE_STREAM_HASH_DATA))
+                .unwrap()
+                .is_file_create_stream_hash()
+        );
+    }
+
+    #[test]
+    fn pipe_event() {
+        match Event::from_str(&event_with_data(18, PIPE_CONNECTED_DATA)).unwrap() {
+            Event::Pipe(event) => {
+                assert!(event.event_data.event_type.is_connect_pipe());
+                assert_eq!(event.event_data.pipe_name, r"\MSSE-1234-server");
+            }
+            event => panic!("expected Pipe, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn dns_query_event() {
+        match Event::from_str(&event_with_data(22, DNS_QUERY_DATA)).unwrap() {
+            Event::DnsQuery(event) => {
+                assert_eq!(event.event_data.query_name, "www.example.com");
+                assert_eq!(
+                    event.event_data.resolved_addresses(),
+                    vec![
+                        "93.184.216.34".parse::<IpAddr>().unwrap(),
+                        "2606:2800:220:1:248:1893:25c8:1946".parse().unwrap(),
+                    ]
+                );
+            }
+            event => panic!("expected DnsQuery, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn file_delete_events() {
+        match Event::from_str(&event_with_data(23, FILE_DELETE_DATA)).unwrap() {
+            Event::FileDelete(event) => {
+                assert_eq!(event.event_data.is_executable, Some(true));
+                assert_eq!(event.event_data.archived, Some(true));
+            }
+            event => panic!("expected FileDelete, got {:?}", event),
+        }
+
+        // FileDeleteDetected events don't report whether the file was archived
+        let detected = FILE_DELETE_DATA.replace(r#"<Data Name="Archived">true</Data>"#, "");
+        match Event::from_str(&event_with_data(26, &detected)).unwrap() {
+            Event::FileDelete(event) => assert_eq!(event.event_data.archived, None),
+            event => panic!("expected FileDelete, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn events_are_parsed_by_event_id() {
+        // The data of a ProcessTerminate is a subset of the data of a FileCreate
+        assert!(Event::from_str(FILE_CREATE).unwrap().is_file_create());
+        assert!(Event::from_str(&event_with_data(11, PROCESS_TERMINATE_DATA)).is_err());
+        assert!(Event::from_str(&event_with_data(4, PROCESS_TERMINATE_DATA)).is_err());
+    }
+
+    #[test]
+    fn missing_fields_are_named() {
+        let file_create =
+            FILE_CREATE.replace(r#"<Data Name="TargetFilename">"#, r#"<Data Name="Target">"#);
+        match Event::from_str(&file_create) {
+            Err(Error::EventData {
+                event_id: 11,
+                source: EventDataError::MissingField("TargetFilename"),
+            }) => (),
+            result => panic!("expected a missing TargetFilename, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn invalid_fields_are_named() {
+        let data = PROCESS_TERMINATE_DATA.replace("6228", "not a pid");
+        match Event::from_str(&event_with_data(5, &data)) {
+            Err(Error::EventData {
+                event_id: 5,
+                source: EventDataError::InvalidField { field, value, .. },
+            }) => {
+                assert_eq!(field, "ProcessId");
+                assert_eq!(value, "not a pid");
+            }
+            result => panic!("expected an invalid ProcessId, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn unsupported_event_ids() {
+        match Event::from_str(&event_with_data(4, PROCESS_TERMINATE_DATA)) {
+            Err(Error::UnsupportedEventId(4)) => (),
+            result => panic!("expected an unsupported event id, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn malformed_xml() {
+        match Event::from_str("<Event><System>") {
+            Err(Error::Xml(_)) => (),
+            result => panic!("expected malformed XML, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn stream_of_events_on_one_line() {
+        let events = format!(
+            "{}{}{}",
+            PROCESS_CREATE.replace('\n', ""),
+            FILE_CREATE.replace('\n', ""),
+            NETWORK_EVENT.replace('\n', "")
+        );
+
+        let events: Vec<Event> = EventStream::new(events.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert!(events[0].is_process_create());
+        assert!(events[1].is_file_create());
+        assert!(events[2].is_outbound_network());
+    }
+
+    #[test]
+    fn stream_of_wrapped_events() {
+        let events = format!(
+            "<?xml version=\"1.0\"?>\n<Events>\n{}\n{}\n</Events>\n",
+            event_with_data(22, DNS_QUERY_DATA),
+            event_with_data(18, PIPE_CONNECTED_DATA),
+        );
+
+        let events: Vec<Event> = EventStream::new(events.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].is_dns_query());
+        assert!(events[1].is_pipe());
+    }
+
+    #[test]
+    fn stream_continues_past_bad_events() {
+        let events = format!(
+            "{}{}{}",
+            event_with_data(4, PROCESS_TERMINATE_DATA),
+            event_with_data(11, PROCESS_TERMINATE_DATA),
+            event_with_data(5, PROCESS_TERMINATE_DATA),
+        );
+
+        let events: Vec<_> = EventStream::new(events.as_bytes()).collect();
+
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], Err(Error::UnsupportedEventId(4))));
+        assert!(matches!(
+            events[1],
+            Err(Error::EventData { event_id: 11, .. })
+        ));
+        assert!(events[2].as_ref().unwrap().is_process_terminate());
+    }
+
+    #[test]
+    fn stream_ending_within_an_event() {
+        let truncated = &PROCESS_CREATE[..PROCESS_CREATE.len() / 2];
+
+        let events: Vec<_> = EventStream::new(truncated.as_bytes()).collect();
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Err(Error::Io(_))));
+    }
 }
@@ -0,0 +1,97 @@
+use std::{
+    io::BufRead,
+    str::FromStr,
+};
+
+use crate::{
+    Error,
+    Event,
+};
+
+/// Parses the events of a stream of Sysmon XML, one `<Event>` element at a time
+///
+/// Events may be laid out however the producer likes: several to a line, spread across lines,
+/// or wrapped in an `<Events>` element. Anything between events is ignored.
+pub struct EventStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> EventStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Reads up to and including the next `>`, returning false at the end of the stream
+    fn read_tag(&mut self) -> Result<bool, Error> {
+        let read = self.reader.read_until(b'>', &mut self.buf)?;
+        Ok(read != 0 && self.buf.ends_with(b">"))
+    }
+
+    /// Reads the next event's XML, or `None` once the stream holds no more events
+    fn next_event_xml(&mut self) -> Result<Option<String>, Error> {
+        // Skip to the start of the next event
+        loop {
+            self.buf.clear();
+            if !self.read_tag()? {
+                return Ok(None);
+            }
+            if let Some(start) = find_event_start(&self.buf) {
+                self.buf.drain(..start);
+                break;
+            }
+        }
+
+        // Collect the event up to its end tag
+        while !self.buf.ends_with(b"</Event>") {
+            if !self.read_tag()? {
+                return Err(Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "Stream ended within an event",
+                )));
+            }
+        }
+
+        Ok(Some(String::from_utf8_lossy(&self.buf).into_owned()))
+    }
+}
+
+impl<R: BufRead> Iterator for EventStream<R> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_event_xml() {
+            Ok(Some(xml)) => Some(Event::from_str(&xml)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Finds an `<Event` start tag within a single tag's worth of input, ruling out `<EventData>`
+/// and `<Events>`
+fn find_event_start(tag: &[u8]) -> Option<usize> {
+    const START: &[u8] = b"<Event";
+
+    let start = tag.windows(START.len()).rposition(|w| w == START)?;
+    match tag.get(start + START.len()) {
+        Some(b'>') => Some(start),
+        Some(c) if c.is_ascii_whitespace() => Some(start),
+        _ => None,
+    }
+}